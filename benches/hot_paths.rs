@@ -0,0 +1,93 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::widgets::{Paragraph, Widget, Wrap};
+use ratatui::Terminal;
+use rust_tui_test::market::gen_universe;
+use rust_tui_test::testing::{app_state, ui_state};
+use rust_tui_test::ui::{draw, market_rows};
+use std::hint::black_box;
+
+// how many instruments the market is timed with
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+// a news summary as long as the feeds cut them to
+const SUMMARY: &str = "Management raises guidance as the backlog of boiler orders reaches record \
+    levels, and the board expects the new foundry at the docks to double the output of valves \
+    and pistons before the end of the season, though the unions have yet to agree to the hours.";
+
+fn market_rows_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("market rows");
+    let ui_state = ui_state();
+    let area = Rect::new(0, 0, 160, 40);
+    for count in SIZES {
+        let companies = gen_universe(count);
+        let app_state = app_state(&companies, 10);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &app_state, |b, app| {
+            b.iter(|| market_rows(app, &ui_state, area))
+        });
+    }
+    group.finish();
+}
+
+fn wrapping_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wrapping");
+    for width in [20u16, 40, 80] {
+        // the descriptions in the market table
+        group.bench_with_input(BenchmarkId::new("textwrap", width), &width, |b, width| {
+            b.iter(|| textwrap::wrap(black_box(SUMMARY), *width as usize))
+        });
+        // and news summaries, wrapped as they're drawn
+        let area = Rect::new(0, 0, width, 20);
+        let mut buffer = Buffer::empty(area);
+        group.bench_with_input(BenchmarkId::new("paragraph", width), &area, |b, area| {
+            b.iter(|| {
+                Paragraph::new(black_box(SUMMARY))
+                    .wrap(Wrap { trim: true })
+                    .render(*area, &mut buffer)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn frame_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("frame");
+    let ui_state = ui_state();
+    for count in SIZES {
+        let companies = gen_universe(count);
+        let app_state = app_state(&companies, 10);
+        let mut terminal = Terminal::new(TestBackend::new(160, 50)).expect("test backend");
+        group.bench_with_input(BenchmarkId::from_parameter(count), &app_state, |b, app| {
+            b.iter(|| {
+                terminal
+                    .draw(|frame| draw(frame, app, &ui_state))
+                    .expect("frame")
+                    .area
+            })
+        });
+    }
+    group.finish();
+}
+
+fn tick_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tick");
+    let mut rng = rand::rng();
+    for count in SIZES {
+        let companies = gen_universe(count);
+        let mut app_state = app_state(&companies, 10);
+        group.bench_function(BenchmarkId::from_parameter(count), |b| {
+            b.iter(|| app_state.tick(&mut rng))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    market_rows_bench,
+    wrapping_bench,
+    frame_bench,
+    tick_bench
+);
+criterion_main!(benches);
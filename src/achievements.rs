@@ -0,0 +1,189 @@
+use crate::market::Instrument;
+use crate::news::{NewsCategory, NewsItem};
+use crate::orders::BlotterEntry;
+use crate::portfolio::Portfolio;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const TEN_PERCENT_DAY_GAIN_PCT: f64 = 10.0;
+const WIDE_PORTFOLIO_TICKERS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Achievement {
+    FirstTrade,
+    TenPercentDay,
+    SurvivedCrash,
+    TenTickers,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 4] = [
+        Achievement::FirstTrade,
+        Achievement::TenPercentDay,
+        Achievement::SurvivedCrash,
+        Achievement::TenTickers,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Achievement::FirstTrade => "First Trade",
+            Achievement::TenPercentDay => "Banner Day",
+            Achievement::SurvivedCrash => "Weathered the Storm",
+            Achievement::TenTickers => "Diversified",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::FirstTrade => "Get an order filled",
+            Achievement::TenPercentDay => "Grow the portfolio by 10% within a day",
+            Achievement::SurvivedCrash => {
+                "Hold on through a disaster until the position is back in profit"
+            }
+            Achievement::TenTickers => "Hold 10 different instruments at once",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Unlocked {
+    pub achievement: Achievement,
+    pub at: DateTime<Utc>,
+}
+
+// what gets written to the data directory
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SavedAchievements {
+    #[serde(default)]
+    unlocked: Vec<Unlocked>,
+}
+
+#[derive(Debug, Default)]
+pub struct Achievements {
+    pub unlocked: Vec<Unlocked>, // oldest first
+    path: Option<PathBuf>,       // not saved when there's nowhere to save to
+    // portfolio value when the current calendar day started
+    day: Option<(NaiveDate, f64)>,
+    // held tickers hit by a disaster, and whether they have gone under water since
+    crash_holdings: Vec<(String, bool)>,
+}
+
+impl Achievements {
+    // a missing or unreadable file just means starting from scratch
+    pub fn load(path: Option<PathBuf>) -> Achievements {
+        let saved = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|text| toml::from_str::<SavedAchievements>(&text).ok())
+            .unwrap_or_default();
+        Achievements {
+            unlocked: saved.unlocked,
+            path,
+            ..Default::default()
+        }
+    }
+
+    // losing an achievement isn't worth interrupting the game over
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let saved = SavedAchievements {
+            unlocked: self.unlocked.clone(),
+        };
+        let Ok(text) = toml::to_string(&saved) else {
+            return;
+        };
+        if path
+            .parent()
+            .is_some_and(|dir| fs::create_dir_all(dir).is_err())
+        {
+            return;
+        }
+        let _ = fs::write(path, text);
+    }
+
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked
+            .iter()
+            .any(|unlocked| unlocked.achievement == achievement)
+    }
+
+    fn unlock(&mut self, achievement: Achievement) {
+        if self.is_unlocked(achievement) {
+            return;
+        }
+        self.unlocked.push(Unlocked {
+            achievement,
+            at: Utc::now(),
+        });
+        self.save();
+    }
+
+    // disasters only count for companies already held when they hit
+    pub fn note_news(&mut self, news_item: &NewsItem, portfolio: &Portfolio) {
+        if news_item.category != NewsCategory::Disaster || news_item.sentiment >= 0.0 {
+            return;
+        }
+        for ticker in news_item.tickers.iter() {
+            if portfolio.quantity(ticker) > 0
+                && !self.crash_holdings.iter().any(|(held, _)| held == ticker)
+            {
+                self.crash_holdings.push((ticker.clone(), false));
+            }
+        }
+    }
+
+    pub fn check(
+        &mut self,
+        portfolio: &Portfolio,
+        portfolio_value: f64,
+        quotes: &[Instrument],
+        blotter: &[BlotterEntry],
+        today: NaiveDate,
+    ) {
+        if blotter
+            .iter()
+            .any(|entry| matches!(entry, BlotterEntry::Fill(_)))
+        {
+            self.unlock(Achievement::FirstTrade);
+        }
+
+        match self.day {
+            Some((day, start_value)) if day == today => {
+                if portfolio_value >= start_value * (1.0 + TEN_PERCENT_DAY_GAIN_PCT / 100.0) {
+                    self.unlock(Achievement::TenPercentDay);
+                }
+            }
+            _ => self.day = Some((today, portfolio_value)),
+        }
+
+        // selling out or getting wiped out is not surviving
+        self.crash_holdings
+            .retain(|(ticker, _)| portfolio.quantity(ticker) > 0);
+        let mut recovered = false;
+        for (ticker, under_water) in self.crash_holdings.iter_mut() {
+            let (Some(position), Some(quote)) = (
+                portfolio.position(ticker),
+                quotes.iter().find(|quote| quote.listing.ticker == *ticker),
+            ) else {
+                continue;
+            };
+            if quote.quote.price < position.avg_cost {
+                *under_water = true;
+            } else if *under_water {
+                recovered = true;
+            }
+        }
+        if recovered {
+            self.unlock(Achievement::SurvivedCrash);
+        }
+
+        if portfolio.positions.len() >= WIDE_PORTFOLIO_TICKERS {
+            self.unlock(Achievement::TenTickers);
+        }
+    }
+}
@@ -0,0 +1,551 @@
+use crate::app::PARALLEL_QUOTES;
+use crate::config::MoversConfig;
+use crate::exchanges::Exchange;
+use crate::forms::{Field, Form};
+use crate::market::{Instrument, Precision};
+use chrono::{DateTime, TimeDelta, Utc};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+// the oldest triggers are dropped past this, acknowledged or not
+const MAX_TRIGGERS: usize = 500;
+pub const DIRECTIONS: &[&str] = &["above", "below"];
+pub const MODES: &[&str] = &["once", "re-arm", "snooze"];
+const DEFAULT_BAND: f64 = 1.0;
+const DEFAULT_SNOOZE_MINUTES: u32 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Above,
+    Below,
+}
+
+impl Direction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Direction::Above => "above",
+            Direction::Below => "below",
+        }
+    }
+}
+
+// what an alert does after going off
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    #[default]
+    Once, // taken down
+    Rearm,  // waits for the price to get back past the band before it can go off again
+    Snooze, // quiet for the snooze period, then goes off again if the price is still there
+}
+
+impl Mode {
+    fn choice(&self) -> usize {
+        match self {
+            Mode::Once => 0,
+            Mode::Rearm => 1,
+            Mode::Snooze => 2,
+        }
+    }
+}
+
+fn default_band() -> f64 {
+    DEFAULT_BAND
+}
+
+fn default_snooze_minutes() -> u32 {
+    DEFAULT_SNOOZE_MINUTES
+}
+
+// goes off when the price crosses the threshold, in the currency the instrument is quoted in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    #[serde(default)]
+    pub id: u64, // how the TUI and the daemon tell their alerts apart, given when it's set
+    pub ticker: String,
+    pub direction: Direction,
+    pub price: f64,
+    #[serde(default)]
+    pub mode: Mode,
+    #[serde(default = "default_band")]
+    pub band: f64, // percent of the threshold a re-arming alert has to clear
+    #[serde(default = "default_snooze_minutes")]
+    pub snooze_minutes: u32,
+    #[serde(default)]
+    pub disarmed: bool, // went off in re-arm mode and the price hasn't cleared the band yet
+    #[serde(default)]
+    pub snoozed_until: Option<DateTime<Utc>>,
+}
+
+impl Alert {
+    fn is_hit(&self, price: f64) -> bool {
+        match self.direction {
+            Direction::Above => price >= self.price,
+            Direction::Below => price <= self.price,
+        }
+    }
+
+    // back on the other side of the threshold by more than the band
+    fn is_clear(&self, price: f64) -> bool {
+        match self.direction {
+            Direction::Above => price < self.price * (1.0 - self.band / 100.0),
+            Direction::Below => price > self.price * (1.0 + self.band / 100.0),
+        }
+    }
+
+    // whether it can go off now, re-arming it first if it's due
+    fn arm(&mut self, price: f64, now: DateTime<Utc>) -> bool {
+        if self.disarmed && self.is_clear(price) {
+            self.disarmed = false;
+        }
+        if self.snoozed_until.is_some_and(|until| now >= until) {
+            self.snoozed_until = None;
+        }
+        !self.disarmed && self.snoozed_until.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trigger {
+    pub alert: Alert,
+    pub price: f64, // what it was when the alert went off
+    pub at: DateTime<Utc>,
+    pub acknowledged: bool,
+    #[serde(default)]
+    pub moved: Option<f64>, // percent, when it's a big move that was flagged rather than an alert
+}
+
+// the length too, since two writes in a row can get the same modification time
+fn modified(path: &Option<PathBuf>) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path.as_ref()?).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+// what gets written to the data directory
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SavedAlerts {
+    #[serde(default)]
+    alerts: Vec<Alert>,
+    #[serde(default)]
+    triggers: Vec<Trigger>,
+}
+
+#[derive(Debug, Default)]
+pub struct Alerts {
+    pub alerts: Vec<Alert>,              // waiting to go off
+    pub triggers: Vec<Trigger>,          // oldest first
+    path: Option<PathBuf>,               // not saved when there's nowhere to save to
+    modified: Option<(SystemTime, u64)>, // of the file as last read or written
+}
+
+impl Alerts {
+    // a missing or unreadable file just means starting from scratch
+    pub fn load(path: Option<PathBuf>) -> Alerts {
+        let saved = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|text| toml::from_str::<SavedAlerts>(&text).ok())
+            .unwrap_or_default();
+        let mut alerts = Alerts {
+            alerts: saved.alerts,
+            triggers: saved.triggers,
+            modified: modified(&path),
+            path,
+        };
+        // saved before alerts had ids
+        for idx in 0..alerts.alerts.len() {
+            if alerts.alerts[idx].id == 0 {
+                alerts.alerts[idx].id = alerts.next_id();
+            }
+        }
+        alerts
+    }
+
+    fn next_id(&self) -> u64 {
+        self.alerts.iter().map(|alert| alert.id).max().unwrap_or(0) + 1
+    }
+
+    // read again when something else wrote the file, the TUI and the daemon both write it, so
+    // this goes before every change and every check
+    fn reload(&mut self) {
+        if modified(&self.path) != self.modified {
+            *self = Alerts::load(self.path.take());
+        }
+    }
+
+    // like achievements, a lost alert isn't worth interrupting the game over
+    fn save(&mut self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let saved = SavedAlerts {
+            alerts: self.alerts.clone(),
+            triggers: self.triggers.clone(),
+        };
+        let Ok(text) = toml::to_string(&saved) else {
+            return;
+        };
+        if path
+            .parent()
+            .is_some_and(|dir| fs::create_dir_all(dir).is_err())
+        {
+            return;
+        }
+        let _ = fs::write(path, text);
+        self.modified = modified(&self.path);
+    }
+
+    pub fn add(&mut self, alert: Alert) {
+        self.reload();
+        let id = self.next_id();
+        self.alerts.push(Alert { id, ..alert });
+        self.save();
+    }
+
+    // an edited alert starts out armed, by id since the other side may have moved it meanwhile
+    pub fn replace(&mut self, id: u64, alert: Alert) {
+        self.reload();
+        if let Some(old) = self.alerts.iter_mut().find(|alert| alert.id == id) {
+            *old = Alert { id, ..alert };
+            self.save();
+        }
+    }
+
+    pub fn remove(&mut self, id: u64) {
+        self.reload();
+        if let Some(idx) = self.alerts.iter().position(|alert| alert.id == id) {
+            self.alerts.remove(idx);
+            self.save();
+        }
+    }
+
+    pub fn unacknowledged(&self) -> usize {
+        self.triggers
+            .iter()
+            .filter(|trigger| !trigger.acknowledged)
+            .count()
+    }
+
+    pub fn acknowledge(&mut self) {
+        self.reload();
+        for trigger in self.triggers.iter_mut() {
+            trigger.acknowledged = true;
+        }
+        self.save();
+    }
+
+    // after every tick, alerts that went off are logged, and taken down, disarmed or
+    // snoozed by their mode
+    pub fn check(&mut self, quotes: &[Instrument], exchanges: &[Exchange]) {
+        self.reload();
+        let price = |ticker: &str| {
+            let quote = quotes.iter().find(|quote| quote.listing.ticker == ticker)?;
+            Some(exchanges[quote.exchange].local_price(quote.quote.price))
+        };
+        let now = Utc::now();
+        let mut changed = false;
+        let mut idx = 0;
+        while idx < self.alerts.len() {
+            let Some(price) = price(&self.alerts[idx].ticker) else {
+                idx += 1;
+                continue;
+            };
+            let alert = &mut self.alerts[idx];
+            let (disarmed, snoozed_until) = (alert.disarmed, alert.snoozed_until);
+            if !alert.arm(price, now) || !alert.is_hit(price) {
+                changed |= (disarmed, snoozed_until) != (alert.disarmed, alert.snoozed_until);
+                idx += 1;
+                continue;
+            }
+            self.triggers.push(Trigger {
+                alert: alert.clone(),
+                price,
+                at: now,
+                acknowledged: false,
+                moved: None,
+            });
+            changed = true;
+            match alert.mode {
+                Mode::Once => {
+                    self.alerts.remove(idx);
+                    continue;
+                }
+                Mode::Rearm => alert.disarmed = true,
+                Mode::Snooze => {
+                    alert.snoozed_until =
+                        Some(now + TimeDelta::minutes(alert.snooze_minutes as i64))
+                }
+            }
+            idx += 1;
+        }
+        if changed {
+            self.trim();
+        }
+    }
+
+    // logged with the alerts that went off, for the big moves flagged without one
+    pub fn log(&mut self, trigger: Trigger) {
+        self.triggers.push(trigger);
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        let excess = self.triggers.len().saturating_sub(MAX_TRIGGERS);
+        self.triggers.drain(..excess);
+        self.save();
+    }
+}
+
+// an instrument flagged for moving more than the threshold within the window
+#[derive(Debug, Clone)]
+pub struct Mover {
+    pub ticker: String,
+    pub change: f64, // percent over the window, as of the last tick
+    pub since: DateTime<Utc>,
+}
+
+// big moves across the whole market, flagged without an alert per ticker
+#[derive(Debug, Default)]
+pub struct Movers {
+    pub config: MoversConfig,
+    pub movers: Vec<Mover>, // biggest move first
+    pub ring: bool,         // one was just flagged with the bell on, the main loop rings it
+}
+
+impl Movers {
+    pub fn new(config: MoversConfig) -> Movers {
+        Movers {
+            config,
+            movers: vec![],
+            ring: false,
+        }
+    }
+
+    pub fn get(&self, ticker: &str) -> Option<&Mover> {
+        self.movers.iter().find(|mover| mover.ticker == ticker)
+    }
+
+    // after every tick, flagged past the threshold and only dropped once back under half of it,
+    // so one hovering around the threshold doesn't go off again every tick
+    pub fn check(&mut self, quotes: &[Instrument], exchanges: &[Exchange], alerts: &mut Alerts) {
+        let threshold = self.config.threshold_pct;
+        if threshold <= 0.0 {
+            return;
+        }
+        let now = Utc::now();
+        let start = now - TimeDelta::seconds(self.config.window_secs as i64);
+        // looking back through every history is the slow part, that goes on the simulation threads
+        let references = quotes
+            .par_iter()
+            .with_min_len(PARALLEL_QUOTES)
+            .map(|quote| quote.history.price_at(start).filter(|price| *price > 0.0))
+            .collect::<Vec<Option<f64>>>();
+        for (quote, reference) in quotes.iter().zip(references) {
+            let ticker = &quote.listing.ticker;
+            let Some(reference) = reference else {
+                continue;
+            };
+            let change = (quote.quote.price / reference - 1.0) * 100.0;
+            match self.movers.iter().position(|mover| mover.ticker == *ticker) {
+                Some(idx) if change.abs() < threshold / 2.0 => {
+                    self.movers.remove(idx);
+                }
+                Some(idx) => self.movers[idx].change = change,
+                None if change.abs() >= threshold => {
+                    self.movers.push(Mover {
+                        ticker: ticker.clone(),
+                        change,
+                        since: now,
+                    });
+                    if self.config.notify {
+                        // in the local currency, like the prices alerts go off at
+                        let exchange = &exchanges[quote.exchange];
+                        alerts.log(Trigger {
+                            alert: Alert {
+                                id: 0, // never set, so not one of the alerts
+                                ticker: ticker.clone(),
+                                direction: if change > 0.0 {
+                                    Direction::Above
+                                } else {
+                                    Direction::Below
+                                },
+                                price: exchange.local_price(reference),
+                                mode: Mode::Once,
+                                band: DEFAULT_BAND,
+                                snooze_minutes: DEFAULT_SNOOZE_MINUTES,
+                                disarmed: false,
+                                snoozed_until: None,
+                            },
+                            price: exchange.local_price(quote.quote.price),
+                            at: now,
+                            acknowledged: false,
+                            moved: Some(change),
+                        });
+                        self.ring |= self.config.bell;
+                    }
+                }
+                None => {}
+            }
+        }
+        // delisted ones aren't moving anymore
+        self.movers.retain(|mover| {
+            quotes
+                .iter()
+                .any(|quote| quote.listing.ticker == mover.ticker)
+        });
+        self.movers
+            .sort_by(|a, b| b.change.abs().total_cmp(&a.change.abs()));
+    }
+}
+
+// setting an alert on an instrument, starting from where the price is now, or changing one
+// that's set
+pub struct AlertForm {
+    pub ticker: String,
+    pub editing: Option<u64>, // id of the alert
+    pub form: Form,
+}
+
+impl AlertForm {
+    pub fn new(ticker: &str, price: f64, precision: Precision) -> AlertForm {
+        let alert = Alert {
+            id: 0,
+            ticker: ticker.to_string(),
+            direction: Direction::Above,
+            price,
+            mode: Mode::default(),
+            band: DEFAULT_BAND,
+            snooze_minutes: DEFAULT_SNOOZE_MINUTES,
+            disarmed: false,
+            snoozed_until: None,
+        };
+        AlertForm::edit(None, &alert, precision)
+    }
+
+    pub fn edit(editing: Option<u64>, alert: &Alert, precision: Precision) -> AlertForm {
+        AlertForm {
+            ticker: alert.ticker.clone(),
+            editing,
+            form: Form::new(vec![
+                Field::select(
+                    "When",
+                    DIRECTIONS,
+                    (alert.direction == Direction::Below) as usize,
+                ),
+                Field::number("Price", true)
+                    .spinner(precision.tick_size, alert.price)
+                    .filled(&format!("{0:.1$}", alert.price, precision.display_decimals)),
+                Field::select("Then", MODES, alert.mode.choice()),
+                Field::number("Re-arm band %", true)
+                    .within(0.01, 50.0)
+                    .spinner(0.5, alert.band)
+                    .filled(&alert.band.to_string()),
+                Field::number("Snooze min", false)
+                    .within(1.0, 1440.0)
+                    .spinner(5.0, alert.snooze_minutes as f64)
+                    .filled(&alert.snooze_minutes.to_string()),
+            ]),
+        }
+    }
+
+    pub fn parse(&mut self) -> Result<Alert, String> {
+        self.form.validate()?;
+        Ok(Alert {
+            id: self.editing.unwrap_or(0),
+            ticker: self.ticker.clone(),
+            direction: match self.form.choice(0) {
+                0 => Direction::Above,
+                _ => Direction::Below,
+            },
+            price: self.form.number(1).unwrap_or(0.0),
+            mode: match self.form.choice(2) {
+                0 => Mode::Once,
+                1 => Mode::Rearm,
+                _ => Mode::Snooze,
+            },
+            band: self.form.number(3).unwrap_or(DEFAULT_BAND),
+            snooze_minutes: self
+                .form
+                .number(4)
+                .map_or(DEFAULT_SNOOZE_MINUTES, |minutes| minutes as u32),
+            disarmed: false,
+            snoozed_until: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::gen_universe;
+    use crate::testing::app_state;
+
+    fn alert(ticker: &str, direction: Direction) -> Alert {
+        Alert {
+            id: 0,
+            ticker: ticker.to_string(),
+            direction,
+            price: 0.0, // always above it and never below it
+            mode: Mode::Once,
+            band: DEFAULT_BAND,
+            snooze_minutes: DEFAULT_SNOOZE_MINUTES,
+            disarmed: false,
+            snoozed_until: None,
+        }
+    }
+
+    // the daemon and the TUI, each having read the same file
+    fn both(name: &str) -> (PathBuf, Alerts, Alerts) {
+        let dir = std::env::temp_dir().join(format!(
+            "iron-ledger-alerts-{0}-{1}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("alerts.toml");
+        let mut daemon = Alerts::load(Some(path.clone()));
+        daemon.add(alert("ONE", Direction::Above));
+        daemon.add(alert("TWO", Direction::Below));
+        let tui = Alerts::load(Some(path.clone()));
+        (path, daemon, tui)
+    }
+
+    #[test]
+    fn a_trigger_from_one_side_survives_a_save_from_the_other() {
+        let companies = gen_universe(1);
+        let app_state = app_state(&companies, 0);
+        let (path, mut daemon, mut tui) = both("trigger");
+        daemon.alerts[0].ticker = app_state.quotes[0].listing.ticker.clone();
+        daemon.save();
+        daemon.check(&app_state.quotes, &app_state.exchanges);
+        assert_eq!(daemon.triggers.len(), 1);
+        // set without having seen it go off
+        tui.add(alert("THREE", Direction::Below));
+        let saved = Alerts::load(Some(path.clone()));
+        assert_eq!(saved.triggers.len(), 1);
+        let tickers = saved
+            .alerts
+            .iter()
+            .map(|alert| alert.ticker.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(tickers, ["TWO", "THREE"]);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn an_edit_goes_to_the_same_alert_after_the_other_side_took_one_down() {
+        let (path, mut daemon, mut tui) = both("edit");
+        let id = tui.alerts[1].id;
+        daemon.remove(daemon.alerts[0].id);
+        tui.replace(id, alert("TWO", Direction::Above));
+        let saved = Alerts::load(Some(path.clone()));
+        assert_eq!(saved.alerts.len(), 1);
+        assert_eq!(saved.alerts[0].id, id);
+        assert_eq!(saved.alerts[0].direction, Direction::Above);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
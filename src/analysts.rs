@@ -0,0 +1,190 @@
+use crate::exchanges::Currency;
+use crate::market::Instrument;
+use crate::news::{NewsCategory, NewsItem};
+use chrono::{DateTime, Utc};
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use ratatui::style::Color;
+use std::ops::RangeInclusive;
+
+// chance that some analyst publishes a note on any given tick
+const NOTE_CHANCE_PER_TICK: f64 = 0.05;
+// how far price targets stray from the current price, in percent
+const TARGET_SPREAD_PCT: f64 = 25.0;
+// news sentiment of an upgrade or downgrade, reiterations move prices half as much
+const RATING_CHANGE_SENTIMENT: f64 = 0.3;
+
+const FIRMS: [&str; 4] = [
+    "Cogsworth & Partners",
+    "Boilerplate Securities",
+    "Gearing Brothers",
+    "Piston Street Research",
+];
+
+// ordered from worst to best so upgrades compare greater
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Rating {
+    Sell,
+    Hold,
+    Buy,
+}
+
+impl Rating {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Rating::Buy => "Buy",
+            Rating::Hold => "Hold",
+            Rating::Sell => "Sell",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            Rating::Buy => Color::Green,
+            Rating::Hold => Color::Yellow,
+            Rating::Sell => Color::Red,
+        }
+    }
+
+    // the rating an analyst would give with that much upside
+    fn for_upside(upside_pct: f64) -> Rating {
+        if upside_pct > 10.0 {
+            Rating::Buy
+        } else if upside_pct < -5.0 {
+            Rating::Sell
+        } else {
+            Rating::Hold
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RatingAction {
+    Initiate,
+    Upgrade,
+    Downgrade,
+    Reiterate,
+}
+
+impl RatingAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RatingAction::Initiate => "Initiates",
+            RatingAction::Upgrade => "Upgrades",
+            RatingAction::Downgrade => "Downgrades",
+            RatingAction::Reiterate => "Reiterates",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AnalystNote {
+    pub firm: &'static str,
+    pub ticker: String,
+    pub rating: Rating,
+    pub previous: Option<Rating>, // the firm's earlier rating, if it covered the ticker
+    pub price_target: f64,
+    pub published: DateTime<Utc>,
+}
+
+impl AnalystNote {
+    pub fn action(&self) -> RatingAction {
+        match self.previous {
+            None => RatingAction::Initiate,
+            Some(previous) if self.rating > previous => RatingAction::Upgrade,
+            Some(previous) if self.rating < previous => RatingAction::Downgrade,
+            Some(_) => RatingAction::Reiterate,
+        }
+    }
+
+    pub fn sentiment(&self) -> f64 {
+        match self.action() {
+            RatingAction::Upgrade => RATING_CHANGE_SENTIMENT,
+            RatingAction::Downgrade => -RATING_CHANGE_SENTIMENT,
+            _ => match self.rating {
+                Rating::Buy => RATING_CHANGE_SENTIMENT / 2.0,
+                Rating::Hold => 0.0,
+                Rating::Sell => -RATING_CHANGE_SENTIMENT / 2.0,
+            },
+        }
+    }
+
+    pub fn news_item(&self, quote: &Instrument, currency: &Currency) -> NewsItem {
+        NewsItem::new(
+            &format!(
+                "{0} {1} {2} ({3}) at {4}",
+                self.firm,
+                self.action().label(),
+                quote.listing.name,
+                self.ticker,
+                self.rating.label()
+            ),
+            &format!(
+                "Price target set at {0}.",
+                currency.format(self.price_target, quote.price_decimals())
+            ),
+            NewsCategory::Analysts,
+            self.sentiment(),
+            &[self.ticker.as_str()],
+        )
+    }
+}
+
+// latest rating of every firm covering the ticker, notes are kept newest first
+pub fn current_ratings<'a>(notes: &'a [AnalystNote], ticker: &str) -> Vec<&'a AnalystNote> {
+    let mut ratings: Vec<&AnalystNote> = vec![];
+    for note in notes.iter().filter(|note| note.ticker == ticker) {
+        if !ratings.iter().any(|rating| rating.firm == note.firm) {
+            ratings.push(note);
+        }
+    }
+    ratings
+}
+
+// most common current rating, ties go to Hold
+pub fn consensus(notes: &[AnalystNote], ticker: &str) -> Option<Rating> {
+    let ratings = current_ratings(notes, ticker);
+    let count = |rating: Rating| ratings.iter().filter(|note| note.rating == rating).count();
+    let (buys, holds, sells) = (count(Rating::Buy), count(Rating::Hold), count(Rating::Sell));
+    if ratings.is_empty() {
+        None
+    } else if buys > holds && buys > sells {
+        Some(Rating::Buy)
+    } else if sells > holds && sells > buys {
+        Some(Rating::Sell)
+    } else {
+        Some(Rating::Hold)
+    }
+}
+
+// analysts only cover companies
+pub fn gen_analyst_note(
+    rng: &mut ThreadRng,
+    quotes: &[Instrument],
+    notes: &[AnalystNote],
+) -> Option<AnalystNote> {
+    let companies = quotes
+        .iter()
+        .filter(|quote| quote.is_company())
+        .collect::<Vec<&Instrument>>();
+    if companies.is_empty() || !rng.random_bool(NOTE_CHANCE_PER_TICK) {
+        return None;
+    }
+    let quote = companies[rng.random_range(0..companies.len())];
+    let firm = FIRMS[rng.random_range(0..FIRMS.len())];
+    let upside_pct = rng.random_range(RangeInclusive::new(-TARGET_SPREAD_PCT, TARGET_SPREAD_PCT));
+    let ticker = &quote.listing.ticker;
+    Some(AnalystNote {
+        firm,
+        ticker: ticker.clone(),
+        rating: Rating::for_upside(upside_pct),
+        previous: current_ratings(notes, ticker)
+            .iter()
+            .find(|note| note.firm == firm)
+            .map(|note| note.rating),
+        price_target: quote
+            .precision
+            .round(quote.quote.price * (1.0 + upside_pct / 100.0)),
+        published: Utc::now(),
+    })
+}
@@ -0,0 +1,1965 @@
+use crate::achievements::Achievements;
+use crate::alerts::{AlertForm, Alerts, Movers};
+use crate::analysts::{gen_analyst_note, AnalystNote};
+use crate::benchmark::Composite;
+use crate::book::gen_book;
+use crate::components::{
+    Component, MarketTable, NewsList, OrdersTable, PortfolioPanel, ScrollView,
+};
+use crate::config::Config;
+use crate::connection::Connection;
+use crate::difficulty::Difficulty;
+use crate::distress::{update_fortunes, DistressLevel, Fortune, FortuneEvent};
+use crate::exchanges::{Currency, Exchange};
+use crate::feeds::FeedUpdate;
+use crate::formatting::Accent;
+use crate::forms::{Field, Form, TextInput};
+use crate::fx::{FxMarket, FxView};
+use crate::graphics::ChartImage;
+use crate::history::{merge, Candle, PriceHistory, Timeframe};
+use crate::keymap::Keymap;
+use crate::locale::{tr, trf};
+use crate::macros::Macros;
+use crate::market::{ChangeHorizon, Instrument, InstrumentClass, Precision};
+use crate::mergers::{
+    completion_news, gen_deal, resolve_rumor, start_rumor, Consideration, Deal, DealStage,
+    MERGER_ARBITRAGE_SPREAD_PCT, OFFER_PULL, RUMOR_SENTIMENT_PER_TICK,
+};
+use crate::news::{gen_news, NewsCategory, NewsItem};
+use crate::options::{option_chain, OptionChain, OptionContract, OptionKind};
+use crate::orders::{
+    execute_orders, Amendment, BlotterEntry, Bracket, BuyingPower, Fill, Order, OrderStatus, Side,
+};
+use crate::ownership::{gen_insider_filing, gen_squeeze, squeeze, InsiderFiling};
+use crate::panels::{registry, Panel};
+use crate::portfolio::{
+    convert_lots, settlement_date, Account, CashEntry, CashKind, Gain, OptionPosition, Portfolio,
+    Position, Unsettled,
+};
+use crate::rebalance::{RebalanceView, Target};
+use crate::report::SessionReport;
+use crate::saves::{list_slots, SaveSlot, SavedGame, SavedQuote};
+use crate::scenario::{Scenario, ScriptedAction};
+use crate::schedules::{ScheduleForm, Schedules};
+use crate::terminal::Hyperlink;
+use crate::theme::Theme;
+use crate::timefmt::TimeFormat;
+use crate::tutorial::Tutorial;
+use crate::ui::chart_marker;
+use crate::undo::{Change, History};
+use crate::worker::{Distribution, Job, Matrix, Outcome, Worker};
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, Utc};
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use ratatui::symbols::Marker;
+use rayon::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+
+// the Latest news panel only shows the most recent items, the rest is in the archive
+pub const LATEST_NEWS_LIMIT: usize = 50;
+pub const NEWS_ARCHIVE_PAGE_SIZE: usize = 20;
+// price moves and what's worked out from them are spread over threads in runs of at least this
+// many instruments, fewer aren't worth handing off
+pub const PARALLEL_QUOTES: usize = 512;
+
+pub struct AppState<'a> {
+    pub quotes: Vec<Instrument<'a>>,
+    pub currency: Currency, // of the home exchange, that everything is counted in
+    pub time_format: TimeFormat,
+    pub news: Vec<NewsItem>,
+    // labels of feeds whose last fetch failed
+    pub offline_feeds: Vec<String>,
+    pub accounts: Vec<Account>, // at least one, the first one opened with the starting cash
+    pub active: usize,          // index into accounts, where orders go
+    pub orders: Vec<Order>,     // newest first
+    pub blotter: Vec<BlotterEntry>, // oldest first
+    pub next_order_id: u64,
+    // how long the most recent fill can still be taken back
+    pub undo_grace: TimeDelta,
+    // quotes without an update for this long are marked
+    pub stale_after: TimeDelta,
+    pub ticks: u64, // simulation ticks so far, option expiries are counted in ticks
+    pub exchanges: Vec<Exchange>, // the home exchange first
+    pub connection: Connection,
+    pub analyst_notes: Vec<AnalystNote>, // newest first
+    pub analysts_move_prices: bool,
+    pub insider_filings: Vec<InsiderFiling>, // newest first
+    pub deal: Option<Deal>,                  // at most one takeover at a time
+    pub fortunes: Vec<Fortune>,
+    pub scenario: Scenario,
+    pub achievements: Achievements,
+    pub alerts: Alerts,
+    pub movers: Movers,
+    pub schedules: Schedules,
+    pub targets: Vec<Target>,   // shares of the portfolio to rebalance to
+    pub cash_interest_pct: f64, // yearly
+    pub borrow_rate_pct: f64,   // yearly, on a negative balance
+    pub accrued_on: NaiveDate,  // the last day interest was paid or charged for
+    pub long_term: TimeDelta,   // how long shares are held for their gains to be long-term
+    pub settlement_days: u32,   // business days until sale proceeds settle, 0 settles at once
+    pub trade_unsettled: bool,  // whether buys can spend proceeds that haven't settled
+    pub margin_pct: f64,        // of the shares held that buys can borrow against
+    pub difficulty: Difficulty,
+    pub started_at: DateTime<Utc>, // of this session, for the report
+    pub equity_curve: Vec<f64>,    // portfolio value at the start and after every tick since
+    pub composite: Composite,      // the index the equity curve is held up against
+    // the prices the last tick started out at by ticker, built once a tick for the index and
+    // the hooks to compare with
+    pub before_tick: HashMap<&'a str, f64>,
+    pub fx: FxMarket,
+    pub new_game: Option<SavedGame>, // how the session started, for starting over
+}
+
+impl<'a> AppState<'a> {
+    // a session starting out with nothing traded yet, set up from the config, with the
+    // achievements, alerts and schedules saved in the data directory when there's one
+    pub fn new(
+        rng: &mut ThreadRng,
+        quotes: Vec<Instrument<'a>>,
+        exchanges: Vec<Exchange>,
+        accounts: Vec<Account>,
+        config: &Config,
+        data_dir: Option<&Path>,
+    ) -> AppState<'a> {
+        let time_format = TimeFormat::new(&config.time);
+        // interest starts being paid from tomorrow
+        let accrued_on = time_format.day(Utc::now());
+        let starting_cash = accounts
+            .iter()
+            .map(|account| account.portfolio.cash)
+            .sum::<f64>();
+        AppState {
+            composite: Composite::default(),
+            before_tick: HashMap::new(),
+            fx: FxMarket::new(rng, &exchanges),
+            quotes,
+            currency: exchanges[0].currency.clone(),
+            time_format,
+            news: vec![],
+            offline_feeds: vec![],
+            accounts,
+            active: 0,
+            orders: vec![],
+            blotter: vec![],
+            next_order_id: 1,
+            undo_grace: TimeDelta::seconds(config.trading.undo_grace_secs as i64),
+            stale_after: TimeDelta::seconds(config.display.stale_after_secs as i64),
+            ticks: 0,
+            exchanges,
+            connection: Connection::new(config.market.flaky_connection, None),
+            analyst_notes: vec![],
+            analysts_move_prices: config.market.analysts_move_prices,
+            insider_filings: vec![],
+            deal: None,
+            fortunes: vec![],
+            scenario: Scenario::default(),
+            achievements: Achievements::load(data_dir.map(|dir| dir.join("achievements.toml"))),
+            alerts: Alerts::load(data_dir.map(|dir| dir.join("alerts.toml"))),
+            movers: Movers::new(config.movers.clone()),
+            schedules: Schedules::load(data_dir.map(|dir| dir.join("schedules.toml"))),
+            targets: vec![],
+            cash_interest_pct: config.trading.cash_interest_pct,
+            borrow_rate_pct: config.trading.borrow_rate_pct,
+            accrued_on,
+            long_term: TimeDelta::days(config.trading.long_term_days as i64),
+            settlement_days: config.trading.settlement_days,
+            trade_unsettled: config.trading.trade_unsettled,
+            margin_pct: config.trading.margin_pct,
+            difficulty: config.game.difficulty,
+            started_at: Utc::now(),
+            equity_curve: vec![starting_cash],
+            new_game: None,
+        }
+    }
+
+    pub fn quote_index(&self, ticker: &str) -> Option<usize> {
+        self.quotes
+            .iter()
+            .position(|quote| quote.listing.ticker == ticker)
+    }
+
+    // whether each exchange is in session right now
+    pub fn open_exchanges(&self) -> Vec<bool> {
+        let now = self.time_format.time_of_day(Utc::now());
+        self.exchanges
+            .iter()
+            .map(|exchange| exchange.is_open(now))
+            .collect()
+    }
+
+    // seconds since the last update of a quote that hasn't moved for a while
+    pub fn quote_age(&self, quote: &Instrument) -> Option<i64> {
+        let age = Utc::now() - quote.updated_at;
+        (age >= self.stale_after).then_some(age.num_seconds())
+    }
+
+    // the same for a quote that should be moving, a closed exchange is just quiet
+    pub fn stale_secs(&self, quote: &Instrument) -> Option<i64> {
+        let trading = self.exchange_open(quote.exchange) || quote.trades_continuously();
+        self.quote_age(quote).filter(|_| trading)
+    }
+
+    // when the exchange last opened, midnight for one that never closes
+    pub fn session_start(&self, exchange: usize) -> DateTime<Utc> {
+        let now = Utc::now();
+        let open = self.exchanges[exchange]
+            .session
+            .map_or(NaiveTime::MIN, |session| session.open);
+        let mut since = self
+            .time_format
+            .time_of_day(now)
+            .signed_duration_since(open);
+        if since < TimeDelta::zero() {
+            since += TimeDelta::days(1);
+        }
+        now - since
+    }
+
+    // in the local currency, yesterday's close at yesterday's exchange rate, and otherwise
+    // prices as far back as the history goes at today's
+    pub fn reference_price(&self, quote: &Instrument, horizon: ChangeHorizon) -> Option<f64> {
+        let exchange = &self.exchanges[quote.exchange];
+        let history = &quote.history;
+        let price = match horizon {
+            ChangeHorizon::Yesterday => {
+                return Some(exchange.local_price_yesterday(quote.quote.price_yesterday));
+            }
+            ChangeHorizon::Tick => history.previous,
+            ChangeHorizon::FiveMinutes => history.price_at(Utc::now() - TimeDelta::minutes(5)),
+            ChangeHorizon::Session => history.price_at(self.started_at),
+            ChangeHorizon::SinceOpen => history.price_at(self.session_start(quote.exchange)),
+        };
+        price.map(|price| exchange.local_price(price))
+    }
+
+    // in the local currency and in percent, none before there's anything to compare with
+    pub fn change(&self, quote: &Instrument, horizon: ChangeHorizon) -> (f64, f64) {
+        let price = self.exchanges[quote.exchange].local_price(quote.quote.price);
+        let reference = self.reference_price(quote, horizon).unwrap_or(price);
+        (price - reference, (price - reference) / reference * 100.0)
+    }
+
+    pub fn exchange_open(&self, exchange: usize) -> bool {
+        self.exchanges[exchange].is_open(self.time_format.time_of_day(Utc::now()))
+    }
+
+    pub fn portfolio(&self) -> &Portfolio {
+        &self.accounts[self.active].portfolio
+    }
+
+    pub fn portfolio_mut(&mut self) -> &mut Portfolio {
+        &mut self.accounts[self.active].portfolio
+    }
+
+    // all accounts as one, for the combined view, achievements and the report
+    pub fn combined(&self) -> Portfolio {
+        Portfolio::combined(self.accounts.iter().map(|account| &account.portfolio))
+    }
+
+    // cash plus everything held at current prices, across all accounts
+    pub fn portfolio_value(&self) -> f64 {
+        self.accounts
+            .iter()
+            .map(|account| self.value_of(&account.portfolio))
+            .sum()
+    }
+
+    pub fn value_of(&self, portfolio: &Portfolio) -> f64 {
+        portfolio.cash
+            + portfolio
+                .positions
+                .iter()
+                .filter_map(|position| {
+                    let idx = self.quote_index(&position.ticker)?;
+                    Some(self.quotes[idx].quote.price * position.quantity as f64)
+                })
+                .sum::<f64>()
+            + portfolio
+                .option_positions
+                .iter()
+                .filter_map(|position| {
+                    Some(self.option_price(&position.contract)? * position.quantity as f64)
+                })
+                .sum::<f64>()
+            + portfolio
+                .foreign_cash
+                .iter()
+                .filter_map(|held| Some(self.fx.find(&held.code)?.rate * held.amount))
+                .sum::<f64>()
+    }
+
+    // foreign cash for the active account's cash at the rate and spread, or back again
+    pub fn change_cash(&mut self, idx: usize, side: Side, amount: f64) -> Result<(), String> {
+        let Some(currency) = self
+            .fx
+            .currencies
+            .get(idx)
+            .filter(|currency| !currency.is_home())
+        else {
+            return Err("pick a foreign currency".to_string());
+        };
+        let rate = currency.price(side);
+        let value = amount * rate;
+        let portfolio = &self.accounts[self.active].portfolio;
+        match side {
+            Side::Buy => {
+                let cash = if self.trade_unsettled {
+                    portfolio.cash
+                } else {
+                    portfolio.settled_cash()
+                };
+                if value > cash {
+                    return Err(format!(
+                        "{0} costs {1:.2}, there's only {2:.2} in cash",
+                        currency.currency.format(amount, 2),
+                        value,
+                        cash.max(0.0)
+                    ));
+                }
+            }
+            Side::Sell => {
+                let held = portfolio.foreign(&currency.code);
+                if amount > held + 0.005 {
+                    return Err(format!("only {0} held", currency.currency.format(held, 2)));
+                }
+            }
+        }
+        let note = format!(
+            "{0} {1} at {2:.4}",
+            side.label(),
+            currency.currency.format(amount, 2),
+            rate
+        );
+        let code = currency.code.clone();
+        self.accounts[self.active]
+            .portfolio
+            .change_cash(&code, side, amount, value, &note);
+        Ok(())
+    }
+
+    // unlocks whatever the latest tick or key press earned
+    pub fn check_achievements(&mut self) {
+        let portfolio_value = self.portfolio_value();
+        self.achievements.check(
+            &self.combined(),
+            portfolio_value,
+            &self.quotes,
+            &self.blotter,
+            self.time_format.day(Utc::now()),
+        );
+    }
+
+    pub fn saved_game(&self) -> SavedGame {
+        SavedGame {
+            saved_at: Utc::now(),
+            portfolio_value: self.portfolio_value(),
+            difficulty: self.difficulty,
+            ticks: self.ticks,
+            next_order_id: self.next_order_id,
+            quotes: self
+                .quotes
+                .iter()
+                .map(|quote| SavedQuote {
+                    ticker: quote.listing.ticker.clone(),
+                    model: quote.model.clone(),
+                    price: quote.quote.price,
+                    price_yesterday: quote.quote.price_yesterday,
+                    sentiment_drift: quote.sentiment_drift,
+                })
+                .collect(),
+            portfolio: None,
+            accounts: self.accounts.clone(),
+            active: self.active,
+            orders: self.orders.clone(),
+            blotter: self.blotter.clone(),
+        }
+    }
+
+    // back to how the session started, instruments taken over since stay delisted
+    pub fn start_over(&mut self) {
+        if let Some(game) = self.new_game.clone() {
+            self.restore(game);
+            self.news.clear();
+        }
+    }
+
+    // picks a saved campaign back up, stories in progress are dropped with the saved news
+    pub fn restore(&mut self, game: SavedGame) {
+        self.quotes.retain(|quote| {
+            game.quotes
+                .iter()
+                .any(|saved| saved.ticker == quote.listing.ticker)
+        });
+        for saved in game.quotes {
+            let Some(idx) = self.quote_index(&saved.ticker) else {
+                continue;
+            };
+            let quote = &mut self.quotes[idx];
+            quote.model = saved.model;
+            quote.quote.price = saved.price;
+            quote.quote.price_yesterday = saved.price_yesterday;
+            quote.sentiment_drift = saved.sentiment_drift;
+            quote.book = gen_book(&mut rand::rng(), saved.price, quote.precision.tick_size);
+        }
+        self.difficulty = game.difficulty;
+        self.ticks = game.ticks;
+        self.next_order_id = game.next_order_id;
+        // saves from before accounts have the one portfolio, it goes to the first account and
+        // the others start out empty
+        if let Some(portfolio) = game.portfolio {
+            for account in self.accounts.iter_mut() {
+                account.portfolio = Portfolio::new(0.0);
+            }
+            self.accounts[0].portfolio = portfolio;
+        } else if !game.accounts.is_empty() {
+            self.accounts = game.accounts;
+        }
+        self.active = game.active.min(self.accounts.len() - 1);
+        for account in self.accounts.iter_mut() {
+            account.portfolio.open_ledger();
+            account.portfolio.open_lots();
+        }
+        self.orders = game.orders;
+        self.blotter = game.blotter;
+        self.analyst_notes.clear();
+        self.insider_filings.clear();
+        self.deal = None;
+        self.fortunes.clear();
+        // a loaded game is a new session
+        self.started_at = Utc::now();
+        self.equity_curve = vec![self.portfolio_value()];
+        self.composite.restart();
+    }
+
+    pub fn price_decimals(&self, ticker: &str) -> usize {
+        self.precision(ticker).display_decimals
+    }
+
+    pub fn precision(&self, ticker: &str) -> Precision {
+        self.quote_index(ticker).map_or_else(
+            || InstrumentClass::Equity.precision(),
+            |idx| self.quotes[idx].precision,
+        )
+    }
+
+    pub fn unread_news_count(&self) -> usize {
+        self.news.iter().filter(|news_item| !news_item.seen).count()
+    }
+
+    // news is kept newest first, any sentiment is felt by the related tickers
+    pub fn publish_news(&mut self, news_item: NewsItem) {
+        self.achievements.note_news(&news_item, &self.combined());
+        for ticker in news_item.tickers.iter() {
+            if let Some(idx) = self.quote_index(ticker) {
+                self.quotes[idx].apply_sentiment(news_item.sentiment);
+            }
+        }
+        self.news.insert(0, news_item);
+    }
+
+    // every note also makes the news
+    fn publish_analyst_note(&mut self, note: AnalystNote) {
+        if let Some(idx) = self.quote_index(&note.ticker) {
+            let mut news_item = note.news_item(&self.quotes[idx], &self.currency);
+            if !self.analysts_move_prices {
+                news_item.sentiment = 0.0;
+            }
+            self.publish_news(news_item);
+        }
+        self.analyst_notes.insert(0, note);
+    }
+
+    pub fn apply_feed_update(&mut self, update: FeedUpdate) {
+        match update.items {
+            Ok(items) => {
+                self.offline_feeds.retain(|label| *label != update.label);
+                // publish oldest first so the feed's newest headline ends up on top
+                for news_item in items.into_iter().rev() {
+                    self.publish_news(news_item);
+                }
+            }
+            Err(_) => {
+                if !self.offline_feeds.contains(&update.label) {
+                    self.offline_feeds.push(update.label);
+                }
+            }
+        }
+    }
+
+    pub fn place_order(
+        &mut self,
+        ticker: &str,
+        side: Side,
+        quantity: u32,
+        limit_price: Option<f64>,
+        bracket: Option<Bracket>,
+    ) -> u64 {
+        let mut order = Order::new(self.next_order_id, ticker, side, quantity, limit_price);
+        self.next_order_id += 1;
+        order.bracket = bracket;
+        order.account = self.active;
+        let id = order.id;
+        self.orders.insert(0, order);
+        id
+    }
+
+    // settled cash from the active account to another one, never more than it has
+    pub fn transfer(&mut self, to: usize, amount: f64) -> Result<(), String> {
+        let from = self.active;
+        if to == from || to >= self.accounts.len() {
+            return Err("pick another account to move the cash to".to_string());
+        }
+        let settled = self.accounts[from].portfolio.settled_cash();
+        if amount > settled {
+            return Err(format!(
+                "{0} only has {1:.2} in settled cash",
+                self.accounts[from].name,
+                settled.max(0.0)
+            ));
+        }
+        let (from_name, to_name) = (
+            self.accounts[from].name.clone(),
+            self.accounts[to].name.clone(),
+        );
+        self.accounts[from].portfolio.post(
+            CashKind::Transfer,
+            -amount,
+            &format!("To {0}", to_name),
+        );
+        self.accounts[to].portfolio.post(
+            CashKind::Transfer,
+            amount,
+            &format!("From {0}", from_name),
+        );
+        Ok(())
+    }
+
+    // on the first tick of a new day, for the days since the last time
+    fn accrue_interest(&mut self) {
+        let today = self.time_format.day(Utc::now());
+        let days = (today - self.accrued_on).num_days();
+        if days <= 0 {
+            return;
+        }
+        self.accrued_on = today;
+        for account in self.accounts.iter_mut() {
+            let portfolio = &mut account.portfolio;
+            let (kind, rate) = if portfolio.cash >= 0.0 {
+                (CashKind::Interest, self.cash_interest_pct)
+            } else {
+                (CashKind::Borrow, self.borrow_rate_pct)
+            };
+            let amount = portfolio.cash * rate / 100.0 / 365.0 * days as f64;
+            if amount != 0.0 {
+                let note = match days {
+                    1 => format!("1 day at {0}%", rate),
+                    _ => format!("{0} days at {1}%", days, rate),
+                };
+                portfolio.post(kind, amount, &note);
+            }
+        }
+    }
+
+    // sale proceeds settle some business days after the trade by the calendar of the [time]
+    // timezone
+    fn settle_trades(&mut self, entries: &[BlotterEntry]) {
+        let today = self.time_format.day(Utc::now());
+        if self.settlement_days > 0 {
+            let settles = settlement_date(today, self.settlement_days);
+            for entry in entries {
+                let BlotterEntry::Fill(fill) = entry else {
+                    continue;
+                };
+                if fill.side == Side::Sell {
+                    self.accounts[fill.account]
+                        .portfolio
+                        .unsettled
+                        .push(Unsettled {
+                            amount: fill.price * fill.quantity as f64 - fill.commission,
+                            settles,
+                            order_id: fill.order_id,
+                            time: fill.time,
+                        });
+                }
+            }
+        }
+        for account in self.accounts.iter_mut() {
+            account.portfolio.settle(today);
+        }
+    }
+
+    // recurring orders go in on the first tick their exchange is open on a day they're due
+    fn run_schedules(&mut self) {
+        let today = self.time_format.day(Utc::now());
+        let open = (0..self.exchanges.len())
+            .map(|exchange| self.exchange_open(exchange))
+            .collect::<Vec<bool>>();
+        let quotes = &self.quotes;
+        let due = self.schedules.due(today, |ticker| {
+            quotes
+                .iter()
+                .find(|quote| quote.listing.ticker == ticker)
+                .is_some_and(|quote| open[quote.exchange])
+        });
+        for schedule in due {
+            self.place_order(
+                &schedule.ticker,
+                schedule.side,
+                schedule.quantity,
+                None,
+                None,
+            );
+            // orders are newest first
+            self.orders[0].account = schedule.account;
+        }
+    }
+
+    // once an entry with a bracket is done filling, whatever was filled gets exit legs
+    fn open_bracket_legs(&mut self) {
+        let mut entries = vec![];
+        for order in self.orders.iter_mut().filter(|order| !order.is_open()) {
+            if let Some(bracket) = order.bracket.take() {
+                if order.filled > 0 {
+                    entries.push((
+                        order.id,
+                        order.ticker.clone(),
+                        order.side,
+                        order.filled,
+                        bracket,
+                        order.account,
+                    ));
+                }
+            }
+        }
+        for (parent, ticker, side, quantity, bracket, account) in entries {
+            let mut take_profit = bracket.take_profit.map(|price| {
+                let order = Order::new(
+                    self.next_order_id,
+                    &ticker,
+                    side.opposite(),
+                    quantity,
+                    Some(price),
+                );
+                self.next_order_id += 1;
+                order
+            });
+            let mut stop_loss = bracket.stop_loss.map(|price| {
+                let mut order =
+                    Order::new(self.next_order_id, &ticker, side.opposite(), quantity, None);
+                self.next_order_id += 1;
+                order.stop_price = Some(price);
+                order
+            });
+            if let (Some(take_profit), Some(stop_loss)) = (&mut take_profit, &mut stop_loss) {
+                take_profit.oco_with = Some(stop_loss.id);
+                stop_loss.oco_with = Some(take_profit.id);
+            }
+            for mut leg in [take_profit, stop_loss].into_iter().flatten() {
+                leg.parent = Some(parent);
+                leg.account = account;
+                self.orders.insert(0, leg);
+            }
+        }
+    }
+
+    pub fn amend_order(
+        &mut self,
+        order_id: u64,
+        quantity: u32,
+        limit_price: Option<f64>,
+    ) -> Result<(), String> {
+        let Some(order) = self.orders.iter_mut().find(|order| order.id == order_id) else {
+            return Err("order no longer exists".to_string());
+        };
+        if !order.is_open() {
+            return Err("order is no longer working".to_string());
+        }
+        if quantity <= order.filled {
+            return Err(format!(
+                "quantity must be above the {0} already filled",
+                order.filled
+            ));
+        }
+        self.blotter.push(BlotterEntry::Amendment(Amendment {
+            order_id,
+            old_quantity: order.quantity,
+            new_quantity: quantity,
+            old_limit_price: order.limit_price,
+            new_limit_price: limit_price,
+            time: Utc::now(),
+        }));
+        order.quantity = quantity;
+        order.limit_price = limit_price;
+        Ok(())
+    }
+
+    // cancelling one leg of a bracket cancels the other one as well
+    pub fn cancel_order(&mut self, order_id: u64) {
+        let Some(order) = self
+            .orders
+            .iter_mut()
+            .find(|order| order.id == order_id && order.is_open())
+        else {
+            return;
+        };
+        order.status = OrderStatus::Cancelled("by user".to_string());
+        let sibling = order.oco_with;
+        self.blotter.push(BlotterEntry::Cancellation {
+            order_id,
+            reason: "by user".to_string(),
+            time: Utc::now(),
+        });
+        if let Some(order) = self
+            .orders
+            .iter_mut()
+            .find(|order| Some(order.id) == sibling && order.is_open())
+        {
+            let reason = "other leg cancelled".to_string();
+            order.status = OrderStatus::Cancelled(reason.clone());
+            self.blotter.push(BlotterEntry::Cancellation {
+                order_id: order.id,
+                reason,
+                time: Utc::now(),
+            });
+        }
+        self.open_bracket_legs();
+    }
+
+    // the latest fill while it is still within the grace window, with the time left
+    pub fn undoable_fill(&self) -> Option<(&Fill, TimeDelta)> {
+        let fill = self.blotter.iter().rev().find_map(|entry| match entry {
+            BlotterEntry::Fill(fill) => Some(fill),
+            _ => None,
+        })?;
+        let left = fill.time + self.undo_grace - Utc::now();
+        // shares of a company taken over since can't be handed back
+        if left > TimeDelta::zero() && self.quote_index(&fill.ticker).is_some() {
+            Some((fill, left))
+        } else {
+            None
+        }
+    }
+
+    // takes back the latest fill, its order is cancelled so it won't simply fill again
+    pub fn undo_last_fill(&mut self) -> bool {
+        if self.undoable_fill().is_none() {
+            return false;
+        }
+        let Some(pos) = self
+            .blotter
+            .iter()
+            .rposition(|entry| matches!(entry, BlotterEntry::Fill(_)))
+        else {
+            return false;
+        };
+        let BlotterEntry::Fill(fill) = self.blotter.remove(pos) else {
+            return false;
+        };
+        if let Some(account) = self.accounts.get_mut(fill.account) {
+            account.portfolio.revert_fill(&fill);
+        }
+        if let Some(order) = self
+            .orders
+            .iter_mut()
+            .find(|order| order.id == fill.order_id)
+        {
+            order.filled -= fill.quantity;
+            order.fill_value -= fill.price * fill.quantity as f64;
+            order.status = OrderStatus::Cancelled("trade undone".to_string());
+        }
+        // bracket legs already opened for the entry cover fewer shares now
+        for leg in self
+            .orders
+            .iter_mut()
+            .filter(|order| order.parent == Some(fill.order_id) && order.is_open())
+        {
+            if leg.quantity.saturating_sub(fill.quantity) > leg.filled {
+                leg.quantity -= fill.quantity;
+            } else {
+                leg.status = OrderStatus::Cancelled("trade undone".to_string());
+            }
+        }
+        self.blotter.push(BlotterEntry::Cancellation {
+            order_id: fill.order_id,
+            reason: format!("after undoing {0} {1}", fill.quantity, fill.ticker),
+            time: Utc::now(),
+        });
+        self.open_bracket_legs();
+        true
+    }
+
+    pub fn option_price(&self, contract: &OptionContract) -> Option<f64> {
+        let idx = self.quote_index(&contract.ticker)?;
+        Some(contract.price(
+            self.quotes[idx].quote.price,
+            self.ticks,
+            self.difficulty.settings().volatility_scale,
+        ))
+    }
+
+    // strikes of held contracts stay listed even after the stock moved away from them
+    pub fn option_chain(&self, ticker: &str) -> Option<OptionChain> {
+        let idx = self.quote_index(ticker)?;
+        let mut chain = option_chain(self.quotes[idx].quote.price, self.ticks);
+        for position in self.portfolio().option_positions.iter() {
+            if position.contract.ticker == ticker
+                && !chain.strikes.contains(&position.contract.strike)
+            {
+                chain.strikes.push(position.contract.strike);
+            }
+        }
+        chain.strikes.sort_by(f64::total_cmp);
+        Some(chain)
+    }
+
+    // expired contracts pay out their intrinsic value
+    fn settle_expired_options(&mut self) {
+        let ticks = self.ticks;
+        let quotes = &self.quotes;
+        for account in self.accounts.iter_mut() {
+            let portfolio = &mut account.portfolio;
+            let (expired, open): (Vec<OptionPosition>, Vec<OptionPosition>) =
+                std::mem::take(&mut portfolio.option_positions)
+                    .into_iter()
+                    .partition(|position| position.contract.expiry_tick <= ticks);
+            portfolio.option_positions = open;
+            for position in expired {
+                if let Some(quote) = quotes
+                    .iter()
+                    .find(|quote| quote.listing.ticker == position.contract.ticker)
+                {
+                    portfolio.settle_option(
+                        &position.contract,
+                        position.contract.intrinsic_value(quote.quote.price)
+                            * position.quantity as f64,
+                    );
+                }
+            }
+        }
+    }
+
+    // moves the takeover storyline along, or starts a new one
+    fn advance_deal(&mut self, rng: &mut ThreadRng) {
+        let Some(mut deal) = self.deal.clone() else {
+            if let Some((deal, news_item)) = gen_deal(rng, &self.quotes, self.ticks) {
+                self.deal = Some(deal);
+                self.publish_news(news_item);
+            }
+            return;
+        };
+        let (Some(acquirer_idx), Some(target_idx)) = (
+            self.quote_index(&deal.acquirer),
+            self.quote_index(&deal.target),
+        ) else {
+            self.deal = None;
+            return;
+        };
+        match deal.stage {
+            DealStage::Rumored if self.ticks < deal.next_stage_tick => {
+                self.quotes[target_idx].apply_sentiment(RUMOR_SENTIMENT_PER_TICK);
+            }
+            DealStage::Rumored => {
+                let news_item = resolve_rumor(
+                    rng,
+                    &mut deal,
+                    &self.quotes[acquirer_idx],
+                    &self.quotes[target_idx],
+                    self.ticks,
+                    &self.currency,
+                );
+                self.deal = (deal.stage != DealStage::Rumored).then_some(deal);
+                self.publish_news(news_item);
+            }
+            DealStage::Announced(consideration) if self.ticks < deal.next_stage_tick => {
+                let offer = consideration.value(self.quotes[acquirer_idx].quote.price);
+                let target = &mut self.quotes[target_idx];
+                let arbitrage_price = offer * (1.0 - MERGER_ARBITRAGE_SPREAD_PCT / 100.0);
+                target.quote.price += (arbitrage_price - target.quote.price) * OFFER_PULL;
+                target.sentiment_drift = 0.0;
+            }
+            DealStage::Announced(consideration) => {
+                self.deal = None;
+                self.complete_deal(acquirer_idx, target_idx, consideration);
+            }
+        }
+    }
+
+    // pays out or converts the target's holders and delists it
+    fn complete_deal(
+        &mut self,
+        acquirer_idx: usize,
+        target_idx: usize,
+        consideration: Consideration,
+    ) {
+        let news_item = completion_news(&self.quotes[acquirer_idx], &self.quotes[target_idx]);
+        let acquirer_ticker = self.quotes[acquirer_idx].listing.ticker.clone();
+        let acquirer_price = self.quotes[acquirer_idx].quote.price;
+        let target_ticker = self.quotes[target_idx].listing.ticker.clone();
+        let offer = consideration.value(acquirer_price);
+
+        // stock deals swap the shares, anything left is paid out by delisting
+        for account in self.accounts.iter_mut() {
+            let portfolio = &mut account.portfolio;
+            if let (Consideration::Stock { ratio }, Some(pos)) = (
+                consideration,
+                portfolio
+                    .positions
+                    .iter()
+                    .position(|position| position.ticker == target_ticker),
+            ) {
+                let position = portfolio.positions.remove(pos);
+                let converted = position.quantity as f64 * ratio;
+                let shares = converted.floor() as u32;
+                // fractional shares are paid out in cash, the whole cost basis moves to the rest
+                let fraction_value = (converted - shares as f64) * acquirer_price;
+                if fraction_value > 0.0 {
+                    portfolio.post(
+                        CashKind::Payout,
+                        fraction_value,
+                        &format!("{0} fractional shares", target_ticker),
+                    );
+                    portfolio.gains.push(Gain {
+                        ticker: target_ticker.clone(),
+                        quantity: 0,
+                        bought: position.lots.first().map_or(Utc::now(), |lot| lot.bought),
+                        sold: Utc::now(),
+                        cost: 0.0,
+                        proceeds: fraction_value,
+                        order_id: None,
+                    });
+                }
+                portfolio.add_realized(&target_ticker, fraction_value);
+                if shares > 0 {
+                    // the old cost basis carries over to the new shares
+                    let cost = position.avg_cost * position.quantity as f64;
+                    let lots = convert_lots(&position.lots, ratio, shares);
+                    match portfolio
+                        .positions
+                        .iter_mut()
+                        .find(|position| position.ticker == acquirer_ticker)
+                    {
+                        Some(held) => {
+                            let total_cost = held.avg_cost * held.quantity as f64 + cost;
+                            held.quantity += shares;
+                            held.avg_cost = total_cost / held.quantity as f64;
+                            held.lots.extend(lots);
+                            held.lots.sort_by_key(|lot| lot.bought);
+                        }
+                        None => portfolio.positions.push(Position {
+                            ticker: acquirer_ticker.clone(),
+                            quantity: shares,
+                            avg_cost: cost / shares as f64,
+                            lots,
+                        }),
+                    }
+                }
+            }
+        }
+        self.delist(target_idx, offer);
+        self.publish_news(news_item);
+    }
+
+    // removes an instrument for good, holders get the payout per share
+    fn delist(&mut self, idx: usize, payout: f64) {
+        let ticker = self.quotes[idx].listing.ticker.clone();
+        for account in self.accounts.iter_mut() {
+            let portfolio = &mut account.portfolio;
+            if let Some(pos) = portfolio
+                .positions
+                .iter()
+                .position(|position| position.ticker == ticker)
+            {
+                let quantity = portfolio.positions[pos].quantity;
+                portfolio.dispose(&ticker, quantity, payout, Utc::now(), None);
+                let position = portfolio.positions.remove(pos);
+                portfolio.post(
+                    CashKind::Payout,
+                    payout * position.quantity as f64,
+                    &format!("{0} {1} delisted", position.quantity, ticker),
+                );
+                portfolio.add_realized(
+                    &ticker,
+                    (payout - position.avg_cost) * position.quantity as f64,
+                );
+            }
+            // options settle at the payout as if they had expired
+            let (settled, open): (Vec<OptionPosition>, Vec<OptionPosition>) =
+                std::mem::take(&mut portfolio.option_positions)
+                    .into_iter()
+                    .partition(|position| position.contract.ticker == ticker);
+            portfolio.option_positions = open;
+            for position in settled {
+                portfolio.settle_option(
+                    &position.contract,
+                    position.contract.intrinsic_value(payout) * position.quantity as f64,
+                );
+            }
+        }
+        for order in self
+            .orders
+            .iter_mut()
+            .filter(|order| order.ticker == ticker && order.is_open())
+        {
+            let reason = "delisted".to_string();
+            order.bracket = None;
+            order.status = OrderStatus::Cancelled(reason.clone());
+            self.blotter.push(BlotterEntry::Cancellation {
+                order_id: order.id,
+                reason,
+                time: Utc::now(),
+            });
+        }
+        self.quotes.remove(idx);
+    }
+
+    // scripted events happen whether the market is open or not
+    fn run_scenario(&mut self, rng: &mut ThreadRng) {
+        let now = self.time_format.time_of_day(Utc::now());
+        for action in self.scenario.due(self.ticks, now) {
+            match action {
+                ScriptedAction::Move {
+                    ticker,
+                    pct,
+                    headline,
+                } => {
+                    let Some(idx) = self.quote_index(&ticker) else {
+                        continue;
+                    };
+                    let precision = self.quotes[idx].precision;
+                    let quote = &mut self.quotes[idx].quote;
+                    quote.price = precision.round(quote.price * (1.0 + pct / 100.0));
+                    if let Some(headline) = headline {
+                        self.publish_news(NewsItem::new(
+                            &headline,
+                            "",
+                            NewsCategory::General,
+                            0.0,
+                            &[ticker.as_str()],
+                        ));
+                    }
+                }
+                ScriptedAction::News {
+                    title,
+                    subtitle,
+                    tickers,
+                    sentiment,
+                } => {
+                    let tickers = tickers.iter().map(String::as_str).collect::<Vec<&str>>();
+                    self.publish_news(NewsItem::new(
+                        &title,
+                        &subtitle,
+                        NewsCategory::General,
+                        sentiment,
+                        &tickers,
+                    ));
+                }
+                // a takeover already in progress keeps going
+                ScriptedAction::MergerRumor { target, acquirer } => {
+                    let Some(target_idx) = self.quote_index(&target) else {
+                        continue;
+                    };
+                    let acquirer_idx = match acquirer {
+                        Some(acquirer) => self.quote_index(&acquirer),
+                        None => {
+                            let bidders = (0..self.quotes.len())
+                                .filter(|idx| *idx != target_idx && self.quotes[*idx].is_company())
+                                .collect::<Vec<usize>>();
+                            (!bidders.is_empty())
+                                .then(|| bidders[rng.random_range(0..bidders.len())])
+                        }
+                    };
+                    let Some(acquirer_idx) = acquirer_idx.filter(|idx| *idx != target_idx) else {
+                        continue;
+                    };
+                    if self.deal.is_some() {
+                        continue;
+                    }
+                    let (deal, news_item) = start_rumor(
+                        rng,
+                        &self.quotes[acquirer_idx],
+                        &self.quotes[target_idx],
+                        self.ticks,
+                    );
+                    self.deal = Some(deal);
+                    self.publish_news(news_item);
+                }
+            }
+        }
+    }
+
+    // distress news escalates until the company is rescued, recovers or goes bust
+    fn follow_fortunes(&mut self, rng: &mut ThreadRng) {
+        for (ticker, event) in update_fortunes(rng, &mut self.fortunes, &self.quotes, self.ticks) {
+            let Some(idx) = self.quote_index(&ticker) else {
+                continue;
+            };
+            let news_item = event.news_item(&self.quotes[idx]);
+            if event == FortuneEvent::Bankrupt {
+                self.delist(idx, 0.0);
+            }
+            self.publish_news(news_item);
+        }
+    }
+
+    pub fn distress_level(&self, ticker: &str) -> Option<DistressLevel> {
+        self.fortunes
+            .iter()
+            .find(|fortune| fortune.ticker == ticker)
+            .and_then(|fortune| fortune.distress.as_ref())
+            .map(|distress| distress.level)
+    }
+
+    // what an instrument does on the tick, decided in order since the connection draws from
+    // the random numbers, in the pass for primary listings or the one for cross-listings
+    fn next_move(
+        &mut self,
+        rng: &mut ThreadRng,
+        idx: usize,
+        cross_listings: bool,
+        open_exchanges: &[bool],
+        replayed: &Option<Vec<(String, f64)>>,
+    ) -> Move {
+        let quote = &self.quotes[idx];
+        if quote.primary.is_some() != cross_listings {
+            return Move::Stay;
+        }
+        let primary = quote
+            .primary
+            .as_deref()
+            .and_then(|ticker| self.quote_index(ticker));
+        let target = match (primary, replayed) {
+            (Some(primary), _) => Some(self.quotes[primary].quote.price),
+            (None, Some(prices)) => {
+                match prices
+                    .iter()
+                    .find(|(ticker, _)| *ticker == quote.listing.ticker)
+                {
+                    Some((_, price)) => Some(*price),
+                    None => return Move::Stay,
+                }
+            }
+            // one whose primary listing is gone trades on its own
+            (None, None) => None,
+        };
+        if !(open_exchanges[quote.exchange] || quote.trades_continuously())
+            || !self.connection.delivers(rng)
+        {
+            return Move::Stay;
+        }
+        match target {
+            Some(price) => Move::Follow(price),
+            None => Move::Walk,
+        }
+    }
+
+    pub fn tick(&mut self, rng: &mut ThreadRng) {
+        self.before_tick = self
+            .quotes
+            .iter()
+            .map(|quote| (quote.listing.ticker.as_str(), quote.quote.price))
+            .collect();
+        let open_exchanges = self.open_exchanges();
+        let market_open = open_exchanges[0];
+        let difficulty = self.difficulty.settings();
+        for exchange in self.exchanges.iter_mut() {
+            exchange.tick(rng);
+        }
+        self.fx.tick(rng, &self.exchanges);
+        self.connection.tick(rng);
+        // a replay only moves the primary listings it has prices for, in the home currency
+        let replayed = self.connection.replay_step();
+        let opened = (0..self.exchanges.len())
+            .map(|exchange| self.session_start(exchange))
+            .collect::<Vec<DateTime<Utc>>>();
+        self.quotes
+            .par_iter_mut()
+            .with_min_len(PARALLEL_QUOTES)
+            .for_each(|quote| quote.extend_session(opened[quote.exchange]));
+        // primary listings first, cross-listings follow them
+        for pass in [false, true] {
+            let moves = (0..self.quotes.len())
+                .map(|idx| self.next_move(rng, idx, pass, &open_exchanges, &replayed))
+                .collect::<Vec<Move>>();
+            self.quotes
+                .par_iter_mut()
+                .zip(moves)
+                .with_min_len(PARALLEL_QUOTES)
+                .for_each_init(rand::rng, |rng, (quote, next)| match next {
+                    Move::Follow(price) => quote.follow(rng, price),
+                    Move::Walk => quote.tick(rng, &difficulty),
+                    Move::Stay => {}
+                });
+        }
+        self.ticks += 1;
+        self.settle_expired_options();
+        let entries = execute_orders(
+            rng,
+            &mut self.orders,
+            &self.quotes,
+            &open_exchanges,
+            &mut self.accounts,
+            BuyingPower {
+                trade_unsettled: self.trade_unsettled,
+                margin_pct: self.margin_pct,
+            },
+            &difficulty,
+        );
+        self.settle_trades(&entries);
+        self.blotter.extend(entries);
+        self.open_bracket_legs();
+        self.alerts.check(&self.quotes, &self.exchanges);
+        self.movers
+            .check(&self.quotes, &self.exchanges, &mut self.alerts);
+        self.run_schedules();
+        self.accrue_interest();
+        if let Some(news_item) = gen_news(rng, &self.quotes, difficulty.disaster_scale) {
+            self.publish_news(news_item);
+        }
+        if let Some(note) = gen_analyst_note(rng, &self.quotes, &self.analyst_notes) {
+            self.publish_analyst_note(note);
+        }
+        self.run_scenario(rng);
+        // insiders, short sellers and bidders need the exchange open
+        if market_open {
+            self.advance_deal(rng);
+            self.follow_fortunes(rng);
+            if let Some(filing) = gen_insider_filing(rng, &self.quotes) {
+                self.insider_filings.insert(0, filing);
+            }
+            if let Some(idx) = gen_squeeze(rng, &self.quotes) {
+                let news_item = squeeze(&mut self.quotes[idx]);
+                self.publish_news(news_item);
+            }
+        }
+        let value = self.portfolio_value();
+        self.equity_curve.push(value);
+        self.composite.record(&self.quotes, &self.before_tick);
+    }
+}
+
+// an instrument's price on a tick
+enum Move {
+    Stay, // closed, not delivered or not in the replay
+    Follow(f64),
+    Walk,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum MarketDataActivePanel {
+    MarketData,
+    Orders,
+    LatestNews,
+}
+
+impl MarketDataActivePanel {
+    // focus order when cycling with the arrow keys
+    const ALL: [MarketDataActivePanel; 3] = [
+        MarketDataActivePanel::MarketData,
+        MarketDataActivePanel::Orders,
+        MarketDataActivePanel::LatestNews,
+    ];
+
+    pub fn next(self) -> MarketDataActivePanel {
+        let pos = Self::ALL.iter().position(|panel| *panel == self).unwrap();
+        Self::ALL[(pos + 1).min(Self::ALL.len() - 1)]
+    }
+
+    pub fn prev(self) -> MarketDataActivePanel {
+        let pos = Self::ALL.iter().position(|panel| *panel == self).unwrap();
+        Self::ALL[pos.saturating_sub(1)]
+    }
+}
+
+// what the instrument detail view shows, the charts replace the details
+#[derive(PartialEq, Clone, Copy)]
+pub enum DetailView {
+    Details,
+    Chart,
+    Depth,
+    Returns,
+}
+
+impl DetailView {
+    // pressing the key of the view shown goes back to the details
+    pub fn toggled(self, view: DetailView) -> DetailView {
+        if self == view {
+            DetailView::Details
+        } else {
+            view
+        }
+    }
+}
+
+// zoomed in candles get wider, zoomed out several are merged into one
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ChartView {
+    pub zoom: i32,
+    pub pan: usize, // candles hidden to the right of the window, 0 shows the latest
+    pub cursor: Option<usize>, // crosshair, counted back from the latest candle too
+    pub timeframe: Timeframe,
+}
+
+impl ChartView {
+    const MIN_ZOOM: i32 = -3;
+    const MAX_ZOOM: i32 = 2;
+
+    pub fn zoom_in(&mut self) {
+        self.zoom = (self.zoom + 1).min(Self::MAX_ZOOM);
+        self.pan = 0;
+        self.cursor = self.cursor.map(|_| 0);
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.zoom = (self.zoom - 1).max(Self::MIN_ZOOM);
+        self.pan = 0;
+        self.cursor = self.cursor.map(|_| 0);
+    }
+
+    pub fn candles_per_bar(&self) -> usize {
+        1 << (-self.zoom).max(0)
+    }
+
+    // the history in candles of the timeframe, merged further when zoomed out
+    pub fn candles(&self, history: &PriceHistory) -> Vec<Candle> {
+        merge(&history.resampled(self.timeframe), self.candles_per_bar())
+    }
+
+    // back to the latest candles, they're different ones now
+    pub fn next_timeframe(&mut self) {
+        self.timeframe = self.timeframe.next();
+        self.pan = 0;
+        self.cursor = self.cursor.map(|_| 0);
+    }
+
+    pub fn candle_width(&self) -> u16 {
+        1 + 2 * self.zoom.max(0) as u16
+    }
+
+    // with the crosshair on the arrows move it, otherwise they pan, towards the past is back
+    // and panning stops with the oldest of the given number of candles on the right edge
+    pub fn step(&mut self, back: bool, len: usize) {
+        let step = |value: usize| {
+            if back {
+                (value + 1).min(len.saturating_sub(1))
+            } else {
+                value.saturating_sub(1)
+            }
+        };
+        match self.cursor {
+            Some(cursor) => self.cursor = Some(step(cursor)),
+            None => self.pan = step(self.pan),
+        }
+    }
+
+    // pan and crosshair clamped to the data, the window of the given size follows the crosshair
+    pub fn window(&self, len: usize, visible: usize) -> (usize, Option<usize>) {
+        let cursor = self.cursor.map(|cursor| cursor.min(len - 1));
+        let pan = self.pan.min(len - 1);
+        let pan = match cursor {
+            Some(cursor) => pan.clamp((cursor + 1).saturating_sub(visible), cursor),
+            None => pan,
+        };
+        (pan, cursor)
+    }
+}
+
+pub struct OrderForm {
+    pub amending: Option<u64>, // id of the order being modified
+    pub ticker: String,
+    pub side: Side,
+    pub form: Form, // quantity, limit, then the bracket on new orders
+    precision: Precision,
+}
+
+impl OrderForm {
+    pub fn new(app_state: &AppState, ticker: &str, side: Side) -> OrderForm {
+        // prices step by the instrument's tick, from the current price
+        let precision = app_state.precision(ticker);
+        let price = app_state
+            .quote_index(ticker)
+            .map_or(0.0, |idx| app_state.quotes[idx].quote.price);
+        let price_field = |label, placeholder| {
+            Field::number(label, true)
+                .optional(placeholder)
+                .spinner(precision.tick_size, price)
+        };
+        OrderForm {
+            amending: None,
+            ticker: ticker.to_string(),
+            side,
+            precision,
+            form: Form::new(vec![
+                Field::number("Quantity", false).spinner(1.0, 0.0),
+                price_field("Limit", "market"),
+                price_field("Take profit", "none"),
+                price_field("Stop loss", "none"),
+            ]),
+        }
+    }
+
+    // prefilled with the order's current terms, a bracket can only be attached to a new order
+    pub fn amend(app_state: &AppState, order: &Order) -> OrderForm {
+        let mut form = OrderForm {
+            amending: Some(order.id),
+            ..OrderForm::new(app_state, &order.ticker, order.side)
+        };
+        let fields = &mut form.form.fields;
+        fields.truncate(2);
+        fields[0].value = TextInput::new(&order.quantity.to_string());
+        if let Some(price) = order.limit_price {
+            fields[1].value = TextInput::new(&price.to_string());
+        }
+        form
+    }
+
+    // each field on its own first, then the bracket against the entry
+    pub fn parse(&mut self) -> Result<(u32, Option<f64>, Option<Bracket>), String> {
+        self.form.validate()?;
+        let quantity = self.form.fields[0]
+            .value
+            .text
+            .parse::<u32>()
+            .map_err(|_| trf("{0} must be a positive whole number", &[&tr("Quantity")]))?;
+        let limit_price = self.form.number(1);
+        let take_profit = self.form.number(2);
+        let stop_loss = self.form.number(3);
+        for idx in 1..self.form.fields.len() {
+            if let Some(price) = self.form.number(idx) {
+                if !self.precision.on_tick(price) {
+                    return Err(trf(
+                        "{0} must be a multiple of the tick size {1}",
+                        &[&tr(self.form.fields[idx].label), &self.precision.tick_size],
+                    ));
+                }
+            }
+        }
+
+        // exits have to be on the profitable and the losing side of the entry
+        let (above, below) = match self.side {
+            Side::Buy => (take_profit, stop_loss),
+            Side::Sell => (stop_loss, take_profit),
+        };
+        if let (Some(above), Some(below)) = (above, below.or(limit_price)) {
+            if above <= below {
+                return Err(match self.side {
+                    Side::Buy => "take-profit must be above the stop-loss and limit",
+                    Side::Sell => "stop-loss must be above the take-profit and limit",
+                }
+                .to_string());
+            }
+        }
+        if let (Some(below), Some(limit)) = (below, limit_price) {
+            if below >= limit {
+                return Err(match self.side {
+                    Side::Buy => "stop-loss must be below the limit price",
+                    Side::Sell => "take-profit must be below the limit price",
+                }
+                .to_string());
+            }
+        }
+        let bracket = if take_profit.is_some() || stop_loss.is_some() {
+            Some(Bracket {
+                take_profit,
+                stop_loss,
+            })
+        } else {
+            None
+        };
+        Ok((quantity, limit_price, bracket))
+    }
+}
+
+pub struct OptionsView {
+    pub ticker: String,
+    pub expiry_idx: usize,
+    pub strike_idx: usize,
+    pub kind: OptionKind,
+    pub error: Option<String>,
+}
+
+impl OptionsView {
+    pub fn new(ticker: &str) -> OptionsView {
+        OptionsView {
+            ticker: ticker.to_string(),
+            expiry_idx: 0,
+            strike_idx: 0,
+            kind: OptionKind::Call,
+            error: None,
+        }
+    }
+
+    // the contract under the cursor, strikes shift as the stock moves so indices are clamped
+    pub fn selected_contract(&self, app_state: &AppState) -> Option<OptionContract> {
+        let chain = app_state.option_chain(&self.ticker)?;
+        let expiry_tick = *chain
+            .expiries
+            .get(self.expiry_idx.min(chain.expiries.len() - 1))?;
+        let strike = *chain
+            .strikes
+            .get(self.strike_idx.min(chain.strikes.len() - 1))?;
+        Some(OptionContract {
+            ticker: self.ticker.clone(),
+            kind: self.kind,
+            strike,
+            expiry_tick,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RowAction {
+    Buy,
+    Sell,
+    Chart,
+    Compare,
+    Options,
+    News,
+    CopyTicker,
+    Alert,
+    Schedule,
+    Liquidate,
+}
+
+impl RowAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            RowAction::Buy => "Buy",
+            RowAction::Sell => "Sell",
+            RowAction::Chart => "Chart",
+            RowAction::Compare => "Mark to compare",
+            RowAction::Options => "Options",
+            RowAction::News => "News by company",
+            RowAction::CopyTicker => "Copy ticker",
+            RowAction::Alert => "Set alert",
+            RowAction::Schedule => "Schedule orders",
+            RowAction::Liquidate => "Sell whole position",
+        }
+    }
+}
+
+// everything the keys on a market row do, for whoever doesn't remember them
+pub struct RowMenu {
+    pub ticker: String,
+    pub actions: Vec<RowAction>,
+    pub cursor: usize,
+}
+
+impl RowMenu {
+    // the options chain doesn't open while watching
+    pub fn new(app_state: &AppState, idx: usize, watch: bool) -> RowMenu {
+        let quote = &app_state.quotes[idx];
+        let mut actions = vec![
+            RowAction::Buy,
+            RowAction::Sell,
+            RowAction::Chart,
+            RowAction::Compare,
+        ];
+        // options are only listed for company shares
+        if quote.class() == InstrumentClass::Equity && !watch {
+            actions.push(RowAction::Options);
+        }
+        actions.extend([
+            RowAction::News,
+            RowAction::CopyTicker,
+            RowAction::Alert,
+            RowAction::Schedule,
+        ]);
+        if app_state.portfolio().quantity(&quote.listing.ticker) > 0 {
+            actions.push(RowAction::Liquidate);
+        }
+        RowMenu {
+            ticker: quote.listing.ticker.clone(),
+            actions,
+            cursor: 0,
+        }
+    }
+}
+
+// what the yes/no dialog is asking about
+#[derive(Debug, Clone, PartialEq)]
+pub enum Confirm {
+    CancelOrder(u64),
+    Liquidate(String), // ticker, at market
+    ClearBlotter,
+    NewGame,
+}
+
+// cursor row 0 starts a new save, the slots follow newest first
+pub struct SavesMenu {
+    pub slots: Vec<SaveSlot>,
+    pub cursor: usize,
+    pub naming: Option<TextInput>, // name being typed for a new save
+    pub error: Option<String>,
+}
+
+// with the slots as they are on disk now
+impl Default for SavesMenu {
+    fn default() -> Self {
+        SavesMenu {
+            slots: list_slots(),
+            cursor: 0,
+            naming: None,
+            error: None,
+        }
+    }
+}
+
+impl SavesMenu {
+    pub fn selected_slot(&self) -> Option<&SaveSlot> {
+        self.cursor
+            .checked_sub(1)
+            .and_then(|idx| self.slots.get(idx))
+    }
+
+    // the slot list after a save, with the cursor on what was just written
+    pub fn refresh(&mut self, saved_name: &str) {
+        self.slots = list_slots();
+        self.cursor = self
+            .slots
+            .iter()
+            .position(|slot| slot.name == saved_name.trim())
+            .map_or(0, |idx| idx + 1);
+        self.error = None;
+    }
+}
+
+// the cash ledger newest first, narrowed down to one kind of movement
+#[derive(Default)]
+pub struct LedgerView {
+    pub scroll: ScrollView,
+    pub filter: Option<CashKind>,
+    pub message: Option<Result<String, String>>, // outcome of the last export
+}
+
+impl LedgerView {
+    pub fn entries<'a>(&self, portfolio: &'a Portfolio) -> Vec<&'a CashEntry> {
+        portfolio
+            .ledger
+            .iter()
+            .rev()
+            .filter(|entry| self.filter.is_none_or(|kind| entry.kind == kind))
+            .collect()
+    }
+
+    // all of them, then each kind in turn
+    pub fn next_filter(&mut self) {
+        let pos = self
+            .filter
+            .and_then(|kind| CashKind::ALL.iter().position(|other| *other == kind));
+        self.filter = match pos {
+            None => Some(CashKind::ALL[0]),
+            Some(pos) => CashKind::ALL.get(pos + 1).copied(),
+        };
+        self.scroll.pos = 0;
+    }
+}
+
+// the accounts with what they're worth, the cursor picks the one orders go to or cash is
+// moved to
+pub struct AccountsView {
+    pub cursor: usize,
+    pub transfer: Option<Form>, // of cash from the active account to the one under the cursor
+}
+
+pub struct ReportView {
+    pub report: SessionReport,
+    pub quitting: bool, // shown on the way out rather than on request
+    pub message: Option<Result<String, String>>, // outcome of the last export
+}
+
+impl ReportView {
+    pub fn new(app_state: &AppState, quitting: bool) -> ReportView {
+        ReportView {
+            report: SessionReport::new(app_state),
+            quitting,
+            message: None,
+        }
+    }
+}
+
+pub struct UIState {
+    pub market_data_active_panel: MarketDataActivePanel,
+    pub market_table: MarketTable,
+    pub news_list: NewsList,
+    pub orders_table: OrdersTable,
+    pub portfolio_panel: PortfolioPanel,
+    pub news_filter_menu: Option<usize>, // cursor position while the menu is open
+    pub news_archive_page: Option<usize>, // page shown while the archive is open
+    pub order_form: Option<OrderForm>,
+    pub alert_form: Option<AlertForm>,
+    pub schedule_form: Option<ScheduleForm>,
+    pub settings: Option<Form>, // the theme being changed, applied on Enter
+    pub confirm: Option<Confirm>, // keys go nowhere else while it's open
+    pub macros: Macros,
+    pub keymap: Keymap,
+    pub help: Option<ScrollView>, // the list of every key while it's open
+    pub history: History,         // of changes to the view, for Ctrl-Z and Ctrl-Y
+    pub options_view: Option<OptionsView>,
+    pub instrument_detail: Option<String>, // ticker shown in the detail view
+    pub instrument_detail_view: DetailView,
+    pub chart: ChartView,
+    pub compare_tickers: Vec<String>, // marked for the comparison chart, in marking order
+    pub compare_open: bool,
+    pub tutorial: Option<Tutorial>,
+    pub achievements_open: bool,
+    pub alerts_open: Option<usize>, // cursor on the alerts waiting to go off
+    pub movers_open: Option<usize>, // cursor on the big movers
+    pub rebalance: Option<RebalanceView>,
+    pub schedules_open: Option<usize>, // cursor on the schedules
+    pub ledger: Option<LedgerView>,
+    pub accounts: Option<AccountsView>,
+    pub fx: Option<FxView>,
+    pub connection_open: bool,
+    pub panels: Vec<Box<dyn Panel>>, // the extra ones compiled in
+    pub panel_open: Option<usize>,
+    pub accents: HashMap<String, Accent>, // [display.tickers] by ticker
+    pub theme: Theme,
+    pub accessible: bool, // the plain linear layout for screen readers
+    pub row_menu: Option<RowMenu>,
+    pub saves_menu: Option<SavesMenu>,
+    pub report_view: Option<ReportView>,
+    pub chart_marker: Marker, // for line charts, braille or half blocks
+    pub graphics: bool,       // whether the candle chart can be drawn as an image
+    pub enhanced_keys: bool,  // whether the terminal speaks the kitty keyboard protocol
+    pub worker: Worker,
+    pub distribution: Option<(String, Distribution)>, // the worker's last one, of the ticker
+    pub correlation: Option<(Vec<String>, Matrix)>,   // the same for the compared tickers
+    pub chart_image: RefCell<Option<ChartImage>>, // left by the last frame for main to put on screen
+    pub hyperlinks: RefCell<Vec<Hyperlink>>,      // the same for headlines with a link
+}
+
+impl UIState {
+    // nothing open yet, laid out and drawn the way the config has it, with the default keys
+    pub fn new(config: &Config) -> UIState {
+        UIState {
+            market_data_active_panel: MarketDataActivePanel::MarketData,
+            market_table: MarketTable::new(false, &config.display, vec![]),
+            news_list: NewsList::default(),
+            orders_table: OrdersTable::default(),
+            portfolio_panel: PortfolioPanel::default(),
+            news_filter_menu: None,
+            news_archive_page: None,
+            order_form: None,
+            alert_form: None,
+            schedule_form: None,
+            settings: None,
+            confirm: None,
+            macros: Macros::default(),
+            keymap: Keymap::default(),
+            help: None,
+            history: History::default(),
+            options_view: None,
+            instrument_detail: None,
+            instrument_detail_view: DetailView::Details,
+            chart: ChartView::default(),
+            compare_tickers: vec![],
+            compare_open: false,
+            tutorial: None,
+            achievements_open: false,
+            alerts_open: None,
+            movers_open: None,
+            rebalance: None,
+            schedules_open: None,
+            ledger: None,
+            accounts: None,
+            fx: None,
+            connection_open: false,
+            panels: registry(),
+            panel_open: None,
+            accents: HashMap::new(),
+            theme: Theme::new(&config.theme),
+            accessible: config.display.accessible,
+            row_menu: None,
+            saves_menu: None,
+            report_view: None,
+            chart_marker: chart_marker(config.display.charts),
+            graphics: false,
+            enhanced_keys: false,
+            worker: Worker::default(),
+            distribution: None,
+            correlation: None,
+            chart_image: RefCell::new(None),
+            hyperlinks: RefCell::new(vec![]),
+        }
+    }
+
+    // what the worker has to work out for the views open now
+    pub fn jobs(&self) -> Vec<Job> {
+        let mut jobs = vec![];
+        if let Some(query) = self.market_table.screen_query() {
+            jobs.push(Job::Screen(query.to_string()));
+        }
+        if let Some(ticker) = &self.instrument_detail {
+            if self.instrument_detail_view == DetailView::Returns {
+                jobs.push(Job::Returns(ticker.clone()));
+            }
+        }
+        if self.compare_open && self.compare_tickers.len() >= 2 {
+            jobs.push(Job::Correlation(self.compare_tickers.clone()));
+        }
+        jobs
+    }
+
+    // a result back from the worker, kept by the view it's for
+    pub fn finish(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Screen { query, matched } => {
+                self.market_table.matched = Some((query, matched))
+            }
+            Outcome::Returns {
+                ticker,
+                distribution,
+            } => self.distribution = Some((ticker, distribution)),
+            Outcome::Correlation { tickers, matrix } => self.correlation = Some((tickers, matrix)),
+        }
+    }
+
+    // any change to news filters invalidates positions in the filtered lists
+    pub fn reset_news_positions(&mut self) {
+        self.news_list.scroll.pos = 0;
+        if self.news_archive_page.is_some() {
+            self.news_archive_page = Some(0);
+        }
+    }
+
+    pub fn news_archive_page_count(&self, app_state: &AppState) -> usize {
+        self.news_list
+            .indices(app_state)
+            .len()
+            .div_ceil(NEWS_ARCHIVE_PAGE_SIZE)
+            .max(1)
+    }
+
+    // the instrument being looked at may have been taken over since
+    pub fn drop_delisted(&mut self, app_state: &AppState) {
+        let listed = |ticker: &String| app_state.quote_index(ticker).is_some();
+        if !self.instrument_detail.as_ref().is_none_or(listed) {
+            self.instrument_detail = None;
+        }
+        if !self
+            .options_view
+            .as_ref()
+            .is_none_or(|view| listed(&view.ticker))
+        {
+            self.options_view = None;
+        }
+        if !self
+            .order_form
+            .as_ref()
+            .is_none_or(|form| listed(&form.ticker))
+        {
+            self.order_form = None;
+        }
+        if !self
+            .row_menu
+            .as_ref()
+            .is_none_or(|menu| listed(&menu.ticker))
+        {
+            self.row_menu = None;
+        }
+        self.compare_tickers.retain(listed);
+        if self.compare_tickers.is_empty() {
+            self.compare_open = false;
+        }
+    }
+
+    // content can shrink under the panels between keys, a tick can delist the last row
+    pub fn clamp_scroll(&mut self, app_state: &AppState) {
+        let len = self.market_table.indices(app_state).len();
+        self.market_table.scroll.clamp(len);
+        let len = self.news_list.visible(app_state).len();
+        self.news_list.scroll.clamp(len);
+        let len = self.orders_table.len(app_state);
+        self.orders_table.scroll.clamp(len);
+        // the movers come and go by themselves
+        if let Some(cursor) = self.movers_open.as_mut() {
+            *cursor = (*cursor).min(app_state.movers.movers.len().saturating_sub(1));
+        }
+    }
+
+    // on whichever tab it's on, with the market panel focused
+    pub fn select_quote(&mut self, app_state: &AppState, idx: usize) {
+        self.market_table.select(app_state, idx);
+        self.market_data_active_panel = MarketDataActivePanel::MarketData;
+    }
+
+    pub fn selected_quote_index(&self, app_state: &AppState) -> Option<usize> {
+        self.market_table.selected(app_state)
+    }
+
+    // the panel keys go to first
+    pub fn focused(&mut self) -> &mut dyn Component {
+        match self.market_data_active_panel {
+            MarketDataActivePanel::MarketData => &mut self.market_table,
+            MarketDataActivePanel::Orders => &mut self.orders_table,
+            MarketDataActivePanel::LatestNews => &mut self.news_list,
+        }
+    }
+
+    // orders can only be picked from the orders table, not the blotter
+    pub fn selected_order<'a>(&self, app_state: &'a AppState) -> Option<&'a Order> {
+        if self.orders_table.show_blotter {
+            return None;
+        }
+        app_state.orders.get(self.orders_table.scroll.pos)
+    }
+}
+
+pub const MAX_COMPARE_TICKERS: usize = 4;
+
+// news counts as seen once it has been the selected item in the focused news panel,
+// archive pages count as read when opened
+pub fn mark_news_seen(app_state: &mut AppState, uistate: &UIState) {
+    if let Some(page) = uistate.news_archive_page {
+        for idx in uistate
+            .news_list
+            .indices(app_state)
+            .into_iter()
+            .skip(page * NEWS_ARCHIVE_PAGE_SIZE)
+            .take(NEWS_ARCHIVE_PAGE_SIZE)
+        {
+            app_state.news[idx].seen = true;
+        }
+        return;
+    }
+    if uistate.market_data_active_panel != MarketDataActivePanel::LatestNews {
+        return;
+    }
+    // only the selected one, news arriving on top while reading further down hasn't been
+    // scrolled past
+    if let Some(idx) = uistate
+        .news_list
+        .indices(app_state)
+        .get(uistate.news_list.scroll.pos)
+    {
+        app_state.news[*idx].seen = true;
+    }
+}
+
+// news is kept newest first, so the oldest unread item is the last one
+pub fn jump_to_oldest_unread_news(app_state: &AppState, uistate: &mut UIState) {
+    if app_state.unread_news_count() == 0 {
+        return;
+    }
+    let find_oldest_unread = |uistate: &UIState| {
+        uistate
+            .news_list
+            .visible(app_state)
+            .iter()
+            .rposition(|news_item| !news_item.seen)
+    };
+    let pos = find_oldest_unread(uistate).or_else(|| {
+        // unread item is hidden by filters, drop them so it can be shown
+        uistate.history.record(Change::news_filter(uistate));
+        uistate.news_list.ticker_filter = None;
+        uistate.news_list.hidden_categories.clear();
+        uistate.news_list.search = None;
+        find_oldest_unread(uistate)
+    });
+    if let Some(pos) = pos {
+        uistate.news_list.scroll.pos = pos;
+        uistate.market_data_active_panel = MarketDataActivePanel::LatestNews;
+    }
+}
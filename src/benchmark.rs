@@ -0,0 +1,99 @@
+use crate::market::Instrument;
+use std::collections::HashMap;
+
+// where the composite index starts
+const BASE_LEVEL: f64 = 100.0;
+
+// a market cap weighted index of all companies, chained from tick to tick over the companies
+// listed on both so takeovers and delistings don't move it
+#[derive(Debug, Clone)]
+pub struct Composite {
+    pub curve: Vec<f64>, // the level at the start and after every tick since, like equity_curve
+}
+
+impl Default for Composite {
+    fn default() -> Composite {
+        Composite {
+            curve: vec![BASE_LEVEL],
+        }
+    }
+}
+
+impl Composite {
+    // from the base level again, when a game is loaded
+    pub fn restart(&mut self) {
+        self.curve = vec![BASE_LEVEL];
+    }
+
+    // after a tick, from the prices it started out at by ticker
+    pub fn record(&mut self, quotes: &[Instrument], before_tick: &HashMap<&str, f64>) {
+        let (mut now, mut before) = (0.0, 0.0);
+        for quote in quotes {
+            if let (Some(shares), Some(previous)) =
+                (quote.shares, before_tick.get(quote.listing.ticker.as_str()))
+            {
+                now += shares as f64 * quote.quote.price;
+                before += shares as f64 * previous;
+            }
+        }
+        let level = self.curve.last().copied().unwrap_or(BASE_LEVEL);
+        self.curve.push(if before > 0.0 {
+            level * now / before
+        } else {
+            level
+        });
+    }
+}
+
+// the index put on the same start as the portfolio
+pub fn rebased(composite: &[f64], start_value: f64) -> Vec<f64> {
+    let base = composite.first().copied().unwrap_or(BASE_LEVEL);
+    composite
+        .iter()
+        .map(|level| level / base * start_value)
+        .collect()
+}
+
+// how the portfolio did against holding the index over the same ticks, in percent
+#[derive(Debug)]
+pub struct Relative {
+    pub index_return: f64,
+    pub excess: f64,        // the portfolio's return less the index's
+    pub beta: Option<f64>,  // from the returns of every tick, none without any movement
+    pub alpha: Option<f64>, // what the return beat the index by at that beta
+}
+
+pub fn relative(equity_curve: &[f64], composite: &[f64]) -> Relative {
+    let total = |curve: &[f64]| match (curve.first(), curve.last()) {
+        (Some(first), Some(last)) if *first != 0.0 => (last / first - 1.0) * 100.0,
+        _ => 0.0,
+    };
+    let len = equity_curve.len().min(composite.len());
+    let (equity_curve, composite) = (&equity_curve[..len], &composite[..len]);
+    let returns = |curve: &[f64]| {
+        curve
+            .windows(2)
+            .map(|pair| pair[1] / pair[0] - 1.0)
+            .collect::<Vec<f64>>()
+    };
+    let (portfolio, index) = (returns(equity_curve), returns(composite));
+    let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len().max(1) as f64;
+    let (portfolio_mean, index_mean) = (mean(&portfolio), mean(&index));
+    let covariance = portfolio
+        .iter()
+        .zip(index.iter())
+        .map(|(portfolio, index)| (portfolio - portfolio_mean) * (index - index_mean))
+        .sum::<f64>();
+    let variance = index
+        .iter()
+        .map(|index| (index - index_mean).powi(2))
+        .sum::<f64>();
+    let (portfolio_return, index_return) = (total(equity_curve), total(composite));
+    let beta = (variance > 0.0).then(|| covariance / variance);
+    Relative {
+        index_return,
+        excess: portfolio_return - index_return,
+        beta,
+        alpha: beta.map(|beta| portfolio_return - beta * index_return),
+    }
+}
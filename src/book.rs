@@ -0,0 +1,75 @@
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use std::ops::RangeInclusive;
+
+// resting orders on each side of the quote, levels further out are deeper
+const BOOK_LEVELS: usize = 12;
+// half the spread and the distance between levels, in percent of the price
+const HALF_SPREAD_PCT: f64 = 0.05;
+const LEVEL_STEP_PCT: f64 = 0.05;
+const LEVEL_SIZE_MIN: u32 = 10;
+const LEVEL_SIZE_MAX: u32 = 150;
+const DEPTH_GROWTH_PER_LEVEL: f64 = 0.2;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BookLevel {
+    pub price: f64,
+    pub size: u32,
+}
+
+// a simulated limit order book around the quote, redrawn every tick
+#[derive(Debug, Clone, Default)]
+pub struct OrderBook {
+    pub bids: Vec<BookLevel>, // best first
+    pub asks: Vec<BookLevel>, // best first
+}
+
+impl OrderBook {
+    pub fn mid(&self) -> Option<f64> {
+        Some((self.bids.first()?.price + self.asks.first()?.price) / 2.0)
+    }
+
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.asks.first()?.price - self.bids.first()?.price)
+    }
+
+    // shares bid at the price or better, what selling down to it would hit
+    pub fn bid_depth(&self, price: f64) -> u32 {
+        self.bids
+            .iter()
+            .filter(|level| level.price >= price)
+            .map(|level| level.size)
+            .sum()
+    }
+
+    pub fn ask_depth(&self, price: f64) -> u32 {
+        self.asks
+            .iter()
+            .filter(|level| level.price <= price)
+            .map(|level| level.size)
+            .sum()
+    }
+
+    // prices covered by the book, lowest bid to highest ask
+    pub fn price_range(&self) -> Option<(f64, f64)> {
+        Some((self.bids.last()?.price, self.asks.last()?.price))
+    }
+}
+
+// levels are on the instrument's ticks, at least one tick apart
+pub fn gen_book(rng: &mut ThreadRng, price: f64, tick_size: f64) -> OrderBook {
+    let mut level = |side: f64, pos: usize| BookLevel {
+        price: price
+            + side
+                * tick_size
+                * (price * (HALF_SPREAD_PCT + LEVEL_STEP_PCT * pos as f64) / 100.0 / tick_size)
+                    .round()
+                    .max(pos as f64 + 1.0),
+        size: (rng.random_range(RangeInclusive::new(LEVEL_SIZE_MIN, LEVEL_SIZE_MAX)) as f64
+            * (1.0 + DEPTH_GROWTH_PER_LEVEL * pos as f64)) as u32,
+    };
+    OrderBook {
+        bids: (0..BOOK_LEVELS).map(|pos| level(-1.0, pos)).collect(),
+        asks: (0..BOOK_LEVELS).map(|pos| level(1.0, pos)).collect(),
+    }
+}
@@ -0,0 +1,229 @@
+use crate::app::AppState;
+use crate::locale::{tr, trf};
+use crate::market::ChangeHorizon;
+use crate::numbers::large;
+use crate::panels::Panel;
+use crossterm::event::KeyEvent;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Gauge, Paragraph};
+use ratatui::Frame;
+use std::cell::RefCell;
+
+// how the whole market is doing, every instrument and not just the rows shown
+#[derive(Debug, Default, Clone)]
+struct Breadth {
+    advancing: usize, // since yesterday's close, like most of the table
+    declining: usize,
+    unchanged: usize,
+    up_volume: u64, // traded in the advancing ones
+    down_volume: u64,
+    new_highs: usize, // above everything since their exchange's session opened, this tick
+    new_lows: usize,
+}
+
+impl Breadth {
+    fn of(app_state: &AppState) -> Breadth {
+        let mut breadth = Breadth::default();
+        for quote in app_state.quotes.iter() {
+            let (_, change) = app_state.change(quote, ChangeHorizon::Yesterday);
+            if change > 0.0 {
+                breadth.advancing += 1;
+                breadth.up_volume += quote.volume;
+            } else if change < 0.0 {
+                breadth.declining += 1;
+                breadth.down_volume += quote.volume;
+            } else {
+                breadth.unchanged += 1;
+            }
+            // kept up on every tick, so this doesn't go through the history
+            if let Some((high, low)) = quote.session.and_then(|range| range.high_low) {
+                let price = quote.quote.price;
+                breadth.new_highs += (price > high) as usize;
+                breadth.new_lows += (price < low) as usize;
+            }
+        }
+        breadth
+    }
+}
+
+// advancers against decliners, their volumes and the new session highs and lows
+#[derive(Default)]
+pub struct BreadthPanel {
+    // worked out once a tick however often it's drawn, by the tick it was
+    cache: RefCell<Option<(u64, Breadth)>>,
+}
+
+impl BreadthPanel {
+    fn breadth(&self, app_state: &AppState) -> Breadth {
+        let mut cache = self.cache.borrow_mut();
+        match cache.as_ref() {
+            Some((ticks, breadth)) if *ticks == app_state.ticks => breadth.clone(),
+            _ => {
+                let breadth = Breadth::of(app_state);
+                *cache = Some((app_state.ticks, breadth.clone()));
+                breadth
+            }
+        }
+    }
+}
+
+impl Panel for BreadthPanel {
+    fn title(&self) -> String {
+        tr("Market breadth").to_string()
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, app_state: &AppState) {
+        use Constraint::{Fill, Length};
+
+        let breadth = self.breadth(app_state);
+        let [counts_area, gauge_area, details_area] =
+            Layout::vertical([Length(1), Length(1), Fill(1)]).areas(area);
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                format!("▲{0} {1}", breadth.advancing, tr("advancing")).fg(Color::Green),
+                "  ".into(),
+                format!("▼{0} {1}", breadth.declining, tr("declining")).fg(Color::Red),
+                "  ".into(),
+                trf("{0} unchanged", &[&breadth.unchanged]).fg(Color::Gray),
+            ])),
+            counts_area,
+        );
+        // the advancing share of those that moved at all
+        let moved = breadth.advancing + breadth.declining;
+        let ratio = if moved > 0 {
+            breadth.advancing as f64 / moved as f64
+        } else {
+            0.5
+        };
+        frame.render_widget(
+            Gauge::default()
+                .ratio(ratio)
+                .label(format!("{0:.0}%", ratio * 100.0))
+                .gauge_style(Style::default().fg(Color::Green).bg(Color::Red))
+                .use_unicode(true),
+            gauge_area,
+        );
+        let volume_ratio = if breadth.down_volume > 0 {
+            format!(
+                "{0:.2}",
+                breadth.up_volume as f64 / breadth.down_volume as f64
+            )
+        } else {
+            "–".to_string()
+        };
+        let lines = vec![
+            Line::from(""),
+            Line::from(vec![
+                format!("{0:<18}", tr("Up volume")).fg(Color::Gray),
+                format!("{0:>10}", large(breadth.up_volume)).fg(Color::Green),
+                format!("  {0:<18}", tr("Down volume")).fg(Color::Gray),
+                format!("{0:>10}", large(breadth.down_volume)).fg(Color::Red),
+            ]),
+            Line::from(vec![
+                format!("{0:<18}", tr("Up/down ratio")).fg(Color::Gray),
+                format!("{0:>10}", volume_ratio).fg(Color::White),
+            ]),
+            Line::from(vec![
+                format!("{0:<18}", tr("New session highs")).fg(Color::Gray),
+                format!("{0:>10}", breadth.new_highs).fg(Color::Green),
+                format!("  {0:<18}", tr("New session lows")).fg(Color::Gray),
+                format!("{0:>10}", breadth.new_lows).fg(Color::Red),
+            ]),
+            Line::from(""),
+            Line::from(trf(
+                "All {0} instruments, since yesterday's close",
+                &[&app_state.quotes.len()],
+            ))
+            .fg(Color::DarkGray),
+        ];
+        frame.render_widget(Paragraph::new(lines), details_area);
+    }
+
+    fn handle_action(&mut self, _key: KeyEvent, _app_state: &AppState) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::widen;
+    use crate::market::{gen_universe, SessionRange};
+    use crate::testing::app_state;
+
+    // an instrument each going up, down and nowhere since yesterday's close of 100
+    fn check(ranges: [Option<(f64, f64)>; 3]) -> Breadth {
+        let companies = gen_universe(3);
+        let mut app_state = app_state(&companies, 0);
+        let opened = app_state.session_start(0);
+        for ((quote, (price, volume)), high_low) in app_state
+            .quotes
+            .iter_mut()
+            .zip([(110.0, 300), (90.0, 200), (100.0, 100)])
+            .zip(ranges)
+        {
+            quote.quote.price = price;
+            quote.quote.price_yesterday = 100.0;
+            quote.volume = volume;
+            quote.session = Some(SessionRange { opened, high_low });
+        }
+        Breadth::of(&app_state)
+    }
+
+    #[test]
+    fn the_session_range_is_kept_up_to_the_tick_before() {
+        let mut rng = rand::rng();
+        let companies = gen_universe(3);
+        let mut app_state = app_state(&companies, 0);
+        // the prices it started out at count too, before any tick was recorded
+        let started = app_state
+            .quotes
+            .iter()
+            .map(|quote| quote.quote.price)
+            .collect::<Vec<f64>>();
+        for _ in 0..20 {
+            app_state.tick(&mut rng);
+        }
+        let opened = app_state.session_start(0);
+        for (quote, started) in app_state.quotes.iter().zip(started) {
+            let ticks = &quote.history.ticks;
+            let expected = ticks
+                .range(..ticks.len() - 1)
+                .filter(|tick| tick.at >= opened)
+                .fold((started, started), |range, tick| {
+                    widen(Some(range), tick.price)
+                });
+            assert_eq!(
+                quote.session.and_then(|range| range.high_low),
+                Some(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn counts_by_the_change_since_yesterday() {
+        let breadth = check([None; 3]);
+        assert_eq!(
+            (breadth.advancing, breadth.declining, breadth.unchanged),
+            (1, 1, 1)
+        );
+        // the unchanged one's volume goes to neither side
+        assert_eq!((breadth.up_volume, breadth.down_volume), (300, 200));
+    }
+
+    #[test]
+    fn new_highs_and_lows_are_past_the_session_range() {
+        let breadth = check([
+            Some((105.0, 95.0)),
+            Some((95.0, 92.0)),
+            Some((100.0, 100.0)),
+        ]);
+        assert_eq!((breadth.new_highs, breadth.new_lows), (1, 1));
+    }
+
+    #[test]
+    fn equalling_the_range_is_not_new() {
+        let breadth = check([Some((110.0, 100.0)), Some((100.0, 90.0)), None]);
+        assert_eq!((breadth.new_highs, breadth.new_lows), (0, 0));
+    }
+}
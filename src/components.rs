@@ -0,0 +1,411 @@
+use crate::app::{AppState, MarketDataActivePanel, UIState, LATEST_NEWS_LIMIT};
+use crate::config::{ColumnConfig, DisplayConfig};
+use crate::formatting::{Accent, FormatRule};
+use crate::forms::TextInput;
+use crate::market::{ChangeHorizon, InstrumentClass};
+use crate::news::{NewsCategory, NewsItem};
+use crate::screener::Screen;
+use crate::terminal::Hyperlink;
+use crate::theme::Theme;
+use crate::ui::{
+    draw_market_table, draw_news_list, draw_orders, draw_portfolio, draw_status_bar, MARKET_COLUMNS,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::{Margin, Rect};
+use ratatui::style::Style;
+use ratatui::widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState};
+use ratatui::Frame;
+use std::cell::RefCell;
+use std::cmp::min;
+use std::collections::{HashMap, HashSet};
+
+// what components are drawn from besides their own state, the same for all of them
+pub struct Context<'a> {
+    pub app_state: &'a AppState<'a>,
+    pub theme: &'a Theme,
+    pub accents: &'a HashMap<String, Accent>,
+    pub compare_tickers: &'a [String], // in their chart colors
+    pub focus: MarketDataActivePanel,
+    pub news_archive: bool, // the news search is typed in the archive while it's open
+    pub hyperlinks: &'a RefCell<Vec<Hyperlink>>, // the news list leaves its links here
+}
+
+impl<'a> Context<'a> {
+    pub fn new(app_state: &'a AppState<'a>, uistate: &'a UIState) -> Context<'a> {
+        Context {
+            app_state,
+            theme: &uistate.theme,
+            accents: &uistate.accents,
+            compare_tickers: &uistate.compare_tickers,
+            focus: uistate.market_data_active_panel,
+            news_archive: uistate.news_archive_page.is_some(),
+            hyperlinks: &uistate.hyperlinks,
+        }
+    }
+}
+
+// a part of the screen that keeps its own state, draws itself and takes the keys meant for it
+pub trait Component {
+    // only while it has the focus, true when the key was used
+    fn on_event(&mut self, key: KeyEvent, app_state: &AppState) -> bool;
+
+    fn render(&self, frame: &mut Frame, area: Rect, ctx: &Context);
+}
+
+// a position in a list with its scrollbar, the top visible row doubles as the selection
+#[derive(Debug, Default)]
+pub struct ScrollView {
+    pub pos: usize,
+}
+
+impl ScrollView {
+    // Up and Down, kept within the content
+    pub fn on_event(&mut self, key: KeyEvent, len: usize) -> bool {
+        self.clamp(len);
+        match key.code {
+            KeyCode::Down => self.pos = min(len.saturating_sub(1), self.pos + 1),
+            KeyCode::Up => self.pos = self.pos.saturating_sub(1),
+            _ => return false,
+        }
+        true
+    }
+
+    // for when the content has shrunk under the position
+    pub fn clamp(&mut self, len: usize) {
+        self.pos = min(self.pos, len.saturating_sub(1));
+    }
+
+    // on the right border of the panel, between the corners, with the thumb as long
+    // as the share of the content that fits in the viewport
+    pub fn render(&self, frame: &mut Frame, area: Rect, len: usize, viewport: usize, style: Style) {
+        let mut state = ScrollbarState::default()
+            .content_length(len)
+            .position(self.pos)
+            .viewport_content_length(viewport);
+        // the scrollbar panics on a panel too small to have a side between the corners
+        let area = area.inner(Margin::new(0, 1));
+        if area.is_empty() {
+            return;
+        }
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(Some("↑"))
+                .end_symbol(Some("↓"))
+                .style(style),
+            area,
+            &mut state,
+        );
+    }
+}
+
+// where a tab of the market table was left, with its own screen
+#[derive(Default)]
+pub struct TabView {
+    pos: usize,
+    screen: Option<TextInput>,
+}
+
+pub struct MarketTable {
+    pub scroll: ScrollView,
+    pub tab: InstrumentClass,
+    pub exchange: usize, // index into app_state.exchanges
+    pub screen: Option<TextInput>,
+    pub screen_editing: bool,
+    pub matched: Option<(String, HashSet<String>)>, // the worker's last screen and its tickers
+    pub watch: bool, // only the --watch tickers are loaded, all of them on the one tab
+    pub frozen: usize, // columns on the left that stay when the others scroll sideways
+    pub scrolled: usize, // columns after the frozen ones scrolled off
+    pub expanded: Option<String>, // ticker of the row opened up with its details under it
+    pub horizon: ChangeHorizon, // of the change column
+    pub totals: bool, // a summary of the rows shown under them
+    pub columns: HashMap<String, ColumnConfig>, // [display.columns] of the config
+    pub format_rules: Vec<FormatRule>,
+    left: HashMap<(usize, InstrumentClass), TabView>, // by exchange and asset class
+}
+
+impl MarketTable {
+    pub fn new(watch: bool, display: &DisplayConfig, format_rules: Vec<FormatRule>) -> MarketTable {
+        MarketTable {
+            scroll: ScrollView::default(),
+            tab: InstrumentClass::Equity,
+            exchange: 0,
+            screen: None,
+            screen_editing: false,
+            matched: None,
+            watch,
+            // at least one column is left to scroll
+            frozen: display.frozen_columns.min(MARKET_COLUMNS.len() - 1),
+            scrolled: 0,
+            expanded: None,
+            horizon: ChangeHorizon::default(),
+            totals: display.totals_row,
+            columns: display.columns.clone(),
+            format_rules,
+            left: HashMap::new(),
+        }
+    }
+
+    // to another tab or exchange, the one left keeps its position and screen for coming back
+    pub fn switch(&mut self, exchange: usize, tab: InstrumentClass) {
+        if (exchange, tab) == (self.exchange, self.tab) {
+            return;
+        }
+        self.left.insert(
+            (self.exchange, self.tab),
+            TabView {
+                pos: self.scroll.pos,
+                screen: self.screen.take(),
+            },
+        );
+        let view = self.left.remove(&(exchange, tab)).unwrap_or_default();
+        self.exchange = exchange;
+        self.tab = tab;
+        self.scroll.pos = view.pos;
+        self.screen = view.screen;
+    }
+
+    // the screen to have the worker run, one that doesn't parse hides nothing
+    pub fn screen_query(&self) -> Option<&str> {
+        self.screen
+            .as_ref()
+            .map(|query| query.text.as_str())
+            .filter(|query| Screen::parse(query).is_ok())
+    }
+
+    // while the worker hasn't got back with the screen as it's typed now
+    pub fn is_screening(&self) -> bool {
+        self.screen_query()
+            .is_some_and(|query| self.matched.as_ref().is_none_or(|(done, _)| done != query))
+    }
+
+    // indices into app_state.quotes of the instruments on the current tab narrowed down
+    // by the screen, by the worker's last one until it's done with a changed query
+    pub fn indices(&self, app_state: &AppState) -> Vec<usize> {
+        let screened = self.screen_query().map(|_| self.matched.as_ref());
+        app_state
+            .quotes
+            .iter()
+            .enumerate()
+            .filter(|(_, quote)| {
+                self.watch || quote.exchange == self.exchange && quote.class() == self.tab
+            })
+            .filter(|(_, quote)| {
+                screened.is_none_or(|matched| {
+                    matched.is_some_and(|(_, tickers)| tickers.contains(&quote.listing.ticker))
+                })
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    // indices into MARKET_COLUMNS of the columns shown
+    pub fn visible_columns(&self) -> Vec<usize> {
+        (0..MARKET_COLUMNS.len())
+            .filter(|idx| *idx < self.frozen || *idx >= self.frozen + self.scrolled)
+            .collect()
+    }
+
+    pub fn selected(&self, app_state: &AppState) -> Option<usize> {
+        self.indices(app_state).get(self.scroll.pos).copied()
+    }
+
+    // on whichever tab it's on
+    pub fn select(&mut self, app_state: &AppState, idx: usize) {
+        self.switch(
+            app_state.quotes[idx].exchange,
+            app_state.quotes[idx].class(),
+        );
+        // don't leave it hidden behind the screen
+        if !self.indices(app_state).contains(&idx) {
+            self.screen = None;
+        }
+        self.scroll.pos = self
+            .indices(app_state)
+            .iter()
+            .position(|tab_idx| *tab_idx == idx)
+            .unwrap_or(0);
+    }
+}
+
+impl Component for MarketTable {
+    fn on_event(&mut self, key: KeyEvent, app_state: &AppState) -> bool {
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Right if shift => {
+                self.scrolled = min(self.scrolled + 1, MARKET_COLUMNS.len() - self.frozen - 1);
+                true
+            }
+            KeyCode::Left if shift => {
+                self.scrolled = self.scrolled.saturating_sub(1);
+                true
+            }
+            KeyCode::Char('%') => {
+                self.horizon = self.horizon.next();
+                true
+            }
+            // another row's details close when this one's open
+            KeyCode::Char(' ') => {
+                let ticker = self
+                    .selected(app_state)
+                    .map(|idx| app_state.quotes[idx].listing.ticker.clone());
+                self.expanded = ticker.filter(|ticker| self.expanded.as_ref() != Some(ticker));
+                true
+            }
+            // watching, there's only the one tab
+            KeyCode::Tab if !self.watch => {
+                let pos = InstrumentClass::ALL
+                    .iter()
+                    .position(|class| *class == self.tab)
+                    .unwrap_or(0);
+                self.switch(
+                    self.exchange,
+                    InstrumentClass::ALL[(pos + 1) % InstrumentClass::ALL.len()],
+                );
+                self.scroll.clamp(self.indices(app_state).len());
+                true
+            }
+            KeyCode::Char('e') | KeyCode::Char('E') if !self.watch => {
+                self.switch((self.exchange + 1) % app_state.exchanges.len(), self.tab);
+                self.scroll.clamp(self.indices(app_state).len());
+                true
+            }
+            _ => self.scroll.on_event(key, self.indices(app_state).len()),
+        }
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, ctx: &Context) {
+        draw_market_table(frame, area, self, ctx);
+    }
+}
+
+#[derive(Default)]
+pub struct NewsList {
+    pub scroll: ScrollView,
+    pub ticker_filter: Option<String>,
+    pub hidden_categories: Vec<NewsCategory>,
+    pub search: Option<TextInput>,
+    pub search_editing: bool,
+}
+
+impl NewsList {
+    // indices into app_state.news that pass the ticker, category and search filters,
+    // the archive goes by the same ones
+    pub fn indices(&self, app_state: &AppState) -> Vec<usize> {
+        app_state
+            .news
+            .iter()
+            .enumerate()
+            .filter(|(_, news_item)| match &self.ticker_filter {
+                Some(ticker) => news_item.mentions(ticker),
+                None => true,
+            })
+            .filter(|(_, news_item)| !self.hidden_categories.contains(&news_item.category))
+            .filter(|(_, news_item)| match &self.search {
+                Some(query) => news_item.matches(&query.text),
+                None => true,
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    // news is published on top, so the selection is moved down with it to stay on the same
+    // item, at the top it stays on the newest
+    pub fn follow(&mut self, app_state: &AppState, previous_len: usize) {
+        let added = app_state.news.len().saturating_sub(previous_len);
+        if self.scroll.pos == 0 || added == 0 {
+            return;
+        }
+        self.scroll.pos += self
+            .indices(app_state)
+            .iter()
+            .take_while(|idx| **idx < added)
+            .count();
+        self.scroll.clamp(self.visible(app_state).len());
+    }
+
+    // what the panel shows
+    pub fn visible<'a>(&self, app_state: &'a AppState) -> Vec<&'a NewsItem> {
+        self.indices(app_state)
+            .into_iter()
+            .take(LATEST_NEWS_LIMIT)
+            .map(|idx| &app_state.news[idx])
+            .collect()
+    }
+}
+
+impl Component for NewsList {
+    fn on_event(&mut self, key: KeyEvent, app_state: &AppState) -> bool {
+        self.scroll.on_event(key, self.visible(app_state).len())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, ctx: &Context) {
+        draw_news_list(frame, area, self, ctx);
+    }
+}
+
+#[derive(Default)]
+pub struct OrdersTable {
+    pub scroll: ScrollView,
+    pub show_blotter: bool,
+    other: ScrollView, // of the view not shown, working orders or the history
+}
+
+impl OrdersTable {
+    pub fn len(&self, app_state: &AppState) -> usize {
+        if self.show_blotter {
+            app_state.blotter.len()
+        } else {
+            app_state.orders.len()
+        }
+    }
+}
+
+impl Component for OrdersTable {
+    fn on_event(&mut self, key: KeyEvent, app_state: &AppState) -> bool {
+        match key.code {
+            KeyCode::Char('h') | KeyCode::Char('H') => {
+                self.show_blotter = !self.show_blotter;
+                std::mem::swap(&mut self.scroll, &mut self.other);
+                self.scroll.clamp(self.len(app_state));
+                true
+            }
+            _ => self.scroll.on_event(key, self.len(app_state)),
+        }
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, ctx: &Context) {
+        draw_orders(frame, area, self, ctx);
+    }
+}
+
+// the cash and positions of the active account or all of them, toggled in the accounts view
+#[derive(Default)]
+pub struct PortfolioPanel {
+    pub all_accounts: bool,
+}
+
+impl Component for PortfolioPanel {
+    fn on_event(&mut self, _key: KeyEvent, _app_state: &AppState) -> bool {
+        false
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, ctx: &Context) {
+        draw_portfolio(frame, area, self, ctx);
+    }
+}
+
+// the key hints along the bottom, made up for every frame from what's open, no keys of its own
+pub struct StatusBar {
+    pub hints: Vec<String>,
+    pub recording: Option<char>, // the register of the macro being recorded
+}
+
+impl Component for StatusBar {
+    fn on_event(&mut self, _key: KeyEvent, _app_state: &AppState) -> bool {
+        false
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, ctx: &Context) {
+        draw_status_bar(frame, area, self, ctx);
+    }
+}
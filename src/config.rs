@@ -0,0 +1,411 @@
+use crate::difficulty::Difficulty;
+use crate::keymap::KeyList;
+use crate::locale::Language;
+use crate::market::{MarketSession, Precision};
+use chrono::NaiveTime;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub news: NewsConfig,
+    pub time: TimeConfig,
+    pub trading: TradingConfig,
+    pub market: MarketConfig,
+    pub game: GameConfig,
+    pub display: DisplayConfig,
+    pub theme: ThemeConfig,
+    pub hooks: HooksConfig,
+    pub movers: MoversConfig,
+    // keys by action like report = "r" or quit = ["q", "Ctrl-C"], see Readme
+    pub keys: HashMap<String, KeyList>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct NewsConfig {
+    // how often all configured feeds get polled
+    pub feed_refresh_secs: u64,
+    pub feeds: Vec<FeedConfig>,
+}
+
+impl Default for NewsConfig {
+    fn default() -> Self {
+        NewsConfig {
+            feed_refresh_secs: 300,
+            feeds: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedConfig {
+    pub label: String,
+    pub url: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TimeConfig {
+    // IANA name like "Europe/London", system local time when not set
+    pub timezone: Option<Tz>,
+    pub clock: ClockFormat,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+pub enum ClockFormat {
+    #[default]
+    #[serde(rename = "24h")]
+    TwentyFourHour,
+    #[serde(rename = "12h")]
+    TwelveHour,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct TradingConfig {
+    // how long the latest fill can be undone, 0 turns undo off
+    pub undo_grace_secs: u64,
+    // percent of the portfolio by ticker or asset class to rebalance to, like BCI = 20 or Bonds = 30
+    pub targets: HashMap<String, f64>,
+    // yearly rates in percent paid on cash and charged on a negative balance, by the day
+    pub cash_interest_pct: f64,
+    pub borrow_rate_pct: f64,
+    // shares held this many days or more make long-term gains
+    pub long_term_days: u32,
+    // business days until sale proceeds settle, and whether buys can spend them before that
+    pub settlement_days: u32,
+    pub trade_unsettled: bool,
+    // percent of the value of the shares held that buys can borrow against, 0 buys with cash only
+    pub margin_pct: f64,
+    // names of separate accounts like "Speculation" and "Pension", one "Main" account when empty
+    pub accounts: Vec<String>,
+}
+
+impl Default for TradingConfig {
+    fn default() -> Self {
+        TradingConfig {
+            undo_grace_secs: 5,
+            targets: HashMap::new(),
+            cash_interest_pct: 2.0,
+            borrow_rate_pct: 8.0,
+            long_term_days: 365,
+            settlement_days: 2,
+            trade_unsettled: true,
+            margin_pct: 0.0,
+            accounts: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct MarketConfig {
+    // trading hours in the [time] timezone like "08:00", the market never closes when unset
+    pub session_open: Option<NaiveTime>,
+    pub session_close: Option<NaiveTime>,
+    // per-tick random walk bounds of Aethercoin, in percent
+    pub crypto_volatility_pct: f64,
+    // whether analyst upgrades and downgrades move prices like other news
+    pub analysts_move_prices: bool,
+    // scripted events to play on top of the simulation
+    pub scenario: Option<PathBuf>,
+    // hours of the other exchanges by code, like LDX = { open = "07:00", close = "15:30" }
+    pub exchange_sessions: HashMap<String, MarketSession>,
+    // drop and delay quote updates like a bad connection would
+    pub flaky_connection: bool,
+    // recorded prices to offer as a data source next to the simulation, see Readme
+    pub replay: Option<PathBuf>,
+    // tick size and display decimals by ticker, over the asset class defaults
+    pub precision: HashMap<String, Precision>,
+    // how often prices move, ticks coming faster than frames are drawn together
+    pub tick_millis: u64,
+    // single ticks are kept this long, then 1-minute bars for this long, daily bars for good
+    pub tick_retention_mins: u32,
+    pub minute_retention_hours: u32,
+}
+
+impl Default for MarketConfig {
+    fn default() -> Self {
+        MarketConfig {
+            session_open: None,
+            session_close: None,
+            crypto_volatility_pct: 3.0,
+            analysts_move_prices: true,
+            scenario: None,
+            exchange_sessions: HashMap::new(),
+            flaky_connection: false,
+            replay: None,
+            precision: HashMap::new(),
+            tick_millis: 1000,
+            tick_retention_mins: 60,
+            minute_retention_hours: 24,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct GameConfig {
+    // "easy", "normal" or "brutal", --difficulty on the command line wins
+    pub difficulty: Difficulty,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    // how line charts are drawn, "auto" picks braille unless the terminal is unlikely to have the glyphs
+    pub charts: ChartStyle,
+    // "auto" draws the candle chart as an image in terminals known to support kitty or sixel graphics
+    pub graphics: GraphicsSetting,
+    // quotes without an update for this long get a clock, trading ones are dimmed too
+    pub stale_after_secs: u64,
+    // plain labelled lines instead of panels, for screen readers, or --accessible
+    pub accessible: bool,
+    // portfolio value and the biggest mover in the terminal's title, for when it's in a tab
+    pub window_title: bool,
+    // "en" or "de", taken from LANG when not set
+    pub language: Option<Language>,
+    // market data rows matching a screen get a style, see Readme
+    pub format_rules: Vec<FormatRuleConfig>,
+    // market table overrides by column name, like price = { align = "left", min_width = 12 }
+    pub columns: HashMap<String, ColumnConfig>,
+    // a color and a glyph by ticker, like BCI = { color = "yellow", glyph = "⚙" }
+    pub tickers: HashMap<String, TickerConfig>,
+    // market table columns from the left that stay in place when Shift and the arrows scroll the rest
+    pub frozen_columns: usize,
+    // volumes and market caps like "1.2M", or in full with thousands separators
+    pub abbreviate_numbers: bool,
+    // advancers, decliners, the average change and market cap of the rows shown, under the market table
+    pub totals_row: bool,
+    // frames drawn a second at most, however fast the ticks come
+    pub max_fps: u32,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        DisplayConfig {
+            charts: ChartStyle::Auto,
+            graphics: GraphicsSetting::Auto,
+            stale_after_secs: 3,
+            accessible: false,
+            window_title: false,
+            language: None,
+            format_rules: vec![],
+            columns: HashMap::new(),
+            tickers: HashMap::new(),
+            frozen_columns: 1,
+            abbreviate_numbers: true,
+            totals_row: false,
+            max_fps: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    // shell commands run with the event as JSON on stdin, see Readme
+    pub on_fill: Option<String>,
+    pub on_session_open: Option<String>,
+    pub on_alert: Option<String>,
+    // a market crash, a disaster in the news or an instrument falling hard in one tick
+    pub on_crash_event: Option<String>,
+    // the ledger itself crashing, with the panic message
+    pub on_panic: Option<String>,
+    // a hook still running after this long is killed
+    pub timeout_secs: u64,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        HooksConfig {
+            on_fill: None,
+            on_session_open: None,
+            on_alert: None,
+            on_crash_event: None,
+            on_panic: None,
+            timeout_secs: 10,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FormatRuleConfig {
+    pub when: String,  // a screen like "price > 2500"
+    pub style: String, // like "bold" or "white on red"
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MoversConfig {
+    // a move of this many percent either way within the window flags the instrument, 0 turns it off
+    pub threshold_pct: f64,
+    pub window_secs: u64,
+    // a newly flagged one goes in the alerts log like an alert that went off
+    pub notify: bool,
+    // and rings the terminal bell
+    pub bell: bool,
+}
+
+impl Default for MoversConfig {
+    fn default() -> Self {
+        MoversConfig {
+            threshold_pct: 5.0,
+            window_secs: 60,
+            notify: false,
+            bell: false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    // "standard", "high-contrast" or "colorblind", p switches between them while running
+    pub palette: Palette,
+    // every other market table row gets a slightly lighter background
+    pub zebra_stripes: bool,
+    // a thin line under every market table row
+    pub row_separators: bool,
+    // blank columns between market table cells
+    pub cell_padding: u16,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        ThemeConfig {
+            palette: Palette::Standard,
+            zebra_stripes: false,
+            row_separators: false,
+            cell_padding: 1,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Palette {
+    #[default]
+    Standard,
+    HighContrast,
+    Colorblind, // blue and orange instead of green and red
+}
+
+impl Palette {
+    pub const ALL: [Palette; 3] = [
+        Palette::Standard,
+        Palette::HighContrast,
+        Palette::Colorblind,
+    ];
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnConfig {
+    pub align: Option<ColumnAlign>,
+    pub min_width: Option<u16>,
+    pub max_width: Option<u16>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TickerConfig {
+    pub color: Option<String>, // a name like "yellow" or "#ff8800"
+    pub glyph: Option<String>, // a letter, symbol or emoji, two columns at most
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnAlign {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChartStyle {
+    #[default]
+    Auto,
+    Braille,
+    Block,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphicsSetting {
+    #[default]
+    Auto,
+    Kitty,
+    Sixel,
+    Off,
+}
+
+// IRON_LEDGER_CONFIG wins, otherwise the usual XDG location
+fn config_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("IRON_LEDGER_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("iron-ledger").join("config.toml"))
+}
+
+// IRON_LEDGER_DATA wins, otherwise the usual XDG location
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("IRON_LEDGER_DATA") {
+        return Some(PathBuf::from(path));
+    }
+    let data_dir = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        })?;
+    Some(data_dir.join("iron-ledger"))
+}
+
+impl Config {
+    // a missing config file is fine, a broken one is not
+    pub fn load() -> Result<Config, String> {
+        let Some(path) = config_path() else {
+            return Ok(Config::default());
+        };
+        match fs::read_to_string(&path) {
+            Ok(text) => {
+                let config: Config = toml::from_str(&text)
+                    .map_err(|err| format!("{0}: {1}", path.display(), err))?;
+                if config.market.session_open.is_some() != config.market.session_close.is_some() {
+                    return Err(format!(
+                        "{0}: session_open and session_close have to be set together",
+                        path.display()
+                    ));
+                }
+                if config.market.tick_millis < 10 || config.display.max_fps == 0 {
+                    return Err(format!(
+                        "{0}: tick_millis has to be at least 10 and max_fps above 0",
+                        path.display()
+                    ));
+                }
+                if config.market.tick_retention_mins == 0
+                    || config.market.minute_retention_hours * 60 < config.market.tick_retention_mins
+                {
+                    return Err(format!(
+                        "{0}: tick_retention_mins has to be above 0 and within minute_retention_hours",
+                        path.display()
+                    ));
+                }
+                Ok(config)
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(format!("{0}: {1}", path.display(), err)),
+        }
+    }
+}
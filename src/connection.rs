@@ -0,0 +1,232 @@
+use crate::replay::Replay;
+use chrono::{DateTime, TimeDelta, Utc};
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+// chances per tick of the link getting worse or recovering, only in flaky mode
+const DEGRADE_CHANCE: f64 = 0.05;
+const DISCONNECT_CHANCE: f64 = 0.2; // of a degraded link dropping
+const RECOVER_CHANCE: f64 = 0.25;
+// ticks the update rate is averaged over
+const RATE_WINDOW: usize = 20;
+const ERROR_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LinkState {
+    Connected,
+    Degraded,
+    Disconnected,
+}
+
+impl LinkState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LinkState::Connected => "connected",
+            LinkState::Degraded => "degraded",
+            LinkState::Disconnected => "disconnected",
+        }
+    }
+
+    fn latency_ms(&self) -> RangeInclusive<u64> {
+        match self {
+            LinkState::Connected => RangeInclusive::new(20, 90),
+            LinkState::Degraded => RangeInclusive::new(300, 1500),
+            LinkState::Disconnected => RangeInclusive::new(0, 0),
+        }
+    }
+
+    // chance of a quote update getting through
+    fn delivery(&self) -> f64 {
+        match self {
+            LinkState::Connected => 0.98,
+            LinkState::Degraded => 0.6,
+            LinkState::Disconnected => 0.0,
+        }
+    }
+}
+
+// where quote updates come from
+#[derive(Debug)]
+pub enum DataSource {
+    Simulation,
+    Replay(Replay),
+}
+
+impl DataSource {
+    pub fn label(&self) -> String {
+        match self {
+            DataSource::Simulation => "Random simulation".to_string(),
+            DataSource::Replay(replay) => format!("CSV replay · {0}", replay.path.display()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ConnectionError {
+    pub at: DateTime<Utc>,
+    pub message: String,
+}
+
+// the market data link, always healthy unless flaky mode is on
+#[derive(Debug)]
+pub struct Connection {
+    pub flaky: bool,
+    pub state: LinkState,
+    pub latency_ms: u64,
+    pub down_since: Option<DateTime<Utc>>,
+    pub sources: Vec<DataSource>, // the simulation first, then whatever is configured
+    pub active: usize,
+    pub connected_at: DateTime<Utc>,
+    pub received: u64, // quote updates since connecting
+    pub lost: u64,
+    pub reconnects: u32,
+    pub errors: Vec<ConnectionError>, // newest last
+    // when each recent tick happened and how many updates it brought
+    recent: VecDeque<(DateTime<Utc>, u64)>,
+    this_tick: u64,
+}
+
+impl Connection {
+    pub fn new(flaky: bool, replay: Option<Replay>) -> Connection {
+        let mut sources = vec![DataSource::Simulation];
+        sources.extend(replay.map(DataSource::Replay));
+        Connection {
+            flaky,
+            state: LinkState::Connected,
+            latency_ms: 0,
+            down_since: None,
+            sources,
+            active: 0,
+            connected_at: Utc::now(),
+            received: 0,
+            lost: 0,
+            reconnects: 0,
+            errors: vec![],
+            recent: VecDeque::new(),
+            this_tick: 0,
+        }
+    }
+
+    fn log_error(&mut self, message: String) {
+        self.errors.push(ConnectionError {
+            at: Utc::now(),
+            message,
+        });
+        if self.errors.len() > ERROR_LIMIT {
+            self.errors.remove(0);
+        }
+    }
+
+    // starts over on the active source, a replay is read from disk again
+    pub fn reconnect(&mut self) {
+        if let DataSource::Replay(replay) = &self.sources[self.active] {
+            match Replay::load(&replay.path) {
+                Ok(reloaded) => self.sources[self.active] = DataSource::Replay(reloaded),
+                Err(err) => self.log_error(err),
+            }
+        }
+        self.state = LinkState::Connected;
+        self.latency_ms = 0;
+        self.down_since = None;
+        self.connected_at = Utc::now();
+        self.received = 0;
+        self.lost = 0;
+        self.recent.clear();
+        self.this_tick = 0;
+        self.reconnects += 1;
+    }
+
+    pub fn switch_source(&mut self) {
+        self.active = (self.active + 1) % self.sources.len();
+        self.reconnect();
+    }
+
+    // the prices of the next replay step, None when simulating
+    pub fn replay_step(&mut self) -> Option<Vec<(String, f64)>> {
+        match &mut self.sources[self.active] {
+            DataSource::Replay(replay) => Some(replay.next_step()),
+            DataSource::Simulation => None,
+        }
+    }
+
+    pub fn uptime(&self) -> Option<TimeDelta> {
+        match self.state {
+            LinkState::Disconnected => None,
+            _ => Some(Utc::now() - self.connected_at),
+        }
+    }
+
+    // quote updates per second over the last few ticks
+    pub fn update_rate(&self) -> f64 {
+        let (Some((first, _)), Some((last, _))) = (self.recent.front(), self.recent.back()) else {
+            return 0.0;
+        };
+        let secs = (*last - *first).num_milliseconds() as f64 / 1000.0;
+        if secs <= 0.0 {
+            return 0.0;
+        }
+        // the first tick's updates arrived before the window started
+        self.recent
+            .iter()
+            .skip(1)
+            .map(|(_, count)| *count)
+            .sum::<u64>() as f64
+            / secs
+    }
+
+    pub fn tick(&mut self, rng: &mut ThreadRng) {
+        self.recent.push_back((Utc::now(), self.this_tick));
+        if self.recent.len() > RATE_WINDOW {
+            self.recent.pop_front();
+        }
+        self.this_tick = 0;
+        if !self.flaky {
+            return;
+        }
+        let was = self.state;
+        self.state = match self.state {
+            LinkState::Connected if rng.random_bool(DEGRADE_CHANCE) => LinkState::Degraded,
+            LinkState::Degraded if rng.random_bool(DISCONNECT_CHANCE) => LinkState::Disconnected,
+            LinkState::Degraded | LinkState::Disconnected if rng.random_bool(RECOVER_CHANCE) => {
+                LinkState::Connected
+            }
+            state => state,
+        };
+        self.down_since = match self.state {
+            LinkState::Disconnected => self.down_since.or(Some(Utc::now())),
+            _ => None,
+        };
+        match (was, self.state) {
+            (LinkState::Disconnected, LinkState::Connected) => self.connected_at = Utc::now(),
+            (LinkState::Degraded, LinkState::Disconnected) => {
+                self.log_error("link dropped".to_string())
+            }
+            _ => {}
+        }
+        self.latency_ms = rng.random_range(self.state.latency_ms());
+    }
+
+    // whether this tick's update of a quote arrives or is lost
+    pub fn delivers(&mut self, rng: &mut ThreadRng) -> bool {
+        let delivered = !self.flaky || rng.random_bool(self.state.delivery());
+        if delivered {
+            self.received += 1;
+            self.this_tick += 1;
+        } else {
+            self.lost += 1;
+        }
+        delivered
+    }
+
+    // ticks arrive late by the latency, give or take
+    pub fn tick_interval(&self, rng: &mut ThreadRng, tick_rate: Duration) -> Duration {
+        if !self.flaky {
+            return tick_rate;
+        }
+        let jitter = rng.random_range(RangeInclusive::new(0, self.latency_ms / 2));
+        tick_rate + Duration::from_millis(self.latency_ms / 2 + jitter)
+    }
+}
@@ -0,0 +1,235 @@
+use crate::config::Palette;
+use crate::orders::Side;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+// a JSON-RPC 2.0 call from the control socket, one per line, answered by the main loop
+pub struct ControlRequest {
+    pub method: String,
+    params: Value,
+    reply: Sender<Result<Value, RpcError>>,
+}
+
+pub enum RpcError {
+    UnknownMethod,
+    InvalidParams(String),
+}
+
+impl RpcError {
+    fn to_json(&self) -> Value {
+        match self {
+            RpcError::UnknownMethod => json!({"code": -32601, "message": "method not found"}),
+            RpcError::InvalidParams(message) => json!({"code": -32602, "message": message}),
+        }
+    }
+}
+
+impl ControlRequest {
+    // named params, missing ones are null
+    pub fn params<T: DeserializeOwned>(&self) -> Result<T, RpcError> {
+        let params = match &self.params {
+            Value::Null => json!({}),
+            params => params.clone(),
+        };
+        serde_json::from_value(params).map_err(|err| RpcError::InvalidParams(err.to_string()))
+    }
+
+    pub fn answer(self, result: Result<Value, RpcError>) {
+        // the client may be gone already
+        let _ = self.reply.send(result);
+    }
+}
+
+#[derive(Deserialize)]
+pub struct TickerParams {
+    pub ticker: String,
+}
+
+#[derive(Deserialize)]
+pub struct OrderParams {
+    pub ticker: String,
+    pub side: Side,
+    pub quantity: u32,
+    pub limit_price: Option<f64>, // a market order without one
+}
+
+#[derive(Deserialize)]
+pub struct ThemeParams {
+    pub palette: Palette,
+}
+
+#[derive(Deserialize)]
+struct Call {
+    id: Option<Value>, // notifications don't get an answer
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+// listens on a Unix socket, a stale socket file from an earlier run is replaced but a socket
+// something still listens on, or anything that isn't a socket, is left alone
+#[cfg(unix)]
+pub fn spawn_control_socket(path: &Path) -> io::Result<Receiver<ControlRequest>> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if !metadata.file_type().is_socket() => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "there's something other than a socket there",
+            ));
+        }
+        Ok(_) if UnixStream::connect(path).is_ok() => {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                "something is listening on it already",
+            ));
+        }
+        Ok(_) => fs::remove_file(path)?,
+        Err(_) => {}
+    }
+    let listener = UnixListener::bind(path)?;
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let Ok(reader) = stream.try_clone() else {
+                    return;
+                };
+                serve(BufReader::new(reader), stream, tx);
+            });
+        }
+    });
+    Ok(rx)
+}
+
+#[cfg(not(unix))]
+pub fn spawn_control_socket(_path: &Path) -> io::Result<Receiver<ControlRequest>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the control socket needs Unix sockets",
+    ))
+}
+
+pub fn remove_control_socket(path: &Path) {
+    let _ = fs::remove_file(path);
+}
+
+fn serve(reader: impl BufRead, mut writer: impl Write, tx: Sender<ControlRequest>) {
+    for line in reader.lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        // JSON that isn't a call still answers with its id when it has one
+        let call = serde_json::from_str::<Value>(&line).map(|value| {
+            let id = value.get("id").cloned().unwrap_or(Value::Null);
+            serde_json::from_value::<Call>(value).map_err(|err| (id, err))
+        });
+        let (id, result) = match call {
+            Ok(Ok(call)) => {
+                let (reply, answer) = mpsc::channel();
+                let request = ControlRequest {
+                    method: call.method,
+                    params: call.params,
+                    reply,
+                };
+                if tx.send(request).is_err() {
+                    return;
+                }
+                let Ok(result) = answer.recv() else {
+                    return;
+                };
+                let Some(id) = call.id else {
+                    continue;
+                };
+                (id, result.map_err(|err| err.to_json()))
+            }
+            Ok(Err((id, err))) => (id, Err(json!({"code": -32600, "message": err.to_string()}))),
+            Err(err) => (
+                Value::Null,
+                Err(json!({"code": -32700, "message": err.to_string()})),
+            ),
+        };
+        let response = match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(error) => json!({"jsonrpc": "2.0", "id": id, "error": error}),
+        };
+        if writeln!(writer, "{0}", response).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    // the lines sent and the answers that came back, from a main loop that knows "echo"
+    fn exchange(lines: &str) -> Vec<Value> {
+        let (tx, rx) = mpsc::channel::<ControlRequest>();
+        thread::spawn(move || {
+            for request in rx {
+                let result = match request.method.as_str() {
+                    "echo" => Ok(request.params.clone()),
+                    _ => Err(RpcError::UnknownMethod),
+                };
+                request.answer(result);
+            }
+        });
+        let mut out = Vec::<u8>::new();
+        serve(Cursor::new(lines.to_string()), &mut out, tx);
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn a_call_is_answered_with_its_id() {
+        let answers = exchange(r#"{"jsonrpc": "2.0", "id": 7, "method": "echo", "params": [1]}"#);
+        assert_eq!(answers, [json!({"jsonrpc": "2.0", "id": 7, "result": [1]})]);
+    }
+
+    #[test]
+    fn notifications_and_blank_lines_get_no_answer() {
+        let answers = exchange(
+            "{\"jsonrpc\": \"2.0\", \"method\": \"echo\"}\n\n{\"id\": 1, \"method\": \"echo\"}\n",
+        );
+        assert_eq!(answers.len(), 1);
+        assert_eq!(answers[0]["id"], 1);
+    }
+
+    #[test]
+    fn an_unknown_method_is_not_found() {
+        let answers = exchange(r#"{"id": 2, "method": "launch"}"#);
+        assert_eq!(answers[0]["id"], 2);
+        assert_eq!(answers[0]["error"]["code"], -32601);
+    }
+
+    #[test]
+    fn json_that_isnt_a_call_is_an_invalid_request() {
+        let answers = exchange(r#"{"id": 3, "params": {}}"#);
+        assert_eq!(answers[0]["id"], 3);
+        assert_eq!(answers[0]["error"]["code"], -32600);
+    }
+
+    #[test]
+    fn what_isnt_json_is_a_parse_error() {
+        let answers = exchange("{\"id\": 4, \"method\"\n");
+        assert_eq!(answers[0]["id"], Value::Null);
+        assert_eq!(answers[0]["error"]["code"], -32700);
+    }
+}
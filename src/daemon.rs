@@ -0,0 +1,120 @@
+use crate::app::AppState;
+use crate::history::Retention;
+use crate::hooks::Hooks;
+use chrono::TimeDelta;
+use rand::rngs::ThreadRng;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+// keeps the market going without a terminal, appending every tick to a file in the replay format
+// so the recording can be played back in the TUI later
+pub fn run_daemon(
+    app_state: &mut AppState,
+    hooks: &mut Hooks,
+    rng: &mut ThreadRng,
+    path: &Path,
+    tick_rate: Duration,
+    retention: Retention,
+) -> Result<(), String> {
+    let error = |err: std::io::Error| format!("{0}: {1}", path.display(), err);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(error)?;
+    }
+    // the ticks go on from an earlier recording so they can stand in for the time
+    let first_tick = compact(path, retention, tick_rate).map_err(error)?;
+    let mut out = open(path).map_err(error)?;
+    let compact_every = ticks_in(retention.ticks, tick_rate);
+    loop {
+        thread::sleep(app_state.connection.tick_interval(rng, tick_rate));
+        app_state.tick(rng);
+        hooks.check(app_state);
+        let tick = first_tick + app_state.ticks;
+        // a replay only moves primary listings, cross-listings follow them anyway
+        for quote in app_state
+            .quotes
+            .iter()
+            .filter(|quote| quote.primary.is_none())
+        {
+            writeln!(
+                out,
+                "{0},{1},{2:.3$}",
+                tick,
+                quote.listing.ticker,
+                quote.quote.price,
+                quote.price_decimals()
+            )
+            .map_err(error)?;
+        }
+        // whatever was recorded is there if the daemon gets killed
+        out.flush().map_err(error)?;
+        if app_state.ticks.is_multiple_of(compact_every) {
+            drop(out);
+            compact(path, retention, tick_rate).map_err(error)?;
+            out = open(path).map_err(error)?;
+        }
+    }
+}
+
+fn open(path: &Path) -> std::io::Result<BufWriter<File>> {
+    let is_new = !path.exists();
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut out = BufWriter::new(file);
+    if is_new {
+        writeln!(out, "tick,ticker,price")?;
+    }
+    Ok(out)
+}
+
+// at least one, a tick is taken for every tick_rate
+fn ticks_in(span: TimeDelta, tick_rate: Duration) -> u64 {
+    (span.num_milliseconds().max(0) as u128 / tick_rate.as_millis().max(1)).max(1) as u64
+}
+
+// thins the recording out like the price history: every tick of the retention, then only the
+// last tick of every minute and past that of every day, answers the tick to go on from
+fn compact(path: &Path, retention: Retention, tick_rate: Duration) -> std::io::Result<u64> {
+    let Ok(text) = fs::read_to_string(path) else {
+        return Ok(0);
+    };
+    let tick_of = |line: &str| line.split(',').next()?.trim().parse::<u64>().ok();
+    let Some(last) = text.lines().filter_map(tick_of).max() else {
+        return Ok(0);
+    };
+    let (raw, minutes) = (
+        ticks_in(retention.ticks, tick_rate),
+        ticks_in(retention.minutes, tick_rate),
+    );
+    let (minute, day) = (
+        ticks_in(TimeDelta::minutes(1), tick_rate),
+        ticks_in(TimeDelta::days(1), tick_rate),
+    );
+    let bucket = |tick: u64| match last - tick {
+        age if age < raw => None,
+        age if age < minutes => Some((0, tick / minute)),
+        _ => Some((1, tick / day)),
+    };
+    let mut kept = HashMap::new();
+    for tick in text.lines().filter_map(tick_of) {
+        if let Some(bucket) = bucket(tick) {
+            let latest = kept.entry(bucket).or_insert(tick);
+            *latest = (*latest).max(tick);
+        }
+    }
+    let lines = text
+        .lines()
+        .filter(|line| match tick_of(line) {
+            Some(tick) => bucket(tick).is_none_or(|bucket| kept[&bucket] == tick),
+            None => true, // the header and comments
+        })
+        .collect::<Vec<&str>>();
+    if lines.len() < text.lines().count() {
+        let staged = path.with_extension("csv.tmp");
+        fs::write(&staged, lines.join("\n") + "\n")?;
+        fs::rename(&staged, path)?;
+    }
+    Ok(last)
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Brutal,
+}
+
+// what a preset changes, everything is relative to Normal
+#[derive(Debug, Clone, Copy)]
+pub struct DifficultySettings {
+    pub volatility_scale: f64,
+    pub drift_pct: f64,      // extra per-tick drift of every price, in percent
+    pub commission_pct: f64, // of the traded value, charged on every fill
+    pub slippage_pct: f64,   // market orders fill up to this much worse than the quote
+    pub disaster_scale: f64, // how much likelier disasters are than other news
+}
+
+impl Difficulty {
+    pub fn parse(name: &str) -> Option<Difficulty> {
+        match name.to_lowercase().as_str() {
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "brutal" => Some(Difficulty::Brutal),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Brutal => "Brutal",
+        }
+    }
+
+    pub fn settings(&self) -> DifficultySettings {
+        match self {
+            Difficulty::Easy => DifficultySettings {
+                volatility_scale: 0.75,
+                drift_pct: 0.01,
+                commission_pct: 0.0,
+                slippage_pct: 0.0,
+                disaster_scale: 0.5,
+            },
+            Difficulty::Normal => DifficultySettings {
+                volatility_scale: 1.0,
+                drift_pct: 0.0,
+                commission_pct: 0.05,
+                slippage_pct: 0.1,
+                disaster_scale: 1.0,
+            },
+            Difficulty::Brutal => DifficultySettings {
+                volatility_scale: 1.5,
+                drift_pct: -0.01,
+                commission_pct: 0.25,
+                slippage_pct: 0.5,
+                disaster_scale: 3.0,
+            },
+        }
+    }
+}
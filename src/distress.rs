@@ -0,0 +1,187 @@
+use crate::market::Instrument;
+use crate::news::{NewsCategory, NewsItem};
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+
+// a company this far below its peak is in trouble, back above the recovery line it's fine
+const DISTRESS_DRAWDOWN_PCT: f64 = 25.0;
+const RECOVERY_DRAWDOWN_PCT: f64 = 15.0;
+// while in distress things get worse every this many ticks, unless someone steps in
+const ESCALATION_TICKS: u64 = 25;
+const RESCUE_CHANCE: f64 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum DistressLevel {
+    Concern,
+    MissedPayment,
+    Administration,
+}
+
+impl DistressLevel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DistressLevel::Concern => "going concern doubts",
+            DistressLevel::MissedPayment => "missed debt payment",
+            DistressLevel::Administration => "in administration",
+        }
+    }
+
+    // None means the next step is bankruptcy
+    fn next(&self) -> Option<DistressLevel> {
+        match self {
+            DistressLevel::Concern => Some(DistressLevel::MissedPayment),
+            DistressLevel::MissedPayment => Some(DistressLevel::Administration),
+            DistressLevel::Administration => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Distress {
+    pub level: DistressLevel,
+    next_escalation_tick: u64,
+}
+
+// how a company has been doing since it last recovered
+#[derive(Debug)]
+pub struct Fortune {
+    pub ticker: String,
+    peak_price: f64,
+    pub distress: Option<Distress>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FortuneEvent {
+    Distressed,
+    Escalated(DistressLevel),
+    Rescued,
+    Recovered,
+    Bankrupt,
+}
+
+impl FortuneEvent {
+    // coverage gets grimmer with every step
+    pub fn news_item(&self, quote: &Instrument) -> NewsItem {
+        let company = format!("{0} ({1})", quote.listing.name, quote.listing.ticker);
+        let (title, subtitle, sentiment) = match self {
+            FortuneEvent::Distressed => (
+                format!("Auditors Raise Doubts Over {0} Finances", company),
+                "Creditors are said to be reviewing their exposure.",
+                -0.4,
+            ),
+            FortuneEvent::Escalated(DistressLevel::MissedPayment) => (
+                format!("{0} Misses Debt Payment", company),
+                "The company has a short grace period to find the money.",
+                -0.7,
+            ),
+            FortuneEvent::Escalated(_) => (
+                format!("{0} Enters Administration", company),
+                "Administrators warn shareholders are unlikely to recover anything.",
+                -1.0,
+            ),
+            FortuneEvent::Rescued => (
+                format!("{0} Secures Rescue Financing", company),
+                "A consortium of lenders has agreed to see the company through.",
+                1.0,
+            ),
+            FortuneEvent::Recovered => (
+                format!("{0} Shakes Off Solvency Concerns", company),
+                "A rebound in the share price has reassured creditors.",
+                0.5,
+            ),
+            FortuneEvent::Bankrupt => (
+                format!("{0} Declared Bankrupt", company),
+                "The shares have been cancelled and delisted.",
+                0.0,
+            ),
+        };
+        NewsItem::new(
+            &title,
+            subtitle,
+            NewsCategory::Disaster,
+            sentiment,
+            &[quote.listing.ticker.as_str()],
+        )
+    }
+}
+
+fn drawdown_pct(peak_price: f64, price: f64) -> f64 {
+    (peak_price - price) / peak_price * 100.0
+}
+
+// follows every listed company, returning what happened to whom this tick
+pub fn update_fortunes(
+    rng: &mut ThreadRng,
+    fortunes: &mut Vec<Fortune>,
+    quotes: &[Instrument],
+    tick: u64,
+) -> Vec<(String, FortuneEvent)> {
+    // looked up by ticker, a big market makes searching the list on every tick slow
+    let listed = quotes
+        .iter()
+        .map(|quote| quote.listing.ticker.as_str())
+        .collect::<HashSet<&str>>();
+    fortunes.retain(|fortune| listed.contains(fortune.ticker.as_str()));
+    let mut positions = fortunes
+        .iter()
+        .enumerate()
+        .map(|(pos, fortune)| (fortune.ticker.clone(), pos))
+        .collect::<HashMap<String, usize>>();
+    let mut events = vec![];
+    for quote in quotes.iter().filter(|quote| quote.is_company()) {
+        let price = quote.quote.price;
+        let ticker = &quote.listing.ticker;
+        let fortune = match positions.get(ticker) {
+            Some(pos) => &mut fortunes[*pos],
+            None => {
+                positions.insert(ticker.clone(), fortunes.len());
+                fortunes.push(Fortune {
+                    ticker: ticker.clone(),
+                    peak_price: price,
+                    distress: None,
+                });
+                continue;
+            }
+        };
+        let drawdown = drawdown_pct(fortune.peak_price, price);
+        let event = match &mut fortune.distress {
+            None if drawdown >= DISTRESS_DRAWDOWN_PCT => {
+                fortune.distress = Some(Distress {
+                    level: DistressLevel::Concern,
+                    next_escalation_tick: tick + ESCALATION_TICKS,
+                });
+                Some(FortuneEvent::Distressed)
+            }
+            None => {
+                fortune.peak_price = fortune.peak_price.max(price);
+                None
+            }
+            Some(_) if drawdown < RECOVERY_DRAWDOWN_PCT => Some(FortuneEvent::Recovered),
+            Some(distress) if tick >= distress.next_escalation_tick => {
+                if rng.random_bool(RESCUE_CHANCE) {
+                    Some(FortuneEvent::Rescued)
+                } else if let Some(level) = distress.level.next() {
+                    distress.level = level;
+                    distress.next_escalation_tick = tick + ESCALATION_TICKS;
+                    Some(FortuneEvent::Escalated(level))
+                } else {
+                    Some(FortuneEvent::Bankrupt)
+                }
+            }
+            Some(_) => None,
+        };
+        // a fresh start is measured from where the price is now
+        if matches!(
+            event,
+            Some(FortuneEvent::Rescued) | Some(FortuneEvent::Recovered)
+        ) {
+            fortune.distress = None;
+            fortune.peak_price = price;
+        }
+        if let Some(event) = event {
+            events.push((ticker.clone(), event));
+        }
+    }
+    events
+}
@@ -0,0 +1,151 @@
+use crate::market::MarketSession;
+use crate::numbers::decimal;
+use chrono::NaiveTime;
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use std::ops::RangeInclusive;
+
+// per-tick walk of exchange rates in percent, pulled back towards the long-run rate
+const FX_VOLATILITY_PCT: f64 = 0.1;
+const FX_REVERSION: f64 = 0.02;
+
+// which side of an amount a currency's symbol goes on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolPosition {
+    Prefix, // £1,234.56
+    Suffix, // 1,234.56 ₡
+}
+
+#[derive(Debug, Clone)]
+pub struct Currency {
+    pub symbol: String,
+    pub position: SymbolPosition,
+    pub spaced: bool, // a space between the symbol and the amount
+}
+
+impl Currency {
+    pub fn new(symbol: &str, position: SymbolPosition, spaced: bool) -> Currency {
+        Currency {
+            symbol: symbol.to_string(),
+            position,
+            spaced,
+        }
+    }
+
+    // grouped like the interface language, "£1,234.56" or "1.234,56 ₡", the sign goes in
+    // front of a symbol that does
+    pub fn format(&self, value: f64, decimals: usize) -> String {
+        let text = decimal(value, decimals);
+        match text.strip_prefix('-') {
+            Some(amount) if self.position == SymbolPosition::Prefix => {
+                format!("-{0}", self.around(amount))
+            }
+            _ => self.around(&text),
+        }
+    }
+
+    // with a plus on gains, for changes and ledger entries
+    pub fn signed(&self, value: f64, decimals: usize) -> String {
+        let text = self.format(value, decimals);
+        if text.starts_with('-') {
+            text
+        } else {
+            format!("+{0}", text)
+        }
+    }
+
+    // the symbol put on an amount written out already, like an abbreviated "1.18T"
+    pub fn around(&self, amount: &str) -> String {
+        let space = if self.spaced { " " } else { "" };
+        match self.position {
+            SymbolPosition::Prefix => format!("{0}{1}{2}", self.symbol, space, amount),
+            SymbolPosition::Suffix => format!("{0}{1}{2}", amount, space, self.symbol),
+        }
+    }
+}
+
+// where instruments are listed, prices are kept in the home currency and only shown in the local one
+#[derive(Debug)]
+pub struct Exchange {
+    pub code: String, // short name for the tabs and the config
+    pub name: String,
+    pub currency_name_plural: String,
+    pub currency: Currency,
+    pub session: Option<MarketSession>, // always open when not set
+    // home currency per unit of the local one, 1 on the home exchange
+    pub fx_rate: f64,
+    pub fx_rate_yesterday: f64,
+    long_run_fx_rate: Option<f64>, // the home currency doesn't move against itself
+}
+
+impl Exchange {
+    pub fn new(
+        code: &str,
+        name: &str,
+        currency_name_plural: &str,
+        currency: Currency,
+        session: Option<MarketSession>,
+    ) -> Exchange {
+        Exchange {
+            code: code.to_string(),
+            name: name.to_string(),
+            currency_name_plural: currency_name_plural.to_string(),
+            currency,
+            session,
+            fx_rate: 1.0,
+            fx_rate_yesterday: 1.0,
+            long_run_fx_rate: None,
+        }
+    }
+
+    // trading in another currency, starting somewhere around the long-run rate
+    pub fn foreign(
+        rng: &mut ThreadRng,
+        code: &str,
+        name: &str,
+        currency_name_plural: &str,
+        currency: Currency,
+        session: Option<MarketSession>,
+        long_run_fx_rate: f64,
+    ) -> Exchange {
+        let fx_rate_yesterday =
+            long_run_fx_rate * rng.random_range(RangeInclusive::new(0.98, 1.02));
+        Exchange {
+            fx_rate: fx_rate_yesterday * rng.random_range(RangeInclusive::new(0.99, 1.01)),
+            fx_rate_yesterday,
+            long_run_fx_rate: Some(long_run_fx_rate),
+            ..Exchange::new(code, name, currency_name_plural, currency, session)
+        }
+    }
+
+    pub fn is_home(&self) -> bool {
+        self.long_run_fx_rate.is_none()
+    }
+
+    pub fn is_open(&self, time: NaiveTime) -> bool {
+        self.session.is_none_or(|session| session.is_open(time))
+    }
+
+    // a home currency price in the local currency
+    pub fn local_price(&self, price: f64) -> f64 {
+        price / self.fx_rate
+    }
+
+    pub fn local_price_yesterday(&self, price_yesterday: f64) -> f64 {
+        price_yesterday / self.fx_rate_yesterday
+    }
+
+    pub fn tick(&mut self, rng: &mut ThreadRng) {
+        let Some(long_run_fx_rate) = self.long_run_fx_rate else {
+            return;
+        };
+        self.fx_rate = walk_rate(rng, self.fx_rate, long_run_fx_rate);
+    }
+}
+
+// an exchange rate one tick on, a random walk pulled back towards where it tends to be
+pub fn walk_rate(rng: &mut ThreadRng, rate: f64, long_run_rate: f64) -> f64 {
+    let walk = rng.random_range(RangeInclusive::new(-FX_VOLATILITY_PCT, FX_VOLATILITY_PCT));
+    let reversion = (long_run_rate - rate) / rate * FX_REVERSION;
+    rate * (1.0 + walk / 100.0 + reversion)
+}
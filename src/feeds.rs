@@ -0,0 +1,216 @@
+use crate::config::FeedConfig;
+use crate::news::{NewsCategory, NewsItem};
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+use ureq::Agent;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+// feed summaries can be whole articles, the news panel only needs a teaser
+const MAX_SUMMARY_CHARS: usize = 200;
+
+pub struct FeedUpdate {
+    pub label: String,
+    // only items not seen in any earlier fetch, newest first
+    pub items: Result<Vec<NewsItem>, String>,
+}
+
+#[derive(Debug, Default)]
+struct FeedEntry {
+    id: String,
+    title: String,
+    summary: String,
+    link: Option<String>,
+}
+
+impl FeedEntry {
+    fn into_news_item(self, label: &str) -> NewsItem {
+        NewsItem {
+            source: Some(label.to_string()),
+            // it goes to the terminal and the browser as is, so only plain web links
+            link: self.link.filter(|link| {
+                (link.starts_with("https://") || link.starts_with("http://"))
+                    && !link.chars().any(char::is_control)
+            }),
+            ..NewsItem::new(
+                self.title.as_str(),
+                summarize(&self.summary).as_str(),
+                NewsCategory::General,
+                0.0,
+                &[],
+            )
+        }
+    }
+}
+
+// polls feeds on a background thread so slow or unreachable hosts never block the UI,
+// the thread exits once the receiver is dropped
+pub fn spawn_feed_fetcher(feeds: Vec<FeedConfig>, refresh: Duration) -> Receiver<FeedUpdate> {
+    let (tx, rx) = mpsc::channel();
+    if feeds.is_empty() {
+        return rx;
+    }
+    thread::spawn(move || {
+        let agent: Agent = Agent::config_builder()
+            .timeout_global(Some(FETCH_TIMEOUT))
+            .build()
+            .into();
+        // links and headlines already published, so stories carried by several feeds
+        // or repeated on every poll only show up once
+        let mut known = HashSet::new();
+        loop {
+            for feed in feeds.iter() {
+                let items = fetch_feed(&agent, &feed.url).map(|entries| {
+                    entries
+                        .into_iter()
+                        .filter(|entry| {
+                            let new_id = known.insert(entry.id.clone());
+                            let new_title = known.insert(entry.title.to_lowercase());
+                            new_id && new_title
+                        })
+                        .map(|entry| entry.into_news_item(&feed.label))
+                        .collect()
+                });
+                let update = FeedUpdate {
+                    label: feed.label.clone(),
+                    items,
+                };
+                if tx.send(update).is_err() {
+                    return;
+                }
+            }
+            thread::sleep(refresh);
+        }
+    });
+    rx
+}
+
+fn fetch_feed(agent: &Agent, url: &str) -> Result<Vec<FeedEntry>, String> {
+    let body = agent
+        .get(url)
+        .call()
+        .and_then(|mut response| response.body_mut().read_to_string())
+        .map_err(|err| err.to_string())?;
+    parse_feed(&body)
+}
+
+// handles both RSS <item> and Atom <entry> elements, everything outside of those is ignored
+fn parse_feed(xml: &str) -> Result<Vec<FeedEntry>, String> {
+    // no text trimming here, it would eat the spaces around entities like &amp;
+    let mut reader = Reader::from_str(xml);
+
+    let mut entries = vec![];
+    let mut current: Option<FeedEntry> = None;
+    let mut field = String::new();
+    loop {
+        let event = reader.read_event().map_err(|err| err.to_string())?;
+        let text = match event {
+            Event::Start(e) => {
+                field = e.local_name().as_ref().to_string();
+                match field.as_str() {
+                    "item" | "entry" => current = Some(FeedEntry::default()),
+                    "link" => take_atom_link(&e, current.as_mut()),
+                    _ => {}
+                }
+                continue;
+            }
+            Event::Empty(e) => {
+                if e.local_name().as_ref() == "link" {
+                    take_atom_link(&e, current.as_mut());
+                }
+                continue;
+            }
+            Event::End(e) => {
+                if matches!(e.local_name().as_ref(), "item" | "entry") {
+                    if let Some(mut entry) = current.take() {
+                        entry.title = entry
+                            .title
+                            .split_whitespace()
+                            .collect::<Vec<&str>>()
+                            .join(" ");
+                        entry.id = entry.id.trim().to_string();
+                        entry.link = entry.link.map(|link| link.trim().to_string());
+                        if entry.id.is_empty() {
+                            entry.id = entry.link.clone().unwrap_or_else(|| entry.title.clone());
+                        }
+                        if !entry.title.is_empty() {
+                            entries.push(entry);
+                        }
+                    }
+                }
+                field.clear();
+                continue;
+            }
+            Event::Text(e) => e.xml10_content().into_owned(),
+            Event::CData(e) => e.xml10_content().into_owned(),
+            Event::GeneralRef(e) => match e.resolve_char_ref() {
+                Ok(Some(c)) => c.to_string(),
+                _ => resolve_predefined_entity(&e)
+                    .unwrap_or_default()
+                    .to_string(),
+            },
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        let Some(entry) = current.as_mut() else {
+            continue;
+        };
+        match field.as_str() {
+            "title" => entry.title.push_str(&text),
+            "description" | "summary" | "content" => entry.summary.push_str(&text),
+            "guid" | "id" => entry.id.push_str(&text),
+            // RSS puts the link in element text, Atom in the href attribute
+            "link" => entry.link.get_or_insert_with(String::new).push_str(&text),
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
+
+fn take_atom_link(e: &BytesStart, entry: Option<&mut FeedEntry>) {
+    let Some(entry) = entry else {
+        return;
+    };
+    let rel = e.try_get_attribute("rel").ok().flatten().and_then(|rel| {
+        rel.normalized_value(XmlVersion::Implicit1_0)
+            .ok()
+            .map(|rel| rel.into_owned())
+    });
+    if rel.is_some_and(|rel| rel != "alternate") {
+        return;
+    }
+    if let Ok(Some(href)) = e.try_get_attribute("href") {
+        if let Ok(href) = href.normalized_value(XmlVersion::Implicit1_0) {
+            entry.link = Some(href.into_owned());
+        }
+    }
+}
+
+// strips markup from HTML summaries and cuts them down to a single short paragraph
+fn summarize(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    if text.chars().count() > MAX_SUMMARY_CHARS {
+        let cut = text.chars().take(MAX_SUMMARY_CHARS).collect::<String>();
+        format!("{0}…", cut.trim_end())
+    } else {
+        text
+    }
+}
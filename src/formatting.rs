@@ -0,0 +1,105 @@
+use crate::config::{FormatRuleConfig, TickerConfig};
+use crate::market::Instrument;
+use crate::screener::Screen;
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::Span;
+use std::str::FromStr;
+
+// a market data row style for quotes matching a screen, like "change < -5" in white on red
+#[derive(Debug)]
+pub struct FormatRule {
+    screen: Screen,
+    style: Style,
+}
+
+impl FormatRule {
+    pub fn parse(config: &FormatRuleConfig) -> Result<FormatRule, String> {
+        let screen = Screen::parse(&config.when)
+            .map_err(|err| format!("format rule \"{0}\": {1}", config.when, err))?;
+        let style = parse_style(&config.style)
+            .map_err(|err| format!("format rule \"{0}\": {1}", config.when, err))?;
+        Ok(FormatRule { screen, style })
+    }
+}
+
+// what a ticker is shown with in the table, the news tags and the charts
+#[derive(Debug, Clone, Default)]
+pub struct Accent {
+    pub color: Option<Color>,
+    pub glyph: Option<String>,
+}
+
+impl Accent {
+    pub fn parse(ticker: &str, config: &TickerConfig) -> Result<Accent, String> {
+        let color = config
+            .color
+            .as_deref()
+            .map(|color| {
+                Color::from_str(color)
+                    .map_err(|_| format!("ticker {0}: \"{1}\" is not a color", ticker, color))
+            })
+            .transpose()?;
+        // any wider and the ticker column would cut the ticker off
+        let glyph = config.glyph.clone().filter(|glyph| !glyph.is_empty());
+        if let Some(glyph) = &glyph {
+            if Span::raw(glyph.as_str()).width() > 2 {
+                return Err(format!(
+                    "ticker {0}: the glyph \"{1}\" is wider than two columns",
+                    ticker, glyph
+                ));
+            }
+        }
+        Ok(Accent { color, glyph })
+    }
+
+    // the ticker behind its glyph, in its color
+    pub fn span<'a>(&self, ticker: &'a str) -> Span<'a> {
+        let span = match &self.glyph {
+            Some(glyph) => Span::raw(format!("{0} {1}", glyph, ticker)),
+            None => Span::raw(ticker),
+        };
+        match self.color {
+            Some(color) => span.fg(color),
+            None => span,
+        }
+    }
+}
+
+// modifiers and a color, then optionally "on" and a background, like "bold yellow" or "white on red"
+fn parse_style(text: &str) -> Result<Style, String> {
+    let mut style = Style::default();
+    let mut background = false;
+    for word in text.split_whitespace() {
+        let modifier = match word {
+            "bold" => Some(Modifier::BOLD),
+            "dim" => Some(Modifier::DIM),
+            "italic" => Some(Modifier::ITALIC),
+            "underlined" => Some(Modifier::UNDERLINED),
+            "reversed" => Some(Modifier::REVERSED),
+            _ => None,
+        };
+        match (word, modifier) {
+            ("on", _) => background = true,
+            (_, Some(modifier)) => style = style.add_modifier(modifier),
+            (color, None) => {
+                let color = Color::from_str(color)
+                    .map_err(|_| format!("\"{0}\" is not a color or modifier", color))?;
+                style = if background {
+                    style.bg(color)
+                } else {
+                    style.fg(color)
+                };
+            }
+        }
+    }
+    Ok(style)
+}
+
+// the style of every matching rule, later ones win where they set the same thing
+pub fn rule_style(rules: &[FormatRule], quote: &Instrument) -> Option<Style> {
+    rules
+        .iter()
+        .filter(|rule| rule.screen.matches(quote))
+        .map(|rule| rule.style)
+        .reduce(|style, next| style.patch(next))
+}
@@ -0,0 +1,459 @@
+use crate::locale::{tr, trf};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use textwrap::core::display_width;
+
+// narrowest the label column gets, so short forms still line up with the lines above them
+const LABEL_WIDTH: usize = 12;
+const VALUE_WIDTH: usize = 12;
+
+// a line of text with a cursor, the search and screen queries are typed into one and so
+// is every text and number field
+#[derive(Debug, Default, Clone)]
+pub struct TextInput {
+    pub text: String,
+    pub cursor: usize, // in characters of the text
+}
+
+impl TextInput {
+    pub fn new(text: &str) -> TextInput {
+        TextInput {
+            text: text.to_string(),
+            cursor: text.chars().count(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn byte_pos(&self, cursor: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(cursor)
+            .map_or(self.text.len(), |(pos, _)| pos)
+    }
+
+    // where the word before the cursor starts, spaces in between are skipped
+    fn word_start(&self) -> usize {
+        let chars = self.text.chars().collect::<Vec<char>>();
+        let mut pos = self.cursor;
+        while pos > 0 && chars[pos - 1].is_whitespace() {
+            pos -= 1;
+        }
+        while pos > 0 && !chars[pos - 1].is_whitespace() {
+            pos -= 1;
+        }
+        pos
+    }
+
+    fn word_end(&self) -> usize {
+        let chars = self.text.chars().collect::<Vec<char>>();
+        let mut pos = self.cursor;
+        while pos < chars.len() && chars[pos].is_whitespace() {
+            pos += 1;
+        }
+        while pos < chars.len() && !chars[pos].is_whitespace() {
+            pos += 1;
+        }
+        pos
+    }
+
+    // the characters between the two cursor positions
+    fn delete(&mut self, from: usize, to: usize) {
+        let range = self.byte_pos(from)..self.byte_pos(to);
+        self.text.replace_range(range, "");
+        self.cursor = from;
+    }
+
+    // at the cursor, a paste of several lines ends up on the one
+    pub fn insert(&mut self, text: &str) {
+        let text = text
+            .chars()
+            .map(|c| if c == '\n' || c == '\t' { ' ' } else { c })
+            .filter(|c| !c.is_control())
+            .collect::<String>();
+        let pos = self.byte_pos(self.cursor);
+        self.text.insert_str(pos, &text);
+        self.cursor += text.chars().count();
+    }
+
+    // the editing keys of a shell prompt, Ctrl or Alt with the arrows and Backspace and
+    // Delete go by words
+    pub fn on_event(&mut self, key: KeyEvent) -> bool {
+        let words = key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Left if words => self.cursor = self.word_start(),
+            KeyCode::Right if words => self.cursor = self.word_end(),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.len(),
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = self.len(),
+            KeyCode::Backspace if words => self.delete(self.word_start(), self.cursor),
+            KeyCode::Char('w') if ctrl => self.delete(self.word_start(), self.cursor),
+            KeyCode::Backspace if self.cursor > 0 => self.delete(self.cursor - 1, self.cursor),
+            KeyCode::Delete if words => {
+                let cursor = self.cursor;
+                self.delete(cursor, self.word_end());
+            }
+            KeyCode::Delete if self.cursor < self.len() => {
+                let cursor = self.cursor;
+                self.delete(cursor, cursor + 1);
+            }
+            KeyCode::Char('u') if ctrl => self.delete(0, self.cursor),
+            KeyCode::Char('k') if ctrl => {
+                let cursor = self.cursor;
+                self.delete(cursor, self.len());
+            }
+            KeyCode::Char(c) if !ctrl => self.insert(&c.to_string()),
+            _ => return false,
+        }
+        true
+    }
+
+    // the character under the cursor in reverse, or a block past the end
+    pub fn spans(&self, style: Style, editing: bool) -> Vec<Span<'static>> {
+        if !editing {
+            return vec![Span::styled(self.text.clone(), style)];
+        }
+        let (before, after) = self.text.split_at(self.byte_pos(self.cursor));
+        let mut chars = after.chars();
+        vec![
+            Span::styled(before.to_string(), style),
+            match chars.next() {
+                Some(c) => Span::styled(c.to_string(), style.reversed()),
+                None => Span::styled("█", style),
+            },
+            Span::styled(chars.as_str().to_string(), style),
+        ]
+    }
+}
+
+pub enum Input {
+    Text,
+    Number {
+        decimals: bool, // whole numbers only without
+        min: f64,
+        max: f64,
+    },
+    Select(&'static [&'static str]),
+    Checkbox,
+}
+
+pub struct Field {
+    pub label: &'static str,
+    pub input: Input,
+    pub value: TextInput, // what's typed into text and number fields
+    pub choice: usize,    // index into the options of a select, 1 for a ticked checkbox
+    pub placeholder: Option<&'static str>, // empty is allowed and means this
+    pub spin: Option<(f64, f64)>, // step of Up and Down, and where they start from when empty
+}
+
+impl Field {
+    pub fn text(label: &'static str) -> Field {
+        Field {
+            label,
+            input: Input::Text,
+            value: TextInput::default(),
+            choice: 0,
+            placeholder: None,
+            spin: None,
+        }
+    }
+
+    // positive, any size
+    pub fn number(label: &'static str, decimals: bool) -> Field {
+        Field {
+            input: Input::Number {
+                decimals,
+                min: if decimals { f64::MIN_POSITIVE } else { 1.0 },
+                max: f64::INFINITY,
+            },
+            ..Field::text(label)
+        }
+    }
+
+    pub fn select(label: &'static str, options: &'static [&'static str], choice: usize) -> Field {
+        Field {
+            input: Input::Select(options),
+            choice,
+            ..Field::text(label)
+        }
+    }
+
+    pub fn checkbox(label: &'static str, checked: bool) -> Field {
+        Field {
+            input: Input::Checkbox,
+            choice: checked as usize,
+            ..Field::text(label)
+        }
+    }
+
+    // both ends included
+    pub fn within(mut self, min: f64, max: f64) -> Field {
+        if let Input::Number { decimals, .. } = self.input {
+            self.input = Input::Number { decimals, min, max };
+        }
+        self
+    }
+
+    pub fn optional(mut self, placeholder: &'static str) -> Field {
+        self.placeholder = Some(placeholder);
+        self
+    }
+
+    // a number field Up and Down count up and down by the step, Shift ten steps at a time
+    pub fn spinner(mut self, step: f64, start: f64) -> Field {
+        self.spin = Some((step, start));
+        self
+    }
+
+    pub fn filled(mut self, text: &str) -> Field {
+        self.value = TextInput::new(text);
+        self
+    }
+
+    // only what could still become a valid number goes into a number field
+    fn accepts(&self, c: char) -> bool {
+        match self.input {
+            Input::Text => !c.is_control(),
+            Input::Number { decimals, .. } => {
+                c.is_ascii_digit() || (decimals && c == '.' && !self.value.text.contains('.'))
+            }
+            Input::Select(_) | Input::Checkbox => false,
+        }
+    }
+
+    fn check(&self) -> Result<(), String> {
+        let Input::Number { decimals, min, max } = self.input else {
+            return Ok(());
+        };
+        if self.value.text.is_empty() && self.placeholder.is_some() {
+            return Ok(());
+        }
+        match self.value.text.parse::<f64>() {
+            Ok(value) if value >= min && value <= max => Ok(()),
+            _ if max.is_finite() => Err(trf(
+                "{0} must be a number from {1} to {2}",
+                &[&tr(self.label), &min, &max],
+            )),
+            _ if decimals => Err(trf("{0} must be a positive number", &[&tr(self.label)])),
+            _ => Err(trf(
+                "{0} must be a positive whole number",
+                &[&tr(self.label)],
+            )),
+        }
+    }
+
+    // onto the nearest step and within the bounds, shown with as many decimals as the step has
+    fn spin(&mut self, steps: f64) {
+        let (Input::Number { min, max, .. }, Some((step, start))) = (&self.input, self.spin) else {
+            return;
+        };
+        let value = self.value.text.parse::<f64>().unwrap_or(start);
+        let value = ((value / step).round() + steps) * step;
+        // the smallest step above zero for fields that have to be positive
+        let min = if *min > 0.0 { min.max(step) } else { *min };
+        let decimals = (-step.log10()).ceil().max(0.0) as usize;
+        self.value = TextInput::new(&format!("{0:.1$}", value.clamp(min, *max), decimals));
+    }
+
+    fn on_event(&mut self, key: KeyEvent) -> bool {
+        let steps = if key.modifiers.contains(KeyModifiers::SHIFT) {
+            10.0
+        } else {
+            1.0
+        };
+        match (&self.input, key.code) {
+            (Input::Number { .. }, KeyCode::Up) if self.spin.is_some() => self.spin(steps),
+            (Input::Number { .. }, KeyCode::Down) if self.spin.is_some() => self.spin(-steps),
+            (Input::Select(options), KeyCode::Left) => {
+                self.choice = (self.choice + options.len() - 1) % options.len()
+            }
+            (Input::Select(options), KeyCode::Right | KeyCode::Char(' ')) => {
+                self.choice = (self.choice + 1) % options.len()
+            }
+            (Input::Checkbox, KeyCode::Char(' ')) => self.choice = 1 - self.choice,
+            (Input::Select(_) | Input::Checkbox, _) => return false,
+            (_, KeyCode::Char(c))
+                if !key.modifiers.contains(KeyModifiers::CONTROL) && !self.accepts(c) =>
+            {
+                return false
+            }
+            _ => return self.value.on_event(key),
+        }
+        true
+    }
+
+    // what doesn't fit the field is left out, so a pasted "1,000" still makes a number
+    fn paste(&mut self, text: &str) -> bool {
+        let mut pasted = false;
+        for c in text.trim().chars() {
+            if self.accepts(c) {
+                self.value.insert(&c.to_string());
+                pasted = true;
+            }
+        }
+        pasted
+    }
+
+    fn value_spans(&self, focused: bool) -> Vec<Span<'static>> {
+        let style = Style::default().fg(Color::White).bg(if focused {
+            Color::DarkGray
+        } else {
+            Color::Reset
+        });
+        let typed = matches!(self.input, Input::Text | Input::Number { .. });
+        let mut spans = match self.input {
+            Input::Select(options) if focused => vec![Span::styled(
+                format!("‹ {0} ›", tr(options[self.choice])),
+                style,
+            )],
+            Input::Select(options) => vec![Span::styled(
+                format!("  {0}", tr(options[self.choice])),
+                style,
+            )],
+            Input::Checkbox if self.choice == 1 => vec![Span::styled("[x]", style)],
+            Input::Checkbox => vec![Span::styled("[ ]", style)],
+            _ => self.value.spans(style, focused),
+        };
+        let width = spans
+            .iter()
+            .map(|span| display_width(&span.content))
+            .sum::<usize>();
+        spans.push(Span::styled(
+            " ".repeat(VALUE_WIDTH.saturating_sub(width)),
+            style,
+        ));
+        if let Some(placeholder) = self
+            .placeholder
+            .filter(|_| typed && self.value.text.is_empty())
+        {
+            spans.push(format!(" {0}", tr(placeholder)).fg(Color::Gray).italic());
+        }
+        spans
+    }
+}
+
+// fields under each other, Tab and the arrows move between them, the error of the last
+// submit shows at the bottom until the next edit
+pub struct Form {
+    pub fields: Vec<Field>,
+    pub focus: usize,
+    pub error: Option<String>,
+}
+
+impl Form {
+    pub fn new(fields: Vec<Field>) -> Form {
+        Form {
+            fields,
+            focus: 0,
+            error: None,
+        }
+    }
+
+    // Enter and Esc are left to whoever opened the form
+    pub fn on_event(&mut self, key: KeyEvent) -> bool {
+        let count = self.fields.len();
+        // on a spinner Up and Down are taken by the field
+        match key.code {
+            KeyCode::Tab => self.focus = (self.focus + 1) % count,
+            KeyCode::BackTab => self.focus = (self.focus + count - 1) % count,
+            _ if self.fields[self.focus].on_event(key) => self.error = None,
+            KeyCode::Down => self.focus = (self.focus + 1) % count,
+            KeyCode::Up => self.focus = (self.focus + count - 1) % count,
+            _ => return false,
+        }
+        true
+    }
+
+    // into the focused field
+    pub fn paste(&mut self, text: &str) {
+        if self.fields[self.focus].paste(text) {
+            self.error = None;
+        }
+    }
+
+    // the first field that doesn't pass gets the focus
+    pub fn validate(&mut self) -> Result<(), String> {
+        for (idx, field) in self.fields.iter().enumerate() {
+            if let Err(err) = field.check() {
+                self.focus = idx;
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    // after validate, None for an optional field left empty
+    pub fn number(&self, idx: usize) -> Option<f64> {
+        self.fields.get(idx)?.value.text.parse().ok()
+    }
+
+    pub fn choice(&self, idx: usize) -> usize {
+        self.fields[idx].choice
+    }
+
+    pub fn checked(&self, idx: usize) -> bool {
+        self.fields[idx].choice == 1
+    }
+
+    pub fn lines(&self) -> Vec<Line<'static>> {
+        let label_width = self
+            .fields
+            .iter()
+            .map(|field| display_width(tr(field.label)) + 1)
+            .fold(LABEL_WIDTH, usize::max);
+        let mut lines = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                let label = tr(field.label);
+                let pad = label_width - display_width(label);
+                let mut spans = vec![format!("{0}{1}", label, " ".repeat(pad)).fg(Color::Gray)];
+                spans.extend(field.value_spans(idx == self.focus));
+                Line::from(spans)
+            })
+            .collect::<Vec<Line>>();
+        if let Some(err) = &self.error {
+            lines.push(Line::from(""));
+            lines.push(Line::styled(err.clone(), Style::default().fg(Color::Red)));
+        }
+        lines
+    }
+
+    // for screen readers, every field with its value on one line
+    pub fn summary(&self) -> String {
+        self.fields
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                let value = match field.input {
+                    Input::Select(options) => tr(options[field.choice]),
+                    Input::Checkbox if field.choice == 1 => tr("on"),
+                    Input::Checkbox => tr("off"),
+                    _ if field.value.text.is_empty() => tr("empty"),
+                    _ => field.value.text.as_str(),
+                };
+                format!(
+                    "{0} {1}{2}",
+                    tr(field.label),
+                    value,
+                    if idx == self.focus {
+                        tr(" (editing)")
+                    } else {
+                        ""
+                    }
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
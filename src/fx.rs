@@ -0,0 +1,153 @@
+use crate::exchanges::{walk_rate, Currency, Exchange, SymbolPosition};
+use crate::forms::Form;
+use crate::orders::Side;
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use std::ops::RangeInclusive;
+
+// rates kept for the charts, the oldest are dropped past this
+const HISTORY_TICKS: usize = 300;
+// charged on the rate both ways when cash is changed
+pub const FX_SPREAD_PCT: f64 = 0.25;
+
+// a currency of the FX market, in home currency per unit like an exchange's fx_rate
+#[derive(Debug)]
+pub struct FxCurrency {
+    pub code: String, // "SOV"
+    pub name: String, // "Sovereigns"
+    pub currency: Currency,
+    pub rate: f64,
+    pub rate_yesterday: f64,
+    pub history: Vec<f64>,   // the rate after every tick, oldest first
+    exchange: Option<usize>, // the exchange quoting in it, whose rate it follows
+    long_run_rate: f64,
+}
+
+impl FxCurrency {
+    fn of_exchange(idx: usize, exchange: &Exchange) -> FxCurrency {
+        FxCurrency {
+            code: exchange
+                .currency_name_plural
+                .chars()
+                .take(3)
+                .collect::<String>()
+                .to_uppercase(),
+            name: exchange.currency_name_plural.clone(),
+            currency: exchange.currency.clone(),
+            rate: exchange.fx_rate,
+            rate_yesterday: exchange.fx_rate_yesterday,
+            history: vec![exchange.fx_rate],
+            exchange: Some(idx),
+            long_run_rate: exchange.fx_rate,
+        }
+    }
+
+    // one no exchange quotes in, walking on its own from somewhere around the long-run rate
+    fn floating(
+        rng: &mut ThreadRng,
+        code: &str,
+        name: &str,
+        currency: Currency,
+        long_run_rate: f64,
+    ) -> FxCurrency {
+        let rate_yesterday = long_run_rate * rng.random_range(RangeInclusive::new(0.98, 1.02));
+        let rate = rate_yesterday * rng.random_range(RangeInclusive::new(0.99, 1.01));
+        FxCurrency {
+            code: code.to_string(),
+            name: name.to_string(),
+            currency,
+            rate,
+            rate_yesterday,
+            history: vec![rate],
+            exchange: None,
+            long_run_rate,
+        }
+    }
+
+    pub fn is_home(&self) -> bool {
+        self.exchange == Some(0)
+    }
+
+    // what cash is changed at, with the spread on top of buys and off sales
+    pub fn price(&self, side: Side) -> f64 {
+        match side {
+            Side::Buy => self.rate * (1.0 + FX_SPREAD_PCT / 100.0),
+            Side::Sell => self.rate * (1.0 - FX_SPREAD_PCT / 100.0),
+        }
+    }
+
+    pub fn change_pct(&self) -> f64 {
+        (self.rate - self.rate_yesterday) / self.rate_yesterday * 100.0
+    }
+}
+
+#[derive(Debug)]
+pub struct FxMarket {
+    pub currencies: Vec<FxCurrency>, // the home currency first, then the other exchanges' ones
+}
+
+impl FxMarket {
+    pub fn new(rng: &mut ThreadRng, exchanges: &[Exchange]) -> FxMarket {
+        let mut currencies = exchanges
+            .iter()
+            .enumerate()
+            .map(|(idx, exchange)| FxCurrency::of_exchange(idx, exchange))
+            .collect::<Vec<FxCurrency>>();
+        currencies.extend([
+            FxCurrency::floating(
+                rng,
+                "STG",
+                "Sterling Gears",
+                Currency::new("§", SymbolPosition::Prefix, false),
+                2.4,
+            ),
+            FxCurrency::floating(
+                rng,
+                "AEC",
+                "Aether Crowns",
+                Currency::new("₳", SymbolPosition::Suffix, true),
+                0.35,
+            ),
+        ]);
+        FxMarket { currencies }
+    }
+
+    // after the exchanges, whose currencies move with them
+    pub fn tick(&mut self, rng: &mut ThreadRng, exchanges: &[Exchange]) {
+        for currency in self.currencies.iter_mut() {
+            currency.rate = match currency.exchange {
+                Some(idx) => exchanges[idx].fx_rate,
+                None => walk_rate(rng, currency.rate, currency.long_run_rate),
+            };
+            currency.history.push(currency.rate);
+            let excess = currency.history.len().saturating_sub(HISTORY_TICKS);
+            currency.history.drain(..excess);
+        }
+    }
+
+    pub fn find(&self, code: &str) -> Option<&FxCurrency> {
+        self.currencies
+            .iter()
+            .find(|currency| currency.code == code)
+    }
+
+    // units of the quote currency one unit of the base one buys
+    pub fn cross(&self, base: usize, quote: usize) -> f64 {
+        self.currencies[base].rate / self.currencies[quote].rate
+    }
+}
+
+// the cross rates and charts, the cursor is on one of the foreign currencies
+pub struct FxView {
+    pub cursor: usize, // index into the FX market's currencies, never the home one
+    pub form: Option<(Side, Form)>, // buying or selling the one under the cursor
+}
+
+impl Default for FxView {
+    fn default() -> Self {
+        FxView {
+            cursor: 1,
+            form: None,
+        }
+    }
+}
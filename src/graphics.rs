@@ -0,0 +1,309 @@
+use crate::config::GraphicsSetting;
+use crate::history::Candle;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use crossterm::cursor::MoveTo;
+use crossterm::queue;
+use crossterm::terminal::window_size;
+use plotters::prelude::*;
+use ratatui::layout::Rect;
+use ratatui::style::Color as TerminalColor;
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, Write};
+
+// one image at a time, the candle chart of the open instrument
+const KITTY_IMAGE_ID: u32 = 1;
+// kitty wants the payload in chunks of at most this many bytes
+const KITTY_CHUNK: usize = 4096;
+// painted first and left transparent, nothing in the chart uses it
+const TRANSPARENT: RGBColor = RGBColor(255, 0, 255);
+const UP_COLOR: RGBColor = RGBColor(80, 200, 120);
+const DOWN_COLOR: RGBColor = RGBColor(220, 80, 80);
+const CROSSHAIR_COLOR: RGBColor = RGBColor(110, 110, 110);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GraphicsProtocol {
+    Kitty,
+    Sixel,
+}
+
+// querying the terminal means reading its answer from stdin between key presses,
+// so go by what terminals advertise in the environment instead
+pub fn detect_graphics(setting: GraphicsSetting) -> Option<GraphicsProtocol> {
+    let term = env::var("TERM").unwrap_or_default();
+    let program = env::var("TERM_PROGRAM").unwrap_or_default();
+    match setting {
+        GraphicsSetting::Off => None,
+        GraphicsSetting::Kitty => Some(GraphicsProtocol::Kitty),
+        GraphicsSetting::Sixel => Some(GraphicsProtocol::Sixel),
+        // tmux and screen swallow both unless specially configured
+        GraphicsSetting::Auto if env::var_os("TMUX").is_some() || term.starts_with("screen") => {
+            None
+        }
+        GraphicsSetting::Auto
+            if env::var_os("KITTY_WINDOW_ID").is_some()
+                || term == "xterm-kitty"
+                || term == "xterm-ghostty"
+                || program == "WezTerm"
+                || program == "ghostty" =>
+        {
+            Some(GraphicsProtocol::Kitty)
+        }
+        GraphicsSetting::Auto
+            if term.contains("sixel")
+                || term.starts_with("foot")
+                || term.starts_with("mlterm")
+                || term.starts_with("yaft")
+                || term == "contour" =>
+        {
+            Some(GraphicsProtocol::Sixel)
+        }
+        GraphicsSetting::Auto => None,
+    }
+}
+
+// pixels per terminal cell, unknown when the terminal doesn't report its size in pixels
+pub fn cell_pixels() -> Option<(u32, u32)> {
+    let size = window_size().ok()?;
+    if size.width == 0 || size.height == 0 || size.columns == 0 || size.rows == 0 {
+        return None;
+    }
+    Some((
+        (size.width / size.columns) as u32,
+        (size.height / size.rows) as u32,
+    ))
+}
+
+// the candles the price chart shows, laid out like the character version
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartImage {
+    pub area: Rect,
+    pub candles: Vec<Candle>,
+    pub candle_width: u16, // in cells, candles are a cell apart
+    pub low: f64,
+    pub high: f64,
+    pub selected: Option<usize>,
+    pub up_color: TerminalColor,
+    pub down_color: TerminalColor,
+}
+
+impl ChartImage {
+    // RGB pixels, TRANSPARENT where nothing is drawn
+    fn render(&self, (cell_width, cell_height): (u32, u32)) -> (u32, u32, Vec<u8>) {
+        let width = self.area.width as u32 * cell_width;
+        let height = self.area.height as u32 * cell_height;
+        let mut pixels = vec![0; (width * height * 3) as usize];
+        {
+            let root = BitMapBackend::with_buffer(&mut pixels, (width, height)).into_drawing_area();
+            let _ = root.fill(&TRANSPARENT);
+            let y_of = |price: f64| {
+                if self.high > self.low {
+                    ((self.high - price) / (self.high - self.low) * (height - 1) as f64).round()
+                        as i32
+                } else {
+                    height as i32 / 2
+                }
+            };
+            let stride = (self.candle_width as u32 + 1) * cell_width;
+            let body_width = self.candle_width as u32 * cell_width;
+            let wick_width = (cell_width / 4).max(1);
+            let x_of = |idx: usize| (idx as u32 * stride) as i32;
+            if let Some(idx) = self.selected {
+                let x = x_of(idx) + body_width as i32 / 2;
+                let y = y_of(self.candles[idx].close);
+                let _ = root.draw(&PathElement::new(
+                    vec![(x, 0), (x, height as i32)],
+                    CROSSHAIR_COLOR,
+                ));
+                let _ = root.draw(&PathElement::new(
+                    vec![(0, y), (width as i32, y)],
+                    CROSSHAIR_COLOR,
+                ));
+            }
+            let up_color = rgb(self.up_color, UP_COLOR);
+            let down_color = rgb(self.down_color, DOWN_COLOR);
+            for (idx, candle) in self.candles.iter().enumerate() {
+                let color = if candle.is_up() { up_color } else { down_color };
+                let x = x_of(idx);
+                let wick_x = x + (body_width - wick_width) as i32 / 2;
+                let _ = root.draw(&Rectangle::new(
+                    [
+                        (wick_x, y_of(candle.high)),
+                        (wick_x + wick_width as i32 - 1, y_of(candle.low)),
+                    ],
+                    color.filled(),
+                ));
+                // a flat candle still gets a line of body
+                let top = y_of(candle.open.max(candle.close));
+                let bottom = y_of(candle.open.min(candle.close)).max(top + 1);
+                let _ = root.draw(&Rectangle::new(
+                    [(x + 1, top), (x + body_width as i32 - 2, bottom)],
+                    color.filled(),
+                ));
+            }
+            let _ = root.present();
+        }
+        (width, height, pixels)
+    }
+}
+
+// the terminal decides what the named colors look like, those get our own shade
+fn rgb(color: TerminalColor, named: RGBColor) -> RGBColor {
+    match color {
+        TerminalColor::Rgb(red, green, blue) => RGBColor(red, green, blue),
+        _ => named,
+    }
+}
+
+fn is_transparent(pixel: &[u8]) -> bool {
+    pixel == [TRANSPARENT.0, TRANSPARENT.1, TRANSPARENT.2]
+}
+
+fn kitty(width: u32, height: u32, pixels: &[u8], area: Rect) -> Vec<u8> {
+    let rgba = pixels
+        .chunks(3)
+        .flat_map(|pixel| {
+            let alpha = if is_transparent(pixel) { 0 } else { 255 };
+            [pixel[0], pixel[1], pixel[2], alpha]
+        })
+        .collect::<Vec<u8>>();
+    let payload = STANDARD.encode(rgba);
+    let chunks = payload.as_bytes().chunks(KITTY_CHUNK).collect::<Vec<_>>();
+    let mut out = format!("\x1b_Ga=d,d=I,i={0},q=2\x1b\\", KITTY_IMAGE_ID).into_bytes();
+    for (idx, chunk) in chunks.iter().enumerate() {
+        let more = (idx + 1 < chunks.len()) as u8;
+        if idx == 0 {
+            out.extend(
+                format!(
+                    "\x1b_Ga=T,f=32,s={0},v={1},i={2},c={3},r={4},C=1,q=2,m={5};",
+                    width, height, KITTY_IMAGE_ID, area.width, area.height, more
+                )
+                .bytes(),
+            );
+        } else {
+            out.extend(format!("\x1b_Gm={0};", more).bytes());
+        }
+        out.extend_from_slice(chunk);
+        out.extend_from_slice(b"\x1b\\");
+    }
+    out
+}
+
+// six pixel rows per band, one pass over the band for each color, transparent pixels left alone
+fn sixel(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    let mut palette: HashMap<[u8; 3], usize> = HashMap::new();
+    for pixel in pixels.chunks(3) {
+        if !is_transparent(pixel) {
+            let next = palette.len();
+            palette
+                .entry([pixel[0], pixel[1], pixel[2]])
+                .or_insert(next);
+        }
+    }
+    let mut out = format!("\x1bP0;1;0q\"1;1;{0};{1}", width, height);
+    let mut colors = palette.iter().collect::<Vec<_>>();
+    colors.sort_by_key(|(_, idx)| **idx);
+    for (rgb, idx) in colors.iter() {
+        let percent = |value: u8| value as u32 * 100 / 255;
+        out.push_str(&format!(
+            "#{0};2;{1};{2};{3}",
+            idx,
+            percent(rgb[0]),
+            percent(rgb[1]),
+            percent(rgb[2])
+        ));
+    }
+    for band in (0..height).step_by(6) {
+        for (rgb, idx) in colors.iter() {
+            let columns = (0..width)
+                .map(|x| {
+                    (0..6)
+                        .filter(|row| band + row < height)
+                        .filter(|row| {
+                            let at = (((band + row) * width + x) * 3) as usize;
+                            pixels[at..at + 3] == rgb[..]
+                        })
+                        .fold(0u8, |bits, row| bits | 1 << row)
+                })
+                .collect::<Vec<u8>>();
+            if columns.iter().all(|bits| *bits == 0) {
+                continue;
+            }
+            out.push_str(&format!("#{0}", idx));
+            let mut x = 0;
+            while x < columns.len() {
+                let run = columns[x..]
+                    .iter()
+                    .take_while(|bits| **bits == columns[x])
+                    .count();
+                let symbol = (b'?' + columns[x]) as char;
+                if run > 3 {
+                    out.push_str(&format!("!{0}{1}", run, symbol));
+                } else {
+                    out.extend(std::iter::repeat_n(symbol, run));
+                }
+                x += run;
+            }
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out.into_bytes()
+}
+
+// what was last put on screen, images are only resent when the chart changes
+#[derive(Debug)]
+pub struct GraphicsOutput {
+    pub protocol: GraphicsProtocol,
+    shown: Option<ChartImage>,
+}
+
+impl GraphicsOutput {
+    pub fn new(protocol: GraphicsProtocol) -> GraphicsOutput {
+        GraphicsOutput {
+            protocol,
+            shown: None,
+        }
+    }
+
+    // called after every frame, returns true when the screen has to be redrawn from scratch
+    // to get rid of an image that can't be deleted
+    pub fn show(&mut self, image: Option<ChartImage>) -> io::Result<bool> {
+        if image == self.shown {
+            return Ok(false);
+        }
+        let mut stdout = io::stdout();
+        let mut redraw = false;
+        match (&image, self.protocol, cell_pixels()) {
+            (Some(image), protocol, Some(cell)) => {
+                let (width, height, pixels) = image.render(cell);
+                let bytes = match protocol {
+                    GraphicsProtocol::Kitty => kitty(width, height, &pixels, image.area),
+                    GraphicsProtocol::Sixel => {
+                        // sixel pixels stay until overwritten, blank the cells under the old chart first
+                        for row in image.area.top()..image.area.bottom() {
+                            queue!(stdout, MoveTo(image.area.x, row))?;
+                            stdout.write_all(" ".repeat(image.area.width as usize).as_bytes())?;
+                        }
+                        sixel(width, height, &pixels)
+                    }
+                };
+                queue!(stdout, MoveTo(image.area.x, image.area.y))?;
+                stdout.write_all(&bytes)?;
+            }
+            _ => match self.protocol {
+                GraphicsProtocol::Kitty => {
+                    stdout.write_all(
+                        format!("\x1b_Ga=d,d=I,i={0},q=2\x1b\\", KITTY_IMAGE_ID).as_bytes(),
+                    )?;
+                }
+                GraphicsProtocol::Sixel => redraw = true,
+            },
+        }
+        stdout.flush()?;
+        self.shown = image;
+        Ok(redraw)
+    }
+}
@@ -0,0 +1,351 @@
+use chrono::{DateTime, DurationRound, NaiveDate, TimeDelta, Utc};
+use std::collections::VecDeque;
+
+// the returns and the ranges go by candles of this many ticks
+pub const CANDLE_TICKS: usize = 10;
+// backfilled days are kept with the recorded ones, this is plenty
+pub const MAX_BACKFILL_DAYS: u32 = 3650;
+
+// what the chart puts in one candle, made from the history when it's drawn
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Timeframe {
+    Tick,
+    #[default]
+    Minute,
+    FiveMinutes,
+    Day,
+}
+
+impl Timeframe {
+    pub fn next(self) -> Timeframe {
+        match self {
+            Timeframe::Tick => Timeframe::Minute,
+            Timeframe::Minute => Timeframe::FiveMinutes,
+            Timeframe::FiveMinutes => Timeframe::Day,
+            Timeframe::Day => Timeframe::Tick,
+        }
+    }
+}
+
+// how long single ticks and minute bars are kept before only the coarser bars are left,
+// daily bars are kept for good
+#[derive(Debug, Clone, Copy)]
+pub struct Retention {
+    pub ticks: TimeDelta,
+    pub minutes: TimeDelta,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Retention {
+            ticks: TimeDelta::hours(1),
+            minutes: TimeDelta::days(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Tick {
+    pub price: f64,
+    pub volume: u64,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candle {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: u64,
+    pub opened: DateTime<Utc>,
+}
+
+impl Candle {
+    fn new(price: f64, volume: u64, opened: DateTime<Utc>) -> Candle {
+        Candle {
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume,
+            opened,
+        }
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.close >= self.open
+    }
+
+    fn add(&mut self, price: f64, volume: u64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.volume += volume;
+    }
+}
+
+// prices since start, oldest first, the ticks go into minute and daily bars as they come in
+// and drop out after the retention so long runs stay the same size
+#[derive(Debug, Clone, Default)]
+pub struct PriceHistory {
+    pub ticks: VecDeque<Tick>, // dropped from the front as they expire
+    pub minutes: VecDeque<Candle>,
+    pub days: Vec<Candle>,     // by the UTC day, backfilled ones too
+    pub previous: Option<f64>, // the price a tick ago
+    pub retention: Retention,
+}
+
+impl PriceHistory {
+    pub fn record(&mut self, price: f64, volume: u64) {
+        self.record_at(price, volume, Utc::now());
+    }
+
+    fn record_at(&mut self, price: f64, volume: u64, now: DateTime<Utc>) {
+        self.previous = self.ticks.back().map(|tick| tick.price);
+        self.ticks.push_back(Tick {
+            price,
+            volume,
+            at: now,
+        });
+        let minute = now.duration_trunc(TimeDelta::minutes(1)).unwrap_or(now);
+        match self.minutes.back_mut() {
+            Some(candle) if candle.opened == minute => candle.add(price, volume),
+            _ => self.minutes.push_back(Candle::new(price, volume, minute)),
+        }
+        let day = day_opened(now.date_naive());
+        match self.days.last_mut() {
+            Some(candle) if candle.opened == day => candle.add(price, volume),
+            _ => self.days.push(Candle::new(price, volume, day)),
+        }
+        let tick_cutoff = now - self.retention.ticks;
+        while self.ticks.front().is_some_and(|tick| tick.at < tick_cutoff) {
+            self.ticks.pop_front();
+        }
+        let minute_cutoff = now - self.retention.minutes;
+        while self
+            .minutes
+            .front()
+            .is_some_and(|candle| candle.opened < minute_cutoff)
+        {
+            self.minutes.pop_front();
+        }
+    }
+
+    // the highest and lowest price since then, from minute bars for the part the single ticks
+    // don't go back to anymore
+    pub fn range_since(&self, since: DateTime<Utc>) -> Option<(f64, f64)> {
+        let first_tick = self.ticks.front().map(|tick| tick.at);
+        let minutes = self
+            .minutes
+            .iter()
+            .filter(|candle| {
+                candle.opened >= since && first_tick.is_none_or(|at| candle.opened < at)
+            })
+            .flat_map(|candle| [candle.high, candle.low]);
+        let ticks = self
+            .ticks
+            .iter()
+            .filter(|tick| tick.at >= since)
+            .map(|tick| tick.price);
+        minutes
+            .chain(ticks)
+            .fold(None, |range, price| Some(widen(range, price)))
+    }
+
+    // made-up daily bars of the days before the first recorded one, oldest first
+    pub fn backfill(&mut self, days: Vec<Candle>) {
+        let recorded = std::mem::replace(&mut self.days, days);
+        self.days.extend(recorded);
+    }
+
+    // the price at the time, from the finest bars that still go back that far
+    pub fn price_at(&self, time: DateTime<Utc>) -> Option<f64> {
+        if self.ticks.front().is_some_and(|tick| tick.at <= time) {
+            let idx = self.ticks.partition_point(|tick| tick.at < time);
+            return self
+                .ticks
+                .get(idx)
+                .or(self.ticks.back())
+                .map(|tick| tick.price);
+        }
+        if self
+            .minutes
+            .front()
+            .is_some_and(|candle| candle.opened <= time)
+        {
+            let idx = self.minutes.partition_point(|candle| candle.opened < time);
+            return self
+                .minutes
+                .get(idx)
+                .or(self.minutes.back())
+                .map(|candle| candle.open);
+        }
+        let idx = self.days.partition_point(|candle| candle.opened < time);
+        self.days
+            .get(idx)
+            .or(self.days.last())
+            .map(|candle| candle.open)
+    }
+
+    // the single ticks as candles of ten, counted back from the latest so it stays whole
+    pub fn candles(&self) -> Vec<Candle> {
+        merge(&self.resampled(Timeframe::Tick), CANDLE_TICKS)
+    }
+
+    // percent change from each candle's close to the next one's
+    pub fn returns(&self) -> Vec<f64> {
+        self.candles()
+            .windows(2)
+            .map(|pair| (pair[1].close - pair[0].close) / pair[0].close * 100.0)
+            .collect()
+    }
+
+    // every tick a candle of its own from the price before, minute bars as they are or five
+    // at a time, or the daily bars
+    pub fn resampled(&self, timeframe: Timeframe) -> Vec<Candle> {
+        match timeframe {
+            Timeframe::Tick => {
+                let mut previous = None;
+                self.ticks
+                    .iter()
+                    .map(|tick| {
+                        let mut candle = Candle::new(previous.unwrap_or(tick.price), 0, tick.at);
+                        candle.add(tick.price, tick.volume);
+                        previous = Some(tick.price);
+                        candle
+                    })
+                    .collect()
+            }
+            Timeframe::Minute => self.minutes.iter().copied().collect(),
+            Timeframe::FiveMinutes => self
+                .minutes
+                .iter()
+                .copied()
+                .collect::<Vec<Candle>>()
+                .chunk_by(|a, b| {
+                    a.opened.timestamp().div_euclid(5 * 60)
+                        == b.opened.timestamp().div_euclid(5 * 60)
+                })
+                .map(combine)
+                .collect(),
+            Timeframe::Day => self.days.clone(),
+        }
+    }
+}
+
+// a high and low that takes the price in too
+pub fn widen(range: Option<(f64, f64)>, price: f64) -> (f64, f64) {
+    match range {
+        Some((high, low)) => (high.max(price), low.min(price)),
+        None => (price, price),
+    }
+}
+
+// midnight UTC of the day, where its daily bar opens
+pub fn day_opened(day: NaiveDate) -> DateTime<Utc> {
+    day.and_time(Default::default()).and_utc()
+}
+
+// every `per` candles combined into one, counted back from the latest so it stays whole
+pub fn merge(candles: &[Candle], per: usize) -> Vec<Candle> {
+    if per <= 1 {
+        return candles.to_vec();
+    }
+    let mut merged = candles.rchunks(per).map(combine).collect::<Vec<Candle>>();
+    merged.reverse();
+    merged
+}
+
+fn combine(chunk: &[Candle]) -> Candle {
+    Candle {
+        open: chunk[0].open,
+        high: chunk
+            .iter()
+            .map(|candle| candle.high)
+            .fold(f64::MIN, f64::max),
+        low: chunk
+            .iter()
+            .map(|candle| candle.low)
+            .fold(f64::MAX, f64::min),
+        close: chunk[chunk.len() - 1].close,
+        volume: chunk.iter().map(|candle| candle.volume).sum(),
+        opened: chunk[0].opened,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start() -> DateTime<Utc> {
+        day_opened(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap())
+    }
+
+    // a price every ten minutes for a day and a half, going up by one each time from zero
+    fn recorded() -> (PriceHistory, DateTime<Utc>) {
+        let mut history = PriceHistory::default();
+        let mut at = start();
+        for price in 0..216 {
+            at = start() + TimeDelta::minutes(10 * price);
+            history.record_at(price as f64, 1, at);
+        }
+        (history, at)
+    }
+
+    #[test]
+    fn ticks_are_kept_for_the_hour_up_to_the_one_exactly_an_hour_old() {
+        let mut history = PriceHistory::default();
+        history.record_at(1.0, 1, start());
+        history.record_at(2.0, 1, start() + TimeDelta::hours(1));
+        assert_eq!(history.ticks.len(), 2);
+        history.record_at(
+            3.0,
+            1,
+            start() + TimeDelta::hours(1) + TimeDelta::seconds(1),
+        );
+        let prices = history
+            .ticks
+            .iter()
+            .map(|tick| tick.price)
+            .collect::<Vec<f64>>();
+        assert_eq!(prices, [2.0, 3.0]);
+    }
+
+    #[test]
+    fn minute_bars_are_kept_for_a_day_and_daily_bars_for_good() {
+        let (history, now) = recorded();
+        assert_eq!(history.ticks.front().unwrap().at, now - TimeDelta::hours(1));
+        assert_eq!(
+            history.minutes.front().unwrap().opened,
+            now - TimeDelta::days(1)
+        );
+        assert_eq!(history.minutes.len(), 24 * 6 + 1);
+        let days = history
+            .days
+            .iter()
+            .map(|candle| (candle.opened, candle.open, candle.close))
+            .collect::<Vec<(DateTime<Utc>, f64, f64)>>();
+        assert_eq!(
+            days,
+            [
+                (start(), 0.0, 143.0),
+                (start() + TimeDelta::days(1), 144.0, 215.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn a_lookup_falls_back_from_ticks_to_minute_bars_to_daily_bars() {
+        let (history, now) = recorded();
+        // still in the ticks, the first one at or after the time
+        assert_eq!(history.price_at(now - TimeDelta::minutes(35)), Some(212.0));
+        // in the minute bars, the ticks only go back an hour
+        assert_eq!(history.price_at(now - TimeDelta::hours(5)), Some(185.0));
+        // before the minute bars, the next day opened after it
+        assert_eq!(history.price_at(start() + TimeDelta::hours(1)), Some(144.0));
+        assert_eq!(history.price_at(start() - TimeDelta::days(1)), Some(0.0));
+        // after the latest tick it's the latest price
+        assert_eq!(history.price_at(now + TimeDelta::hours(1)), Some(215.0));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::app::AppState;
+use crate::config::HooksConfig;
+use crate::news::NewsCategory;
+use crate::orders::BlotterEntry;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use std::io::Write;
+use std::panic;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// how often a running hook is checked on
+const WAIT_STEP: Duration = Duration::from_millis(50);
+// a fall of this many percent in a single tick counts as a crash, like a scripted one
+const CRASH_PCT: f64 = 10.0;
+
+// a shell command with the event as JSON on stdin, killed when it runs past the timeout
+fn run_hook(command: &str, event: &Value, timeout: Duration) {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let Ok(mut child) = shell
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return;
+    };
+    // stdin is closed when it's dropped, so the hook sees the end of the event
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = writeln!(stdin, "{0}", event);
+    }
+    let started = Instant::now();
+    while started.elapsed() < timeout {
+        match child.try_wait() {
+            Ok(None) => thread::sleep(WAIT_STEP),
+            _ => return,
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+// watches for the events there are hooks for, the hooks run one after another on a thread
+// of their own so drawing never waits for them
+pub struct Hooks {
+    config: HooksConfig,
+    tx: Option<Sender<(String, Value)>>,
+    last_fill: DateTime<Utc>,    // fills from a loaded save are older
+    last_trigger: DateTime<Utc>, // and alerts from the saved log
+    last_news: DateTime<Utc>,    // the news made up at the start is older
+    ticks: u64,                  // the last tick the prices were looked at on
+    open_exchanges: Vec<bool>,
+}
+
+impl Hooks {
+    pub fn new(config: HooksConfig, app_state: &AppState) -> Hooks {
+        let timeout = Duration::from_secs(config.timeout_secs);
+        let tx = (config.on_fill.is_some()
+            || config.on_session_open.is_some()
+            || config.on_alert.is_some()
+            || config.on_crash_event.is_some())
+        .then(|| {
+            let (tx, rx) = mpsc::channel::<(String, Value)>();
+            thread::spawn(move || {
+                for (command, event) in rx {
+                    run_hook(&command, &event, timeout);
+                }
+            });
+            tx
+        });
+        Hooks {
+            config,
+            tx,
+            last_fill: Utc::now(),
+            last_trigger: Utc::now(),
+            last_news: Utc::now(),
+            ticks: app_state.ticks,
+            open_exchanges: app_state.open_exchanges(),
+        }
+    }
+
+    fn fire(&self, command: &Option<String>, event: Value) {
+        if let (Some(command), Some(tx)) = (command, &self.tx) {
+            let _ = tx.send((command.clone(), event));
+        }
+    }
+
+    // fills, alerts, session opens and crashes since the last check
+    pub fn check(&mut self, app_state: &AppState) {
+        // the blotter is oldest first, so the new fills are at the end
+        let mut fills = app_state
+            .blotter
+            .iter()
+            .rev()
+            .filter_map(|entry| match entry {
+                BlotterEntry::Fill(fill) => Some(fill),
+                _ => None,
+            })
+            .take_while(|fill| fill.time > self.last_fill)
+            .collect::<Vec<_>>();
+        fills.reverse();
+        for fill in fills.iter() {
+            self.fire(&self.config.on_fill, json!({"event": "fill", "fill": fill}));
+        }
+        if let Some(fill) = fills.last() {
+            self.last_fill = fill.time;
+        }
+        // so is the alerts log, the big moves that were flagged go in it too
+        let mut triggers = app_state
+            .alerts
+            .triggers
+            .iter()
+            .rev()
+            .take_while(|trigger| trigger.at > self.last_trigger)
+            .collect::<Vec<_>>();
+        triggers.reverse();
+        for trigger in triggers.iter() {
+            self.fire(
+                &self.config.on_alert,
+                json!({"event": "alert", "trigger": trigger}),
+            );
+        }
+        if let Some(trigger) = triggers.last() {
+            self.last_trigger = trigger.at;
+        }
+        let open_exchanges = app_state.open_exchanges();
+        for (idx, exchange) in app_state.exchanges.iter().enumerate() {
+            if open_exchanges[idx] && !self.open_exchanges[idx] {
+                self.fire(
+                    &self.config.on_session_open,
+                    json!({
+                        "event": "session_open",
+                        "exchange": exchange.code,
+                        "name": exchange.name,
+                    }),
+                );
+            }
+        }
+        self.open_exchanges = open_exchanges;
+        self.check_crashes(app_state);
+    }
+
+    // disasters in the news and whatever fell hard on a tick since the last check
+    fn check_crashes(&mut self, app_state: &AppState) {
+        let news = app_state
+            .news
+            .iter()
+            .take_while(|news_item| news_item.published > self.last_news)
+            .collect::<Vec<_>>();
+        for news_item in news.iter().rev() {
+            if news_item.category == NewsCategory::Disaster {
+                self.fire(
+                    &self.config.on_crash_event,
+                    json!({
+                        "event": "crash",
+                        "title": news_item.title,
+                        "subtitle": news_item.subtitle,
+                        "tickers": news_item.tickers,
+                        "sentiment": news_item.sentiment,
+                    }),
+                );
+            }
+        }
+        if let Some(news_item) = news.first() {
+            self.last_news = news_item.published;
+        }
+        if app_state.ticks == self.ticks {
+            return;
+        }
+        self.ticks = app_state.ticks;
+        if self.config.on_crash_event.is_none() {
+            return;
+        }
+        for quote in app_state.quotes.iter() {
+            let Some(previous) = app_state
+                .before_tick
+                .get(quote.listing.ticker.as_str())
+                .filter(|price| **price > 0.0)
+            else {
+                continue;
+            };
+            let change = (quote.quote.price / previous - 1.0) * 100.0;
+            if change <= -CRASH_PCT {
+                self.fire(
+                    &self.config.on_crash_event,
+                    json!({
+                        "event": "crash",
+                        "ticker": quote.listing.ticker,
+                        "change_pct": change,
+                        "price": quote.quote.price,
+                    }),
+                );
+            }
+        }
+    }
+
+    // on a panic, after the terminal is restored, and waited for since the process is going down
+    pub fn install_panic_hook(&self) {
+        let Some(command) = self.config.on_panic.clone() else {
+            return;
+        };
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            previous(info);
+            run_hook(
+                &command,
+                &json!({"event": "panic", "message": info.to_string()}),
+                timeout,
+            );
+        }));
+    }
+}
@@ -0,0 +1,548 @@
+use crate::app::MarketDataActivePanel;
+use crate::locale::tr;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// where a binding works: anywhere in normal mode, or only with its panel focused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Context {
+    Global,
+    Market,
+    Orders,
+    News,
+}
+
+impl Context {
+    pub const ALL: [Context; 4] = [
+        Context::Market,
+        Context::Orders,
+        Context::News,
+        Context::Global,
+    ];
+
+    pub fn of(panel: MarketDataActivePanel) -> Context {
+        match panel {
+            MarketDataActivePanel::MarketData => Context::Market,
+            MarketDataActivePanel::Orders => Context::Orders,
+            MarketDataActivePanel::LatestNews => Context::News,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Context::Global => "Anywhere",
+            Context::Market => "Market data",
+            Context::Orders => "Orders",
+            Context::News => "Latest news",
+        }
+    }
+
+    // a global key works with every panel focused, so it can't be a panel's too
+    fn overlaps(self, other: Context) -> bool {
+        self == other || self == Context::Global || other == Context::Global
+    }
+}
+
+// what a key is bound to in normal mode, the keys of dialogs and views stay with them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Details,
+    Buy,
+    Sell,
+    RowMenu,
+    Screen,
+    MarkCompare,
+    Options,
+    ByCompany,
+    Liquidate,
+    Modify,
+    CancelOrder,
+    ClearHistory,
+    Jump,
+    Search,
+    OpenLink,
+    PrevPanel,
+    NextPanel,
+    Compare,
+    Categories,
+    Archive,
+    Unread,
+    Tutorial,
+    Achievements,
+    Alerts,
+    Movers,
+    Rebalance,
+    Schedules,
+    Ledger,
+    Accounts,
+    Fx,
+    Connection,
+    Panels,
+    Record,
+    Play,
+    Undo,
+    Redo,
+    UndoFill,
+    Palette,
+    Settings,
+    Saves,
+    Help,
+    Report,
+    Quit,
+}
+
+// every action there is with its keys out of the box, in the order the hints and the help show them
+const BINDINGS: &[(Context, Action, &[&str])] = &[
+    (Context::Market, Action::Details, &["Enter"]),
+    (Context::Market, Action::Buy, &["b"]),
+    (Context::Market, Action::Sell, &["s"]),
+    (Context::Market, Action::RowMenu, &["m", "M"]),
+    (Context::Market, Action::Screen, &["/"]),
+    (Context::Market, Action::MarkCompare, &["c"]),
+    (Context::Market, Action::Options, &["o", "O"]),
+    (Context::Market, Action::ByCompany, &["n"]),
+    (Context::Market, Action::Liquidate, &["X"]),
+    (Context::Orders, Action::Modify, &["e", "E", "Enter"]),
+    (Context::Orders, Action::CancelOrder, &["x", "X"]),
+    (Context::Orders, Action::ClearHistory, &["D"]),
+    (Context::News, Action::Jump, &["Enter"]),
+    (Context::News, Action::Search, &["/"]),
+    (Context::News, Action::OpenLink, &["o", "O"]),
+    (Context::Global, Action::PrevPanel, &["Left"]),
+    (Context::Global, Action::NextPanel, &["Right"]),
+    (Context::Global, Action::Compare, &["C"]),
+    (Context::Global, Action::Categories, &["F", "f"]),
+    (Context::Global, Action::Archive, &["A", "a"]),
+    (Context::Global, Action::Unread, &["N"]),
+    (Context::Global, Action::Tutorial, &["t", "T"]),
+    (Context::Global, Action::Achievements, &["g", "G"]),
+    (Context::Global, Action::Alerts, &["!"]),
+    (Context::Global, Action::Movers, &["^"]),
+    (Context::Global, Action::Rebalance, &["B"]),
+    (Context::Global, Action::Schedules, &["W"]),
+    (Context::Global, Action::Ledger, &["$"]),
+    (Context::Global, Action::Accounts, &["w"]),
+    (Context::Global, Action::Fx, &["K"]),
+    (Context::Global, Action::Connection, &["L", "l"]),
+    (Context::Global, Action::Panels, &["v", "V"]),
+    (Context::Global, Action::Record, &["Ctrl-R"]),
+    (Context::Global, Action::Play, &["@"]),
+    (Context::Global, Action::Undo, &["Ctrl-Z"]),
+    (Context::Global, Action::Redo, &["Ctrl-Y"]),
+    (Context::Global, Action::UndoFill, &["u", "U"]),
+    (Context::Global, Action::Palette, &["p", "P"]),
+    (Context::Global, Action::Settings, &[","]),
+    (Context::Global, Action::Saves, &["S"]),
+    (Context::Global, Action::Help, &["?"]),
+    (Context::Global, Action::Report, &["R"]),
+    (Context::Global, Action::Quit, &["Esc", "Q", "q"]),
+];
+
+// what the panels do with keys themselves before the bindings get them, and the counts
+// for @, nothing can be bound to these where they apply
+const RESERVED: &[(Context, &[&str])] = &[
+    (
+        Context::Global,
+        &[
+            "Up", "Down", "0", "1", "2", "3", "4", "5", "6", "7", "8", "9",
+        ],
+    ),
+    (Context::Market, &["Tab", "e", "E", "Space", "%"]),
+    (Context::Orders, &["h", "H"]),
+];
+
+// a key as the bindings know it, Shift is in the character already and Alt is left out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    code: KeyCode,
+    ctrl: bool,
+}
+
+impl Key {
+    pub fn of(event: &KeyEvent) -> Key {
+        Key {
+            code: event.code,
+            ctrl: event.modifiers.contains(KeyModifiers::CONTROL),
+        }
+    }
+
+    // like "q", "Esc", "Ctrl-R" or "PageDown", the way they're written in the config
+    pub fn parse(text: &str) -> Result<Key, String> {
+        let (ctrl, name) = match text.get(..5) {
+            Some(prefix) if text.len() > 5 && prefix.eq_ignore_ascii_case("ctrl-") => {
+                (true, &text[5..])
+            }
+            _ => (false, text),
+        };
+        let code = match name.to_lowercase().as_str() {
+            "esc" => KeyCode::Esc,
+            "enter" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            lower => match (name.chars().next(), name.chars().count()) {
+                // Ctrl and a letter comes as the lowercase letter
+                (Some(c), 1) if ctrl => KeyCode::Char(c.to_ascii_lowercase()),
+                (Some(c), 1) => KeyCode::Char(c),
+                _ => match lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key \"{0}\"", text)),
+                },
+            },
+        };
+        Ok(Key { code, ctrl })
+    }
+
+    // for the hints and the help
+    pub fn label(self) -> String {
+        let name = match self.code {
+            KeyCode::Char(' ') => tr("Space").to_string(),
+            KeyCode::Char(c) if self.ctrl => c.to_ascii_uppercase().to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::F(n) => format!("F{0}", n),
+            code => format!("{0:?}", code),
+        };
+        if self.ctrl {
+            format!("{0}-{1}", tr("Ctrl"), name)
+        } else {
+            name
+        }
+    }
+}
+
+// one key or several for an action in the [keys] of the config
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyList::One(key) => vec![key.as_str()],
+            KeyList::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+// context to key to action, what the keys do, the status bar says and the help lists, all from
+// the one table and whatever the config changes in it
+pub struct Keymap {
+    keys: Vec<(Action, Vec<Key>)>, // table order, the keys in the order they were given
+    actions: HashMap<Context, HashMap<Key, Action>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::new(&HashMap::new()).expect("the default bindings conflict")
+    }
+}
+
+impl Keymap {
+    // the defaults with the [keys] of the config over them, an action given keys there has only
+    // those, and two actions on the same key where both would apply is an error
+    pub fn new(remapped: &HashMap<String, KeyList>) -> Result<Keymap, String> {
+        if let Some(name) = remapped
+            .keys()
+            .find(|name| !BINDINGS.iter().any(|(_, action, _)| action.name() == *name))
+        {
+            return Err(format!("unknown action \"{0}\" in [keys]", name));
+        }
+        let keys = BINDINGS
+            .iter()
+            .map(|(_, action, keys)| {
+                let names = remapped
+                    .get(action.name())
+                    .map_or_else(|| keys.to_vec(), KeyList::keys);
+                let keys = names
+                    .into_iter()
+                    .map(Key::parse)
+                    .collect::<Result<Vec<Key>, String>>()
+                    .map_err(|err| format!("{0} for {1} in [keys]", err, action.name()))?;
+                Ok((*action, keys))
+            })
+            .collect::<Result<Vec<(Action, Vec<Key>)>, String>>()?;
+
+        let mut taken: Vec<(Context, Key, &str)> = RESERVED
+            .iter()
+            .flat_map(|(context, names)| {
+                names.iter().map(|name| {
+                    let key = Key::parse(name).expect("a reserved key that doesn't parse");
+                    (*context, key, "the panels")
+                })
+            })
+            .collect();
+        let mut actions: HashMap<Context, HashMap<Key, Action>> = HashMap::new();
+        for ((context, _, _), (action, keys)) in BINDINGS.iter().zip(&keys) {
+            for key in keys {
+                if let Some((_, _, other)) = taken
+                    .iter()
+                    .find(|(bound, taken, _)| taken == key && bound.overlaps(*context))
+                {
+                    return Err(format!(
+                        "{0} is bound to both {1} and {2} in [keys]",
+                        key.label(),
+                        action.name(),
+                        other
+                    ));
+                }
+                taken.push((*context, *key, action.name()));
+                actions.entry(*context).or_default().insert(*key, *action);
+            }
+        }
+        Ok(Keymap { keys, actions })
+    }
+
+    // the focused panel's bindings, then the ones that work anywhere
+    pub fn action(&self, context: Context, event: &KeyEvent) -> Option<Action> {
+        let key = Key::of(event);
+        [context, Context::Global]
+            .iter()
+            .find_map(|context| self.actions.get(context)?.get(&key))
+            .copied()
+    }
+
+    pub fn keys(&self, action: Action) -> &[Key] {
+        self.keys
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map_or(&[], |(_, keys)| keys.as_slice())
+    }
+
+    // "Esc/Q - Quit", a letter bound in both cases shows once, nothing for an action without keys
+    pub fn hint(&self, action: Action, label: &'static str) -> Option<String> {
+        let mut labels: Vec<String> = vec![];
+        for key in self.keys(action) {
+            let label = key.label();
+            if !labels
+                .iter()
+                .any(|shown| shown.eq_ignore_ascii_case(&label))
+            {
+                labels.push(label);
+            }
+        }
+        (!labels.is_empty()).then(|| format!("{0} - {1}", labels.join("/"), tr(label)))
+    }
+
+    // the actions of the focused panel and then the global ones, that the status bar has room for
+    pub fn footer(&self, context: Context) -> Vec<Action> {
+        [context, Context::Global]
+            .iter()
+            .flat_map(|context| {
+                BINDINGS
+                    .iter()
+                    .filter(move |(bound, action, _)| bound == context && action.in_footer())
+                    .map(|(_, action, _)| *action)
+            })
+            .collect()
+    }
+
+    // every action of the context, for the help
+    pub fn all(&self, context: Context) -> Vec<Action> {
+        BINDINGS
+            .iter()
+            .filter(|(bound, _, _)| *bound == context)
+            .map(|(_, action, _)| *action)
+            .collect()
+    }
+}
+
+impl Action {
+    // in the [keys] of the config
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Details => "details",
+            Action::Buy => "buy",
+            Action::Sell => "sell",
+            Action::RowMenu => "actions",
+            Action::Screen => "screen",
+            Action::MarkCompare => "mark-compare",
+            Action::Options => "options",
+            Action::ByCompany => "by-company",
+            Action::Liquidate => "liquidate",
+            Action::Modify => "modify",
+            Action::CancelOrder => "cancel-order",
+            Action::ClearHistory => "clear-history",
+            Action::Jump => "jump",
+            Action::Search => "search",
+            Action::OpenLink => "open-link",
+            Action::PrevPanel => "previous-panel",
+            Action::NextPanel => "next-panel",
+            Action::Compare => "compare",
+            Action::Categories => "categories",
+            Action::Archive => "archive",
+            Action::Unread => "unread",
+            Action::Tutorial => "tutorial",
+            Action::Achievements => "achievements",
+            Action::Alerts => "alerts",
+            Action::Movers => "movers",
+            Action::Rebalance => "rebalance",
+            Action::Schedules => "schedules",
+            Action::Ledger => "ledger",
+            Action::Accounts => "accounts",
+            Action::Fx => "fx",
+            Action::Connection => "connection",
+            Action::Panels => "panels",
+            Action::Record => "record",
+            Action::Play => "play",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::UndoFill => "undo-fill",
+            Action::Palette => "palette",
+            Action::Settings => "settings",
+            Action::Saves => "saves",
+            Action::Help => "help",
+            Action::Report => "report",
+            Action::Quit => "quit",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Details => "Details",
+            Action::Buy => "Buy Order",
+            Action::Sell => "Sell Order",
+            Action::RowMenu => "Actions",
+            Action::Screen => "Screen",
+            Action::MarkCompare => "Mark to Compare",
+            Action::Options => "Options",
+            Action::ByCompany => "By Company",
+            Action::Liquidate => "Sell Whole Position",
+            Action::Modify => "Modify",
+            Action::CancelOrder => "Cancel Order",
+            Action::ClearHistory => "Clear History",
+            Action::Jump => "Jump",
+            Action::Search => "Search",
+            Action::OpenLink => "Open Link",
+            Action::PrevPanel => "Previous Panel",
+            Action::NextPanel => "Next Panel",
+            Action::Compare => "Compare",
+            Action::Categories => "Categories",
+            Action::Archive => "Archive",
+            Action::Unread => "Unread",
+            Action::Tutorial => "Tutorial",
+            Action::Achievements => "Achievements",
+            Action::Alerts => "Alerts",
+            Action::Movers => "Movers",
+            Action::Rebalance => "Rebalance",
+            Action::Schedules => "Schedules",
+            Action::Ledger => "Cash Ledger",
+            Action::Accounts => "Accounts",
+            Action::Fx => "Currencies",
+            Action::Connection => "Connection",
+            Action::Panels => "Panels",
+            Action::Record => "Record",
+            Action::Play => "Play",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::UndoFill => "Undo Last Fill",
+            Action::Palette => "Palette",
+            Action::Settings => "Settings",
+            Action::Saves => "Saves",
+            Action::Help => "Help",
+            Action::Report => "Report",
+            Action::Quit => "Quit",
+        }
+    }
+
+    // the help lists them all, the status bar only those it has room for
+    fn in_footer(self) -> bool {
+        !matches!(self, Action::Liquidate | Action::Fx | Action::UndoFill)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remapped(bindings: &[(&str, &str)]) -> HashMap<String, KeyList> {
+        bindings
+            .iter()
+            .map(|(action, key)| (action.to_string(), KeyList::One(key.to_string())))
+            .collect()
+    }
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn keys_parse_the_way_the_config_writes_them() {
+        let parsed = |text| Key::parse(text).unwrap();
+        assert_eq!(parsed("q").code, KeyCode::Char('q'));
+        assert_eq!(parsed("Q").code, KeyCode::Char('Q'));
+        assert_eq!(parsed("PageDown").code, KeyCode::PageDown);
+        assert_eq!(parsed("space").code, KeyCode::Char(' '));
+        assert_eq!(parsed("F12").code, KeyCode::F(12));
+        // Ctrl and a letter comes as the lowercase letter
+        let ctrl_r = parsed("Ctrl-R");
+        assert_eq!((ctrl_r.code, ctrl_r.ctrl), (KeyCode::Char('r'), true));
+    }
+
+    #[test]
+    fn unknown_key_names_dont_parse() {
+        for text in ["", "Hyper", "F13", "F0", "Ctrl-", "Ctrl-Hyper"] {
+            let err = Key::parse(text).unwrap_err();
+            assert!(err.contains("unknown key"), "{0}", err);
+        }
+    }
+
+    #[test]
+    fn a_remapped_action_has_only_the_keys_given() {
+        let keymap = Keymap::new(&remapped(&[("report", "Ctrl-P")])).unwrap();
+        assert_eq!(keymap.keys(Action::Report), [Key::parse("Ctrl-P").unwrap()]);
+        let ctrl_p = press(KeyCode::Char('p'), KeyModifiers::CONTROL);
+        assert_eq!(
+            keymap.action(Context::Market, &ctrl_p),
+            Some(Action::Report)
+        );
+        let r = press(KeyCode::Char('R'), KeyModifiers::SHIFT);
+        assert_eq!(keymap.action(Context::Market, &r), None);
+    }
+
+    #[test]
+    fn an_unknown_action_is_an_error() {
+        let err = Keymap::new(&remapped(&[("launch", "L")])).err().unwrap();
+        assert_eq!(err, "unknown action \"launch\" in [keys]");
+    }
+
+    #[test]
+    fn an_unparsable_key_names_the_action() {
+        let err = Keymap::new(&remapped(&[("report", "Hyper-R")]))
+            .err()
+            .unwrap();
+        assert_eq!(err, "unknown key \"Hyper-R\" for report in [keys]");
+    }
+
+    #[test]
+    fn a_key_bound_twice_is_an_error() {
+        // quit keeps its q
+        let err = Keymap::new(&remapped(&[("report", "q")])).err().unwrap();
+        assert!(err.starts_with("q is bound to both"), "{0}", err);
+        assert!(err.contains("report") && err.contains("quit"), "{0}", err);
+    }
+
+    #[test]
+    fn a_reserved_key_cant_be_bound() {
+        let err = Keymap::new(&remapped(&[("report", "5")])).err().unwrap();
+        assert_eq!(err, "5 is bound to both report and the panels in [keys]");
+        // Tab is only the market panel's, but a binding that works anywhere works there too
+        assert!(Keymap::new(&remapped(&[("report", "Tab")])).is_err());
+    }
+}
@@ -0,0 +1,57 @@
+pub mod achievements;
+pub mod alerts;
+pub mod analysts;
+pub mod app;
+pub mod benchmark;
+pub mod book;
+#[cfg(feature = "market-breadth")]
+pub mod breadth;
+pub mod components;
+pub mod config;
+pub mod connection;
+pub mod control;
+pub mod daemon;
+pub mod difficulty;
+pub mod distress;
+pub mod exchanges;
+pub mod feeds;
+pub mod formatting;
+pub mod forms;
+pub mod fx;
+pub mod graphics;
+pub mod history;
+pub mod hooks;
+pub mod keymap;
+pub mod locale;
+pub mod macros;
+pub mod market;
+pub mod mergers;
+pub mod mode;
+pub mod news;
+pub mod numbers;
+pub mod options;
+pub mod orders;
+pub mod overlay;
+pub mod ownership;
+pub mod panels;
+pub mod portfolio;
+pub mod rebalance;
+pub mod recording;
+pub mod replay;
+pub mod report;
+pub mod saves;
+pub mod scenario;
+pub mod schedules;
+pub mod screener;
+pub mod snapshot;
+pub mod terminal;
+#[doc(hidden)]
+pub mod testing;
+pub mod theme;
+pub mod timefmt;
+pub mod tutorial;
+pub mod ui;
+pub mod undo;
+#[cfg(feature = "londinium-weather")]
+pub mod weather;
+pub mod worker;
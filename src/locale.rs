@@ -0,0 +1,627 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+// the language of the interface, simulated headlines and company profiles stay in English
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "de")]
+    German,
+}
+
+impl Language {
+    // from a locale like "de_DE.UTF-8", English for anything without a translation
+    pub fn from_locale(locale: &str) -> Language {
+        match locale.to_lowercase().get(..2) {
+            Some("de") => Language::German,
+            _ => Language::English,
+        }
+    }
+
+    pub fn detect() -> Language {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .map_or(Language::English, |locale| Language::from_locale(&locale))
+    }
+
+    fn catalog(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => &[],
+            Language::German => GERMAN,
+        }
+    }
+}
+
+static TRANSLATIONS: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+// once at startup, before anything is drawn
+pub fn set_language(language: Language) {
+    let _ = TRANSLATIONS.set(language.catalog().iter().copied().collect());
+    let _ = LANGUAGE.set(language);
+}
+
+// for what goes by more than the translations, like number separators
+pub fn language() -> Language {
+    LANGUAGE.get().copied().unwrap_or_default()
+}
+
+// the English text is the key, and what's shown when there is no translation
+pub fn tr(text: &'static str) -> &'static str {
+    TRANSLATIONS
+        .get()
+        .and_then(|translations| translations.get(text))
+        .copied()
+        .unwrap_or(text)
+}
+
+// a translated template with {0}, {1}, ... filled in, formatting happens before
+pub fn trf(template: &'static str, args: &[&dyn Display]) -> String {
+    args.iter()
+        .enumerate()
+        .fold(tr(template).to_string(), |text, (idx, arg)| {
+            text.replace(&format!("{{{0}}}", idx), &arg.to_string())
+        })
+}
+
+const GERMAN: &[(&str, &str)] = &[
+    // labels
+    ("Buy", "Kauf"),
+    ("Sell", "Verk."),
+    ("Hold", "Halten"),
+    ("Equities", "Aktien"),
+    ("Commodities", "Rohstoffe"),
+    ("Bonds", "Anleihen"),
+    ("Crypto", "Krypto"),
+    ("Earnings", "Zahlen"),
+    ("M&A", "Fusionen"),
+    ("Politics", "Politik"),
+    ("Technology", "Technik"),
+    ("Disaster", "Katastrophe"),
+    ("General", "Allgemein"),
+    ("connected", "verbunden"),
+    ("degraded", "gestört"),
+    ("disconnected", "getrennt"),
+    ("Easy", "Leicht"),
+    ("Normal", "Normal"),
+    ("Brutal", "Brutal"),
+    ("going concern doubts", "Zweifel am Fortbestand"),
+    ("missed debt payment", "Zahlung versäumt"),
+    ("in administration", "in Insolvenz"),
+    ("Initiates", "Beginnt"),
+    ("Upgrades", "Hebt an"),
+    ("Downgrades", "Senkt"),
+    ("Reiterates", "Bestätigt"),
+    ("Call", "Call"),
+    ("Put", "Put"),
+    ("just now", "gerade eben"),
+    ("{0}m ago", "vor {0}m"),
+    ("{0}h ago", "vor {0}h"),
+    ("{0}d ago", "vor {0}d"),
+    // market table
+    ("Ticker", "Kürzel"),
+    ("Name", "Name"),
+    ("Price", "Kurs"),
+    ("Change%", "Änd.%"),
+    ("Change", "Änd."),
+    ("{0} shown", "{0} angezeigt"),
+    ("avg", "Schnitt"),
+    ("cap", "Börsenwert"),
+    ("Volume", "Umsatz"),
+    ("Mkt Cap", "Börsenw."),
+    ("Rating", "Urteil"),
+    ("Description", "Beschreibung"),
+    ("Exchange", "Börse"),
+    ("market closed", "Börse geschlossen"),
+    ("watching", "Beobachtung"),
+    ("{0} · Prices in {1}", "{0} · Kurse in {1}"),
+    ("Stale · {0}s", "Veraltet · {0}s"),
+    ("Distressed · {0}", "Notleidend · {0}"),
+    ("Yield {0}%", "Rendite {0}%"),
+    // news
+    ("Latest news", "Aktuelle Nachrichten"),
+    ("{0} offline", "{0} nicht erreichbar"),
+    ("{0} unread", "{0} ungelesen"),
+    ("{0} unread news", "{0} ungelesene Nachrichten"),
+    ("no unread news", "keine ungelesenen Nachrichten"),
+    ("Show categories", "Kategorien anzeigen"),
+    (
+        "News archive · page {0}/{1} · {2} items",
+        "Nachrichtenarchiv · Seite {0}/{1} · {2} Meldungen",
+    ),
+    ("No news matches", "Keine passenden Nachrichten"),
+    // orders
+    ("Orders · {0} working", "Aufträge · {0} offen"),
+    (
+        " · last fill #{0} {1} {2} {3} @ {4}, {5}",
+        " · letzte Ausführung #{0} {1} {2} {3} @ {4}, {5}",
+    ),
+    (" · history", " · Verlauf"),
+    ("Stop armed", "Stop aktiv"),
+    ("Partial", "Teilweise"),
+    ("Working", "Offen"),
+    ("Filled", "Ausgeführt"),
+    ("Cancelled: {0}", "Storniert: {0}"),
+    (" · bracket", " · Klammer"),
+    ("stop {0}", "Stop {0}"),
+    ("market", "bestens"),
+    ("Time", "Zeit"),
+    ("Side", "Art"),
+    ("Filled/Qty", "Ausgef./Stk"),
+    ("Left", "Offen"),
+    ("Limit/Stop", "Limit/Stop"),
+    ("Avg fill", "Ø Kurs"),
+    ("Status", "Status"),
+    (
+        "No orders yet, press b or s on a market row",
+        "Noch keine Aufträge, b oder s in einer Marktzeile drücken",
+    ),
+    ("Fill", "Ausf."),
+    (
+        "{0} {1} {2} @ {3}, fee {4}",
+        "{0} {1} {2} @ {3}, Gebühr {4}",
+    ),
+    ("Amend", "Änder."),
+    (
+        "qty {0} → {1}, limit {2} → {3}",
+        "Stück {0} → {1}, Limit {2} → {3}",
+    ),
+    ("Cancel", "Storno"),
+    ("cancelled {0}", "storniert {0}"),
+    ("Nothing has happened yet", "Bisher ist nichts passiert"),
+    (
+        "Cancel #{0} {1} {2} {3} @ {4}?",
+        "#{0} {1} {2} {3} @ {4} stornieren?",
+    ),
+    (
+        "Sell all {0} {1} at market?",
+        "Alle {0} {1} zum Marktpreis verkaufen?",
+    ),
+    (
+        "Clear all {0} entries of the history? Fills can't be undone after.",
+        "Alle {0} Einträge des Verlaufs löschen? Ausführungen lassen sich danach nicht mehr rückgängig machen.",
+    ),
+    (
+        "Start over with fresh cash and the starting prices? Anything not saved is lost.",
+        "Mit frischem Kapital und den Anfangskursen neu beginnen? Nicht Gespeichertes geht verloren.",
+    ),
+    ("Sell whole position", "Ganze Position verkaufen"),
+    ("Clear history", "Verlauf löschen"),
+    ("New game", "Neues Spiel"),
+    ("Cancel order", "Auftrag stornieren"),
+    (
+        "{0} {1} {2} @ {3} · u to undo ({4}s)",
+        "{0} {1} {2} @ {3} · u macht rückgängig ({4}s)",
+    ),
+    ("Bought", "Gekauft"),
+    ("Sold", "Verkauft"),
+    // order form
+    ("Holding", "Bestand"),
+    ("Quantity", "Stückzahl"),
+    ("Limit", "Limit"),
+    ("Take profit", "Gewinnziel"),
+    ("Stop loss", "Stop-Loss"),
+    ("none", "keine"),
+    (
+        "{0} must be a number from {1} to {2}",
+        "{0} muss eine Zahl von {1} bis {2} sein",
+    ),
+    ("{0} must be a positive number", "{0} muss eine positive Zahl sein"),
+    (
+        "{0} must be a positive whole number",
+        "{0} muss eine positive ganze Zahl sein",
+    ),
+    ("Palette", "Farben"),
+    ("Standard", "Standard"),
+    ("High contrast", "Hoher Kontrast"),
+    ("Colorblind", "Farbenblind"),
+    ("Zebra stripes", "Zebrastreifen"),
+    ("Row separators", "Zeilenlinien"),
+    ("Cell padding", "Zellabstand"),
+    ("Modify #{0} {1} {2}", "#{0} ändern, {1} {2}"),
+    // portfolio
+    ("Portfolio · {0} · {1}", "Depot · {0} · {1}"),
+    ("Cash", "Bargeld"),
+    ("Qty", "Stück"),
+    ("Avg cost", "Ø Kosten"),
+    ("P&L", "G/V"),
+    // achievements, saves, report
+    ("Achievement unlocked", "Erfolg freigeschaltet"),
+    ("g to view", "g zum Ansehen"),
+    ("locked", "gesperrt"),
+    ("Achievements · {0}/{1}", "Erfolge · {0}/{1}"),
+    ("Name: ", "Name: "),
+    ("+ New save", "+ Neuer Spielstand"),
+    ("No saved games yet", "Noch keine Spielstände"),
+    ("Saved games", "Spielstände"),
+    ("Session report · {0}", "Sitzungsbericht · {0}"),
+    ("Portfolio value", "Depotwert"),
+    ("Portfolio", "Depot"),
+    ("Composite index", "Gesamtindex"),
+    (
+        "Best and worst positions",
+        "Beste und schlechteste Positionen",
+    ),
+    (
+        "No positions this session",
+        "Keine Positionen in dieser Sitzung",
+    ),
+    ("Written to {0}", "Gespeichert unter {0}"),
+    // connection
+    ("Connection", "Verbindung"),
+    ("State", "Zustand"),
+    ("Uptime", "Verbunden"),
+    ("Latency", "Latenz"),
+    ("Updates", "Updates"),
+    ("Received", "Empfangen"),
+    ("Reconnects", "Neustarts"),
+    ("down for {0}", "getrennt seit {0}"),
+    ("{0} · {1} lost", "{0} · {1} verloren"),
+    ("Sources", "Quellen"),
+    ("step {0}/{1}", "Schritt {0}/{1}"),
+    ("Errors", "Fehler"),
+    // tutorial, options, comparison
+    ("Tutorial {0}/{1}", "Einführung {0}/{1}"),
+    ("Expires in ", "Verfall in "),
+    ("{0} ticks", "{0} Ticks"),
+    ("Strike", "Basis"),
+    ("Options · {0} · spot {1}", "Optionen · {0} · Kurs {1}"),
+    (
+        "{0} {1} {2}, one contract is one share",
+        "{0} {1} {2}, ein Kontrakt ist eine Aktie",
+    ),
+    ("Comparison · % since {0}", "Vergleich · % seit {0}"),
+    ("Waiting for the first prices", "Warte auf die ersten Kurse"),
+    ("Correlation of returns", "Korrelation der Renditen"),
+    ("Computing…", "Wird berechnet…"),
+    // instrument detail
+    ("vol", "Umsatz"),
+    ("{0} · range {1} – {2}", "{0} · Spanne {1} – {2}"),
+    ("1 tick per candle", "1 Tick pro Kerze"),
+    ("{0} ticks per candle", "{0} Ticks pro Kerze"),
+    ("{0} min per candle", "{0} Min. pro Kerze"),
+    ("1 day per candle", "1 Tag pro Kerze"),
+    ("{0} days per candle", "{0} Tage pro Kerze"),
+    (" · {0} candles back", " · {0} Kerzen zurück"),
+    ("Vol", "Umsatz"),
+    ("No orders in the book", "Keine Aufträge im Buch"),
+    ("Bid depth", "Geldtiefe"),
+    ("Ask depth", "Brieftiefe"),
+    ("Spread", "Spanne"),
+    (
+        "Not enough prices for a distribution yet",
+        "Noch nicht genug Kurse für eine Verteilung",
+    ),
+    ("stale, updated {0}s ago", "veraltet, vor {0}s aktualisiert"),
+    ("distressed, {0}", "notleidend, {0}"),
+    ("Short int.", "Leerverk."),
+    ("{0}% of float", "{0}% des Streubesitzes"),
+    ("Analysts", "Analysten"),
+    ("consensus", "Konsens"),
+    (
+        " ({0} Buy, {1} Hold, {2} Sell) · avg target {3}",
+        " ({0} Kaufen, {1} Halten, {2} Verkaufen) · Ø Kursziel {3}",
+    ),
+    (" · no coverage yet", " · noch keine Abdeckung"),
+    ("target", "Ziel"),
+    ("Insider filings", "Insidermeldungen"),
+    (" · none yet", " · noch keine"),
+    // accessible layout
+    ("unchanged", "unverändert"),
+    ("up {0} percent", "plus {0} Prozent"),
+    ("down {0} percent", "minus {0} Prozent"),
+    ("{0} (focused)", "{0} (fokussiert)"),
+    (
+        "Iron Ledger. {0} {1}. Connection {2}. {3} unread news.",
+        "Iron Ledger. {0} {1}. Verbindung {2}. {3} ungelesene Nachrichten.",
+    ),
+    ("open", "geöffnet"),
+    ("closed", "geschlossen"),
+    (
+        "{0}Portfolio value {1} {3}, cash {2} {3}.",
+        "{0}Depotwert {1} {3}, Bargeld {2} {3}.",
+    ),
+    (
+        "{0} price {1} {2}, {3}, volume {4}",
+        "{0} Kurs {1} {2}, {3}, Umsatz {4}",
+    ),
+    (", rated {0}", ", Urteil {0}"),
+    (", stale for {0} seconds", ", seit {0} Sekunden veraltet"),
+    (
+        "Market, {0} on {1}, {2} instruments",
+        "Markt, {0} an {1}, {2} Werte",
+    ),
+    ("stop armed", "Stop aktiv"),
+    ("working", "offen"),
+    ("filled", "ausgeführt"),
+    ("cancelled, {0}", "storniert, {0}"),
+    (
+        "Order {0}, {1} {2} {3}, {4}, {5} of {2} filled, {6}",
+        "Auftrag {0}, {1} {2} {3}, {4}, {5} von {2} ausgeführt, {6}",
+    ),
+    ("limit {0}", "Limit {0}"),
+    ("Orders, {0}", "Aufträge, {0}"),
+    (
+        "{0}, {1} held, average cost {2}, {3}",
+        "{0}, {1} im Bestand, Ø Kosten {2}, {3}",
+    ),
+    ("Positions, {0}", "Positionen, {0}"),
+    ("Unread. ", "Ungelesen. "),
+    ("positive", "positiv"),
+    ("negative", "negativ"),
+    ("neutral", "neutral"),
+    ("Latest news, {0}", "Aktuelle Nachrichten, {0}"),
+    ("empty", "leer"),
+    ("on", "an"),
+    ("off", "aus"),
+    ("Settings", "Einstellungen"),
+    (" (editing)", " (in Bearbeitung)"),
+    ("{0} order for {1}.", "{0}-Auftrag für {1}."),
+    ("{0} y or n.", "{0} y oder n."),
+    ("Details of {0}, {1}, {2}.", "Details zu {0}, {1}, {2}."),
+    ("Keys", "Tasten"),
+    // key hints
+    (
+        "m/h - Save Markdown/HTML",
+        "m/h - Als Markdown/HTML speichern",
+    ),
+    ("q/Enter - Quit", "q/Enter - Beenden"),
+    ("Esc - Back", "Esc - Zurück"),
+    ("Esc/R - Close", "Esc/R - Schließen"),
+    ("↑↓ - Strike", "↑↓ - Basis"),
+    ("←→ - Expiry", "←→ - Verfall"),
+    ("Tab - Call/Put", "Tab - Call/Put"),
+    ("b/s - Buy/Sell 1", "b/s - 1 kaufen/verkaufen"),
+    ("Esc/O - Close", "Esc/O - Schließen"),
+    ("c - Details", "c - Details"),
+    ("c - Chart", "c - Chart"),
+    ("+/- - Zoom", "+/- - Zoom"),
+    ("←→ - Move Cursor", "←→ - Cursor bewegen"),
+    ("←→ - Pan", "←→ - Verschieben"),
+    ("x - Crosshair", "x - Fadenkreuz"),
+    ("t - Timeframe", "t - Zeitraum"),
+    ("d - Details", "d - Details"),
+    ("d - Depth Chart", "d - Markttiefe"),
+    ("r - Details", "r - Details"),
+    ("r - Returns", "r - Renditen"),
+    ("b/s - Buy/Sell", "b/s - Kaufen/Verkaufen"),
+    ("Esc/Enter - Close", "Esc/Enter - Schließen"),
+    ("y - Cancel Order", "y - Stornieren"),
+    ("n/Esc - Keep", "n/Esc - Behalten"),
+    ("y - Sell All", "y - Alles verkaufen"),
+    ("y - Clear History", "y - Verlauf löschen"),
+    ("y - Start Over", "y - Neu beginnen"),
+    ("n - New Game", "n - Neues Spiel"),
+    ("Tab - Next Field", "Tab - Nächstes Feld"),
+    ("←→ - Change", "←→ - Ändern"),
+    ("↑↓ - Step", "↑↓ - Schrittweise"),
+    ("Enter - Apply", "Enter - Übernehmen"),
+    ("Enter - Submit", "Enter - Absenden"),
+    ("Ctrl-Enter - Submit, Keep Open", "Strg-Enter - Absenden, offen lassen"),
+    ("Alt-Enter - Submit, Keep Open", "Alt-Enter - Absenden, offen lassen"),
+    ("Esc - Cancel", "Esc - Abbrechen"),
+    ("Enter - Done", "Enter - Fertig"),
+    ("Esc - Clear Search", "Esc - Suche löschen"),
+    ("Esc - Clear Screen", "Esc - Filter löschen"),
+    ("↑↓ - Move", "↑↓ - Bewegen"),
+    ("Space - Toggle", "Leertaste - Umschalten"),
+    ("Esc/F - Close", "Esc/F - Schließen"),
+    ("Enter - Save", "Enter - Speichern"),
+    ("Enter - Load/New", "Enter - Laden/Neu"),
+    ("s - Overwrite", "s - Überschreiben"),
+    ("Esc/S - Close", "Esc/S - Schließen"),
+    ("Esc/C - Close", "Esc/C - Schließen"),
+    ("Esc/G - Close", "Esc/G - Schließen"),
+    ("r - Reconnect", "r - Neu verbinden"),
+    ("Tab - Switch Source", "Tab - Quelle wechseln"),
+    ("Esc/L - Close", "Esc/L - Schließen"),
+    ("Tab - Next Panel", "Tab - Nächstes Fenster"),
+    ("Esc/V - Close", "Esc/V - Schließen"),
+    ("←→ - Page", "←→ - Seite"),
+    ("Home/End - First/Last", "Pos1/Ende - Erste/Letzte"),
+    ("/ - Search", "/ - Suchen"),
+    ("F - Categories", "F - Kategorien"),
+    ("Esc/A - Close", "Esc/A - Schließen"),
+    ("↑↓ - Scroll Up/Down", "↑↓ - Blättern"),
+    ("Tab - Asset Class", "Tab - Anlageklasse"),
+    ("e - Exchange", "e - Börse"),
+    ("Shift-←→ - Columns", "Umschalt-←→ - Spalten"),
+    ("Space - Expand", "Leertaste - Aufklappen"),
+    ("% - Change Since", "% - Änderung seit"),
+    ("Tick%", "Tick%"),
+    ("5m%", "5min%"),
+    ("Session%", "Sitzung%"),
+    ("Open%", "Eröff.%"),
+    (
+        "{0} must be a multiple of the tick size {1}",
+        "{0} muss ein Vielfaches der Tickgröße {1} sein",
+    ),
+    ("Bid/Ask", "Geld/Brief"),
+    ("{0} range", "Spanne {0}"),
+    ("not scheduled", "nicht angesetzt"),
+    ("not scheduled, last {0}", "nicht angesetzt, zuletzt {0}"),
+    ("Enter/m - Actions", "Enter/m - Aktionen"),
+    ("Esc - Close", "Esc - Schließen"),
+    ("Enter - Choose", "Enter - Auswählen"),
+    ("Esc/M - Close", "Esc/M - Schließen"),
+    ("Chart", "Chart"),
+    ("Options", "Optionen"),
+    ("Mark to compare", "Zum Vergleich markieren"),
+    ("News by company", "Nachrichten zur Firma"),
+    ("Copy ticker", "Kürzel kopieren"),
+    ("Set alert", "Alarm setzen"),
+    ("When", "Wenn"),
+    ("above", "über"),
+    ("below", "unter"),
+    ("Alert on {0}", "Alarm für {0}"),
+    ("Enter - Set", "Enter - Setzen"),
+    ("Then", "Danach"),
+    ("once", "einmalig"),
+    ("re-arm", "neu scharf"),
+    ("snooze", "schlummern"),
+    ("Re-arm band %", "Rückkehrband %"),
+    ("Snooze min", "Schlummern min"),
+    ("waiting to re-arm", "wartet auf Rückkehr"),
+    ("re-arms past {0}%", "wieder scharf nach {0}%"),
+    ("snoozed till {0}", "schlummert bis {0}"),
+    ("snoozes {0} min", "schlummert {0} min"),
+    ("Edit alert on {0}", "Alarm für {0} ändern"),
+    ("e - Edit", "e - Ändern"),
+    ("Rebalance", "Umschichten"),
+    ("Rebalance · Review", "Umschichten · Prüfen"),
+    ("{0} {1} {2} at market, about {3}", "{0} {1} {2} billigst/bestens, etwa {3}"),
+    ("Enter places all {0} orders, Esc goes back", "Enter gibt alle {0} Aufträge auf, Esc geht zurück"),
+    ("No targets set, see [trading] targets in the Readme", "Keine Ziele gesetzt, siehe [trading] targets in der Readme"),
+    ("Target", "Ziel"),
+    ("Now", "Jetzt"),
+    ("Order", "Auftrag"),
+    ("Nothing held in {0} to spread its target over", "Nichts aus {0} im Bestand, auf das sich das Ziel verteilt"),
+    ("Enter - Place All", "Enter - Alle aufgeben"),
+    ("s - Stage Orders", "s - Aufträge vorbereiten"),
+    ("Esc/B - Close", "Esc/B - Schließen"),
+    ("Schedule orders", "Aufträge planen"),
+    ("Schedule orders of {0}", "Aufträge für {0} planen"),
+    ("Every", "Alle"),
+    ("buy", "kaufen"),
+    ("sell", "verkaufen"),
+    ("day", "Tag"),
+    ("week", "Woche"),
+    ("Schedules · {0}", "Pläne · {0}"),
+    ("No schedules, m on a market row sets one up", "Keine Pläne, m auf einer Marktzeile legt einen an"),
+    ("{0} {1} {2} every {3}", "{0} {1} {2} jede(n) {3}"),
+    ("paused", "pausiert"),
+    ("last {0}", "zuletzt {0}"),
+    ("not run yet", "noch nicht gelaufen"),
+    ("p - Pause/Resume", "p - Pause/Weiter"),
+    ("Esc/W - Close", "Esc/W - Schließen"),
+    ("Interest", "Zinsen"),
+    ("Borrow cost", "Sollzinsen"),
+    ("$ ledger  w accounts", "$ Kassenbuch  w Konten"),
+    ("Deposit", "Einzahlung"),
+    ("Trade", "Handel"),
+    ("Commission", "Provision"),
+    ("Option", "Option"),
+    ("Payout", "Auszahlung"),
+    ("All", "Alle"),
+    ("{0} · {1} entries", "{0} · {1} Buchungen"),
+    ("Nothing posted of this kind yet", "Noch nichts dieser Art gebucht"),
+    ("↑↓ - Scroll", "↑↓ - Blättern"),
+    ("f - Filter", "f - Filtern"),
+    ("x - Export CSV", "x - Als CSV exportieren"),
+    ("Cash ledger · {0}% interest · {1}% on a negative balance", "Kassenbuch · {0}% Zinsen · {1}% auf negativen Saldo"),
+    ("Esc/$ - Close", "Esc/$ - Schließen"),
+    ("Transfer", "Umbuchung"),
+    ("All accounts", "Alle Konten"),
+    ("Portfolio · {0} · {1} · {2}", "Depot · {0} · {1} · {2}"),
+    ("Account", "Konto"),
+    ("Value", "Wert"),
+    ("Amount", "Betrag"),
+    ("Accounts · {0}", "Konten · {0}"),
+    ("Move cash from {0} to {1}", "Bargeld von {0} nach {1} umbuchen"),
+    ("Enter - Move", "Enter - Umbuchen"),
+    ("Enter - Trade Here", "Enter - Hier handeln"),
+    ("t - Move Cash Here", "t - Bargeld hierher"),
+    ("a - Active Account", "a - Aktives Konto"),
+    ("a - All Accounts", "a - Alle Konten"),
+    ("Esc/w - Close", "Esc/w - Schließen"),
+    ("c - Capital Gains CSV", "c - Veräußerungsgewinne als CSV"),
+    ("Settled {0}, {1} on {2}", "Abgerechnet {0}, {1} am {2}"),
+    (" {0} {1} of it unsettled.", " Davon {0} {1} nicht abgerechnet."),
+    ("FX", "Devisen"),
+    ("FX · {0}% spread", "Devisen · {0}% Spanne"),
+    ("Currency", "Währung"),
+    ("Rate", "Kurs"),
+    ("Held", "Bestand"),
+    ("Cross", "Kreuz"),
+    ("Buy {0} at {1}", "{0} kaufen zu {1}"),
+    ("Sell {0} at {1}", "{0} verkaufen zu {1}"),
+    ("Foreign {0}", "Devisen {0}"),
+    ("Enter - Change", "Enter - Tauschen"),
+    ("b - Buy", "b - Kaufen"),
+    ("s - Sell", "s - Verkaufen"),
+    ("Esc/K - Close", "Esc/K - Schließen"),
+    ("K - FX", "K - Devisen"),
+    ("Set", "Gesetzt"),
+    ("No alerts set, m on a market row sets one", "Keine Alarme, m auf einer Marktzeile setzt einen"),
+    ("Went off", "Ausgelöst"),
+    ("Nothing yet", "Noch nichts"),
+    ("at {0}", "bei {0}"),
+    ("Alerts · {0} set · {1} new", "Alarme · {0} gesetzt · {1} neu"),
+    ("{0} alerts, ! to see", "{0} Alarme, ! zum Ansehen"),
+    ("d - Delete", "d - Löschen"),
+    ("a - Acknowledge", "a - Quittieren"),
+    ("Esc/! - Close", "Esc/! - Schließen"),
+    ("{0} moved {1}", "{0} bewegt {1}"),
+    ("Nothing is moving much", "Nichts bewegt sich stark"),
+    ("since {0}", "seit {0}"),
+    ("Movers · {0}% in {1}s", "Bewegte Werte · {0}% in {1}s"),
+    ("Enter - Details", "Enter - Details"),
+    ("h - History", "h - Verlauf"),
+    ("recording @{0}", "Aufzeichnung @{0}"),
+    ("Esc/Q - Quit", "Esc/Q - Beenden"),
+    ("Details", "Details"),
+    ("Buy Order", "Kaufauftrag"),
+    ("Sell Order", "Verkaufsauftrag"),
+    ("Actions", "Aktionen"),
+    ("Screen", "Filtern"),
+    ("Mark to Compare", "Zum Vergleich markieren"),
+    ("By Company", "Nach Firma"),
+    ("Sell Whole Position", "Ganze Position verkaufen"),
+    ("Modify", "Ändern"),
+    ("Cancel Order", "Stornieren"),
+    ("Clear History", "Verlauf löschen"),
+    ("Jump", "Springen"),
+    ("Search", "Suchen"),
+    ("Open Link", "Link öffnen"),
+    ("Previous Panel", "Voriger Bereich"),
+    ("Next Panel", "Nächster Bereich"),
+    ("Compare", "Vergleichen"),
+    ("Categories", "Kategorien"),
+    ("Archive", "Archiv"),
+    ("Unread", "Ungelesen"),
+    ("Tutorial", "Einführung"),
+    ("Achievements", "Erfolge"),
+    ("Alerts", "Alarme"),
+    ("Movers", "Bewegte Werte"),
+    ("Index", "Index"),
+    ("Market breadth", "Marktbreite"),
+    ("advancing", "steigend"),
+    ("declining", "fallend"),
+    ("{0} unchanged", "{0} unverändert"),
+    ("Up volume", "Volumen steigend"),
+    ("Down volume", "Volumen fallend"),
+    ("Up/down ratio", "Verhältnis"),
+    ("New session highs", "Neue Sitzungshochs"),
+    ("New session lows", "Neue Sitzungstiefs"),
+    (
+        "All {0} instruments, since yesterday's close",
+        "Alle {0} Instrumente, seit dem gestrigen Schluss",
+    ),
+    ("Schedules", "Pläne"),
+    ("Cash Ledger", "Kassenbuch"),
+    ("Accounts", "Konten"),
+    ("Currencies", "Währungen"),
+    ("Panels", "Zusatzfenster"),
+    ("Record", "Aufzeichnen"),
+    ("Stop Recording", "Aufzeichnung beenden"),
+    ("Play", "Abspielen"),
+    ("Undo", "Rückgängig"),
+    ("Redo", "Wiederholen"),
+    ("Undo Last Fill", "Letzte Ausführung zurücknehmen"),
+    ("Saves", "Spielstände"),
+    ("Help", "Hilfe"),
+    ("Report", "Bericht"),
+    ("Quit", "Beenden"),
+    ("Anywhere", "Überall"),
+    ("Market data", "Marktdaten"),
+    ("Orders", "Aufträge"),
+    ("Space", "Leertaste"),
+    ("Ctrl", "Strg"),
+];
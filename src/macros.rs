@@ -0,0 +1,67 @@
+use crossterm::event::KeyEvent;
+use std::collections::HashMap;
+
+// longest count typed in front of @, so a stray row of digits can't replay a macro for ages
+const MAX_COUNT: usize = 999;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pending {
+    Record, // Ctrl-R was pressed, the next letter is the register
+    Play,   // the same after @
+}
+
+// key sequences recorded into registers a to z and played back, like q and @ in vi,
+// only for this session
+#[derive(Debug, Default)]
+pub struct Macros {
+    registers: HashMap<char, Vec<KeyEvent>>,
+    pub recording: Option<char>,
+    pub pending: Option<Pending>,
+    pub count: Option<usize>, // typed in front of @
+    pub playing: bool,        // no recording and no @ within a macro
+    last_played: Option<char>,
+}
+
+impl Macros {
+    pub fn push_digit(&mut self, digit: u32) {
+        let count = self.count.unwrap_or(0) * 10 + digit as usize;
+        self.count = Some(count.min(MAX_COUNT));
+    }
+
+    pub fn start(&mut self, register: char) {
+        self.registers.insert(register, vec![]);
+        self.recording = Some(register);
+    }
+
+    pub fn record(&mut self, key: KeyEvent) {
+        if let Some(register) = self.recording {
+            self.registers.entry(register).or_default().push(key);
+        }
+    }
+
+    // the key that stopped it was recorded like any other and is dropped
+    pub fn stop(&mut self) {
+        if let Some(register) = self.recording.take() {
+            self.registers.entry(register).or_default().pop();
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.registers.values().all(|keys| keys.is_empty())
+    }
+
+    // what @ followed by the register plays, @ again for the last one played
+    pub fn keys(&mut self, register: char) -> Vec<KeyEvent> {
+        let register = match register {
+            '@' => self.last_played,
+            _ => Some(register),
+        };
+        self.last_played = register;
+        let keys = register
+            .and_then(|register| self.registers.get(&register))
+            .cloned()
+            .unwrap_or_default();
+        let count = self.count.take().unwrap_or(1);
+        keys.repeat(count)
+    }
+}
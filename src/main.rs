@@ -1,378 +1,2419 @@
-use crossterm::event::{self, Event, KeyCode};
-use rand::rngs::ThreadRng;
-use rand::Rng;
-use ratatui::layout::{Alignment, Constraint};
-use ratatui::layout::{Layout, Margin};
-use ratatui::style::{Color, Modifier, Style, Stylize};
-use ratatui::text::{Line, Text};
-use ratatui::widgets::{
-    Block, Borders, Cell, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, Table,
-    Wrap,
+use chrono::{TimeDelta, Utc};
+use crossterm::event::{
+    self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers, KeyboardEnhancementFlags, MouseButton, MouseEvent, MouseEventKind,
+    PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
 };
-use ratatui::Frame;
+use crossterm::execute;
+use crossterm::terminal::{supports_keyboard_enhancement, SetTitle};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use ratatui::backend::{CrosstermBackend, TestBackend};
+use ratatui::symbols::Marker;
+use ratatui::Terminal;
+use rust_tui_test::alerts::AlertForm;
+use rust_tui_test::app::{
+    jump_to_oldest_unread_news, mark_news_seen, AccountsView, AppState, ChartView, Confirm,
+    DetailView, LedgerView, MarketDataActivePanel, OptionsView, OrderForm, ReportView, RowAction,
+    RowMenu, SavesMenu, UIState, MAX_COMPARE_TICKERS,
+};
+use rust_tui_test::components::{MarketTable, ScrollView};
+use rust_tui_test::config::{data_dir, Config};
+use rust_tui_test::connection::Connection;
+use rust_tui_test::control::{
+    remove_control_socket, spawn_control_socket, ControlRequest, OrderParams, RpcError,
+    ThemeParams, TickerParams,
+};
+use rust_tui_test::daemon::run_daemon;
+use rust_tui_test::difficulty::Difficulty;
+use rust_tui_test::exchanges::{Currency, Exchange, SymbolPosition};
+use rust_tui_test::feeds::spawn_feed_fetcher;
+use rust_tui_test::formatting::{Accent, FormatRule};
+use rust_tui_test::forms::{Field, Form, TextInput};
+use rust_tui_test::fx::FxView;
+use rust_tui_test::graphics::{cell_pixels, detect_graphics, GraphicsOutput};
+use rust_tui_test::history::{Retention, MAX_BACKFILL_DAYS};
+use rust_tui_test::hooks::Hooks;
+use rust_tui_test::keymap::{Action, Context, Keymap};
+use rust_tui_test::locale::{set_language, Language};
+use rust_tui_test::macros::Pending;
+use rust_tui_test::market::{
+    gen_commodity, gen_consol, gen_crypto, gen_equities, gen_universe, InstrumentClass, Listing,
+    MarketSession,
+};
+use rust_tui_test::mode::Mode;
+use rust_tui_test::news::{NewsCategory, NewsItem};
+use rust_tui_test::numbers::set_abbreviated;
+use rust_tui_test::options::OptionKind;
+use rust_tui_test::orders::Side;
+use rust_tui_test::portfolio::{Account, Portfolio};
+use rust_tui_test::rebalance::{parse_targets, plan, RebalanceView};
+use rust_tui_test::recording::{Cast, Recorder};
+use rust_tui_test::replay::Replay;
+use rust_tui_test::report::{write_gains, write_ledger, write_report, ReportFormat};
+use rust_tui_test::saves::{read_slot, write_slot};
+use rust_tui_test::scenario::Scenario;
+use rust_tui_test::schedules::ScheduleForm;
+use rust_tui_test::snapshot::print_quotes;
+use rust_tui_test::terminal::{
+    copy_to_clipboard, downgrade, open_url, restore_title, ring_bell, save_title, utf8_locale,
+    write_hyperlinks, ColorDepth,
+};
+use rust_tui_test::theme::Theme;
+use rust_tui_test::tutorial::Tutorial;
+use rust_tui_test::ui::{draw, help_lines, window_title, MARKET_COLUMNS};
+use rust_tui_test::undo::{redo, undo, Change};
+use serde_json::{json, Value};
 use std::cmp::{max, min};
-use std::ops::RangeInclusive;
-use textwrap::Options;
-
-#[derive(Debug)]
-struct Company {
-    ticker: String,
-    name: String,
-    description: String,
+use std::collections::HashMap;
+use std::io::{self, IsTerminal};
+use std::panic;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const STARTING_CASH: f64 = 100_000.0;
+const MAX_UNIVERSE: usize = 100_000;
+const BENCHMARK_TICKS: u32 = 20;
+const MONKEY_EVENTS: u32 = 10_000;
+// the market ticks once in this many monkey events
+const MONKEY_TICK_EVERY: u32 = 50;
+// how long a control request can wait for the main loop
+const CONTROL_POLL: Duration = Duration::from_millis(50);
+// how soon the worker's results are looked for while it has jobs running
+const WORKER_POLL: Duration = Duration::from_millis(10);
+
+// returns false when the app should quit
+fn handle_key(key: KeyEvent, app_state: &mut AppState, ui_state: &mut UIState) -> bool {
+    if !ui_state.macros.playing {
+        ui_state.macros.record(key);
+    }
+    // whatever the mode is has the key to itself, a q typed into a search is part of the query
+    match Mode::of(ui_state) {
+        Mode::Command => handle_command(key, app_state, ui_state),
+        Mode::Modal => handle_modal(key, app_state, ui_state),
+        Mode::Detail => {
+            handle_detail(key, app_state, ui_state);
+            true
+        }
+        Mode::Search => {
+            handle_search(key, ui_state);
+            true
+        }
+        Mode::Normal => handle_normal(key, app_state, ui_state),
+    }
 }
 
-impl Company {
-    fn new(ticker: &str, name: &str, description: &str) -> Company {
-        Company {
-            ticker: ticker.to_string(),
-            name: name.to_string(),
-            description: description.to_string(),
+// the register letter after Ctrl-R or @, anything else calls it off
+fn handle_command(key: KeyEvent, app_state: &mut AppState, ui_state: &mut UIState) -> bool {
+    let Some(pending) = ui_state.macros.pending.take() else {
+        return true;
+    };
+    match (pending, key.code) {
+        (Pending::Record, KeyCode::Char(register @ 'a'..='z')) => ui_state.macros.start(register),
+        (Pending::Play, KeyCode::Char(register @ ('a'..='z' | '@'))) => {
+            let keys = ui_state.macros.keys(register);
+            ui_state.macros.playing = true;
+            let running = keys
+                .into_iter()
+                .all(|key| handle_key(key, app_state, ui_state));
+            ui_state.macros.playing = false;
+            return running;
         }
+        _ => ui_state.macros.count = None,
     }
+    true
 }
 
-#[derive(Debug)]
-struct NewsItem {
-    title: String,
-    subtitle: String,
+// a dialog, form or view over the panels, the ones that can open over the instrument view first
+fn handle_modal(key: KeyEvent, app_state: &mut AppState, ui_state: &mut UIState) -> bool {
+    // the question gets answered before anything else
+    if let Some(confirm) = ui_state.confirm.take() {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                confirmed(confirm, app_state, ui_state)
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {}
+            _ => ui_state.confirm = Some(confirm),
+        }
+        return true;
+    }
+    // order entry is modal too, digits and Enter belong to the form
+    if let Some(form) = ui_state.order_form.as_mut() {
+        match key.code {
+            KeyCode::Esc => ui_state.order_form = None,
+            KeyCode::Enter => match form.parse() {
+                Ok((quantity, limit_price, bracket)) => match form.amending {
+                    Some(order_id) => {
+                        match app_state.amend_order(order_id, quantity, limit_price) {
+                            Ok(()) => ui_state.order_form = None,
+                            Err(err) => form.form.error = Some(err),
+                        }
+                    }
+                    None => {
+                        app_state.place_order(
+                            &form.ticker,
+                            form.side,
+                            quantity,
+                            limit_price,
+                            bracket,
+                        );
+                        ui_state.orders_table.scroll.pos = 0;
+                        // Ctrl-Enter, or Alt-Enter where the terminal sends Ctrl-Enter as
+                        // Enter, keeps the form open for another of the same
+                        if key
+                            .modifiers
+                            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                        {
+                            form.form.error = None;
+                        } else {
+                            ui_state.order_form = None;
+                        }
+                    }
+                },
+                Err(err) => form.form.error = Some(err),
+            },
+            _ => {
+                form.form.on_event(key);
+            }
+        }
+        return true;
+    }
+    if let Some(form) = ui_state.alert_form.as_mut() {
+        match key.code {
+            KeyCode::Esc => ui_state.alert_form = None,
+            KeyCode::Enter => match form.parse() {
+                Ok(alert) => {
+                    match form.editing {
+                        Some(id) => app_state.alerts.replace(id, alert),
+                        None => app_state.alerts.add(alert),
+                    }
+                    ui_state.alert_form = None;
+                }
+                Err(err) => form.form.error = Some(err),
+            },
+            _ => {
+                form.form.on_event(key);
+            }
+        }
+        return true;
+    }
+    if let Some(form) = ui_state.schedule_form.as_mut() {
+        match key.code {
+            KeyCode::Esc => ui_state.schedule_form = None,
+            KeyCode::Enter => match form.parse() {
+                Ok(schedule) => {
+                    app_state.schedules.add(schedule);
+                    ui_state.schedule_form = None;
+                }
+                Err(err) => form.form.error = Some(err),
+            },
+            _ => {
+                form.form.on_event(key);
+            }
+        }
+        return true;
+    }
+    if let Some(form) = ui_state.settings.as_mut() {
+        match key.code {
+            KeyCode::Esc => ui_state.settings = None,
+            KeyCode::Enter => match form.validate() {
+                Ok(()) => {
+                    let theme = Theme::from_form(form);
+                    ui_state.history.record(Change::theme(ui_state));
+                    ui_state.theme = theme;
+                    ui_state.settings = None;
+                }
+                Err(err) => form.error = Some(err),
+            },
+            _ => {
+                form.on_event(key);
+            }
+        }
+        return true;
+    }
+    if let Some(view) = ui_state.options_view.as_mut() {
+        let (expiry_count, strike_count) = app_state
+            .option_chain(&view.ticker)
+            .map_or((0, 0), |chain| (chain.expiries.len(), chain.strikes.len()));
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('o') | KeyCode::Char('O') => ui_state.options_view = None,
+            KeyCode::Left => view.expiry_idx = view.expiry_idx.saturating_sub(1),
+            KeyCode::Right => {
+                view.expiry_idx = min(view.expiry_idx + 1, expiry_count.saturating_sub(1))
+            }
+            KeyCode::Up => view.strike_idx = view.strike_idx.saturating_sub(1),
+            KeyCode::Down => {
+                view.strike_idx = min(view.strike_idx + 1, strike_count.saturating_sub(1))
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                view.kind = match view.kind {
+                    OptionKind::Call => OptionKind::Put,
+                    OptionKind::Put => OptionKind::Call,
+                }
+            }
+            KeyCode::Char('b') | KeyCode::Char('s') => {
+                if let Some(contract) = view.selected_contract(app_state) {
+                    let price = app_state.option_price(&contract).unwrap_or(0.0);
+                    let result = if key.code == KeyCode::Char('b') {
+                        app_state.portfolio_mut().buy_option(&contract, price)
+                    } else {
+                        app_state.portfolio_mut().sell_option(&contract, price)
+                    };
+                    view.error = result.err();
+                }
+            }
+            _ => {}
+        }
+        return true;
+    }
+    // the row menu is modal, it can be opened from the detail view too
+    if let Some(menu) = ui_state.row_menu.as_mut() {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('m') | KeyCode::Char('M') => ui_state.row_menu = None,
+            KeyCode::Up => menu.cursor = menu.cursor.saturating_sub(1),
+            KeyCode::Down => menu.cursor = min(menu.actions.len() - 1, menu.cursor + 1),
+            KeyCode::Enter => {
+                let action = menu.actions[menu.cursor];
+                let target = app_state.quote_index(&menu.ticker);
+                ui_state.row_menu = None;
+                if let Some(idx) = target {
+                    row_action(action, idx, app_state, ui_state);
+                }
+            }
+            _ => {}
+        }
+        return true;
+    }
+    if let Some(cursor) = ui_state.news_filter_menu {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('f') | KeyCode::Char('F') => {
+                ui_state.news_filter_menu = None
+            }
+            KeyCode::Up => ui_state.news_filter_menu = Some(cursor.saturating_sub(1)),
+            KeyCode::Down => {
+                ui_state.news_filter_menu = Some(min(NewsCategory::ALL.len() - 1, cursor + 1))
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                ui_state.history.record(Change::news_filter(ui_state));
+                let category = NewsCategory::ALL[cursor];
+                if let Some(pos) = ui_state
+                    .news_list
+                    .hidden_categories
+                    .iter()
+                    .position(|c| *c == category)
+                {
+                    ui_state.news_list.hidden_categories.remove(pos);
+                } else {
+                    ui_state.news_list.hidden_categories.push(category);
+                }
+                ui_state.reset_news_positions();
+            }
+            _ => {}
+        }
+        return true;
+    }
+    // the report is the last screen before quitting, a second q really quits
+    if let Some(view) = ui_state.report_view.as_mut() {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Enter if view.quitting => {
+                return false
+            }
+            KeyCode::Esc | KeyCode::Char('R') => ui_state.report_view = None,
+            KeyCode::Char('m') | KeyCode::Char('M') | KeyCode::Char('h') | KeyCode::Char('H') => {
+                let format = if let KeyCode::Char('m') | KeyCode::Char('M') = key.code {
+                    ReportFormat::Markdown
+                } else {
+                    ReportFormat::Html
+                };
+                view.message =
+                    Some(write_report(&view.report, format).map(|path| path.display().to_string()));
+            }
+            KeyCode::Char('c') | KeyCode::Char('C') => {
+                view.message =
+                    Some(write_gains(&view.report).map(|path| path.display().to_string()))
+            }
+            _ => {}
+        }
+        return true;
+    }
+    if let Some(menu) = ui_state.saves_menu.as_mut() {
+        if let Some(name) = menu.naming.as_mut() {
+            match key.code {
+                KeyCode::Enter => {
+                    let name = name.text.clone();
+                    match write_slot(&name, &app_state.saved_game()) {
+                        Ok(()) => {
+                            menu.naming = None;
+                            menu.refresh(&name);
+                        }
+                        Err(err) => menu.error = Some(err),
+                    }
+                }
+                KeyCode::Esc => {
+                    menu.naming = None;
+                    menu.error = None;
+                }
+                _ => {
+                    name.on_event(key);
+                }
+            }
+            return true;
+        }
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('S') => ui_state.saves_menu = None,
+            KeyCode::Up => menu.cursor = menu.cursor.saturating_sub(1),
+            KeyCode::Down => menu.cursor = min(menu.slots.len(), menu.cursor + 1),
+            KeyCode::Enter => match menu.selected_slot() {
+                None => menu.naming = Some(TextInput::default()),
+                Some(slot) => match read_slot(&slot.name) {
+                    Ok(game) => {
+                        app_state.restore(game);
+                        ui_state.saves_menu = None;
+                        ui_state.orders_table.scroll.pos = 0;
+                        ui_state.drop_delisted(app_state);
+                        ui_state.clamp_scroll(app_state);
+                    }
+                    Err(err) => menu.error = Some(err),
+                },
+            },
+            KeyCode::Char('s') => {
+                if let Some(name) = menu.selected_slot().map(|slot| slot.name.clone()) {
+                    match write_slot(&name, &app_state.saved_game()) {
+                        Ok(()) => menu.refresh(&name),
+                        Err(err) => menu.error = Some(err),
+                    }
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') => ui_state.confirm = Some(Confirm::NewGame),
+            _ => {}
+        }
+        return true;
+    }
+    if ui_state.compare_open {
+        if let KeyCode::Esc | KeyCode::Char('C') = key.code {
+            ui_state.compare_open = false;
+        }
+        return true;
+    }
+    if let Some(view) = ui_state.ledger.as_mut() {
+        // exports go oldest first like the ledger itself
+        let mut entries = view.entries(app_state.portfolio());
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('$') => ui_state.ledger = None,
+            KeyCode::Char('f') | KeyCode::Char('F') => view.next_filter(),
+            KeyCode::Char('x') | KeyCode::Char('X') => {
+                entries.reverse();
+                view.message = Some(write_ledger(&entries).map(|path| path.display().to_string()));
+            }
+            _ => {
+                view.scroll.on_event(key, entries.len());
+            }
+        }
+        return true;
+    }
+    if let Some(view) = ui_state.accounts.as_mut() {
+        let len = app_state.accounts.len();
+        if let Some(form) = view.transfer.as_mut() {
+            match key.code {
+                KeyCode::Esc => view.transfer = None,
+                KeyCode::Enter => match form
+                    .validate()
+                    .and_then(|_| app_state.transfer(view.cursor, form.number(0).unwrap_or(0.0)))
+                {
+                    Ok(()) => view.transfer = None,
+                    Err(err) => form.error = Some(err),
+                },
+                _ => {
+                    form.on_event(key);
+                }
+            }
+            return true;
+        }
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('w') => ui_state.accounts = None,
+            KeyCode::Up => view.cursor = view.cursor.saturating_sub(1),
+            KeyCode::Down => view.cursor = min(len - 1, view.cursor + 1),
+            KeyCode::Enter => {
+                app_state.active = view.cursor;
+                ui_state.portfolio_panel.all_accounts = false;
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => {
+                ui_state.portfolio_panel.all_accounts = !ui_state.portfolio_panel.all_accounts
+            }
+            KeyCode::Char('t') | KeyCode::Char('T') if view.cursor != app_state.active => {
+                view.transfer = Some(Form::new(vec![Field::number("Amount", true)]));
+            }
+            _ => {}
+        }
+        return true;
+    }
+    if let Some(view) = ui_state.fx.as_mut() {
+        let len = app_state.fx.currencies.len();
+        if let Some((side, form)) = view.form.as_mut() {
+            match key.code {
+                KeyCode::Esc => view.form = None,
+                KeyCode::Enter => match form.validate().and_then(|_| {
+                    app_state.change_cash(view.cursor, *side, form.number(0).unwrap_or(0.0))
+                }) {
+                    Ok(()) => view.form = None,
+                    Err(err) => form.error = Some(err),
+                },
+                _ => {
+                    form.on_event(key);
+                }
+            }
+            return true;
+        }
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('K') => ui_state.fx = None,
+            // the home currency at the top isn't traded against itself
+            KeyCode::Up => view.cursor = max(1, view.cursor.saturating_sub(1)),
+            KeyCode::Down => view.cursor = min(len - 1, view.cursor + 1),
+            KeyCode::Char('b') | KeyCode::Char('s') => {
+                let side = if key.code == KeyCode::Char('b') {
+                    Side::Buy
+                } else {
+                    Side::Sell
+                };
+                view.form = Some((side, Form::new(vec![Field::number("Amount", true)])));
+            }
+            _ => {}
+        }
+        return true;
+    }
+    if ui_state.achievements_open {
+        if let KeyCode::Esc | KeyCode::Char('g') | KeyCode::Char('G') = key.code {
+            ui_state.achievements_open = false;
+        }
+        return true;
+    }
+    if let Some(cursor) = ui_state.alerts_open {
+        let len = app_state.alerts.alerts.len();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('!') => ui_state.alerts_open = None,
+            KeyCode::Up => ui_state.alerts_open = Some(cursor.saturating_sub(1)),
+            KeyCode::Down => ui_state.alerts_open = Some(min(len.saturating_sub(1), cursor + 1)),
+            KeyCode::Char('a') | KeyCode::Char('A') => app_state.alerts.acknowledge(),
+            // the view stays open under the form and comes back after it
+            KeyCode::Char('e') | KeyCode::Char('E') => {
+                if let Some(alert) = app_state.alerts.alerts.get(cursor) {
+                    let precision = app_state.precision(&alert.ticker);
+                    ui_state.alert_form = Some(AlertForm::edit(Some(alert.id), alert, precision));
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                if let Some(alert) = app_state.alerts.alerts.get(cursor) {
+                    app_state.alerts.remove(alert.id);
+                }
+                ui_state.alerts_open = Some(min(cursor, len.saturating_sub(2)));
+            }
+            _ => {}
+        }
+        return true;
+    }
+    // closed with Esc or whatever opens it, Enter goes to the instrument
+    if let Some(cursor) = ui_state.movers_open {
+        let len = app_state.movers.movers.len();
+        match key.code {
+            KeyCode::Esc => ui_state.movers_open = None,
+            KeyCode::Up => ui_state.movers_open = Some(cursor.saturating_sub(1)),
+            KeyCode::Down => ui_state.movers_open = Some(min(len.saturating_sub(1), cursor + 1)),
+            KeyCode::Enter => {
+                if let Some(mover) = app_state.movers.movers.get(cursor) {
+                    ui_state.instrument_detail = Some(mover.ticker.clone());
+                    ui_state.instrument_detail_view = DetailView::Details;
+                    ui_state.chart = ChartView::default();
+                    ui_state.movers_open = None;
+                }
+            }
+            _ if ui_state.keymap.action(Context::Global, &key) == Some(Action::Movers) => {
+                ui_state.movers_open = None
+            }
+            _ => {}
+        }
+        return true;
+    }
+    if let Some(cursor) = ui_state.schedules_open {
+        let len = app_state.schedules.schedules.len();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('W') => ui_state.schedules_open = None,
+            KeyCode::Up => ui_state.schedules_open = Some(cursor.saturating_sub(1)),
+            KeyCode::Down => ui_state.schedules_open = Some(min(len.saturating_sub(1), cursor + 1)),
+            KeyCode::Char('p') | KeyCode::Char('P') | KeyCode::Char(' ') => {
+                app_state.schedules.toggle_paused(cursor)
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                app_state.schedules.remove(cursor);
+                ui_state.schedules_open = Some(min(cursor, len.saturating_sub(2)));
+            }
+            _ => {}
+        }
+        return true;
+    }
+    if let Some(view) = ui_state.rebalance.as_mut() {
+        match (key.code, view.staged.take()) {
+            // back from the review to the plan
+            (KeyCode::Esc, Some(_)) => {}
+            (KeyCode::Esc | KeyCode::Char('B'), None) => ui_state.rebalance = None,
+            (KeyCode::Char('s') | KeyCode::Char('S'), None) => {
+                let orders = plan(app_state, &app_state.targets).orders();
+                view.staged = (!orders.is_empty()).then_some(orders);
+            }
+            (KeyCode::Enter, Some(orders)) => {
+                for leg in orders {
+                    app_state.place_order(&leg.ticker, leg.side, leg.quantity, None, None);
+                }
+                ui_state.rebalance = None;
+                ui_state.market_data_active_panel = MarketDataActivePanel::Orders;
+            }
+            (_, staged) => view.staged = staged,
+        }
+        return true;
+    }
+    if ui_state.connection_open {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('l') | KeyCode::Char('L') => {
+                ui_state.connection_open = false
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => app_state.connection.reconnect(),
+            KeyCode::Tab => app_state.connection.switch_source(),
+            _ => {}
+        }
+        return true;
+    }
+    if let Some(idx) = ui_state.panel_open {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('v') | KeyCode::Char('V') => ui_state.panel_open = None,
+            KeyCode::Tab => ui_state.panel_open = Some((idx + 1) % ui_state.panels.len()),
+            _ => ui_state.panels[idx].handle_action(key, app_state),
+        }
+        return true;
+    }
+    // so is the archive, it covers both panels
+    if let Some(page) = ui_state.news_archive_page {
+        let last_page = ui_state.news_archive_page_count(app_state) - 1;
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('a') | KeyCode::Char('A') => {
+                ui_state.news_archive_page = None
+            }
+            KeyCode::Left | KeyCode::Up | KeyCode::PageUp => {
+                ui_state.news_archive_page = Some(page.saturating_sub(1))
+            }
+            KeyCode::Right | KeyCode::Down | KeyCode::PageDown => {
+                ui_state.news_archive_page = Some(min(last_page, page + 1))
+            }
+            KeyCode::Home => ui_state.news_archive_page = Some(0),
+            KeyCode::End => ui_state.news_archive_page = Some(last_page),
+            KeyCode::Char('/') => {
+                ui_state.history.record(Change::news_filter(ui_state));
+                ui_state.news_list.search_editing = true
+            }
+            KeyCode::Char('f') | KeyCode::Char('F') => ui_state.news_filter_menu = Some(0),
+            _ => {}
+        }
+        return true;
+    }
+    // closed with Esc or whatever opens it
+    if let Some(scroll) = ui_state.help.as_mut() {
+        if key.code == KeyCode::Esc
+            || ui_state.keymap.action(Context::Global, &key) == Some(Action::Help)
+        {
+            ui_state.help = None;
+        } else {
+            scroll.on_event(key, help_lines(&ui_state.keymap).len());
+        }
+        return true;
+    }
+    true
+}
+
+// the order form and the row menu open over it, they're modals of their own
+fn handle_detail(key: KeyEvent, app_state: &mut AppState, ui_state: &mut UIState) {
+    let Some(ticker) = &ui_state.instrument_detail else {
+        return;
+    };
+    match key.code {
+        KeyCode::Esc => ui_state.instrument_detail = None,
+        // a second Enter, the first one opened the view
+        KeyCode::Enter | KeyCode::Char('m') | KeyCode::Char('M') => {
+            if let Some(idx) = app_state.quote_index(ticker) {
+                ui_state.row_menu = Some(RowMenu::new(app_state, idx, ui_state.market_table.watch));
+            }
+        }
+        KeyCode::Char('c') | KeyCode::Char('C') => {
+            ui_state.instrument_detail_view =
+                ui_state.instrument_detail_view.toggled(DetailView::Chart)
+        }
+        KeyCode::Char('d') | KeyCode::Char('D') => {
+            ui_state.instrument_detail_view =
+                ui_state.instrument_detail_view.toggled(DetailView::Depth)
+        }
+        KeyCode::Char('r') | KeyCode::Char('R') => {
+            ui_state.instrument_detail_view =
+                ui_state.instrument_detail_view.toggled(DetailView::Returns)
+        }
+        KeyCode::Char('+') | KeyCode::Char('=')
+            if ui_state.instrument_detail_view == DetailView::Chart =>
+        {
+            ui_state.chart.zoom_in()
+        }
+        KeyCode::Char('-') if ui_state.instrument_detail_view == DetailView::Chart => {
+            ui_state.chart.zoom_out()
+        }
+        KeyCode::Left | KeyCode::Right if ui_state.instrument_detail_view == DetailView::Chart => {
+            let len = app_state.quote_index(ticker).map_or(0, |idx| {
+                ui_state.chart.candles(&app_state.quotes[idx].history).len()
+            });
+            ui_state.chart.step(key.code == KeyCode::Left, len)
+        }
+        KeyCode::Char('t') | KeyCode::Char('T')
+            if ui_state.instrument_detail_view == DetailView::Chart =>
+        {
+            ui_state.chart.next_timeframe()
+        }
+        KeyCode::Char('x') | KeyCode::Char('X')
+            if ui_state.instrument_detail_view == DetailView::Chart =>
+        {
+            let chart = &mut ui_state.chart;
+            chart.cursor = match chart.cursor {
+                Some(_) => None,
+                None => Some(chart.pan),
+            }
+        }
+        KeyCode::Char('b') | KeyCode::Char('s') => {
+            let side = if key.code == KeyCode::Char('b') {
+                Side::Buy
+            } else {
+                Side::Sell
+            };
+            ui_state.order_form = Some(OrderForm::new(app_state, ticker, side));
+        }
+        _ => {}
+    }
+}
+
+// every key goes into the news search or the market screen, whichever is being typed
+fn handle_search(key: KeyEvent, ui_state: &mut UIState) {
+    if ui_state.news_list.search_editing {
+        match key.code {
+            KeyCode::Enter => {
+                ui_state.news_list.search_editing = false;
+                if ui_state
+                    .news_list
+                    .search
+                    .as_ref()
+                    .is_some_and(|query| query.text.is_empty())
+                {
+                    ui_state.news_list.search = None;
+                }
+            }
+            KeyCode::Esc => {
+                ui_state.news_list.search_editing = false;
+                ui_state.news_list.search = None;
+            }
+            _ => {
+                ui_state
+                    .news_list
+                    .search
+                    .get_or_insert_with(TextInput::default)
+                    .on_event(key);
+            }
+        }
+        ui_state.reset_news_positions();
+    } else {
+        // the rows update as the screen is typed
+        match key.code {
+            KeyCode::Enter => {
+                ui_state.market_table.screen_editing = false;
+                if ui_state
+                    .market_table
+                    .screen
+                    .as_ref()
+                    .is_some_and(|query| query.text.is_empty())
+                {
+                    ui_state.market_table.screen = None;
+                }
+            }
+            KeyCode::Esc => {
+                ui_state.market_table.screen_editing = false;
+                ui_state.market_table.screen = None;
+            }
+            _ => {
+                ui_state
+                    .market_table
+                    .screen
+                    .get_or_insert_with(TextInput::default)
+                    .on_event(key);
+            }
+        }
+        ui_state.market_table.scroll.pos = 0;
+    }
 }
 
-impl NewsItem {
-    fn new(title: &str, subtitle: &str) -> NewsItem {
-        NewsItem {
-            title: title.to_string(),
-            subtitle: subtitle.to_string(),
+// the panels and whatever the keymap binds, the focused panel's keys before the global ones
+fn handle_normal(key: KeyEvent, app_state: &mut AppState, ui_state: &mut UIState) -> bool {
+    // scrolling and whatever else belongs to the focused panel
+    if ui_state.focused().on_event(key, app_state) {
+        return true;
+    }
+    if let KeyCode::Char(c @ '0'..='9') = key.code {
+        ui_state.macros.push_digit(c.to_digit(10).unwrap_or(0));
+        return true;
+    }
+    let panel = ui_state.market_data_active_panel;
+    let Some(action) = ui_state.keymap.action(Context::of(panel), &key) else {
+        ui_state.macros.count = None;
+        return true;
+    };
+    // a count only goes with the @ right after it
+    if action != Action::Play {
+        ui_state.macros.count = None;
+    }
+    match action {
+        Action::Record => {
+            if ui_state.macros.recording.is_some() {
+                ui_state.macros.stop();
+            } else if !ui_state.macros.playing {
+                ui_state.macros.pending = Some(Pending::Record);
+            }
+        }
+        Action::Play if !ui_state.macros.playing => ui_state.macros.pending = Some(Pending::Play),
+        Action::Quit => ui_state.report_view = Some(ReportView::new(app_state, true)),
+        Action::Report => ui_state.report_view = Some(ReportView::new(app_state, false)),
+        // the watch layout has the market panel only, with every watched ticker on it
+        Action::PrevPanel | Action::NextPanel if ui_state.market_table.watch => {}
+        Action::PrevPanel => ui_state.market_data_active_panel = panel.prev(),
+        Action::NextPanel => ui_state.market_data_active_panel = panel.next(),
+        Action::Jump => {
+            let target = ui_state
+                .news_list
+                .visible(app_state)
+                .get(ui_state.news_list.scroll.pos)
+                .and_then(|news_item| news_item.tickers.first())
+                .and_then(|ticker| app_state.quote_index(ticker));
+            if let Some(idx) = target {
+                ui_state.select_quote(app_state, idx);
+            }
+        }
+        Action::OpenLink => {
+            if let Some(link) = ui_state
+                .news_list
+                .visible(app_state)
+                .get(ui_state.news_list.scroll.pos)
+                .and_then(|news_item| news_item.link.as_ref())
+            {
+                // nowhere to tell about a missing browser, the headline is a link anyway
+                let _ = open_url(link);
+            }
+        }
+        Action::Categories => ui_state.news_filter_menu = Some(0),
+        Action::Archive => ui_state.news_archive_page = Some(0),
+        Action::Search => {
+            ui_state.history.record(Change::news_filter(ui_state));
+            ui_state.news_list.search_editing = true
+        }
+        Action::Screen => {
+            ui_state.history.record(Change::screen(ui_state));
+            ui_state.market_table.screen_editing = true
+        }
+        Action::Achievements => ui_state.achievements_open = true,
+        Action::Alerts => ui_state.alerts_open = Some(0),
+        Action::Movers => ui_state.movers_open = Some(0),
+        Action::Connection => ui_state.connection_open = true,
+        Action::Panels if !ui_state.panels.is_empty() => ui_state.panel_open = Some(0),
+        Action::Palette => {
+            ui_state.history.record(Change::theme(ui_state));
+            ui_state.theme.next_palette()
+        }
+        Action::Settings => ui_state.settings = Some(ui_state.theme.form()),
+        Action::Saves => ui_state.saves_menu = Some(SavesMenu::default()),
+        Action::Help => ui_state.help = Some(ScrollView::default()),
+        Action::Tutorial => {
+            ui_state.tutorial = match ui_state.tutorial {
+                Some(_) => None,
+                None => Some(Tutorial::new(app_state)),
+            }
+        }
+        Action::Unread => jump_to_oldest_unread_news(app_state, ui_state),
+        Action::UndoFill => {
+            app_state.undo_last_fill();
+        }
+        // whichever came last, a trade still in its grace window or a change to the view
+        Action::Undo => {
+            let fill_time = app_state.undoable_fill().map(|(fill, _)| fill.time);
+            let last_change = ui_state.history.last_change();
+            if fill_time.is_some_and(|fill_time| last_change.is_none_or(|time| fill_time > time)) {
+                app_state.undo_last_fill();
+            } else {
+                undo(ui_state);
+            }
+        }
+        Action::Redo => redo(ui_state),
+        Action::Modify => {
+            // stop orders can only be cancelled, the form has no stop price field
+            if let Some(order) = ui_state
+                .selected_order(app_state)
+                .filter(|order| order.is_open() && order.stop_price.is_none())
+            {
+                ui_state.order_form = Some(OrderForm::amend(app_state, order));
+            }
+        }
+        Action::CancelOrder => {
+            if let Some(order) = ui_state
+                .selected_order(app_state)
+                .filter(|order| order.is_open())
+            {
+                ui_state.confirm = Some(Confirm::CancelOrder(order.id));
+            }
+        }
+        Action::ClearHistory
+            if ui_state.orders_table.show_blotter && !app_state.blotter.is_empty() =>
+        {
+            ui_state.confirm = Some(Confirm::ClearBlotter)
+        }
+        Action::Details => {
+            if let Some(idx) = ui_state.selected_quote_index(app_state) {
+                ui_state.instrument_detail = Some(app_state.quotes[idx].listing.ticker.clone());
+                ui_state.instrument_detail_view = DetailView::Details;
+                ui_state.chart = ChartView::default();
+            }
+        }
+        Action::RowMenu => {
+            if let Some(idx) = ui_state.selected_quote_index(app_state) {
+                ui_state.row_menu = Some(RowMenu::new(app_state, idx, ui_state.market_table.watch));
+            }
         }
+        Action::Compare if !ui_state.compare_tickers.is_empty() => ui_state.compare_open = true,
+        Action::Rebalance => ui_state.rebalance = Some(RebalanceView::default()),
+        Action::Schedules => ui_state.schedules_open = Some(0),
+        Action::Ledger => ui_state.ledger = Some(LedgerView::default()),
+        Action::Fx => ui_state.fx = Some(FxView::default()),
+        Action::Accounts => {
+            ui_state.accounts = Some(AccountsView {
+                cursor: app_state.active,
+                transfer: None,
+            })
+        }
+        // options are only listed for company shares
+        Action::Options
+            if ui_state.market_table.tab == InstrumentClass::Equity
+                && !ui_state.market_table.watch =>
+        {
+            if let Some(idx) = ui_state.selected_quote_index(app_state) {
+                row_action(RowAction::Options, idx, app_state, ui_state);
+            }
+        }
+        Action::Buy
+        | Action::Sell
+        | Action::MarkCompare
+        | Action::ByCompany
+        | Action::Liquidate => {
+            let action = match action {
+                Action::Buy => RowAction::Buy,
+                Action::Sell => RowAction::Sell,
+                Action::MarkCompare => RowAction::Compare,
+                Action::ByCompany => RowAction::News,
+                _ => RowAction::Liquidate,
+            };
+            if let Some(idx) = ui_state.selected_quote_index(app_state) {
+                row_action(action, idx, app_state, ui_state);
+            }
+        }
+        _ => {}
     }
+    true
 }
 
-#[derive(Debug)]
-struct Quote {
-    price: f64,
-    price_yesterday: f64,
+// what an event did, quitting is the one the loop has to know about
+enum Handled {
+    Nothing,
+    Changed,
+    Quit,
 }
 
-impl Quote {
-    fn random(
-        rng: &mut ThreadRng,
-        price_min: f64,
-        price_max: f64,
-        change_pct_min: f64,
-        change_pct_max: f64,
-    ) -> Quote {
-        let price = rng.random_range(RangeInclusive::new(price_min, price_max));
-        Quote {
-            price,
-            price_yesterday: (1.0
-                + rng.random_range(RangeInclusive::new(change_pct_min, change_pct_max)) / 100.0)
-                * price,
+// a key, a paste or a change to the terminal, made-up ones from the monkey too, releases
+// don't do anything
+fn handle_event(event: Event, app_state: &mut AppState, ui_state: &mut UIState) -> Handled {
+    match event {
+        Event::Key(key) if key.kind != KeyEventKind::Release => {
+            if !handle_key(key, app_state, ui_state) {
+                return Handled::Quit;
+            }
+            if let Some(tutorial) = ui_state.tutorial {
+                ui_state.tutorial = Some(tutorial.advanced(app_state, ui_state));
+            }
+            app_state.check_achievements();
+            Handled::Changed
+        }
+        Event::Paste(text) => {
+            handle_paste(&text, ui_state);
+            Handled::Changed
         }
+        Event::Resize(_, _) | Event::FocusGained => Handled::Changed,
+        _ => Handled::Nothing,
     }
 }
 
-#[derive(Debug)]
-struct StockQuote<'a> {
-    company: &'a Company,
-    quote: Quote,
+// the keys that move a cursor or scroll, held down they repeat
+fn is_navigation(key: &KeyEvent) -> bool {
+    matches!(
+        key.code,
+        KeyCode::Up
+            | KeyCode::Down
+            | KeyCode::Left
+            | KeyCode::Right
+            | KeyCode::PageUp
+            | KeyCode::PageDown
+    )
 }
 
-fn gen_quotes<'a>(rng: &mut ThreadRng, companies: &'a Vec<Company>) -> Vec<StockQuote<'a>> {
-    companies
-        .iter()
-        .map(|company| StockQuote {
-            company,
-            quote: Quote::random(rng, 500.0, 3000.0, -10.0, 10.0),
-        })
-        .collect()
+// a repeat of the navigation key that moved the cursor since the last frame, dropped so the
+// cursor doesn't run on after the key is let go. with the kitty protocol that's only what it
+// reports as repeats, elsewhere presses and repeats look the same and any of the same key is
+fn is_held(held: &mut Option<KeyEvent>, event: &Event, enhanced_keys: bool) -> bool {
+    let Event::Key(key) = event else {
+        return false;
+    };
+    if key.kind == KeyEventKind::Release {
+        return false;
+    }
+    let repeated = (!enhanced_keys || key.kind == KeyEventKind::Repeat)
+        && held.is_some_and(|held| held.code == key.code && held.modifiers == key.modifiers);
+    *held = is_navigation(key).then_some(*key);
+    repeated
 }
 
-struct AppState<'a> {
-    quotes: Vec<StockQuote<'a>>,
-    currency_name_plural: String,
-    currency_symbol: String,
-    news: Vec<NewsItem>,
+// a paste only goes into whatever is being typed, anywhere else it's dropped
+fn handle_paste(text: &str, ui_state: &mut UIState) {
+    let text = text.trim();
+    if ui_state.confirm.is_some() {
+        return;
+    }
+    if let Some(form) = ui_state.order_form.as_mut() {
+        form.form.paste(text);
+    } else if let Some(form) = ui_state.alert_form.as_mut() {
+        form.form.paste(text);
+    } else if let Some(form) = ui_state.schedule_form.as_mut() {
+        form.form.paste(text);
+    } else if let Some(form) = ui_state
+        .accounts
+        .as_mut()
+        .and_then(|view| view.transfer.as_mut())
+    {
+        form.paste(text);
+    } else if let Some((_, form)) = ui_state.fx.as_mut().and_then(|view| view.form.as_mut()) {
+        form.paste(text);
+    } else if let Some(form) = ui_state.settings.as_mut() {
+        form.paste(text);
+    } else if ui_state.news_list.search_editing {
+        ui_state
+            .news_list
+            .search
+            .get_or_insert_with(TextInput::default)
+            .insert(text);
+        ui_state.reset_news_positions();
+    } else if ui_state.market_table.screen_editing {
+        ui_state
+            .market_table
+            .screen
+            .get_or_insert_with(TextInput::default)
+            .insert(text);
+        ui_state.market_table.scroll.pos = 0;
+    } else if let Some(name) = ui_state
+        .saves_menu
+        .as_mut()
+        .and_then(|menu| menu.naming.as_mut())
+    {
+        name.insert(text);
+    }
+}
+
+// what can be done to a market row, by its key or from the row menu
+fn row_action(action: RowAction, idx: usize, app_state: &AppState, ui_state: &mut UIState) {
+    let ticker = &app_state.quotes[idx].listing.ticker;
+    match action {
+        RowAction::Buy => ui_state.order_form = Some(OrderForm::new(app_state, ticker, Side::Buy)),
+        RowAction::Sell => {
+            ui_state.order_form = Some(OrderForm::new(app_state, ticker, Side::Sell))
+        }
+        RowAction::Chart => {
+            ui_state.instrument_detail = Some(ticker.clone());
+            ui_state.instrument_detail_view = DetailView::Chart;
+            ui_state.chart = ChartView::default();
+        }
+        // marking a fifth ticker pushes out the first one marked
+        RowAction::Compare => {
+            ui_state.history.record(Change::compare(ui_state));
+            if let Some(pos) = ui_state.compare_tickers.iter().position(|t| t == ticker) {
+                ui_state.compare_tickers.remove(pos);
+            } else {
+                if ui_state.compare_tickers.len() == MAX_COMPARE_TICKERS {
+                    ui_state.compare_tickers.remove(0);
+                }
+                ui_state.compare_tickers.push(ticker.clone());
+            }
+        }
+        RowAction::Options => {
+            let mut view = OptionsView::new(ticker);
+            // start on the at-the-money strike
+            view.strike_idx = app_state
+                .option_chain(ticker)
+                .map_or(0, |chain| chain.strikes.len() / 2);
+            ui_state.options_view = Some(view);
+        }
+        // pressing again on the same company clears the filter
+        RowAction::News => {
+            ui_state.history.record(Change::news_filter(ui_state));
+            ui_state.news_list.ticker_filter =
+                if ui_state.news_list.ticker_filter.as_ref() == Some(ticker) {
+                    None
+                } else {
+                    Some(ticker.clone())
+                };
+            ui_state.reset_news_positions();
+        }
+        // nowhere to tell about a terminal that ignores it
+        RowAction::CopyTicker => {
+            let _ = copy_to_clipboard(ticker);
+        }
+        RowAction::Alert => {
+            let quote = &app_state.quotes[idx];
+            ui_state.alert_form = Some(AlertForm::new(
+                ticker,
+                app_state.exchanges[quote.exchange].local_price(quote.quote.price),
+                quote.precision,
+            ))
+        }
+        RowAction::Schedule => {
+            ui_state.schedule_form = Some(ScheduleForm::new(ticker, app_state.active))
+        }
+        RowAction::Liquidate if app_state.portfolio().quantity(ticker) > 0 => {
+            ui_state.confirm = Some(Confirm::Liquidate(ticker.clone()))
+        }
+        RowAction::Liquidate => {}
+    }
 }
 
-#[derive(PartialEq)]
-enum MarketDataActivePanel {
-    MarketData,
-    LatestNews,
+// after y or Enter in the dialog
+fn confirmed(confirm: Confirm, app_state: &mut AppState, ui_state: &mut UIState) {
+    match confirm {
+        Confirm::CancelOrder(order_id) => app_state.cancel_order(order_id),
+        // whatever is held by the time it's confirmed
+        Confirm::Liquidate(ticker) => {
+            let quantity = app_state.portfolio().quantity(&ticker);
+            if quantity > 0 {
+                app_state.place_order(&ticker, Side::Sell, quantity, None, None);
+            }
+        }
+        Confirm::ClearBlotter => {
+            app_state.blotter.clear();
+            ui_state.orders_table.scroll.pos = 0;
+        }
+        Confirm::NewGame => {
+            app_state.start_over();
+            ui_state.saves_menu = None;
+            ui_state.orders_table.scroll.pos = 0;
+            ui_state.drop_delisted(app_state);
+            ui_state.clamp_scroll(app_state);
+        }
+    }
 }
 
-struct UIState {
-    market_data_active_panel: MarketDataActivePanel,
-    market_data_scroll_pos: usize,
-    latest_news_scroll_pos: usize,
+// what the control socket can do, checked the way the order form and the keys would
+fn handle_control(
+    request: &ControlRequest,
+    app_state: &mut AppState,
+    ui_state: &mut UIState,
+) -> Result<Value, RpcError> {
+    let unknown = |ticker: &str| RpcError::InvalidParams(format!("unknown ticker {0}", ticker));
+    match request.method.as_str() {
+        "select_ticker" => {
+            let params: TickerParams = request.params()?;
+            let idx = app_state
+                .quote_index(&params.ticker)
+                .ok_or_else(|| unknown(&params.ticker))?;
+            ui_state.select_quote(app_state, idx);
+            Ok(Value::Null)
+        }
+        "place_order" => {
+            let params: OrderParams = request.params()?;
+            if app_state.quote_index(&params.ticker).is_none() {
+                return Err(unknown(&params.ticker));
+            }
+            if params.quantity == 0 {
+                return Err(RpcError::InvalidParams(
+                    "quantity must be at least 1".to_string(),
+                ));
+            }
+            if params.limit_price.is_some_and(|price| price <= 0.0) {
+                return Err(RpcError::InvalidParams(
+                    "limit_price must be above 0".to_string(),
+                ));
+            }
+            let order_id = app_state.place_order(
+                &params.ticker,
+                params.side,
+                params.quantity,
+                params.limit_price,
+                None,
+            );
+            Ok(json!({ "order_id": order_id }))
+        }
+        "set_theme" => {
+            let params: ThemeParams = request.params()?;
+            ui_state.theme.palette = params.palette;
+            Ok(Value::Null)
+        }
+        "get_state" => Ok(control_state(app_state, ui_state)),
+        _ => Err(RpcError::UnknownMethod),
+    }
 }
 
-fn build_market_data_row<'a>(
-    quote: &'a StockQuote<'a>,
-    currency_symbol: &String,
-    description_width: u16,
-) -> Row<'a> {
-    let percent_change =
-        (quote.quote.price - quote.quote.price_yesterday) / quote.quote.price_yesterday * 100.0;
-
-    let description_text = Text::from(
-        textwrap::wrap(
-            quote.company.description.as_str(),
-            Options::new(description_width as usize),
-        )
+fn control_state(app_state: &AppState, ui_state: &UIState) -> Value {
+    let quotes = app_state
+        .quotes
         .iter()
-        .map(|s| Line::from(s.clone()))
-        .collect::<Vec<Line>>(),
-    );
-    let description_height = description_text.lines.len() as u16;
-
-    Row::new(vec![
-        Cell::from(quote.company.ticker.as_str()),
-        Cell::from(quote.company.name.as_str()),
-        Cell::from(format!(
-            "{0:>7.2} {1:<3}",
-            quote.quote.price, currency_symbol
-        )),
-        Cell::from(format!("{0:>6.2}%", percent_change)).style(if percent_change >= 0.0 {
-            Color::Green
-        } else {
-            Color::Red
-        }),
-        Cell::from(description_text),
-    ])
-    .style(Style::default().fg(Color::White))
-    .height(description_height)
+        .map(|quote| {
+            let exchange = &app_state.exchanges[quote.exchange];
+            let price = exchange.local_price(quote.quote.price);
+            let price_yesterday = exchange.local_price_yesterday(quote.quote.price_yesterday);
+            json!({
+                "ticker": quote.listing.ticker,
+                "exchange": exchange.code,
+                "price": price,
+                "change_pct": (price - price_yesterday) / price_yesterday * 100.0,
+                "volume": quote.volume,
+            })
+        })
+        .collect::<Vec<Value>>();
+    json!({
+        "ticks": app_state.ticks,
+        "selected_ticker": ui_state
+            .selected_quote_index(app_state)
+            .map(|idx| &app_state.quotes[idx].listing.ticker),
+        "palette": ui_state.theme.palette,
+        "account": app_state.accounts[app_state.active].name,
+        "cash": app_state.portfolio().cash,
+        "unsettled_cash": app_state.portfolio().unsettled_cash(),
+        "portfolio_value": app_state.portfolio_value(),
+        "quotes": quotes,
+        "positions": app_state.portfolio().positions,
+        "orders": app_state.orders,
+    })
 }
 
-fn draw(frame: &mut Frame, app_state: &AppState, uistate: &UIState) {
-    use Constraint::{Fill, Length, Min};
+// --control /tmp/iron-ledger.sock, where to take JSON-RPC calls
+fn control_arg() -> Result<Option<PathBuf>, String> {
+    match arg_value("--control") {
+        Some(Some(path)) => Ok(Some(PathBuf::from(path))),
+        Some(None) => Err("--control takes the socket path to listen on".to_string()),
+        None => Ok(None),
+    }
+}
 
-    let main_vertical_layout = Layout::vertical([Min(0), Length(1)]);
-    let [main_area, status_area] = main_vertical_layout.areas(frame.area());
-    let middle_horizontal_layout = Layout::horizontal([Fill(3), Fill(2)]);
-    let [market_data_area, latest_news_area] = middle_horizontal_layout.areas(main_area);
+// the value of an option given as --name value or --name=value, Some(None) when it's missing
+fn arg_value(name: &str) -> Option<Option<String>> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        return match arg.strip_prefix(name) {
+            Some("") => Some(args.next()),
+            Some(rest) if rest.starts_with('=') => Some(Some(rest[1..].to_string())),
+            _ => continue,
+        };
+    }
+    None
+}
 
-    let active_border_style = Style::default().fg(Color::Cyan);
-    let inactive_border_style = Style::default();
+// --difficulty easy|normal|brutal, or --difficulty=brutal
+fn difficulty_arg() -> Result<Option<Difficulty>, String> {
+    let Some(value) = arg_value("--difficulty") else {
+        return Ok(None);
+    };
+    match value.as_deref().and_then(Difficulty::parse) {
+        Some(difficulty) => Ok(Some(difficulty)),
+        None => Err("--difficulty takes easy, normal or brutal".to_string()),
+    }
+}
 
-    // conditional style based on active panel affecting border color only
-    let market_data_block = Block::bordered().title("The Iron Ledger").border_style(
-        if uistate.market_data_active_panel == MarketDataActivePanel::MarketData {
-            active_border_style
-        } else {
-            inactive_border_style
-        },
-    );
-    let latest_news_block = Block::bordered().title("Latest news").border_style(
-        if uistate.market_data_active_panel == MarketDataActivePanel::LatestNews {
-            active_border_style
-        } else {
-            inactive_border_style
+// --color 16|256|truecolor, otherwise whatever the terminal advertises
+fn color_arg() -> Result<ColorDepth, String> {
+    let Some(value) = arg_value("--color") else {
+        return Ok(ColorDepth::detect());
+    };
+    value
+        .as_deref()
+        .and_then(ColorDepth::parse)
+        .ok_or("--color takes 16, 256 or truecolor".to_string())
+}
+
+// --watch BCI,AETH,SSF, or the same list in IRON_LEDGER_WATCH, for a bare price monitor
+fn watch_arg() -> Result<Option<Vec<String>>, String> {
+    let value = match arg_value("--watch") {
+        Some(value) => value.unwrap_or_default(),
+        None => match std::env::var("IRON_LEDGER_WATCH") {
+            Ok(value) if !value.trim().is_empty() => value,
+            _ => return Ok(None),
         },
-    );
+    };
+    let tickers = value
+        .split(',')
+        .map(|ticker| ticker.trim().to_uppercase())
+        .filter(|ticker| !ticker.is_empty())
+        .collect::<Vec<String>>();
+    if tickers.is_empty() {
+        return Err("--watch takes a list of tickers like BCI,AETH,SSF".to_string());
+    }
+    Ok(Some(tickers))
+}
 
-    let market_data_inner_area = market_data_block.inner(market_data_area);
-    let latest_news_inner_area = latest_news_block.inner(latest_news_area);
-    let [market_data_table_area, market_data_status_area] =
-        Layout::vertical([Fill(1), Length(1)]).areas(market_data_inner_area);
+// --record session.cast, the file to keep an asciinema recording of the session in
+fn record_arg() -> Result<Option<PathBuf>, String> {
+    match arg_value("--record") {
+        Some(Some(path)) => Ok(Some(PathBuf::from(path))),
+        Some(None) => Err("--record takes the file to record to".to_string()),
+        None => Ok(None),
+    }
+}
 
-    let market_data_column_constraints = [Length(8), Length(30), Length(10), Length(7), Fill(1)];
+// --backfill 30, days of made-up daily candles to start the charts with, the same ones every
+// time with --seed 7
+fn backfill_arg() -> Result<Option<(u32, u64)>, String> {
+    let days = match arg_value("--backfill") {
+        Some(value) => value
+            .as_deref()
+            .and_then(|days| days.parse::<u32>().ok())
+            .filter(|days| (1..=MAX_BACKFILL_DAYS).contains(days))
+            .ok_or(format!(
+                "--backfill takes the number of days, from 1 to {0}",
+                MAX_BACKFILL_DAYS
+            ))?,
+        None => return Ok(None),
+    };
+    Ok(Some((days, seed_arg()?)))
+}
 
-    let description_width = max(
-        Layout::horizontal(market_data_column_constraints).areas::<5>(market_data_table_area)[4]
-            .width,
-        24,
-    ) - 4; //remember to subtract column spacing, and give it some minimum
+// --seed 7, for the same made-up history or monkey events every time, random when not set
+fn seed_arg() -> Result<u64, String> {
+    match arg_value("--seed") {
+        Some(value) => value
+            .as_deref()
+            .and_then(|seed| seed.parse::<u64>().ok())
+            .ok_or("--seed takes a whole number".to_string()),
+        None => Ok(rand::rng().random()),
+    }
+}
 
-    let rows = app_state
-        .quotes
-        .iter()
-        .skip(uistate.market_data_scroll_pos)
-        .map(|quote| build_market_data_row(quote, &app_state.currency_symbol, description_width));
-
-    let table = Table::new(rows, market_data_column_constraints)
-        .column_spacing(1)
-        .header(
-            Row::new(vec!["Ticker", "Name", "Price", "Change%", "Description"])
-                .style(Style::new().fg(Color::Gray).italic())
-                .bottom_margin(1),
-        );
+// --monkey, or --monkey 100000 for that many random events instead of the default
+fn monkey_arg() -> Result<Option<u32>, String> {
+    let Some(value) = arg_value("--monkey") else {
+        return Ok(None);
+    };
+    match value.filter(|value| !value.starts_with("--")) {
+        Some(value) => value
+            .parse::<u32>()
+            .ok()
+            .filter(|events| *events > 0)
+            .map(Some)
+            .ok_or("--monkey takes the number of events".to_string()),
+        None => Ok(Some(MONKEY_EVENTS)),
+    }
+}
 
-    let news = Paragraph::new(
-        app_state
-            .news
-            .iter()
-            .skip(uistate.latest_news_scroll_pos)
-            .map(|news_item| {
-                let title = Line::from(news_item.title.as_str())
-                    .style(Style::default().fg(Color::White).bold());
-                let subtitle = Line::from(news_item.subtitle.as_str());
-                vec![title, subtitle, Line::from("")]
-            })
-            .flatten()
-            .collect::<Vec<Line>>(),
-    )
-    .wrap(Wrap { trim: true });
-
-    frame.render_widget(latest_news_block, latest_news_area);
-    frame.render_widget(market_data_block, market_data_area);
-    frame.render_widget(
-        Block::new()
-            .borders(Borders::TOP)
-            .title(
-                "↑↓ - Scroll Up/Down"
-                    .bg(Color::Cyan)
-                    .fg(Color::Black)
-                    .bold(),
-            )
-            .title("←→ - Switch Panels".bg(Color::Cyan).fg(Color::Black).bold())
-            .title("Esc/Q - Quit".bg(Color::Cyan).fg(Color::Black).bold())
-            .border_style(Style::default().fg(Color::Cyan)),
-        status_area,
+// --universe 20000, that many made-up companies on top of the built-in ones, to try out huge
+// markets with
+fn universe_arg() -> Result<usize, String> {
+    match arg_value("--universe") {
+        Some(value) => value
+            .as_deref()
+            .and_then(|count| count.parse::<usize>().ok())
+            .filter(|count| (1..=MAX_UNIVERSE).contains(count))
+            .ok_or(format!(
+                "--universe takes the number of companies, from 1 to {0}",
+                MAX_UNIVERSE
+            )),
+        None => Ok(0),
+    }
+}
+
+// keys other than the printable characters the monkey presses
+const MONKEY_KEYS: [KeyCode; 16] = [
+    KeyCode::Enter,
+    KeyCode::Esc,
+    KeyCode::Tab,
+    KeyCode::BackTab,
+    KeyCode::Backspace,
+    KeyCode::Delete,
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::F(1),
+    KeyCode::Insert,
+];
+
+// mostly keys, some with modifiers, now and then a click, a resize or a paste
+fn monkey_event(rng: &mut StdRng) -> Event {
+    let modifiers = match rng.random_range(0..10) {
+        0 => KeyModifiers::SHIFT,
+        1 => KeyModifiers::CONTROL,
+        2 => KeyModifiers::ALT,
+        _ => KeyModifiers::NONE,
+    };
+    match rng.random_range(0..100) {
+        0..60 => Event::Key(KeyEvent::new(
+            KeyCode::Char(rng.random_range(' '..='~')),
+            modifiers,
+        )),
+        60..90 => Event::Key(KeyEvent::new(
+            MONKEY_KEYS[rng.random_range(0..MONKEY_KEYS.len())],
+            modifiers,
+        )),
+        90..95 => Event::Mouse(MouseEvent {
+            kind: match rng.random_range(0..3) {
+                0 => MouseEventKind::Down(MouseButton::Left),
+                1 => MouseEventKind::ScrollDown,
+                _ => MouseEventKind::ScrollUp,
+            },
+            column: rng.random_range(0..200),
+            row: rng.random_range(0..60),
+            modifiers,
+        }),
+        95..98 => Event::Resize(rng.random_range(1..=200), rng.random_range(1..=60)),
+        _ => Event::Paste(
+            (0..rng.random_range(0..40))
+                .map(|_| rng.random_range(' '..='~'))
+                .collect(),
+        ),
+    }
+}
+
+// random events as fast as they can be handled, drawn into a test terminal, the market ticking
+// every so often and the lists checked after every event, the seed gives the same events again
+fn run_monkey(app_state: &mut AppState, ui_state: &mut UIState, events: u32, seed: u64) {
+    eprintln!(
+        "monkey: {0} events, run again with --monkey {0} --seed {1}",
+        events, seed
     );
-    frame.render_widget(table, market_data_table_area);
-    frame.render_widget(news, latest_news_inner_area);
-
-    // we might as well construct this on every render for now
-    let mut market_data_scrollbar_state = ScrollbarState::default()
-        .content_length(app_state.quotes.len())
-        .position(uistate.market_data_scroll_pos)
-        .viewport_content_length(5);
-
-    frame.render_stateful_widget(
-        Scrollbar::new(ScrollbarOrientation::VerticalRight)
-            .begin_symbol(Some("↑"))
-            .end_symbol(Some("↓"))
-            .style(
-                if uistate.market_data_active_panel == MarketDataActivePanel::MarketData {
-                    active_border_style
-                } else {
-                    inactive_border_style
-                },
+    let event_idx = Arc::new(AtomicU32::new(0));
+    let previous = panic::take_hook();
+    let failed_at = event_idx.clone();
+    panic::set_hook(Box::new(move |info| {
+        eprintln!(
+            "monkey: failed at event {0} of seed {1}",
+            failed_at.load(Ordering::Relaxed),
+            seed
+        );
+        previous(info);
+    }));
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut ticks = rand::rng();
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).expect("failed to make a terminal");
+    let (mut quits, started) = (0, Instant::now());
+    for idx in 0..events {
+        event_idx.store(idx, Ordering::Relaxed);
+        let event = monkey_event(&mut rng);
+        if let Event::Resize(width, height) = event {
+            terminal.backend_mut().resize(width, height);
+        }
+        if let Handled::Quit = handle_event(event, app_state, ui_state) {
+            quits += 1;
+        }
+        if idx % MONKEY_TICK_EVERY == 0 {
+            app_state.tick(&mut ticks);
+            ui_state.drop_delisted(app_state);
+            ui_state.clamp_scroll(app_state);
+        }
+        for outcome in ui_state.worker.collect() {
+            ui_state.finish(outcome);
+        }
+        let jobs = ui_state.jobs();
+        ui_state.worker.request(app_state, jobs);
+        terminal.autoresize().expect("failed to resize");
+        terminal
+            .draw(|frame| draw(frame, app_state, ui_state))
+            .expect("failed to draw frame");
+        let lists = [
+            (
+                "market table",
+                ui_state.market_table.scroll.pos,
+                ui_state.market_table.indices(app_state).len(),
             ),
-        market_data_area.inner(Margin::new(0, 1)),
-        &mut market_data_scrollbar_state,
-    );
-
-    let mut latest_news_scrollbar_state = ScrollbarState::default()
-        .content_length(app_state.news.len())
-        .position(uistate.latest_news_scroll_pos)
-        .viewport_content_length(5);
-    frame.render_stateful_widget(
-        Scrollbar::new(ScrollbarOrientation::VerticalRight)
-            .begin_symbol(Some("↑"))
-            .end_symbol(Some("↓"))
-            .style(
-                if uistate.market_data_active_panel == MarketDataActivePanel::LatestNews {
-                    active_border_style
-                } else {
-                    inactive_border_style
-                },
+            (
+                "news",
+                ui_state.news_list.scroll.pos,
+                ui_state.news_list.visible(app_state).len(),
+            ),
+            (
+                "orders",
+                ui_state.orders_table.scroll.pos,
+                ui_state.orders_table.len(app_state),
             ),
-        latest_news_area.inner(Margin::new(0, 1)),
-        &mut latest_news_scrollbar_state,
+        ];
+        for (list, pos, len) in lists {
+            assert!(
+                pos < len || pos == 0,
+                "the {0} selection is on row {1} of {2}",
+                list,
+                pos,
+                len
+            );
+        }
+    }
+    eprintln!(
+        "monkey: {0} events in {1:.1} s, {2} tries to quit, nothing broke",
+        events,
+        started.elapsed().as_secs_f64(),
+        quits
     );
+}
 
-    frame.render_widget(
-        Line::styled(
-            format!("Prices in {0}", app_state.currency_name_plural),
-            (Color::Gray, Modifier::ITALIC),
-        )
-        .alignment(Alignment::Left),
-        market_data_status_area,
+// the time a tick of the whole market takes on one thread and on all of them, the ticks take
+// turns so both are timed on a market whose history has grown the same, after as many to warm up
+fn run_benchmark(app_state: &mut AppState) -> Result<(), String> {
+    let serial = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .map_err(|err| err.to_string())?;
+    let time = |app_state: &mut AppState| {
+        let started = Instant::now();
+        app_state.tick(&mut rand::rng());
+        started.elapsed()
+    };
+    for _ in 0..BENCHMARK_TICKS {
+        time(app_state);
+    }
+    let (mut one, mut all) = (Duration::ZERO, Duration::ZERO);
+    for _ in 0..BENCHMARK_TICKS {
+        one += serial.install(|| time(app_state));
+        all += time(app_state);
+    }
+    let (one, all) = (one / BENCHMARK_TICKS, all / BENCHMARK_TICKS);
+    let threads = rayon::current_num_threads();
+    println!(
+        "{0} instruments, {1} ticks each",
+        app_state.quotes.len(),
+        BENCHMARK_TICKS
+    );
+    println!(
+        "{0:>3} thread  {1:>9.2} ms per tick",
+        1,
+        one.as_secs_f64() * 1000.0
+    );
+    println!(
+        "{0:>3} {1} {2:>9.2} ms per tick, {3:.1}x as fast",
+        threads,
+        if threads == 1 { "thread " } else { "threads" },
+        all.as_secs_f64() * 1000.0,
+        one.as_secs_f64() / all.as_secs_f64()
     );
+    if threads == 1 {
+        println!("only one core, so there's nothing for the threads to gain here");
+    }
+    Ok(())
 }
 
 fn main() {
+    let monkey = match monkey_arg().and_then(|events| Ok((events, seed_arg()?))) {
+        Ok((events, seed)) => events.map(|events| (events, seed)),
+        Err(err) => {
+            eprintln!("{0}", err);
+            std::process::exit(1);
+        }
+    };
+    // the monkey saves, exports and earns achievements like anyone, away from the real ones
+    let monkey_dir =
+        std::env::temp_dir().join(format!("iron-ledger-monkey-{0}", std::process::id()));
+    if monkey.is_some() {
+        std::env::set_var("IRON_LEDGER_DATA", &monkey_dir);
+    }
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("failed to load config: {0}", err);
+            std::process::exit(1);
+        }
+    };
+    let color_depth = match color_arg() {
+        Ok(color_depth) => color_depth,
+        Err(err) => {
+            eprintln!("{0}", err);
+            std::process::exit(1);
+        }
+    };
+    set_language(config.display.language.unwrap_or_else(Language::detect));
+    set_abbreviated(config.display.abbreviate_numbers);
+    let accessible = config.display.accessible || std::env::args().any(|arg| arg == "--accessible");
+    // box drawing and arrows need a UTF-8 locale
+    let ascii = std::env::args().any(|arg| arg == "--ascii") || !utf8_locale();
+    let difficulty = match difficulty_arg() {
+        Ok(difficulty) => difficulty.unwrap_or(config.game.difficulty),
+        Err(err) => {
+            eprintln!("{0}", err);
+            std::process::exit(1);
+        }
+    };
+    let scenario = match &config.market.scenario {
+        Some(path) => match Scenario::load(path) {
+            Ok(scenario) => scenario,
+            Err(err) => {
+                eprintln!("failed to load scenario: {0}", err);
+                std::process::exit(1);
+            }
+        },
+        None => Scenario::default(),
+    };
+    let format_rules = match config
+        .display
+        .format_rules
+        .iter()
+        .map(FormatRule::parse)
+        .collect::<Result<Vec<FormatRule>, String>>()
+    {
+        Ok(format_rules) => format_rules,
+        Err(err) => {
+            eprintln!("{0}", err);
+            std::process::exit(1);
+        }
+    };
+    let accents = match config
+        .display
+        .tickers
+        .iter()
+        .map(|(ticker, accent)| Ok((ticker.clone(), Accent::parse(ticker, accent)?)))
+        .collect::<Result<HashMap<String, Accent>, String>>()
+    {
+        Ok(accents) => accents,
+        Err(err) => {
+            eprintln!("{0}", err);
+            std::process::exit(1);
+        }
+    };
+    let keymap = match Keymap::new(&config.keys) {
+        Ok(keymap) => keymap,
+        Err(err) => {
+            eprintln!("{0}", err);
+            std::process::exit(1);
+        }
+    };
+    if let Some(name) = config
+        .display
+        .columns
+        .keys()
+        .find(|name| !MARKET_COLUMNS.iter().any(|(column, _, _)| column == name))
+    {
+        eprintln!(
+            "unknown column \"{0}\" in [display.columns], try {1}",
+            name,
+            MARKET_COLUMNS.map(|(column, _, _)| column).join(", ")
+        );
+        std::process::exit(1);
+    }
+    if !(0.0..100.0).contains(&config.trading.margin_pct) {
+        eprintln!("margin_pct in [trading] must be at least 0 and below 100");
+        std::process::exit(1);
+    }
+    let record = match record_arg() {
+        Ok(record) => record,
+        Err(err) => {
+            eprintln!("{0}", err);
+            std::process::exit(1);
+        }
+    };
+    let control_path = match control_arg() {
+        Ok(control_path) => control_path,
+        Err(err) => {
+            eprintln!("{0}", err);
+            std::process::exit(1);
+        }
+    };
+    let watch = match watch_arg() {
+        Ok(watch) => watch,
+        Err(err) => {
+            eprintln!("{0}", err);
+            std::process::exit(1);
+        }
+    };
+    let universe = match universe_arg() {
+        Ok(universe) => gen_universe(universe),
+        Err(err) => {
+            eprintln!("{0}", err);
+            std::process::exit(1);
+        }
+    };
+    let backfill = match backfill_arg() {
+        Ok(backfill) => backfill,
+        Err(err) => {
+            eprintln!("{0}", err);
+            std::process::exit(1);
+        }
+    };
+    let replay = match &config.market.replay {
+        Some(path) => match Replay::load(path) {
+            Ok(replay) => Some(replay),
+            Err(err) => {
+                eprintln!("failed to load replay: {0}", err);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
     let companies = vec![
-        Company::new("BCI", "BrassCog Industries", "Specializes in manufacturing precision brass cogs and gears for airships and automatons."),
-        Company::new("AETH", "Aether Dynamics", "A leading innovator in aether-based propulsion systems and energy harnessing technologies."),
-        Company::new("CWR", "Clockwork Corsairs Ltd.", "Designs and produces modular automaton soldiers and personal defense systems."),
-        Company::new("NASC", "Nimbus & Sons Airship Co.", "Renowned for their luxury dirigibles and airship travel services."),
-        Company::new("SSF", "Steamspire Foundry", "Produces high-quality steam engines, turbines, and other essential industrial machinery."),
-        Company::new("GLIM", "Gaslight Illumination Corp.", "A dominant player in gaslamp manufacturing, offering advanced lighting for urban and industrial use."),
-        Company::new("IRON", "Ironclad Armaments", "Focuses on creating steam-powered exoskeletons, weaponry, and fortifications."),
-        Company::new("VAPT", "Vaporworks Transcontinental", "Operates railways and trade routes with high-speed steam locomotives across continents."),
-        Company::new("CHIM", "Chimera Clockworks", "Specializes in bespoke clockwork gadgets, mechanical pets, and high-end timepieces."),
-        Company::new("GHRT", "Gearheart Pharmaceuticals", "Develops medical tonics, aetheric remedies, and advanced prosthetic enhancements.")
+        Listing::new("BCI", "BrassCog Industries", "Specializes in manufacturing precision brass cogs and gears for airships and automatons."),
+        Listing::new("AETH", "Aether Dynamics", "A leading innovator in aether-based propulsion systems and energy harnessing technologies."),
+        Listing::new("CWR", "Clockwork Corsairs Ltd.", "Designs and produces modular automaton soldiers and personal defense systems."),
+        Listing::new("NASC", "Nimbus & Sons Airship Co.", "Renowned for their luxury dirigibles and airship travel services."),
+        Listing::new("SSF", "Steamspire Foundry", "Produces high-quality steam engines, turbines, and other essential industrial machinery."),
+        Listing::new("GLIM", "Gaslight Illumination Corp.", "A dominant player in gaslamp manufacturing, offering advanced lighting for urban and industrial use."),
+        Listing::new("IRON", "Ironclad Armaments", "Focuses on creating steam-powered exoskeletons, weaponry, and fortifications."),
+        Listing::new("VAPT", "Vaporworks Transcontinental", "Operates railways and trade routes with high-speed steam locomotives across continents."),
+        Listing::new("CHIM", "Chimera Clockworks", "Specializes in bespoke clockwork gadgets, mechanical pets, and high-end timepieces."),
+        Listing::new("GHRT", "Gearheart Pharmaceuticals", "Develops medical tonics, aetheric remedies, and advanced prosthetic enhancements.")
+    ];
+
+    let commodities = [
+        (
+            Listing::new(
+                "RAE",
+                "Refined Aether",
+                "Per flask. Fuel for propulsion engines and aether lamps alike.",
+            ),
+            320.0,
+        ),
+        (
+            Listing::new(
+                "BRIN",
+                "Brass Ingots",
+                "Per hundredweight. The raw stuff every cog and gear is made of.",
+            ),
+            85.0,
+        ),
+    ];
+    let consols = [
+        (
+            Listing::new(
+                "CC25",
+                "Cogmark Consols 2½%",
+                "Undated government bond paying 2½% a year on 100 ₡ of face value.",
+            ),
+            2.5,
+            3.0,
+        ),
+        (
+            Listing::new(
+                "CC4",
+                "Cogmark Consols 4%",
+                "Undated government bond paying 4% a year on 100 ₡ of face value.",
+            ),
+            4.0,
+            3.0,
+        ),
+    ];
+
+    // the Londinium Exchange lists a few companies of its own and two of ours
+    let londinium_companies = vec![
+        Listing::new(
+            "THPW",
+            "Thames Pneumatic Works",
+            "Lays the pneumatic tube networks that carry the post and parcels under Londinium.",
+        ),
+        Listing::new(
+            "FOGL",
+            "Fogbound Lantern Co.",
+            "Makes storm lanterns and fog beacons for river pilots and lighthouse keepers.",
+        ),
+        Listing::new(
+            "RVDK",
+            "Riverside Dockyards",
+            "Repairs and outfits steam barges along the wharves of the Thames.",
+        ),
+    ];
+    let cross_listings = vec![
+        Listing::new(
+            "BCI.L",
+            "BrassCog Industries",
+            "Londinium listing of BCI, trades at the Cogmark price in Sovereigns.",
+        ),
+        Listing::new(
+            "AETH.L",
+            "Aether Dynamics",
+            "Londinium listing of AETH, trades at the Cogmark price in Sovereigns.",
+        ),
     ];
+    let cross_listed = ["BCI", "AETH"];
+
+    let aethercoin = Listing::new(
+        "AEC",
+        "Aethercoin",
+        "Mined by aether engines running day and night. Trades around the clock.",
+    );
 
-    let news = vec![
+    let mut news = vec![
         NewsItem::new(
             "Aether Dynamics (AETH) Soars to Record High as Demand for Aether Propulsion Fuels Industrial Boom",
             "Analysts predict sustained growth as governments invest heavily in aetheric infrastructure.",
+            NewsCategory::Technology,
+            0.8,
+            &["AETH"],
         ),
         NewsItem::new(
             "Nimbus & Sons Airship Co. (NASC) Unveils Luxury Dirigible Line, Shares Inflate by 15%",
             "New \"Gilded Skies\" model caters to elite travelers, signaling a lucrative market shift.",
+            NewsCategory::Earnings,
+            0.6,
+            &["NASC"],
         ),
         NewsItem::new(
             "Steamspire Foundry (SSF) and Gaslight Illumination Corp. (GLIM) Forge Alliance to Modernize Urban Steam Grids",
             "The partnership aims to illuminate cities more efficiently, boosting investor confidence.",
+            NewsCategory::MergersAcquisitions,
+            0.4,
+            &["SSF", "GLIM"],
         ),
         NewsItem::new(
             "Clockwork Corsairs Ltd. (CWR) Faces Turbulence Amid Regulatory Crackdown on Autonomous Automaton Deployment",
             "Shares dip 8% as concerns grow over compliance costs and international sanctions.",
+            NewsCategory::Politics,
+            -0.6,
+            &["CWR"],
         ),
         NewsItem::new(
             "Ironclad Armaments (IRON) Secures Major Defense Contract; Cogmark Exchange Hits All-Time High",
             "Market optimism surges as geopolitical tensions drive demand for mechanized weaponry.",
+            NewsCategory::Politics,
+            0.7,
+            &["IRON"],
+        ),
+        NewsItem::new(
+            "Boiler Rupture Halts Production at Steamspire Foundry (SSF) Works in Lower Cogstown",
+            "No fatalities reported, but repairs are expected to idle the main turbine hall for weeks.",
+            NewsCategory::Disaster,
+            -0.7,
+            &["SSF"],
         ),
     ];
+    // the built-in headlines are from earlier today, spread them out a bit
+    let now = Utc::now();
+    for (idx, news_item) in news.iter_mut().enumerate() {
+        news_item.published = now - TimeDelta::minutes(40 * (idx as i64 + 1));
+    }
 
+    // the price moves run on their own threads and leave a core to the terminal
+    let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+    let _ = rayon::ThreadPoolBuilder::new()
+        .num_threads((threads - 1).max(1))
+        .thread_name(|idx| format!("simulation-{0}", idx))
+        .build_global();
     let mut rng = rand::rng();
-    let app_state = AppState {
-        quotes: gen_quotes(&mut rng, &companies),
-        currency_name_plural: "Cogmarks".to_string(),
-        currency_symbol: "₡".to_string(),
+    let mut quotes = gen_equities(&mut rng, &companies);
+    quotes.extend(gen_equities(&mut rng, &universe));
+    quotes.extend(
+        commodities
+            .iter()
+            .map(|(listing, long_run_price)| gen_commodity(&mut rng, listing, *long_run_price)),
+    );
+    quotes.extend(
+        consols
+            .iter()
+            .map(|(listing, coupon_pct, long_run_yield_pct)| {
+                gen_consol(&mut rng, listing, *coupon_pct, *long_run_yield_pct)
+            }),
+    );
+    quotes.push(gen_crypto(
+        &mut rng,
+        &aethercoin,
+        config.market.crypto_volatility_pct,
+    ));
+    let session = config
+        .market
+        .session_open
+        .zip(config.market.session_close)
+        .map(|(open, close)| MarketSession { open, close });
+    let exchanges = vec![
+        Exchange::new(
+            "CGX",
+            "Cogmark Exchange",
+            "Cogmarks",
+            Currency::new("₡", SymbolPosition::Suffix, true),
+            session,
+        ),
+        Exchange::foreign(
+            &mut rng,
+            "LDX",
+            "Londinium Exchange",
+            "Sovereigns",
+            Currency::new("£", SymbolPosition::Prefix, false),
+            config.market.exchange_sessions.get("LDX").copied(),
+            7.5,
+        ),
+    ];
+    for mut quote in gen_equities(&mut rng, &londinium_companies) {
+        quote.exchange = 1;
+        quotes.push(quote);
+    }
+    for (mut quote, primary) in gen_equities(&mut rng, &cross_listings)
+        .into_iter()
+        .zip(cross_listed)
+    {
+        if let Some(primary_quote) = quotes
+            .iter()
+            .find(|primary_quote| primary_quote.listing.ticker == primary)
+        {
+            quote.quote.price = primary_quote.quote.price;
+            quote.quote.price_yesterday = primary_quote.quote.price_yesterday;
+            quote.shares = primary_quote.shares;
+        }
+        quote.exchange = 1;
+        quote.primary = Some(primary.to_string());
+        quotes.push(quote);
+    }
+    for (ticker, precision) in config.market.precision.iter() {
+        let Some(quote) = quotes
+            .iter_mut()
+            .find(|quote| quote.listing.ticker == *ticker)
+        else {
+            eprintln!("unknown ticker \"{0}\" in [market.precision]", ticker);
+            std::process::exit(1);
+        };
+        if !precision.tick_size.is_finite() || precision.tick_size <= 0.0 {
+            eprintln!(
+                "tick_size of {0} in [market.precision] must be above 0",
+                ticker
+            );
+            std::process::exit(1);
+        }
+        quote.set_precision(&mut rng, *precision);
+    }
+    let retention = Retention {
+        ticks: TimeDelta::minutes(config.market.tick_retention_mins as i64),
+        minutes: TimeDelta::hours(config.market.minute_retention_hours as i64),
+    };
+    for quote in quotes.iter_mut() {
+        quote.history.retention = retention;
+    }
+    // cross-listings get the history of their primary listing, whose prices they trade at
+    if let Some((days, seed)) = backfill {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let start = Utc::now();
+        for quote in quotes.iter_mut().filter(|quote| quote.primary.is_none()) {
+            quote.backfill(&mut rng, days, start);
+        }
+        for idx in 0..quotes.len() {
+            let Some(primary) = &quotes[idx].primary else {
+                continue;
+            };
+            if let Some(history) = quotes
+                .iter()
+                .find(|quote| quote.listing.ticker == *primary)
+                .map(|quote| quote.history.clone())
+            {
+                quotes[idx].history = history;
+            }
+        }
+    }
+    let targets = match parse_targets(&config.trading.targets, &quotes) {
+        Ok(targets) => targets,
+        Err(err) => {
+            eprintln!("{0}", err);
+            std::process::exit(1);
+        }
+    };
+    let names = if config.trading.accounts.is_empty() {
+        vec!["Main".to_string()]
+    } else {
+        config.trading.accounts.clone()
+    };
+    if let Some((_, name)) = names
+        .iter()
+        .enumerate()
+        .find(|(idx, name)| name.trim().is_empty() || names[..*idx].contains(name))
+    {
+        eprintln!(
+            "account \"{0}\" in [trading] accounts is empty or there twice",
+            name
+        );
+        std::process::exit(1);
+    }
+    // the first account is opened with the starting cash, the others start out empty
+    let accounts = names
+        .into_iter()
+        .enumerate()
+        .map(|(idx, name)| Account {
+            name,
+            portfolio: Portfolio::new(if idx == 0 { STARTING_CASH } else { 0.0 }),
+        })
+        .collect::<Vec<Account>>();
+    // watching, the rest of the market isn't loaded at all and the tickers keep the order given
+    if let Some(watch) = &watch {
+        if let Some(ticker) = watch
+            .iter()
+            .find(|ticker| !quotes.iter().any(|quote| quote.listing.ticker == **ticker))
+        {
+            eprintln!(
+                "unknown ticker \"{0}\" in --watch, try {1}",
+                ticker,
+                quotes
+                    .iter()
+                    .map(|quote| quote.listing.ticker.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            );
+            std::process::exit(1);
+        }
+        quotes.retain(|quote| watch.contains(&quote.listing.ticker));
+        quotes.sort_by_key(|quote| {
+            watch
+                .iter()
+                .position(|ticker| *ticker == quote.listing.ticker)
+        });
+    }
+    let mut app_state = AppState {
         news,
+        connection: Connection::new(config.market.flaky_connection, replay),
+        scenario,
+        targets,
+        difficulty,
+        ..AppState::new(
+            &mut rng,
+            quotes,
+            exchanges,
+            accounts,
+            &config,
+            data_dir().as_deref(),
+        )
     };
+    app_state.new_game = Some(app_state.saved_game());
+    if std::env::args().any(|arg| arg == "--benchmark") {
+        if let Err(err) = run_benchmark(&mut app_state) {
+            eprintln!("failed to run the benchmark: {0}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+    // one tick's prices as a table on stdout, for scripts
+    if std::env::args().any(|arg| arg == "--once") {
+        app_state.tick(&mut rng);
+        let colored = io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        print_quotes(
+            &mut io::stdout().lock(),
+            &app_state,
+            &Theme::new(&config.theme),
+            colored,
+            ascii,
+            color_depth,
+        )
+        .expect("failed to print quotes");
+        return;
+    }
+    let mut hooks = Hooks::new(config.hooks.clone(), &app_state);
+    let tick_rate = Duration::from_millis(config.market.tick_millis);
+    // no terminal at all, the ticks go to a file the TUI can replay
+    if std::env::args().any(|arg| arg == "--daemon") {
+        let Some(path) = data_dir().map(|dir| dir.join("ticks.csv")) else {
+            eprintln!("no data directory to record ticks to, set IRON_LEDGER_DATA");
+            std::process::exit(1);
+        };
+        eprintln!("recording ticks to {0}", path.display());
+        if let Err(err) = run_daemon(
+            &mut app_state,
+            &mut hooks,
+            &mut rng,
+            &path,
+            tick_rate,
+            retention,
+        ) {
+            eprintln!("failed to record ticks: {0}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
 
     let mut ui_state = UIState {
-        market_data_active_panel: MarketDataActivePanel::MarketData,
-        market_data_scroll_pos: 0,
-        latest_news_scroll_pos: 0,
+        market_table: MarketTable::new(watch.is_some(), &config.display, format_rules),
+        keymap,
+        accents,
+        accessible,
+        ..UIState::new(&config)
     };
+    if ascii {
+        ui_state.chart_marker = Marker::Block;
+    }
+    if let Some((events, seed)) = monkey {
+        run_monkey(&mut app_state, &mut ui_state, events, seed);
+        let _ = std::fs::remove_dir_all(&monkey_dir);
+        return;
+    }
 
-    let mut terminal = ratatui::init();
-    loop {
-        terminal
-            .draw(|frame| draw(frame, &app_state, &ui_state))
-            .expect("failed to draw frame");
-        if let Event::Key(key) = event::read().expect("failed to read event") {
-            match key.code {
-                KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => break,
-                KeyCode::Left => {
-                    ui_state.market_data_active_panel = MarketDataActivePanel::MarketData
+    let feed_updates = spawn_feed_fetcher(
+        config.news.feeds.clone(),
+        Duration::from_secs(config.news.feed_refresh_secs),
+    );
+
+    let mut tick_interval = tick_rate;
+    let mut last_tick = Instant::now();
+    let mut graphics = detect_graphics(config.display.graphics).map(GraphicsOutput::new);
+    let control = match &control_path {
+        Some(path) => match spawn_control_socket(path) {
+            Ok(control) => Some(control),
+            Err(err) => {
+                eprintln!(
+                    "failed to open the control socket: {0}: {1}",
+                    path.display(),
+                    err
+                );
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+    let cast = match &record {
+        Some(path) => {
+            let size = crossterm::terminal::size().unwrap_or((80, 24));
+            match Cast::create(path, size) {
+                Ok(cast) => Some(cast),
+                Err(err) => {
+                    eprintln!("failed to start recording: {0}: {1}", path.display(), err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => None,
+    };
+    // only for the raw mode, the alternate screen and the panic hook, drawing goes through
+    // the recorder whether there's a recording or not
+    let _ = ratatui::init();
+    // pasted text comes as one event instead of keys that would each do something
+    let _ = execute!(io::stdout(), EnableBracketedPaste);
+    // terminals with the kitty keyboard protocol tell held keys from pressed ones, Esc from
+    // Alt and Ctrl-Enter from Enter, the others go on with the keys they always sent
+    ui_state.enhanced_keys = supports_keyboard_enhancement().unwrap_or(false);
+    if ui_state.enhanced_keys {
+        let _ = execute!(
+            io::stdout(),
+            PushKeyboardEnhancementFlags(
+                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                    | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+            )
+        );
+        // taken off on a panic too, before ratatui's hook leaves the alternate screen, which
+        // keeps flags of its own, or the shell is left getting the kitty codes
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let _ = execute!(io::stdout(), PopKeyboardEnhancementFlags);
+            previous(info);
+        }));
+    }
+    hooks.install_panic_hook();
+    let mut terminal = Terminal::new(CrosstermBackend::new(Recorder::new(io::stdout(), cast)))
+        .expect("failed to set up the terminal");
+    let mut shown_title = String::new();
+    if config.display.window_title {
+        let _ = save_title();
+    }
+    // a frame is only drawn when something could have changed it: a tick, a key, a paste,
+    // a resize, news from the feeds or a control call, waking up for nothing draws nothing,
+    // and never more often than max_fps, so fast ticks are drawn several at once
+    let mut dirty = true;
+    let frame_interval = Duration::from_secs_f64(1.0 / config.display.max_fps as f64);
+    let mut last_frame = None::<Instant>;
+    // the arrow key that moved the cursor since the last frame, its repeats are dropped until
+    // the next one
+    let mut held = None;
+    'running: loop {
+        let news_len = app_state.news.len();
+        for update in feed_updates.try_iter() {
+            app_state.apply_feed_update(update);
+            dirty = true;
+        }
+        ui_state.news_list.follow(&app_state, news_len);
+        for request in control.iter().flat_map(|control| control.try_iter()) {
+            let result = handle_control(&request, &mut app_state, &mut ui_state);
+            request.answer(result);
+            dirty = true;
+        }
+        for outcome in ui_state.worker.collect() {
+            ui_state.finish(outcome);
+            dirty = true;
+        }
+        let jobs = ui_state.jobs();
+        ui_state.worker.request(&app_state, jobs);
+        mark_news_seen(&mut app_state, &ui_state);
+        // the pixel size can change with the font, check it every frame
+        let can_show = graphics.is_some() && cell_pixels().is_some();
+        dirty |= can_show != ui_state.graphics;
+        ui_state.graphics = can_show;
+        let frame_due = last_frame.is_none_or(|last_frame| last_frame.elapsed() >= frame_interval);
+        if dirty && frame_due {
+            if config.display.window_title {
+                let title = window_title(&app_state);
+                if title != shown_title {
+                    execute!(io::stdout(), SetTitle(&title)).expect("failed to set the title");
+                    shown_title = title;
                 }
-                KeyCode::Right => {
-                    ui_state.market_data_active_panel = MarketDataActivePanel::LatestNews
+            }
+            let frame = terminal
+                .draw(|frame| {
+                    draw(frame, &app_state, &ui_state);
+                    downgrade(frame.buffer_mut(), ascii, color_depth);
+                })
+                .expect("failed to draw frame");
+            write_hyperlinks(frame.buffer, &ui_state.hyperlinks.borrow())
+                .expect("failed to write links");
+            if let Some(graphics) = graphics.as_mut() {
+                let image = ui_state.chart_image.take();
+                if graphics.show(image).expect("failed to draw chart image") {
+                    terminal.clear().expect("failed to clear the screen");
+                }
+            }
+            dirty = false;
+            last_frame = Some(Instant::now());
+            held = None;
+        }
+        let mut timeout = tick_interval.saturating_sub(last_tick.elapsed());
+        if let Some(last_frame) = last_frame.filter(|_| dirty) {
+            timeout = timeout.min(frame_interval.saturating_sub(last_frame.elapsed()));
+        }
+        if control.is_some() {
+            timeout = timeout.min(CONTROL_POLL);
+        }
+        if ui_state.worker.is_busy() {
+            timeout = timeout.min(WORKER_POLL);
+        }
+        // every event waiting is handled before the next tick, keys never queue up behind
+        // a fast feed
+        let mut waiting = event::poll(timeout).expect("failed to poll events");
+        while waiting {
+            let event = event::read().expect("failed to read event");
+            // a held arrow key moves once a frame
+            if !is_held(&mut held, &event, ui_state.enhanced_keys) {
+                match handle_event(event, &mut app_state, &mut ui_state) {
+                    Handled::Quit => break 'running,
+                    Handled::Changed => dirty = true,
+                    Handled::Nothing => {}
                 }
-                KeyCode::Down => match ui_state.market_data_active_panel {
-                    MarketDataActivePanel::MarketData => {
-                        ui_state.market_data_scroll_pos = min(
-                            app_state.quotes.len().saturating_sub(1),
-                            ui_state.market_data_scroll_pos + 1,
-                        );
-                    }
-                    MarketDataActivePanel::LatestNews => {
-                        ui_state.latest_news_scroll_pos = min(
-                            app_state.news.len().saturating_sub(1),
-                            ui_state.latest_news_scroll_pos + 1,
-                        );
-                    }
-                },
-                KeyCode::Up => match ui_state.market_data_active_panel {
-                    MarketDataActivePanel::MarketData => {
-                        ui_state.market_data_scroll_pos =
-                            ui_state.market_data_scroll_pos.saturating_sub(1);
-                    }
-                    MarketDataActivePanel::LatestNews => {
-                        ui_state.latest_news_scroll_pos =
-                            ui_state.latest_news_scroll_pos.saturating_sub(1);
-                    }
-                },
-                _ => {}
             }
+            waiting = event::poll(Duration::ZERO).expect("failed to poll events");
         }
+        // a tick that comes late under load isn't made up for, the next one is just due later
+        if last_tick.elapsed() >= tick_interval {
+            let news_len = app_state.news.len();
+            app_state.tick(&mut rng);
+            if std::mem::take(&mut app_state.movers.ring) {
+                let _ = ring_bell();
+            }
+            ui_state.news_list.follow(&app_state, news_len);
+            tick_interval = app_state.connection.tick_interval(&mut rng, tick_rate);
+            app_state.check_achievements();
+            ui_state.drop_delisted(&app_state);
+            ui_state.clamp_scroll(&app_state);
+            last_tick = Instant::now();
+            dirty = true;
+        }
+        hooks.check(&app_state);
+    }
+    if let Some(graphics) = graphics.as_mut() {
+        let _ = graphics.show(None);
+    }
+    if config.display.window_title {
+        let _ = restore_title();
+    }
+    if let Some(path) = &control_path {
+        remove_control_socket(path);
     }
+    if ui_state.enhanced_keys {
+        let _ = execute!(io::stdout(), PopKeyboardEnhancementFlags);
+    }
+    let _ = execute!(io::stdout(), DisableBracketedPaste);
     ratatui::restore();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_tui_test::testing::{app_state, ui_state};
+
+    fn press(code: KeyCode, app_state: &mut AppState, ui_state: &mut UIState) -> bool {
+        handle_key(KeyEvent::new(code, KeyModifiers::NONE), app_state, ui_state)
+    }
+
+    fn key(code: KeyCode, kind: KeyEventKind) -> Event {
+        Event::Key(KeyEvent::new_with_kind(code, KeyModifiers::NONE, kind))
+    }
+
+    // how many of the events go through in one frame
+    fn not_held(events: &[Event], enhanced_keys: bool) -> usize {
+        let mut held = None;
+        events
+            .iter()
+            .filter(|event| !is_held(&mut held, event, enhanced_keys))
+            .count()
+    }
+
+    #[test]
+    fn releases_do_nothing_and_repeats_move() {
+        let companies = gen_universe(20);
+        let (mut app_state, mut ui_state) = (app_state(&companies, 10), ui_state());
+        let selected =
+            |app_state: &AppState, ui_state: &UIState| ui_state.market_table.selected(app_state);
+        let first = selected(&app_state, &ui_state);
+        let release = key(KeyCode::Down, KeyEventKind::Release);
+        assert!(matches!(
+            handle_event(release, &mut app_state, &mut ui_state),
+            Handled::Nothing
+        ));
+        assert_eq!(selected(&app_state, &ui_state), first);
+        for kind in [KeyEventKind::Press, KeyEventKind::Repeat] {
+            assert!(matches!(
+                handle_event(key(KeyCode::Down, kind), &mut app_state, &mut ui_state),
+                Handled::Changed
+            ));
+        }
+        let table = ui_state.market_table.indices(&app_state);
+        assert_eq!(selected(&app_state, &ui_state), Some(table[2]));
+        // a released Esc doesn't close what the press opened
+        press(KeyCode::Enter, &mut app_state, &mut ui_state);
+        let release = key(KeyCode::Esc, KeyEventKind::Release);
+        handle_event(release, &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Detail);
+    }
+
+    #[test]
+    fn with_the_kitty_protocol_only_repeats_are_held() {
+        use KeyEventKind::{Press, Release, Repeat};
+        let down = |kind| key(KeyCode::Down, kind);
+        let events = [
+            down(Press),
+            down(Repeat),
+            down(Repeat),
+            down(Release),
+            down(Press),
+        ];
+        assert_eq!(not_held(&events, true), 3);
+        // the first repeat after a frame still moves
+        assert_eq!(not_held(&[down(Repeat), down(Repeat)], true), 1);
+    }
+
+    #[test]
+    fn elsewhere_the_same_key_twice_is_held() {
+        let down = key(KeyCode::Down, KeyEventKind::Press);
+        let up = key(KeyCode::Up, KeyEventKind::Press);
+        assert_eq!(
+            not_held(&[down.clone(), down.clone(), down.clone()], false),
+            1
+        );
+        assert_eq!(not_held(&[down.clone(), up, down], false), 3);
+        // only navigation keys are held
+        let typed = key(KeyCode::Char('j'), KeyEventKind::Press);
+        assert_eq!(not_held(&[typed.clone(), typed], false), 2);
+    }
+
+    fn screen(ui_state: &UIState) -> Option<&str> {
+        ui_state
+            .market_table
+            .screen
+            .as_ref()
+            .map(|query| query.text.as_str())
+    }
+
+    #[test]
+    fn q_typed_into_a_screen_is_part_of_it() {
+        let companies = gen_universe(20);
+        let (mut app_state, mut ui_state) = (app_state(&companies, 10), ui_state());
+        press(KeyCode::Char('/'), &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Search);
+        assert!(press(KeyCode::Char('q'), &mut app_state, &mut ui_state));
+        assert_eq!(Mode::of(&ui_state), Mode::Search);
+        assert!(ui_state.report_view.is_none());
+        assert_eq!(screen(&ui_state), Some("q"));
+        press(KeyCode::Enter, &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Normal);
+        assert_eq!(screen(&ui_state), Some("q"));
+    }
+
+    #[test]
+    fn esc_leaves_a_news_search_without_quitting() {
+        let companies = gen_universe(20);
+        let (mut app_state, mut ui_state) = (app_state(&companies, 10), ui_state());
+        ui_state.market_data_active_panel = MarketDataActivePanel::LatestNews;
+        press(KeyCode::Char('/'), &mut app_state, &mut ui_state);
+        for c in "quit".chars() {
+            assert!(press(KeyCode::Char(c), &mut app_state, &mut ui_state));
+        }
+        assert_eq!(Mode::of(&ui_state), Mode::Search);
+        press(KeyCode::Esc, &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Normal);
+        assert!(ui_state.news_list.search.is_none());
+        assert!(ui_state.report_view.is_none());
+    }
+
+    #[test]
+    fn modals_over_the_detail_go_back_to_it() {
+        let companies = gen_universe(20);
+        let (mut app_state, mut ui_state) = (app_state(&companies, 10), ui_state());
+        press(KeyCode::Enter, &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Detail);
+        press(KeyCode::Char('m'), &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Modal);
+        press(KeyCode::Esc, &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Detail);
+        // the order form has the letters, they don't switch the view behind it
+        press(KeyCode::Char('b'), &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Modal);
+        press(KeyCode::Char('c'), &mut app_state, &mut ui_state);
+        assert!(ui_state.instrument_detail_view == DetailView::Details);
+        press(KeyCode::Esc, &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Detail);
+        press(KeyCode::Esc, &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Normal);
+    }
+
+    #[test]
+    fn the_next_key_finishes_a_command() {
+        let companies = gen_universe(20);
+        let (mut app_state, mut ui_state) = (app_state(&companies, 10), ui_state());
+        let ctrl_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        handle_key(ctrl_r, &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Command);
+        press(KeyCode::Char('a'), &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Normal);
+        assert!(ui_state.macros.recording.is_some());
+        handle_key(ctrl_r, &mut app_state, &mut ui_state);
+        press(KeyCode::Char('@'), &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Command);
+        // Esc calls a command off, it doesn't get as far as quitting
+        press(KeyCode::Esc, &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Normal);
+        assert!(ui_state.report_view.is_none());
+    }
+
+    #[test]
+    fn a_search_from_the_archive_is_typed_over_it() {
+        let companies = gen_universe(20);
+        let (mut app_state, mut ui_state) = (app_state(&companies, 10), ui_state());
+        press(KeyCode::Char('a'), &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Modal);
+        press(KeyCode::Char('/'), &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Search);
+        press(KeyCode::Char('a'), &mut app_state, &mut ui_state);
+        assert_eq!(ui_state.news_archive_page, Some(0));
+        press(KeyCode::Enter, &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Modal);
+        press(KeyCode::Esc, &mut app_state, &mut ui_state);
+        assert_eq!(Mode::of(&ui_state), Mode::Normal);
+    }
+
+    #[test]
+    fn only_the_report_quits() {
+        let companies = gen_universe(20);
+        let (mut app_state, mut ui_state) = (app_state(&companies, 10), ui_state());
+        assert!(press(KeyCode::Char('q'), &mut app_state, &mut ui_state));
+        assert_eq!(Mode::of(&ui_state), Mode::Modal);
+        assert!(!press(KeyCode::Char('q'), &mut app_state, &mut ui_state));
+    }
+}
@@ -0,0 +1,600 @@
+use crate::book::{gen_book, OrderBook};
+use crate::difficulty::DifficultySettings;
+use crate::history::{day_opened, widen, Candle, PriceHistory};
+use chrono::{DateTime, NaiveTime, TimeDelta, Utc};
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+// per-tick random walk bounds, in percent
+const TICK_VOLATILITY_PCT: f64 = 0.4;
+// drift in percent per tick contributed by news with sentiment of 1.0
+const SENTIMENT_DRIFT_PCT: f64 = 0.5;
+// fraction of the news-induced drift that survives each tick
+const SENTIMENT_DECAY: f64 = 0.9;
+// commodities swing harder but get pulled back towards their long-run price
+const COMMODITY_VOLATILITY_PCT: f64 = 0.8;
+const COMMODITY_REVERSION: f64 = 0.05;
+// consol yields move by a few basis points per tick and revert to their long-run level
+const YIELD_VOLATILITY_PCT: f64 = 0.02;
+const YIELD_REVERSION: f64 = 0.02;
+// short interest wanders by this many percentage points per tick, within bounds
+const SHORT_INTEREST_VOLATILITY: f64 = 0.3;
+const SHORT_INTEREST_RANGE: RangeInclusive<f64> = RangeInclusive::new(0.5, 45.0);
+// traded per tick on a quiet tick, every percent the price moves adds this much again
+const VOLUME_PER_PCT_MOVE: f64 = 1.5;
+// a backfilled day takes this many quiet ticks of volume, a trading session's worth
+const BACKFILL_DAY_TICKS: u64 = 8 * 60 * 60;
+// companies are floated with somewhere between these many shares
+const SHARES_OUTSTANDING: RangeInclusive<u64> = RangeInclusive::new(2_000_000, 400_000_000);
+// consols are quoted per this much face value
+pub const CONSOL_FACE_VALUE: f64 = 100.0;
+
+// standard deviation of the per-tick change as a fraction, the walk is uniform
+pub fn tick_volatility(volatility_scale: f64) -> f64 {
+    TICK_VOLATILITY_PCT * volatility_scale / 100.0 / 3f64.sqrt()
+}
+
+// what is being traded, shared by all instrument classes
+#[derive(Debug)]
+pub struct Listing {
+    pub ticker: String,
+    pub name: String,
+    pub description: String,
+}
+
+impl Listing {
+    pub fn new(ticker: &str, name: &str, description: &str) -> Listing {
+        Listing {
+            ticker: ticker.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+        }
+    }
+}
+
+// the smallest step an instrument's price moves in and the decimals it's shown with
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Precision {
+    pub tick_size: f64,
+    pub display_decimals: usize,
+}
+
+impl Precision {
+    // on the nearest tick, never below the first one
+    pub fn round(&self, price: f64) -> f64 {
+        ((price / self.tick_size).round() * self.tick_size).max(self.tick_size)
+    }
+
+    // with some slack, ticks like 0.01 aren't exact in binary
+    pub fn on_tick(&self, price: f64) -> bool {
+        let ticks = price / self.tick_size;
+        (ticks - ticks.round()).abs() < 1e-6
+    }
+}
+
+#[derive(Debug)]
+pub struct Quote {
+    pub price: f64,
+    pub price_yesterday: f64,
+}
+
+impl Quote {
+    // yesterday's close first, today's price has moved away from it overnight
+    pub fn random(
+        rng: &mut ThreadRng,
+        close_min: f64,
+        close_max: f64,
+        change_pct_min: f64,
+        change_pct_max: f64,
+    ) -> Quote {
+        let price_yesterday = rng.random_range(RangeInclusive::new(close_min, close_max));
+        Quote {
+            price: price_yesterday
+                * (1.0
+                    + rng.random_range(RangeInclusive::new(change_pct_min, change_pct_max))
+                        / 100.0),
+            price_yesterday,
+        }
+    }
+
+    pub fn round(self, precision: Precision) -> Quote {
+        Quote {
+            price: precision.round(self.price),
+            price_yesterday: precision.round(self.price_yesterday),
+        }
+    }
+}
+
+// daily trading hours, a close before the open means the session runs past midnight
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct MarketSession {
+    pub open: NaiveTime,
+    pub close: NaiveTime,
+}
+
+impl MarketSession {
+    pub fn is_open(&self, time: NaiveTime) -> bool {
+        if self.open <= self.close {
+            self.open <= time && time < self.close
+        } else {
+            time >= self.open || time < self.close
+        }
+    }
+}
+
+// what the change column compares the price against
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ChangeHorizon {
+    Tick,
+    FiveMinutes,
+    Session, // since the ledger started
+    #[default]
+    Yesterday,
+    SinceOpen, // of the instrument's exchange today
+}
+
+impl ChangeHorizon {
+    const ALL: [ChangeHorizon; 5] = [
+        ChangeHorizon::Tick,
+        ChangeHorizon::FiveMinutes,
+        ChangeHorizon::Session,
+        ChangeHorizon::Yesterday,
+        ChangeHorizon::SinceOpen,
+    ];
+
+    // the column header
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChangeHorizon::Tick => "Tick%",
+            ChangeHorizon::FiveMinutes => "5m%",
+            ChangeHorizon::Session => "Session%",
+            ChangeHorizon::Yesterday => "Change%",
+            ChangeHorizon::SinceOpen => "Open%",
+        }
+    }
+
+    pub fn next(self) -> ChangeHorizon {
+        let pos = ChangeHorizon::ALL
+            .iter()
+            .position(|horizon| *horizon == self)
+            .unwrap_or(0);
+        ChangeHorizon::ALL[(pos + 1) % ChangeHorizon::ALL.len()]
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+pub enum InstrumentClass {
+    Equity,
+    Commodity,
+    Bond,
+    Crypto,
+}
+
+impl InstrumentClass {
+    pub const ALL: [InstrumentClass; 4] = [
+        InstrumentClass::Equity,
+        InstrumentClass::Commodity,
+        InstrumentClass::Bond,
+        InstrumentClass::Crypto,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            InstrumentClass::Equity => "Equities",
+            InstrumentClass::Commodity => "Commodities",
+            InstrumentClass::Bond => "Bonds",
+            InstrumentClass::Crypto => "Crypto",
+        }
+    }
+
+    // unless [market.precision] says otherwise
+    pub fn precision(&self) -> Precision {
+        let (tick_size, display_decimals) = match self {
+            InstrumentClass::Crypto => (0.00001, 5),
+            InstrumentClass::Commodity => (0.05, 2),
+            _ => (0.01, 2),
+        };
+        Precision {
+            tick_size,
+            display_decimals,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PricingModel {
+    // random walk nudged by news sentiment, short interest is in percent of the float
+    Equity {
+        short_interest_pct: f64,
+    },
+    // wider random walk pulled back towards a long-run price
+    Commodity {
+        long_run_price: f64,
+    },
+    // perpetual bond, the yield walks and the price follows from it
+    Consol {
+        coupon_pct: f64,
+        yield_pct: f64,
+        long_run_yield_pct: f64,
+    },
+    // the equity walk with much wider bounds
+    Crypto {
+        volatility_pct: f64,
+    },
+}
+
+#[derive(Debug)]
+pub struct Instrument<'a> {
+    pub listing: &'a Listing,
+    pub model: PricingModel,
+    pub quote: Quote,
+    // extra per-tick drift (in percent) from recent news, decays every tick
+    pub sentiment_drift: f64,
+    pub book: OrderBook,
+    pub volume: u64, // traded since start
+    pub history: PriceHistory,
+    pub exchange: usize, // index into the exchanges, 0 is the home exchange
+    pub primary: Option<String>, // ticker of the listing a cross-listing follows
+    pub updated_at: DateTime<Utc>, // when the price last came in
+    pub precision: Precision,
+    pub shares: Option<u64>, // outstanding, only companies have them
+    pub session: Option<SessionRange>, // none before the first tick
+}
+
+// the highest and lowest price since the exchange's session opened, before the latest move,
+// kept up tick by tick so it isn't looked for in the history every time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SessionRange {
+    pub opened: DateTime<Utc>,
+    pub high_low: Option<(f64, f64)>, // none until a price comes in during the session
+}
+
+impl Instrument<'_> {
+    pub fn class(&self) -> InstrumentClass {
+        match self.model {
+            PricingModel::Equity { .. } => InstrumentClass::Equity,
+            PricingModel::Commodity { .. } => InstrumentClass::Commodity,
+            PricingModel::Consol { .. } => InstrumentClass::Bond,
+            PricingModel::Crypto { .. } => InstrumentClass::Crypto,
+        }
+    }
+
+    // what news, analysts and deals are about, cross-listings just follow their primary listing
+    pub fn is_company(&self) -> bool {
+        self.class() == InstrumentClass::Equity && self.primary.is_none()
+    }
+
+    // crypto never stops trading, everything else follows the market session
+    pub fn trades_continuously(&self) -> bool {
+        self.class() == InstrumentClass::Crypto
+    }
+
+    // decimal places prices are shown with
+    pub fn price_decimals(&self) -> usize {
+        self.precision.display_decimals
+    }
+
+    // prices put on the new ticks, yesterday's close too
+    pub fn set_precision(&mut self, rng: &mut ThreadRng, precision: Precision) {
+        self.precision = precision;
+        self.quote.price = precision.round(self.quote.price);
+        self.quote.price_yesterday = precision.round(self.quote.price_yesterday);
+        self.book = gen_book(rng, self.quote.price, precision.tick_size);
+    }
+
+    // in home currency, for companies
+    pub fn market_cap(&self) -> Option<f64> {
+        self.shares.map(|shares| shares as f64 * self.quote.price)
+    }
+
+    // only companies have shares to short
+    pub fn short_interest_pct(&self) -> Option<f64> {
+        match self.model {
+            PricingModel::Equity { short_interest_pct } => Some(short_interest_pct),
+            _ => None,
+        }
+    }
+
+    // shorts buying back after a squeeze, keeping the given fraction of the interest
+    pub fn cover_shorts(&mut self, remaining_fraction: f64) {
+        if let PricingModel::Equity { short_interest_pct } = &mut self.model {
+            *short_interest_pct = (*short_interest_pct * remaining_fraction)
+                .clamp(*SHORT_INTEREST_RANGE.start(), *SHORT_INTEREST_RANGE.end());
+        }
+    }
+
+    // units traded on a quiet tick, shares, contracts, bonds or coins
+    fn quiet_volume(&self) -> RangeInclusive<u64> {
+        match self.class() {
+            InstrumentClass::Equity => RangeInclusive::new(1_000, 20_000),
+            InstrumentClass::Commodity => RangeInclusive::new(100, 2_000),
+            InstrumentClass::Bond => RangeInclusive::new(50, 500),
+            InstrumentClass::Crypto => RangeInclusive::new(10_000, 500_000),
+        }
+    }
+
+    // bounds of a backfilled day's move in percent, the day's range reaches half as far again
+    fn daily_volatility_pct(&self) -> f64 {
+        match self.model {
+            PricingModel::Equity { .. } => 2.5,
+            PricingModel::Commodity { .. } => 2.0,
+            PricingModel::Consol { .. } => 0.5,
+            PricingModel::Crypto { volatility_pct } => volatility_pct * 2.0,
+        }
+    }
+
+    // made-up daily candles for the days before `start`, walked back from yesterday's close
+    pub fn backfill(&mut self, rng: &mut impl Rng, days: u32, start: DateTime<Utc>) {
+        let volatility = self.daily_volatility_pct();
+        let mut close = self.quote.price_yesterday;
+        let mut candles = vec![];
+        for day in 1..=days {
+            let change_pct = rng.random_range(RangeInclusive::new(-volatility, volatility));
+            let open = self.precision.round(close / (1.0 + change_pct / 100.0));
+            let wick = RangeInclusive::new(0.0, volatility / 2.0 / 100.0);
+            let high = open.max(close) * (1.0 + rng.random_range(wick.clone()));
+            let low = open.min(close) * (1.0 - rng.random_range(wick));
+            let volume = (rng.random_range(self.quiet_volume()) as f64
+                * BACKFILL_DAY_TICKS as f64
+                * (1.0 + VOLUME_PER_PCT_MOVE * change_pct.abs())) as u64;
+            candles.push(Candle {
+                open,
+                high: self.precision.round(high),
+                low: self.precision.round(low),
+                close,
+                volume,
+                opened: day_opened(start.date_naive() - TimeDelta::days(day as i64)),
+            });
+            close = open;
+        }
+        candles.reverse();
+        self.history.backfill(candles);
+    }
+
+    // before the tick moves it, the price it's at goes into the session range, which starts
+    // over from the history with every session
+    pub fn extend_session(&mut self, opened: DateTime<Utc>) {
+        let high_low = match self.session {
+            Some(range) if range.opened == opened => range.high_low,
+            _ => self.history.range_since(opened),
+        };
+        let high_low = if self.updated_at >= opened {
+            Some(widen(high_low, self.quote.price))
+        } else {
+            high_low
+        };
+        self.session = Some(SessionRange { opened, high_low });
+    }
+
+    pub fn apply_sentiment(&mut self, sentiment: f64) {
+        self.sentiment_drift += sentiment * SENTIMENT_DRIFT_PCT;
+    }
+
+    pub fn tick(&mut self, rng: &mut ThreadRng, difficulty: &DifficultySettings) {
+        let scale = difficulty.volatility_scale;
+        let old_price = self.quote.price;
+        match &mut self.model {
+            PricingModel::Equity { short_interest_pct } => {
+                *short_interest_pct = (*short_interest_pct
+                    + rng.random_range(RangeInclusive::new(
+                        -SHORT_INTEREST_VOLATILITY,
+                        SHORT_INTEREST_VOLATILITY,
+                    )))
+                .clamp(*SHORT_INTEREST_RANGE.start(), *SHORT_INTEREST_RANGE.end());
+                let change_pct = rng.random_range(RangeInclusive::new(
+                    -TICK_VOLATILITY_PCT * scale,
+                    TICK_VOLATILITY_PCT * scale,
+                )) + self.sentiment_drift
+                    + difficulty.drift_pct;
+                self.quote.price *= 1.0 + change_pct / 100.0;
+            }
+            PricingModel::Crypto { volatility_pct } => {
+                let change_pct = rng.random_range(RangeInclusive::new(
+                    -*volatility_pct * scale,
+                    *volatility_pct * scale,
+                )) + self.sentiment_drift
+                    + difficulty.drift_pct;
+                self.quote.price *= 1.0 + change_pct / 100.0;
+            }
+            PricingModel::Commodity { long_run_price } => {
+                let reversion_pct = COMMODITY_REVERSION * (*long_run_price - self.quote.price)
+                    / self.quote.price
+                    * 100.0;
+                let change_pct = rng.random_range(RangeInclusive::new(
+                    -COMMODITY_VOLATILITY_PCT * scale,
+                    COMMODITY_VOLATILITY_PCT * scale,
+                )) + reversion_pct
+                    + self.sentiment_drift;
+                self.quote.price *= 1.0 + change_pct / 100.0;
+            }
+            PricingModel::Consol {
+                coupon_pct,
+                yield_pct,
+                long_run_yield_pct,
+            } => {
+                *yield_pct = (*yield_pct
+                    + rng.random_range(RangeInclusive::new(
+                        -YIELD_VOLATILITY_PCT * scale,
+                        YIELD_VOLATILITY_PCT * scale,
+                    ))
+                    + YIELD_REVERSION * (*long_run_yield_pct - *yield_pct))
+                    .max(0.1);
+                self.quote.price = consol_price(*coupon_pct, *yield_pct);
+            }
+        }
+        self.sentiment_drift *= SENTIMENT_DECAY;
+        self.record_move(rng, old_price);
+    }
+
+    // a cross-listing trades at the price of the primary listing, in home currency too
+    pub fn follow(&mut self, rng: &mut ThreadRng, price: f64) {
+        let old_price = self.quote.price;
+        self.quote.price = price;
+        self.record_move(rng, old_price);
+    }
+
+    fn record_move(&mut self, rng: &mut ThreadRng, old_price: f64) {
+        self.updated_at = Utc::now();
+        self.quote.price = self.precision.round(self.quote.price);
+        self.book = gen_book(rng, self.quote.price, self.precision.tick_size);
+        // big moves draw more trading
+        let move_pct = ((self.quote.price - old_price) / old_price * 100.0).abs();
+        let volume = (rng.random_range(self.quiet_volume()) as f64
+            * (1.0 + VOLUME_PER_PCT_MOVE * move_pct)) as u64;
+        self.volume += volume;
+        self.history.record(self.quote.price, volume);
+    }
+}
+
+// a perpetual bond is worth its coupon divided by the yield
+fn consol_price(coupon_pct: f64, yield_pct: f64) -> f64 {
+    CONSOL_FACE_VALUE * coupon_pct / yield_pct
+}
+
+// named from a few words, the tickers go from ZAAAA on where none of the built-in ones are
+pub fn gen_universe(count: usize) -> Vec<Listing> {
+    const FIRST: [&str; 8] = [
+        "Brass", "Copper", "Steam", "Aether", "Gear", "Valve", "Piston", "Boiler",
+    ];
+    const SECOND: [&str; 6] = [
+        "Works",
+        "Foundry",
+        "Mills",
+        "Syndicate",
+        "Holdings",
+        "Company",
+    ];
+    (0..count)
+        .map(|idx| {
+            let letters = (0..4)
+                .rev()
+                .map(|place| (b'A' + (idx / 26usize.pow(place) % 26) as u8) as char)
+                .collect::<String>();
+            Listing::new(
+                &format!("Z{0}", letters),
+                &format!(
+                    "{0} {1} No. {2}",
+                    FIRST[idx % FIRST.len()],
+                    SECOND[idx / FIRST.len() % SECOND.len()],
+                    idx + 1
+                ),
+                "One of the made-up companies of --universe.",
+            )
+        })
+        .collect()
+}
+
+pub fn gen_equities<'a>(rng: &mut ThreadRng, companies: &'a [Listing]) -> Vec<Instrument<'a>> {
+    companies
+        .iter()
+        .map(|listing| {
+            let precision = InstrumentClass::Equity.precision();
+            let quote = Quote::random(rng, 500.0, 3000.0, -10.0, 10.0).round(precision);
+            Instrument {
+                listing,
+                model: PricingModel::Equity {
+                    short_interest_pct: rng.random_range(RangeInclusive::new(2.0, 30.0)),
+                },
+                book: gen_book(rng, quote.price, precision.tick_size),
+                quote,
+                sentiment_drift: 0.0,
+                volume: 0,
+                history: PriceHistory::default(),
+                exchange: 0,
+                primary: None,
+                updated_at: Utc::now(),
+                precision,
+                shares: Some(rng.random_range(SHARES_OUTSTANDING)),
+                session: None,
+            }
+        })
+        .collect()
+}
+
+// starts somewhere around the long-run price
+pub fn gen_commodity<'a>(
+    rng: &mut ThreadRng,
+    listing: &'a Listing,
+    long_run_price: f64,
+) -> Instrument<'a> {
+    let precision = InstrumentClass::Commodity.precision();
+    let quote =
+        Quote::random(rng, long_run_price * 0.9, long_run_price * 1.1, -3.0, 3.0).round(precision);
+    Instrument {
+        listing,
+        model: PricingModel::Commodity { long_run_price },
+        book: gen_book(rng, quote.price, precision.tick_size),
+        quote,
+        sentiment_drift: 0.0,
+        volume: 0,
+        history: PriceHistory::default(),
+        exchange: 0,
+        primary: None,
+        updated_at: Utc::now(),
+        precision,
+        shares: None,
+        session: None,
+    }
+}
+
+pub fn gen_consol<'a>(
+    rng: &mut ThreadRng,
+    listing: &'a Listing,
+    coupon_pct: f64,
+    long_run_yield_pct: f64,
+) -> Instrument<'a> {
+    // the yield closed somewhere near its long-run level and has moved a little since
+    let yield_yesterday_pct = long_run_yield_pct + rng.random_range(RangeInclusive::new(-0.3, 0.3));
+    let yield_pct = yield_yesterday_pct + rng.random_range(RangeInclusive::new(-0.05, 0.05));
+    let precision = InstrumentClass::Bond.precision();
+    let quote = Quote {
+        price: consol_price(coupon_pct, yield_pct),
+        price_yesterday: consol_price(coupon_pct, yield_yesterday_pct),
+    }
+    .round(precision);
+    Instrument {
+        listing,
+        model: PricingModel::Consol {
+            coupon_pct,
+            yield_pct,
+            long_run_yield_pct,
+        },
+        book: gen_book(rng, quote.price, precision.tick_size),
+        quote,
+        sentiment_drift: 0.0,
+        volume: 0,
+        history: PriceHistory::default(),
+        exchange: 0,
+        primary: None,
+        updated_at: Utc::now(),
+        precision,
+        shares: None,
+        session: None,
+    }
+}
+
+pub fn gen_crypto<'a>(
+    rng: &mut ThreadRng,
+    listing: &'a Listing,
+    volatility_pct: f64,
+) -> Instrument<'a> {
+    let precision = InstrumentClass::Crypto.precision();
+    let quote = Quote::random(rng, 0.5, 2.0, -25.0, 25.0).round(precision);
+    Instrument {
+        listing,
+        model: PricingModel::Crypto { volatility_pct },
+        book: gen_book(rng, quote.price, precision.tick_size),
+        quote,
+        sentiment_drift: 0.0,
+        volume: 0,
+        history: PriceHistory::default(),
+        exchange: 0,
+        primary: None,
+        updated_at: Utc::now(),
+        precision,
+        shares: None,
+        session: None,
+    }
+}
@@ -0,0 +1,183 @@
+use crate::exchanges::Currency;
+use crate::market::Instrument;
+use crate::news::{NewsCategory, NewsItem};
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use std::ops::RangeInclusive;
+
+// chance that a takeover rumor starts on a tick without a deal in progress
+const DEAL_CHANCE_PER_TICK: f64 = 0.01;
+// no new takeovers once fewer companies than this are listed
+const MIN_COMPANIES: usize = 4;
+// ticks spent in each stage of the storyline
+const RUMOR_TICKS: RangeInclusive<u64> = RangeInclusive::new(20, 40);
+const CLOSING_TICKS: RangeInclusive<u64> = RangeInclusive::new(20, 40);
+const RUMOR_DENIED_CHANCE: f64 = 0.3;
+// per-tick sentiment while the rumor runs, enough for a steady run-up
+pub const RUMOR_SENTIMENT_PER_TICK: f64 = 0.04;
+const PREMIUM_PCT: RangeInclusive<f64> = RangeInclusive::new(15.0, 35.0);
+// once announced the target trades just under the offer, closing the gap this fast
+pub const MERGER_ARBITRAGE_SPREAD_PCT: f64 = 2.0;
+pub const OFFER_PULL: f64 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Consideration {
+    Cash { price: f64 },  // per target share
+    Stock { ratio: f64 }, // acquirer shares per target share
+}
+
+impl Consideration {
+    // what one target share is worth under the offer
+    pub fn value(&self, acquirer_price: f64) -> f64 {
+        match self {
+            Consideration::Cash { price } => *price,
+            Consideration::Stock { ratio } => ratio * acquirer_price,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DealStage {
+    Rumored,
+    Announced(Consideration),
+}
+
+#[derive(Debug, Clone)]
+pub struct Deal {
+    pub acquirer: String,
+    pub target: String,
+    pub stage: DealStage,
+    pub next_stage_tick: u64, // the rumor resolves or the deal closes on this tick
+}
+
+fn company_label(quote: &Instrument) -> String {
+    format!("{0} ({1})", quote.listing.name, quote.listing.ticker)
+}
+
+// starts the storyline with a rumor about two listed companies
+pub fn gen_deal(rng: &mut ThreadRng, quotes: &[Instrument], tick: u64) -> Option<(Deal, NewsItem)> {
+    let companies = quotes
+        .iter()
+        .filter(|quote| quote.is_company())
+        .collect::<Vec<&Instrument>>();
+    if companies.len() < MIN_COMPANIES || !rng.random_bool(DEAL_CHANCE_PER_TICK) {
+        return None;
+    }
+    let acquirer = companies[rng.random_range(0..companies.len())];
+    let targets = companies
+        .iter()
+        .filter(|quote| quote.listing.ticker != acquirer.listing.ticker)
+        .collect::<Vec<_>>();
+    let target = targets[rng.random_range(0..targets.len())];
+    Some(start_rumor(rng, acquirer, target, tick))
+}
+
+pub fn start_rumor(
+    rng: &mut ThreadRng,
+    acquirer: &Instrument,
+    target: &Instrument,
+    tick: u64,
+) -> (Deal, NewsItem) {
+    let news_item = NewsItem::new(
+        &format!(
+            "Rumors Swirl of {0} Bid for {1}",
+            acquirer.listing.name,
+            company_label(target)
+        ),
+        "People familiar with the matter say talks are at an early stage.",
+        NewsCategory::MergersAcquisitions,
+        0.5,
+        &[target.listing.ticker.as_str()],
+    );
+    let deal = Deal {
+        acquirer: acquirer.listing.ticker.clone(),
+        target: target.listing.ticker.clone(),
+        stage: DealStage::Rumored,
+        next_stage_tick: tick + rng.random_range(RUMOR_TICKS),
+    };
+    (deal, news_item)
+}
+
+// the rumor either comes to nothing or turns into a firm offer
+pub fn resolve_rumor(
+    rng: &mut ThreadRng,
+    deal: &mut Deal,
+    acquirer: &Instrument,
+    target: &Instrument,
+    tick: u64,
+    currency: &Currency,
+) -> NewsItem {
+    if rng.random_bool(RUMOR_DENIED_CHANCE) {
+        return NewsItem::new(
+            &format!(
+                "{0} Denies Interest in {1}",
+                acquirer.listing.name,
+                company_label(target)
+            ),
+            "A spokesman called the takeover reports pure speculation.",
+            NewsCategory::MergersAcquisitions,
+            -0.6,
+            &[target.listing.ticker.as_str()],
+        );
+    }
+    let offer = target
+        .precision
+        .round(target.quote.price * (1.0 + rng.random_range(PREMIUM_PCT) / 100.0));
+    let (consideration, terms) = if rng.random_bool(0.5) {
+        (
+            Consideration::Cash { price: offer },
+            format!(
+                "{0} in cash per share",
+                currency.format(offer, target.price_decimals())
+            ),
+        )
+    } else {
+        let ratio = offer / acquirer.quote.price;
+        (
+            Consideration::Stock { ratio },
+            format!(
+                "{0:.4} {1} shares per share",
+                ratio, acquirer.listing.ticker
+            ),
+        )
+    };
+    deal.stage = DealStage::Announced(consideration);
+    deal.next_stage_tick = tick + rng.random_range(CLOSING_TICKS);
+    NewsItem::new(
+        &format!(
+            "{0} Agrees to Acquire {1}",
+            acquirer.listing.name,
+            company_label(target)
+        ),
+        &format!(
+            "Shareholders are offered {0}, the deal is expected to close shortly.",
+            terms
+        ),
+        NewsCategory::MergersAcquisitions,
+        0.0,
+        &[
+            target.listing.ticker.as_str(),
+            acquirer.listing.ticker.as_str(),
+        ],
+    )
+}
+
+pub fn completion_news(acquirer: &Instrument, target: &Instrument) -> NewsItem {
+    NewsItem::new(
+        &format!(
+            "{0} Completes Takeover of {1}",
+            acquirer.listing.name,
+            company_label(target)
+        ),
+        &format!(
+            "{0} shares have been delisted and holders paid out under the agreed terms.",
+            target.listing.ticker
+        ),
+        NewsCategory::MergersAcquisitions,
+        0.0,
+        &[
+            target.listing.ticker.as_str(),
+            acquirer.listing.ticker.as_str(),
+        ],
+    )
+}
@@ -0,0 +1,98 @@
+use crate::app::UIState;
+use crate::overlay::Overlay;
+
+// who a key goes to, there's always exactly one and nothing gets past it to the ones below,
+// worked out from what's open so it can't disagree with what's on screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Normal,  // the focused panel and the keys that work anywhere
+    Search,  // typing a news search or a market screen, every key is text
+    Command, // the register after Ctrl-R or @, the next key finishes the command
+    Modal,   // a dialog, form or view over the panels
+    Detail,  // the instrument view
+}
+
+impl Mode {
+    pub fn of(ui_state: &UIState) -> Mode {
+        if ui_state.macros.pending.is_some() {
+            return Mode::Command;
+        }
+        let open = Overlay::open(ui_state);
+        // these open over the instrument view as well as over the panels
+        if open.iter().any(Overlay::over_detail) {
+            return Mode::Modal;
+        }
+        if ui_state.instrument_detail.is_some() {
+            return Mode::Detail;
+        }
+        // a search can be started from the archive, it's typed over it
+        if ui_state.news_list.search_editing || ui_state.market_table.screen_editing {
+            return Mode::Search;
+        }
+        if open.iter().any(Overlay::is_modal) {
+            return Mode::Modal;
+        }
+        Mode::Normal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Confirm;
+    use crate::config::Config;
+    use crate::macros::Pending;
+
+    #[test]
+    fn nothing_open_is_normal() {
+        assert_eq!(Mode::of(&UIState::new(&Config::default())), Mode::Normal);
+    }
+
+    #[test]
+    fn a_pending_command_comes_before_everything() {
+        let mut ui_state = UIState::new(&Config::default());
+        ui_state.instrument_detail = Some("BCI".to_string());
+        ui_state.confirm = Some(Confirm::ClearBlotter);
+        ui_state.macros.pending = Some(Pending::Play);
+        assert_eq!(Mode::of(&ui_state), Mode::Command);
+    }
+
+    #[test]
+    fn a_dialog_over_the_detail_gets_the_keys() {
+        let mut ui_state = UIState::new(&Config::default());
+        ui_state.instrument_detail = Some("BCI".to_string());
+        assert_eq!(Mode::of(&ui_state), Mode::Detail);
+        ui_state.confirm = Some(Confirm::NewGame);
+        assert_eq!(Mode::of(&ui_state), Mode::Modal);
+    }
+
+    #[test]
+    fn a_view_over_the_detail_does_not() {
+        let mut ui_state = UIState::new(&Config::default());
+        ui_state.instrument_detail = Some("BCI".to_string());
+        ui_state.compare_open = true;
+        assert_eq!(Mode::of(&ui_state), Mode::Detail);
+    }
+
+    #[test]
+    fn a_search_is_typed_over_the_archive() {
+        let mut ui_state = UIState::new(&Config::default());
+        ui_state.news_archive_page = Some(0);
+        assert_eq!(Mode::of(&ui_state), Mode::Modal);
+        ui_state.news_list.search_editing = true;
+        assert_eq!(Mode::of(&ui_state), Mode::Search);
+        ui_state.news_list.search_editing = false;
+        ui_state.market_table.screen_editing = true;
+        assert_eq!(Mode::of(&ui_state), Mode::Search);
+    }
+
+    #[test]
+    fn every_view_over_the_panels_is_modal() {
+        let mut ui_state = UIState::new(&Config::default());
+        ui_state.movers_open = Some(0);
+        assert_eq!(Mode::of(&ui_state), Mode::Modal);
+        ui_state.movers_open = None;
+        ui_state.connection_open = true;
+        assert_eq!(Mode::of(&ui_state), Mode::Modal);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::market::Instrument;
+use chrono::{DateTime, Utc};
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use ratatui::style::Color;
+use std::ops::RangeInclusive;
+
+// chance that a fresh news item gets published on any given tick
+const NEWS_CHANCE_PER_TICK: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NewsCategory {
+    Earnings,
+    MergersAcquisitions,
+    Politics,
+    Technology,
+    Disaster,
+    Analysts,
+    General, // anything that can't be told apart, like headlines from RSS feeds
+}
+
+impl NewsCategory {
+    pub const ALL: [NewsCategory; 7] = [
+        NewsCategory::Earnings,
+        NewsCategory::MergersAcquisitions,
+        NewsCategory::Politics,
+        NewsCategory::Technology,
+        NewsCategory::Disaster,
+        NewsCategory::Analysts,
+        NewsCategory::General,
+    ];
+
+    // categories the news generator picks from
+    const GENERATED: [NewsCategory; 5] = [
+        NewsCategory::Earnings,
+        NewsCategory::MergersAcquisitions,
+        NewsCategory::Politics,
+        NewsCategory::Technology,
+        NewsCategory::Disaster,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NewsCategory::Earnings => "Earnings",
+            NewsCategory::MergersAcquisitions => "M&A",
+            NewsCategory::Politics => "Politics",
+            NewsCategory::Technology => "Technology",
+            NewsCategory::Disaster => "Disaster",
+            NewsCategory::Analysts => "Analysts",
+            NewsCategory::General => "General",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            NewsCategory::Earnings => Color::Green,
+            NewsCategory::MergersAcquisitions => Color::Magenta,
+            NewsCategory::Politics => Color::Blue,
+            NewsCategory::Technology => Color::Cyan,
+            NewsCategory::Disaster => Color::Red,
+            NewsCategory::Analysts => Color::LightYellow,
+            NewsCategory::General => Color::Gray,
+        }
+    }
+
+    // headline and subtitle templates, "{company}" gets replaced with name and ticker
+    fn templates(&self, positive: bool) -> (&'static str, &'static str) {
+        match (self, positive) {
+            (NewsCategory::Earnings, true) => (
+                "{company} Beats Quarterly Estimates on Strong Orders",
+                "Management raises guidance as backlog reaches record levels.",
+            ),
+            (NewsCategory::Earnings, false) => (
+                "{company} Misses Earnings Forecast, Cites Rising Coal Costs",
+                "Margins squeezed as suppliers pass on higher fuel prices.",
+            ),
+            (NewsCategory::MergersAcquisitions, true) => (
+                "{company} Announces Acquisition of Rival Workshop",
+                "The deal is expected to expand capacity and cut costs within a year.",
+            ),
+            (NewsCategory::MergersAcquisitions, false) => (
+                "Merger Talks Collapse for {company}",
+                "Negotiators reportedly failed to agree on valuation of key patents.",
+            ),
+            (NewsCategory::Politics, true) => (
+                "Parliament Grants New Subsidies Benefiting {company}",
+                "The measure passed with broad support from industrial districts.",
+            ),
+            (NewsCategory::Politics, false) => (
+                "New Tariffs Threaten {company} Exports",
+                "Trade partners retaliate against the latest round of import duties.",
+            ),
+            (NewsCategory::Technology, true) => (
+                "{company} Patents Breakthrough Mechanism",
+                "Engineers claim the design doubles efficiency over current models.",
+            ),
+            (NewsCategory::Technology, false) => (
+                "Design Flaw Discovered in {company} Flagship Product",
+                "A recall is being considered after reports of failing components.",
+            ),
+            (NewsCategory::Disaster, _)
+            | (NewsCategory::Analysts, _)
+            | (NewsCategory::General, _) => (
+                "Fire Breaks Out at {company} Facility",
+                "Production is halted while inspectors assess the damage.",
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct NewsItem {
+    pub title: String,
+    pub subtitle: String,
+    pub category: NewsCategory,
+    // from -1.0 (very bad for the related tickers) to 1.0 (very good)
+    pub sentiment: f64,
+    pub tickers: Vec<String>,
+    pub published: DateTime<Utc>,
+    pub seen: bool,
+    // feed label for items that didn't come from the simulation
+    pub source: Option<String>,
+    pub link: Option<String>, // the article, for feed items that have one
+}
+
+impl NewsItem {
+    pub fn new(
+        title: &str,
+        subtitle: &str,
+        category: NewsCategory,
+        sentiment: f64,
+        tickers: &[&str],
+    ) -> NewsItem {
+        NewsItem {
+            title: title.to_string(),
+            subtitle: subtitle.to_string(),
+            category,
+            sentiment,
+            tickers: tickers.iter().map(|t| t.to_string()).collect(),
+            published: Utc::now(),
+            seen: false,
+            source: None,
+            link: None,
+        }
+    }
+
+    pub fn mentions(&self, ticker: &str) -> bool {
+        self.tickers.iter().any(|t| t == ticker)
+    }
+
+    // case-insensitive search over headline, subtitle and tickers
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.title.to_lowercase().contains(&query)
+            || self.subtitle.to_lowercase().contains(&query)
+            || self.tickers.iter().any(|t| t.to_lowercase() == query)
+    }
+}
+
+// generated stories are always about companies
+// disaster_scale weighs disasters against the other categories, 1.0 makes them all equally likely
+pub fn gen_news(
+    rng: &mut ThreadRng,
+    quotes: &[Instrument],
+    disaster_scale: f64,
+) -> Option<NewsItem> {
+    let companies = quotes
+        .iter()
+        .filter(|quote| quote.is_company())
+        .collect::<Vec<&Instrument>>();
+    if companies.is_empty() || !rng.random_bool(NEWS_CHANCE_PER_TICK) {
+        return None;
+    }
+    let company = companies[rng.random_range(0..companies.len())].listing;
+    let weight = |category: &NewsCategory| {
+        if *category == NewsCategory::Disaster {
+            disaster_scale
+        } else {
+            1.0
+        }
+    };
+    let mut pick = rng.random_range(0.0..NewsCategory::GENERATED.iter().map(weight).sum::<f64>());
+    let category = *NewsCategory::GENERATED
+        .iter()
+        .find(|category| {
+            pick -= weight(category);
+            pick < 0.0
+        })
+        .unwrap_or(&NewsCategory::GENERATED[NewsCategory::GENERATED.len() - 1]);
+    // disasters are never good news
+    let positive = category != NewsCategory::Disaster && rng.random_bool(0.5);
+    let magnitude = rng.random_range(RangeInclusive::new(0.2, 1.0));
+
+    let (title, subtitle) = category.templates(positive);
+    let company_label = format!("{0} ({1})", company.name, company.ticker);
+    Some(NewsItem::new(
+        title.replace("{company}", &company_label).as_str(),
+        subtitle,
+        category,
+        if positive { magnitude } else { -magnitude },
+        &[company.ticker.as_str()],
+    ))
+}
@@ -0,0 +1,82 @@
+use crate::locale::{language, Language};
+use std::sync::OnceLock;
+
+static ABBREVIATED: OnceLock<bool> = OnceLock::new();
+
+// once at startup, whether volumes and market caps are cut down to "1.2M"
+pub fn set_abbreviated(abbreviated: bool) {
+    let _ = ABBREVIATED.set(abbreviated);
+}
+
+// thousands and decimal separators of the interface language
+fn separators() -> (char, char) {
+    match language() {
+        Language::English => (',', '.'),
+        Language::German => ('.', ','),
+    }
+}
+
+// whole units with thousands separators, "104,320" or "104.320"
+pub fn grouped(value: f64) -> String {
+    let (thousands, _) = separators();
+    let digits = format!("{0:.0}", value.abs());
+    let mut text = String::new();
+    for (idx, digit) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx) % 3 == 0 {
+            text.push(thousands);
+        }
+        text.push(digit);
+    }
+    if value <= -0.5 {
+        text.insert(0, '-');
+    }
+    text
+}
+
+// with thousands separators and a decimal point or comma, "104,320.50" or "104.320,50"
+pub fn decimal(value: f64, decimals: usize) -> String {
+    let (_, point) = separators();
+    let text = format!("{0:.1$}", value.abs(), decimals);
+    let (whole, fraction) = text.split_once('.').unwrap_or((&text, ""));
+    let mut text = grouped(whole.parse::<f64>().unwrap_or(0.0));
+    if !fraction.is_empty() {
+        text.push(point);
+        text.push_str(fraction);
+    }
+    // only what rounds to something below zero gets a sign
+    if value < 0.0
+        && text
+            .chars()
+            .any(|digit| digit.is_ascii_digit() && digit != '0')
+    {
+        text.insert(0, '-');
+    }
+    text
+}
+
+// "950", "12.3K", "1.2M", "3.45B", "1.07T", with a decimal comma in German
+pub fn abbreviated(value: u64) -> String {
+    let value_f = value as f64;
+    let text = if value < 1_000 {
+        value.to_string()
+    } else if value < 1_000_000 {
+        format!("{0:.1}K", value_f / 1e3)
+    } else if value < 1_000_000_000 {
+        format!("{0:.1}M", value_f / 1e6)
+    } else if value < 1_000_000_000_000 {
+        format!("{0:.2}B", value_f / 1e9)
+    } else {
+        format!("{0:.2}T", value_f / 1e12)
+    };
+    let (_, decimal) = separators();
+    text.replace('.', &decimal.to_string())
+}
+
+// volumes and market caps, abbreviated unless [display] abbreviate_numbers is off
+pub fn large(value: u64) -> String {
+    if *ABBREVIATED.get().unwrap_or(&true) {
+        abbreviated(value)
+    } else {
+        grouped(value as f64)
+    }
+}
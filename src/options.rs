@@ -0,0 +1,104 @@
+use crate::market::tick_volatility;
+use serde::{Deserialize, Serialize};
+
+// a new expiry is listed every this many ticks, the chain shows the nearest few
+const EXPIRY_CYCLE_TICKS: u64 = 30;
+const EXPIRIES_LISTED: u64 = 3;
+const STRIKES_EACH_SIDE: i32 = 3;
+// distance between strikes as a percentage of the spot price
+const STRIKE_STEP_PCT: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OptionKind {
+    Call,
+    Put,
+}
+
+impl OptionKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OptionKind::Call => "Call",
+            OptionKind::Put => "Put",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptionContract {
+    pub ticker: String,
+    pub kind: OptionKind,
+    pub strike: f64,
+    pub expiry_tick: u64,
+}
+
+impl OptionContract {
+    pub fn intrinsic_value(&self, spot: f64) -> f64 {
+        match self.kind {
+            OptionKind::Call => (spot - self.strike).max(0.0),
+            OptionKind::Put => (self.strike - spot).max(0.0),
+        }
+    }
+
+    // Black-Scholes without interest rates, time is measured in ticks so the per-tick
+    // volatility of the simulated random walk can be used directly
+    pub fn price(&self, spot: f64, tick: u64, volatility_scale: f64) -> f64 {
+        let ticks_left = self.expiry_tick.saturating_sub(tick);
+        if ticks_left == 0 {
+            return self.intrinsic_value(spot);
+        }
+        let vol = tick_volatility(volatility_scale) * (ticks_left as f64).sqrt();
+        let d1 = ((spot / self.strike).ln() + vol * vol / 2.0) / vol;
+        let d2 = d1 - vol;
+        match self.kind {
+            OptionKind::Call => spot * normal_cdf(d1) - self.strike * normal_cdf(d2),
+            OptionKind::Put => self.strike * normal_cdf(-d2) - spot * normal_cdf(-d1),
+        }
+    }
+
+    // like "BCI 2000C 25t", with the ticks left until expiry
+    pub fn short_label(&self, tick: u64) -> String {
+        format!(
+            "{0} {1:.0}{2} {3}t",
+            self.ticker,
+            self.strike,
+            &self.kind.label()[..1],
+            self.expiry_tick.saturating_sub(tick)
+        )
+    }
+}
+
+// Abramowitz and Stegun approximation of the error function, plenty for a game
+fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / 2f64.sqrt();
+    let t = 1.0 / (1.0 + 0.3275911 * z);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-z * z).exp();
+    if x >= 0.0 {
+        (1.0 + erf) / 2.0
+    } else {
+        (1.0 - erf) / 2.0
+    }
+}
+
+pub struct OptionChain {
+    pub expiries: Vec<u64>, // ticks at which the listed contracts expire
+    pub strikes: Vec<f64>,  // ascending
+}
+
+// strikes are centered on the current spot price, so the chain moves with the stock
+pub fn option_chain(spot: f64, tick: u64) -> OptionChain {
+    let first_expiry = (tick / EXPIRY_CYCLE_TICKS + 1) * EXPIRY_CYCLE_TICKS;
+    let step = (spot * STRIKE_STEP_PCT / 100.0).round().max(1.0);
+    let center = (spot / step).round() * step;
+    OptionChain {
+        expiries: (0..EXPIRIES_LISTED)
+            .map(|idx| first_expiry + idx * EXPIRY_CYCLE_TICKS)
+            .collect(),
+        strikes: (-STRIKES_EACH_SIDE..=STRIKES_EACH_SIDE)
+            .map(|idx| center + idx as f64 * step)
+            .filter(|strike| *strike > 0.0)
+            .collect(),
+    }
+}
@@ -0,0 +1,389 @@
+use crate::difficulty::DifficultySettings;
+use crate::market::Instrument;
+use crate::portfolio::{Account, Portfolio};
+use chrono::{DateTime, Utc};
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+// shares per tick the simulated market is willing to trade with us, per ticker
+const LIQUIDITY_MIN: u32 = 10;
+const LIQUIDITY_MAX: u32 = 150;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Side {
+    #[serde(alias = "buy")]
+    Buy,
+    #[serde(alias = "sell")]
+    Sell,
+}
+
+impl Side {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Side::Buy => "Buy",
+            Side::Sell => "Sell",
+        }
+    }
+
+    pub fn opposite(&self) -> Side {
+        match self {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OrderStatus {
+    Working,
+    Filled,
+    Cancelled(String), // with the reason
+}
+
+// exit legs opened once the entry order is done filling
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bracket {
+    pub take_profit: Option<f64>,
+    pub stop_loss: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Order {
+    pub id: u64,
+    pub ticker: String,
+    pub side: Side,
+    pub quantity: u32,
+    pub limit_price: Option<f64>, // market order when not set
+    pub stop_price: Option<f64>,
+    pub stop_triggered: bool,
+    pub bracket: Option<Bracket>,
+    pub oco_with: Option<u64>, // the other leg of a bracket
+    pub parent: Option<u64>,   // entry order of a bracket leg
+    pub filled: u32,
+    pub fill_value: f64, // sum of price * quantity over all fills
+    pub status: OrderStatus,
+    pub created: DateTime<Utc>,
+    #[serde(default)]
+    pub account: usize, // index into the accounts, saves from before them have only the one
+}
+
+impl Order {
+    pub fn new(
+        id: u64,
+        ticker: &str,
+        side: Side,
+        quantity: u32,
+        limit_price: Option<f64>,
+    ) -> Order {
+        Order {
+            id,
+            ticker: ticker.to_string(),
+            side,
+            quantity,
+            limit_price,
+            stop_price: None,
+            stop_triggered: false,
+            bracket: None,
+            oco_with: None,
+            parent: None,
+            filled: 0,
+            fill_value: 0.0,
+            status: OrderStatus::Working,
+            created: Utc::now(),
+            account: 0,
+        }
+    }
+
+    pub fn remaining(&self) -> u32 {
+        self.quantity - self.filled
+    }
+
+    pub fn avg_fill_price(&self) -> Option<f64> {
+        if self.filled > 0 {
+            Some(self.fill_value / self.filled as f64)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.status == OrderStatus::Working
+    }
+
+    fn accepts_price(&self, price: f64) -> bool {
+        match (self.side, self.limit_price) {
+            (_, None) => true,
+            (Side::Buy, Some(limit)) => price <= limit,
+            (Side::Sell, Some(limit)) => price >= limit,
+        }
+    }
+
+    // stop orders wait for the price to cross the stop, after that they trade like the
+    // underlying market or limit order
+    fn is_tradable_at(&mut self, price: f64) -> bool {
+        if let Some(stop) = self.stop_price {
+            self.stop_triggered |= match self.side {
+                Side::Buy => price >= stop,
+                Side::Sell => price <= stop,
+            };
+            if !self.stop_triggered {
+                return false;
+            }
+        }
+        self.accepts_price(price)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fill {
+    pub order_id: u64,
+    pub ticker: String,
+    pub side: Side,
+    pub quantity: u32,
+    pub price: f64,
+    pub commission: f64,
+    pub avg_cost_before: f64, // of the position, so the fill can be reverted
+    pub time: DateTime<Utc>,
+    #[serde(default)]
+    pub account: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Amendment {
+    pub order_id: u64,
+    pub old_quantity: u32,
+    pub new_quantity: u32,
+    pub old_limit_price: Option<f64>,
+    pub new_limit_price: Option<f64>,
+    pub time: DateTime<Utc>,
+}
+
+// everything that happened to orders, in the order it happened
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BlotterEntry {
+    Fill(Fill),
+    Amendment(Amendment),
+    Cancellation {
+        order_id: u64,
+        reason: String,
+        time: DateTime<Utc>,
+    },
+}
+
+impl BlotterEntry {
+    pub fn order_id(&self) -> u64 {
+        match self {
+            BlotterEntry::Fill(fill) => fill.order_id,
+            BlotterEntry::Amendment(amendment) => amendment.order_id,
+            BlotterEntry::Cancellation { order_id, .. } => *order_id,
+        }
+    }
+
+    pub fn time(&self) -> DateTime<Utc> {
+        match self {
+            BlotterEntry::Fill(fill) => fill.time,
+            BlotterEntry::Amendment(amendment) => amendment.time,
+            BlotterEntry::Cancellation { time, .. } => *time,
+        }
+    }
+}
+
+// what buys can spend, the cash and, on margin, a loan against the shares held
+#[derive(Debug, Clone, Copy)]
+pub struct BuyingPower {
+    pub trade_unsettled: bool, // proceeds that haven't settled count as cash
+    pub margin_pct: f64,       // of the value of the shares held that can be borrowed, 0 for none
+}
+
+impl BuyingPower {
+    // the most shares a buy at the price can take, leaving the loan, a negative balance, within
+    // the margin of everything held including the shares just bought
+    fn shares(&self, portfolio: &Portfolio, quotes: &[Instrument], price: f64, rate: f64) -> u32 {
+        let cash = if self.trade_unsettled {
+            portfolio.cash
+        } else {
+            portfolio.settled_cash()
+        };
+        let margin = self.margin_pct / 100.0;
+        let held = portfolio
+            .positions
+            .iter()
+            .filter_map(|position| {
+                let quote = quotes
+                    .iter()
+                    .find(|quote| quote.listing.ticker == position.ticker)?;
+                Some(quote.quote.price * position.quantity as f64)
+            })
+            .sum::<f64>();
+        ((cash + margin * held).max(0.0) / (price * (1.0 + rate - margin))).floor() as u32
+    }
+}
+
+// executes working orders against the current quotes, each ticker only has so much
+// liquidity per tick so large orders fill in several partial executions
+pub fn execute_orders(
+    rng: &mut ThreadRng,
+    orders: &mut [Order],
+    quotes: &[Instrument],
+    open_exchanges: &[bool],
+    accounts: &mut [Account],
+    buying_power: BuyingPower,
+    difficulty: &DifficultySettings,
+) -> Vec<BlotterEntry> {
+    let mut fills: Vec<Fill> = vec![];
+    let mut liquidity: Vec<(String, u32)> = vec![];
+    for order in orders.iter_mut().filter(|order| order.is_open()) {
+        let Some(quote) = quotes
+            .iter()
+            .find(|quote| quote.listing.ticker == order.ticker)
+        else {
+            order.status = OrderStatus::Cancelled("unknown ticker".to_string());
+            continue;
+        };
+        // orders wait for the exchange to open, except for instruments that never close
+        if !open_exchanges[quote.exchange] && !quote.trades_continuously() {
+            continue;
+        }
+        if !order.is_tradable_at(quote.quote.price) {
+            continue;
+        }
+        // market orders pay for immediacy, limits never fill past their price
+        let slippage = rng.random_range(0.0..=difficulty.slippage_pct) / 100.0;
+        let price = match (order.side, order.limit_price) {
+            (Side::Buy, None) => quote.quote.price * (1.0 + slippage),
+            (Side::Sell, None) => quote.quote.price * (1.0 - slippage),
+            (_, Some(_)) => quote.quote.price,
+        };
+        let commission_rate = difficulty.commission_pct / 100.0;
+
+        // orders for the same ticker share its liquidity within a tick
+        let liquidity_idx = match liquidity.iter().position(|(t, _)| *t == order.ticker) {
+            Some(idx) => idx,
+            None => {
+                let available = rng.random_range(RangeInclusive::new(LIQUIDITY_MIN, LIQUIDITY_MAX));
+                liquidity.push((order.ticker.clone(), available));
+                liquidity.len() - 1
+            }
+        };
+        let available = &mut liquidity[liquidity_idx].1;
+        // orders of an account that isn't there anymore go to the first one
+        let account = if order.account < accounts.len() {
+            order.account
+        } else {
+            0
+        };
+        let portfolio = &mut accounts[account].portfolio;
+        let allowed = match order.side {
+            Side::Buy => buying_power.shares(portfolio, quotes, price, commission_rate),
+            Side::Sell => portfolio.quantity(&order.ticker),
+        };
+        let quantity = order.remaining().min(*available).min(allowed);
+        if quantity == 0 {
+            if allowed == 0 {
+                order.status = OrderStatus::Cancelled(match order.side {
+                    Side::Buy => "insufficient cash".to_string(),
+                    Side::Sell => "no shares left to sell".to_string(),
+                });
+            }
+            continue;
+        }
+
+        *available -= quantity;
+        order.filled += quantity;
+        order.fill_value += price * quantity as f64;
+        if order.remaining() == 0 {
+            order.status = OrderStatus::Filled;
+        }
+        let fill = Fill {
+            order_id: order.id,
+            ticker: order.ticker.clone(),
+            side: order.side,
+            quantity,
+            price,
+            commission: price * quantity as f64 * commission_rate,
+            avg_cost_before: portfolio
+                .position(&order.ticker)
+                .map_or(0.0, |position| position.avg_cost),
+            time: Utc::now(),
+            account,
+        };
+        portfolio.apply_fill(&fill);
+        fills.push(fill);
+    }
+
+    // one-cancels-other, once a bracket leg trades its sibling is withdrawn
+    let siblings = orders
+        .iter()
+        .filter(|order| fills.iter().any(|fill| fill.order_id == order.id))
+        .filter_map(|order| order.oco_with)
+        .collect::<Vec<u64>>();
+    let mut entries = fills
+        .into_iter()
+        .map(BlotterEntry::Fill)
+        .collect::<Vec<BlotterEntry>>();
+    for order in orders
+        .iter_mut()
+        .filter(|order| order.is_open() && siblings.contains(&order.id))
+    {
+        let reason = "other leg executed".to_string();
+        order.status = OrderStatus::Cancelled(reason.clone());
+        entries.push(BlotterEntry::Cancellation {
+            order_id: order.id,
+            reason,
+            time: Utc::now(),
+        });
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::{gen_equities, gen_universe};
+    use crate::portfolio::Position;
+
+    fn buying_power(margin_pct: f64) -> BuyingPower {
+        BuyingPower {
+            trade_unsettled: true,
+            margin_pct,
+        }
+    }
+
+    #[test]
+    fn without_margin_buys_stop_at_the_cash() {
+        let portfolio = Portfolio::new(10_000.0);
+        assert_eq!(buying_power(0.0).shares(&portfolio, &[], 100.0, 0.0), 100);
+        // the commission has to be paid for too
+        assert_eq!(buying_power(0.0).shares(&portfolio, &[], 100.0, 0.01), 99);
+        let overdrawn = Portfolio::new(-500.0);
+        assert_eq!(buying_power(0.0).shares(&overdrawn, &[], 100.0, 0.0), 0);
+    }
+
+    #[test]
+    fn margin_borrows_against_what_the_buy_adds_too() {
+        let portfolio = Portfolio::new(10_000.0);
+        assert_eq!(buying_power(50.0).shares(&portfolio, &[], 100.0, 0.0), 200);
+    }
+
+    #[test]
+    fn margin_borrows_against_the_shares_held() {
+        let companies = gen_universe(1);
+        let mut quotes = gen_equities(&mut rand::rng(), &companies);
+        quotes[0].quote.price = 100.0;
+        let mut portfolio = Portfolio::new(-1_000.0);
+        portfolio.positions.push(Position {
+            ticker: quotes[0].listing.ticker.clone(),
+            quantity: 100,
+            avg_cost: 100.0,
+            lots: vec![],
+        });
+        // a loan of 1,000 against 10,000 held leaves 4,000 of the margin, which buys 8,000 more
+        assert_eq!(
+            buying_power(50.0).shares(&portfolio, &quotes, 100.0, 0.0),
+            80
+        );
+        assert_eq!(buying_power(0.0).shares(&portfolio, &quotes, 100.0, 0.0), 0);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::alerts::AlertForm;
+use crate::app::{AccountsView, UIState};
+use crate::app::{Confirm, LedgerView, OptionsView, OrderForm, ReportView, RowMenu, SavesMenu};
+use crate::components::ScrollView;
+use crate::forms::Form;
+use crate::fx::FxView;
+use crate::panels::Panel;
+use crate::rebalance::RebalanceView;
+use crate::schedules::ScheduleForm;
+use crate::tutorial::Tutorial;
+
+// something open over the panels, with what it's drawn from
+pub enum Overlay<'a> {
+    InstrumentDetail(&'a str),
+    OrderForm(&'a OrderForm),
+    OptionsChain(&'a OptionsView),
+    RowMenu(&'a RowMenu),
+    NewsArchive(usize), // the page
+    NewsFilter(usize),  // the cursor
+    Comparison,
+    Achievements,
+    Ledger(&'a LedgerView),
+    Accounts(&'a AccountsView),
+    Fx(&'a FxView),
+    Connection,
+    Panel(&'a dyn Panel),
+    Saves(&'a SavesMenu),
+    Settings(&'a Form),
+    Alerts(usize), // the cursor
+    Movers(usize), // the cursor
+    Rebalance(&'a RebalanceView),
+    Schedules(usize), // the cursor
+    ScheduleForm(&'a ScheduleForm),
+    AlertForm(&'a AlertForm),
+    Help(&'a ScrollView),
+    Tutorial(&'a Tutorial),
+    Confirm(&'a Confirm),
+    Report(&'a ReportView),
+}
+
+impl<'a> Overlay<'a> {
+    // everything open, bottom first in the order it's drawn
+    pub fn open(ui_state: &'a UIState) -> Vec<Overlay<'a>> {
+        [
+            ui_state
+                .instrument_detail
+                .as_deref()
+                .map(Overlay::InstrumentDetail),
+            ui_state.order_form.as_ref().map(Overlay::OrderForm),
+            ui_state.options_view.as_ref().map(Overlay::OptionsChain),
+            ui_state.row_menu.as_ref().map(Overlay::RowMenu),
+            ui_state.news_archive_page.map(Overlay::NewsArchive),
+            ui_state.news_filter_menu.map(Overlay::NewsFilter),
+            ui_state.compare_open.then_some(Overlay::Comparison),
+            ui_state.achievements_open.then_some(Overlay::Achievements),
+            ui_state.ledger.as_ref().map(Overlay::Ledger),
+            ui_state.accounts.as_ref().map(Overlay::Accounts),
+            ui_state.fx.as_ref().map(Overlay::Fx),
+            ui_state.connection_open.then_some(Overlay::Connection),
+            ui_state
+                .panel_open
+                .map(|idx| Overlay::Panel(ui_state.panels[idx].as_ref())),
+            ui_state.saves_menu.as_ref().map(Overlay::Saves),
+            ui_state.settings.as_ref().map(Overlay::Settings),
+            ui_state.alerts_open.map(Overlay::Alerts),
+            ui_state.movers_open.map(Overlay::Movers),
+            ui_state.rebalance.as_ref().map(Overlay::Rebalance),
+            ui_state.schedules_open.map(Overlay::Schedules),
+            ui_state.schedule_form.as_ref().map(Overlay::ScheduleForm),
+            ui_state.alert_form.as_ref().map(Overlay::AlertForm),
+            ui_state.help.as_ref().map(Overlay::Help),
+            ui_state.tutorial.as_ref().map(Overlay::Tutorial),
+            ui_state.confirm.as_ref().map(Overlay::Confirm),
+            ui_state.report_view.as_ref().map(Overlay::Report),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    // drawn over the headlines, so their links mustn't work through it
+    pub fn covers_news(&self) -> bool {
+        match self {
+            // over the market table, or a box in the corner
+            Overlay::InstrumentDetail(_) | Overlay::OrderForm(_) | Overlay::Tutorial(_) => false,
+            Overlay::OptionsChain(_)
+            | Overlay::RowMenu(_)
+            | Overlay::NewsArchive(_)
+            | Overlay::NewsFilter(_)
+            | Overlay::Comparison
+            | Overlay::Achievements
+            | Overlay::Ledger(_)
+            | Overlay::Accounts(_)
+            | Overlay::Fx(_)
+            | Overlay::Connection
+            | Overlay::Panel(_)
+            | Overlay::Saves(_)
+            | Overlay::Settings(_)
+            | Overlay::Alerts(_)
+            | Overlay::Movers(_)
+            | Overlay::Rebalance(_)
+            | Overlay::Schedules(_)
+            | Overlay::ScheduleForm(_)
+            | Overlay::AlertForm(_)
+            | Overlay::Help(_)
+            | Overlay::Confirm(_)
+            | Overlay::Report(_) => true,
+        }
+    }
+
+    // takes the keys even over the instrument view
+    pub fn over_detail(&self) -> bool {
+        matches!(
+            self,
+            Overlay::Confirm(_)
+                | Overlay::OrderForm(_)
+                | Overlay::AlertForm(_)
+                | Overlay::ScheduleForm(_)
+                | Overlay::Settings(_)
+                | Overlay::OptionsChain(_)
+                | Overlay::RowMenu(_)
+        )
+    }
+
+    // keeps the keys from the panels, the tutorial only points at things
+    pub fn is_modal(&self) -> bool {
+        !matches!(self, Overlay::InstrumentDetail(_) | Overlay::Tutorial(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn the_stack_is_in_drawing_order() {
+        let mut ui_state = UIState::new(&Config::default());
+        assert!(Overlay::open(&ui_state).is_empty());
+        ui_state.confirm = Some(Confirm::NewGame);
+        ui_state.instrument_detail = Some("BCI".to_string());
+        ui_state.compare_open = true;
+        let open = Overlay::open(&ui_state);
+        assert!(matches!(
+            open[..],
+            [
+                Overlay::InstrumentDetail("BCI"),
+                Overlay::Comparison,
+                Overlay::Confirm(_)
+            ]
+        ));
+    }
+
+    #[test]
+    fn the_detail_leaves_the_news_uncovered_and_the_help_does_not() {
+        let mut ui_state = UIState::new(&Config::default());
+        ui_state.instrument_detail = Some("BCI".to_string());
+        assert!(!Overlay::open(&ui_state).iter().any(Overlay::covers_news));
+        ui_state.help = Some(ScrollView::default());
+        assert!(Overlay::open(&ui_state).iter().any(Overlay::covers_news));
+    }
+}
@@ -0,0 +1,94 @@
+use crate::market::Instrument;
+use crate::news::{NewsCategory, NewsItem};
+use crate::orders::Side;
+use chrono::{DateTime, Utc};
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+// chance that some insider files a trade on any given tick
+const FILING_CHANCE_PER_TICK: f64 = 0.04;
+// heavily shorted companies with good news behind them may squeeze
+const SQUEEZE_MIN_SHORT_INTEREST_PCT: f64 = 20.0;
+const SQUEEZE_CHANCE_PER_TICK: f64 = 0.02;
+// fraction of the short interest left once the shorts have covered
+const SQUEEZE_REMAINING_SHORTS: f64 = 0.3;
+
+const INSIDERS: [&str; 5] = [
+    "Chief Executive",
+    "Chief Financial Officer",
+    "Chief Engineer",
+    "Board Director",
+    "Founding Family Trust",
+];
+
+#[derive(Debug)]
+pub struct InsiderFiling {
+    pub ticker: String,
+    pub insider: &'static str,
+    pub side: Side,
+    pub quantity: u32,
+    pub price: f64,
+    pub filed: DateTime<Utc>,
+}
+
+// insiders only hold company shares
+pub fn gen_insider_filing(rng: &mut ThreadRng, quotes: &[Instrument]) -> Option<InsiderFiling> {
+    let companies = quotes
+        .iter()
+        .filter(|quote| quote.is_company())
+        .collect::<Vec<&Instrument>>();
+    if companies.is_empty() || !rng.random_bool(FILING_CHANCE_PER_TICK) {
+        return None;
+    }
+    let quote = companies[rng.random_range(0..companies.len())];
+    Some(InsiderFiling {
+        ticker: quote.listing.ticker.clone(),
+        insider: INSIDERS[rng.random_range(0..INSIDERS.len())],
+        side: if rng.random_bool(0.5) {
+            Side::Buy
+        } else {
+            Side::Sell
+        },
+        quantity: rng.random_range(1..=50) * 100,
+        price: quote.quote.price,
+        filed: Utc::now(),
+    })
+}
+
+// index into quotes of a company whose shorts are forced to cover this tick
+pub fn gen_squeeze(rng: &mut ThreadRng, quotes: &[Instrument]) -> Option<usize> {
+    let candidates = quotes
+        .iter()
+        .enumerate()
+        .filter(|(_, quote)| {
+            quote
+                .short_interest_pct()
+                .is_some_and(|pct| pct > SQUEEZE_MIN_SHORT_INTEREST_PCT)
+                && quote.sentiment_drift > 0.0
+        })
+        .map(|(idx, _)| idx)
+        .collect::<Vec<usize>>();
+    if candidates.is_empty() || !rng.random_bool(SQUEEZE_CHANCE_PER_TICK) {
+        return None;
+    }
+    Some(candidates[rng.random_range(0..candidates.len())])
+}
+
+// the squeeze news carries the price up while the shorts cover
+pub fn squeeze(quote: &mut Instrument) -> NewsItem {
+    let short_interest_pct = quote.short_interest_pct().unwrap_or(0.0);
+    quote.cover_shorts(SQUEEZE_REMAINING_SHORTS);
+    NewsItem::new(
+        &format!(
+            "Short Squeeze Sends {0} ({1}) Soaring",
+            quote.listing.name, quote.listing.ticker
+        ),
+        &format!(
+            "Traders scramble to cover after short interest reached {0:.1}% of the float.",
+            short_interest_pct
+        ),
+        NewsCategory::General,
+        1.0,
+        &[quote.listing.ticker.as_str()],
+    )
+}
@@ -0,0 +1,29 @@
+use crate::app::AppState;
+use crossterm::event::KeyEvent;
+use ratatui::layout::Rect;
+use ratatui::Frame;
+
+// an extra panel kept out of the core, opened with v on top of the usual ones
+pub trait Panel {
+    fn title(&self) -> String;
+
+    // inside the border that carries the title
+    fn render(&self, frame: &mut Frame, area: Rect, app_state: &AppState);
+
+    // keys other than the ones for switching and closing panels
+    fn handle_action(&mut self, key: KeyEvent, app_state: &AppState);
+
+    fn key_hints(&self) -> Vec<&'static str> {
+        vec![]
+    }
+}
+
+// every panel compiled in, each behind a feature of its own
+pub fn registry() -> Vec<Box<dyn Panel>> {
+    vec![
+        #[cfg(feature = "londinium-weather")]
+        Box::new(crate::weather::WeatherPanel::default()),
+        #[cfg(feature = "market-breadth")]
+        Box::new(crate::breadth::BreadthPanel::default()),
+    ]
+}
@@ -0,0 +1,717 @@
+use crate::options::OptionContract;
+use crate::orders::{Fill, Side};
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Position {
+    pub ticker: String,
+    pub quantity: u32,
+    pub avg_cost: f64,
+    #[serde(default)]
+    pub lots: Vec<Lot>, // oldest first, adding up to the quantity
+}
+
+// shares bought together, sold first in first out for the capital gains
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lot {
+    pub quantity: u32,
+    pub price: f64,
+    pub bought: DateTime<Utc>,
+}
+
+// shares of a lot sold or paid out for, before commissions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Gain {
+    pub ticker: String,
+    pub quantity: u32,
+    pub bought: DateTime<Utc>,
+    pub sold: DateTime<Utc>,
+    pub cost: f64,
+    pub proceeds: f64,
+    #[serde(default)]
+    pub order_id: Option<u64>, // of the sale, so undoing it can put the lot back
+}
+
+impl Gain {
+    pub fn amount(&self) -> f64 {
+        self.proceeds - self.cost
+    }
+
+    // held for at least that long, so a year to the day is long-term with the default
+    pub fn is_long_term(&self, long_term: TimeDelta) -> bool {
+        self.sold - self.bought >= long_term
+    }
+}
+
+// a takeover's shares, the lots keep when they were bought and their whole cost, lots too
+// small for a whole share pass their cost on to the next
+pub fn convert_lots(lots: &[Lot], ratio: f64, shares: u32) -> Vec<Lot> {
+    let mut left = shares;
+    let mut carried = 0.0;
+    let mut converted = vec![];
+    for (idx, lot) in lots.iter().enumerate() {
+        let quantity = if idx + 1 == lots.len() {
+            left
+        } else {
+            ((lot.quantity as f64 * ratio).floor() as u32).min(left)
+        };
+        left -= quantity;
+        let cost = lot.price * lot.quantity as f64 + carried;
+        if quantity == 0 {
+            carried = cost;
+            continue;
+        }
+        carried = 0.0;
+        converted.push(Lot {
+            quantity,
+            price: cost / quantity as f64,
+            bought: lot.bought,
+        });
+    }
+    converted
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionPosition {
+    pub contract: OptionContract,
+    pub quantity: u32,
+    pub avg_cost: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CashKind {
+    Deposit,
+    Trade,
+    Commission,
+    Option,   // premiums, and settlement at expiry
+    Payout,   // cash from takeovers and delistings
+    Interest, // paid on cash for every day
+    Borrow,   // charged on a negative balance for every day
+    Transfer, // between accounts
+    Exchange, // foreign cash bought or sold
+}
+
+impl CashKind {
+    pub const ALL: [CashKind; 9] = [
+        CashKind::Deposit,
+        CashKind::Trade,
+        CashKind::Commission,
+        CashKind::Option,
+        CashKind::Payout,
+        CashKind::Interest,
+        CashKind::Borrow,
+        CashKind::Transfer,
+        CashKind::Exchange,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CashKind::Deposit => "Deposit",
+            CashKind::Trade => "Trade",
+            CashKind::Commission => "Commission",
+            CashKind::Option => "Option",
+            CashKind::Payout => "Payout",
+            CashKind::Interest => "Interest",
+            CashKind::Borrow => "Borrow cost",
+            CashKind::Transfer => "Transfer",
+            CashKind::Exchange => "FX",
+        }
+    }
+}
+
+// a cash movement with the balance it left
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CashEntry {
+    pub at: DateTime<Utc>,
+    pub kind: CashKind,
+    pub amount: f64,
+    pub balance: f64,
+    #[serde(default)]
+    pub note: String, // what it was for, like "Buy 10 BCI @ 1200.00"
+}
+
+// like "BCI 2000 Call"
+fn contract_note(contract: &OptionContract) -> String {
+    format!(
+        "{0} {1:.0} {2}",
+        contract.ticker,
+        contract.strike,
+        contract.kind.label()
+    )
+}
+
+fn fill_note(fill: &Fill) -> String {
+    format!(
+        "{0} {1} {2} @ {3:.2}",
+        fill.side.label(),
+        fill.quantity,
+        fill.ticker,
+        fill.price
+    )
+}
+
+// proceeds of a sale counted in the cash but not there to take out until they settle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Unsettled {
+    pub amount: f64,
+    pub settles: NaiveDate,
+    pub order_id: u64,
+    pub time: DateTime<Utc>, // of the fill, so undoing it takes the proceeds back
+}
+
+// the business day a trade settles on, weekends don't count
+pub fn settlement_date(traded: NaiveDate, days: u32) -> NaiveDate {
+    let mut date = traded;
+    let mut left = days;
+    while left > 0 {
+        date += TimeDelta::days(1);
+        if !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+            left -= 1;
+        }
+    }
+    date
+}
+
+// cash held in another currency, by the code of the FX market
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForeignCash {
+    pub code: String,
+    pub amount: f64,
+}
+
+// a named account with cash and positions of its own, like "Pension"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Account {
+    pub name: String,
+    pub portfolio: Portfolio,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Portfolio {
+    pub cash: f64,
+    pub positions: Vec<Position>,
+    pub option_positions: Vec<OptionPosition>, // long only
+    // gains locked in per ticker since the game started or was loaded, net of commissions
+    #[serde(skip)]
+    pub realized_pnl: Vec<(String, f64)>,
+    // every cash movement, oldest first and only ever added to, undone fills are posted back
+    #[serde(default)]
+    pub ledger: Vec<CashEntry>,
+    // every sale of shares by lot, oldest first
+    #[serde(default)]
+    pub gains: Vec<Gain>,
+    // sale proceeds waiting to settle, soonest first
+    #[serde(default)]
+    pub unsettled: Vec<Unsettled>,
+    #[serde(default)]
+    pub foreign_cash: Vec<ForeignCash>,
+}
+
+impl Portfolio {
+    pub fn new(cash: f64) -> Portfolio {
+        let mut portfolio = Portfolio {
+            cash: 0.0,
+            positions: vec![],
+            option_positions: vec![],
+            realized_pnl: vec![],
+            ledger: vec![],
+            gains: vec![],
+            unsettled: vec![],
+            foreign_cash: vec![],
+        };
+        if cash != 0.0 {
+            portfolio.post(CashKind::Deposit, cash, "Starting cash");
+        }
+        portfolio
+    }
+
+    // everything held across the accounts as one, without a ledger
+    pub fn combined<'a>(portfolios: impl Iterator<Item = &'a Portfolio>) -> Portfolio {
+        let mut combined = Portfolio::new(0.0);
+        for portfolio in portfolios {
+            combined.cash += portfolio.cash;
+            for position in portfolio.positions.iter() {
+                match combined
+                    .positions
+                    .iter_mut()
+                    .find(|held| held.ticker == position.ticker)
+                {
+                    Some(held) => {
+                        let cost = held.avg_cost * held.quantity as f64
+                            + position.avg_cost * position.quantity as f64;
+                        held.quantity += position.quantity;
+                        held.avg_cost = cost / held.quantity as f64;
+                        held.lots.extend(position.lots.iter().cloned());
+                        held.lots.sort_by_key(|lot| lot.bought);
+                    }
+                    None => combined.positions.push(position.clone()),
+                }
+            }
+            for position in portfolio.option_positions.iter() {
+                match combined
+                    .option_positions
+                    .iter_mut()
+                    .find(|held| held.contract == position.contract)
+                {
+                    Some(held) => {
+                        let cost = held.avg_cost * held.quantity as f64
+                            + position.avg_cost * position.quantity as f64;
+                        held.quantity += position.quantity;
+                        held.avg_cost = cost / held.quantity as f64;
+                    }
+                    None => combined.option_positions.push(position.clone()),
+                }
+            }
+            for (ticker, pnl) in portfolio.realized_pnl.iter() {
+                combined.add_realized(ticker, *pnl);
+            }
+            combined.gains.extend(portfolio.gains.iter().cloned());
+            combined
+                .unsettled
+                .extend(portfolio.unsettled.iter().cloned());
+            for held in portfolio.foreign_cash.iter() {
+                combined.add_foreign(&held.code, held.amount);
+            }
+        }
+        combined.gains.sort_by_key(|gain| gain.sold);
+        combined
+            .unsettled
+            .sort_by_key(|unsettled| unsettled.settles);
+        combined
+    }
+
+    // the only way cash changes, so the ledger adds up to it
+    pub fn post(&mut self, kind: CashKind, amount: f64, note: &str) {
+        self.cash += amount;
+        self.ledger.push(CashEntry {
+            at: Utc::now(),
+            kind,
+            amount,
+            balance: self.cash,
+            note: note.to_string(),
+        });
+    }
+
+    pub fn foreign(&self, code: &str) -> f64 {
+        self.foreign_cash
+            .iter()
+            .find(|held| held.code == code)
+            .map_or(0.0, |held| held.amount)
+    }
+
+    fn add_foreign(&mut self, code: &str, amount: f64) {
+        match self.foreign_cash.iter_mut().find(|held| held.code == code) {
+            Some(held) => held.amount += amount,
+            None => self.foreign_cash.push(ForeignCash {
+                code: code.to_string(),
+                amount,
+            }),
+        }
+        // what's left of a currency sold off is rounding
+        self.foreign_cash.retain(|held| held.amount.abs() >= 0.005);
+    }
+
+    // foreign cash bought for what it cost in home cash, or sold for what it brought in
+    pub fn change_cash(&mut self, code: &str, side: Side, amount: f64, value: f64, note: &str) {
+        match side {
+            Side::Buy => {
+                self.add_foreign(code, amount);
+                self.post(CashKind::Exchange, -value, note);
+            }
+            Side::Sell => {
+                self.add_foreign(code, -amount);
+                self.post(CashKind::Exchange, value, note);
+            }
+        }
+    }
+
+    pub fn unsettled_cash(&self) -> f64 {
+        self.unsettled
+            .iter()
+            .map(|unsettled| unsettled.amount)
+            .sum()
+    }
+
+    // what can be moved out, below zero when unsettled proceeds were spent
+    pub fn settled_cash(&self) -> f64 {
+        self.cash - self.unsettled_cash()
+    }
+
+    // on every tick, what's due by today in the calendar is settled
+    pub fn settle(&mut self, today: NaiveDate) {
+        self.unsettled.retain(|unsettled| unsettled.settles > today);
+    }
+
+    // saves from before the ledger start it with what they had
+    pub fn open_ledger(&mut self) {
+        if self.ledger.is_empty() {
+            let cash = self.cash;
+            self.cash = 0.0;
+            self.post(CashKind::Deposit, cash, "Opening balance");
+        }
+    }
+
+    // saves from before lots have the shares held as one lot bought now at the average cost
+    pub fn open_lots(&mut self) {
+        for position in self.positions.iter_mut() {
+            if position.lots.is_empty() {
+                position.lots.push(Lot {
+                    quantity: position.quantity,
+                    price: position.avg_cost,
+                    bought: Utc::now(),
+                });
+            }
+        }
+    }
+
+    // takes the shares out of the oldest lots first and records what each made
+    pub fn dispose(
+        &mut self,
+        ticker: &str,
+        quantity: u32,
+        price: f64,
+        sold: DateTime<Utc>,
+        order_id: Option<u64>,
+    ) {
+        let Some(position) = self
+            .positions
+            .iter_mut()
+            .find(|position| position.ticker == ticker)
+        else {
+            return;
+        };
+        let mut left = quantity;
+        while left > 0 && !position.lots.is_empty() {
+            let lot = &mut position.lots[0];
+            let sold_quantity = left.min(lot.quantity);
+            self.gains.push(Gain {
+                ticker: ticker.to_string(),
+                quantity: sold_quantity,
+                bought: lot.bought,
+                sold,
+                cost: lot.price * sold_quantity as f64,
+                proceeds: price * sold_quantity as f64,
+                order_id,
+            });
+            lot.quantity -= sold_quantity;
+            left -= sold_quantity;
+            if lot.quantity == 0 {
+                position.lots.remove(0);
+            }
+        }
+    }
+
+    pub fn settle_option(&mut self, contract: &OptionContract, value: f64) {
+        if value != 0.0 {
+            self.post(
+                CashKind::Option,
+                value,
+                &format!("{0} settled", contract_note(contract)),
+            );
+        }
+    }
+
+    pub fn add_realized(&mut self, ticker: &str, amount: f64) {
+        match self
+            .realized_pnl
+            .iter_mut()
+            .find(|(realized_ticker, _)| realized_ticker == ticker)
+        {
+            Some((_, pnl)) => *pnl += amount,
+            None => self.realized_pnl.push((ticker.to_string(), amount)),
+        }
+    }
+
+    pub fn position(&self, ticker: &str) -> Option<&Position> {
+        self.positions
+            .iter()
+            .find(|position| position.ticker == ticker)
+    }
+
+    pub fn quantity(&self, ticker: &str) -> u32 {
+        self.position(ticker)
+            .map_or(0, |position| position.quantity)
+    }
+
+    // commissions come out of cash, the average cost is the traded price only
+    pub fn apply_fill(&mut self, fill: &Fill) {
+        let value = fill.price * fill.quantity as f64;
+        let note = fill_note(fill);
+        if fill.commission != 0.0 {
+            self.post(CashKind::Commission, -fill.commission, &note);
+        }
+        self.add_realized(&fill.ticker, -fill.commission);
+        match fill.side {
+            Side::Buy => {
+                self.post(CashKind::Trade, -value, &note);
+                let lot = Lot {
+                    quantity: fill.quantity,
+                    price: fill.price,
+                    bought: fill.time,
+                };
+                match self
+                    .positions
+                    .iter_mut()
+                    .find(|position| position.ticker == fill.ticker)
+                {
+                    Some(position) => {
+                        let cost = position.avg_cost * position.quantity as f64 + value;
+                        position.quantity += fill.quantity;
+                        position.avg_cost = cost / position.quantity as f64;
+                        position.lots.push(lot);
+                    }
+                    None => self.positions.push(Position {
+                        ticker: fill.ticker.clone(),
+                        quantity: fill.quantity,
+                        avg_cost: fill.price,
+                        lots: vec![lot],
+                    }),
+                }
+            }
+            Side::Sell => {
+                self.post(CashKind::Trade, value, &note);
+                self.dispose(
+                    &fill.ticker,
+                    fill.quantity,
+                    fill.price,
+                    fill.time,
+                    Some(fill.order_id),
+                );
+                if let Some(position) = self
+                    .positions
+                    .iter_mut()
+                    .find(|position| position.ticker == fill.ticker)
+                {
+                    position.quantity = position.quantity.saturating_sub(fill.quantity);
+                    let gain = (fill.price - position.avg_cost) * fill.quantity as f64;
+                    self.add_realized(&fill.ticker, gain);
+                }
+                self.positions.retain(|position| position.quantity > 0);
+            }
+        }
+    }
+
+    // undoes apply_fill, the fill must be the latest one for its ticker
+    pub fn revert_fill(&mut self, fill: &Fill) {
+        let value = fill.price * fill.quantity as f64;
+        let note = format!("{0} undone", fill_note(fill));
+        if fill.commission != 0.0 {
+            self.post(CashKind::Commission, fill.commission, &note);
+        }
+        self.add_realized(&fill.ticker, fill.commission);
+        match fill.side {
+            Side::Buy => {
+                self.post(CashKind::Trade, value, &note);
+                if let Some(position) = self
+                    .positions
+                    .iter_mut()
+                    .find(|position| position.ticker == fill.ticker)
+                {
+                    position.quantity = position.quantity.saturating_sub(fill.quantity);
+                    position.avg_cost = fill.avg_cost_before;
+                    let mut left = fill.quantity;
+                    while let Some(lot) = position.lots.last_mut().filter(|_| left > 0) {
+                        let taken = left.min(lot.quantity);
+                        lot.quantity -= taken;
+                        left -= taken;
+                        if lot.quantity == 0 {
+                            position.lots.pop();
+                        }
+                    }
+                }
+                self.positions.retain(|position| position.quantity > 0);
+            }
+            Side::Sell => {
+                self.post(CashKind::Trade, -value, &note);
+                self.unsettled.retain(|unsettled| {
+                    unsettled.order_id != fill.order_id || unsettled.time != fill.time
+                });
+                let gain = (fill.price - fill.avg_cost_before) * fill.quantity as f64;
+                self.add_realized(&fill.ticker, -gain);
+                // the lots the sale took from go back in front, oldest first
+                let (undone, gains): (Vec<Gain>, Vec<Gain>) = std::mem::take(&mut self.gains)
+                    .into_iter()
+                    .partition(|gain| {
+                        gain.order_id == Some(fill.order_id) && gain.sold == fill.time
+                    });
+                self.gains = gains;
+                let lots = undone.into_iter().map(|gain| Lot {
+                    quantity: gain.quantity,
+                    price: gain.cost / gain.quantity as f64,
+                    bought: gain.bought,
+                });
+                match self
+                    .positions
+                    .iter_mut()
+                    .find(|position| position.ticker == fill.ticker)
+                {
+                    Some(position) => {
+                        position.quantity += fill.quantity;
+                        position.lots.splice(0..0, lots);
+                    }
+                    None => self.positions.push(Position {
+                        ticker: fill.ticker.clone(),
+                        quantity: fill.quantity,
+                        avg_cost: fill.avg_cost_before,
+                        lots: lots.collect(),
+                    }),
+                }
+            }
+        }
+    }
+
+    pub fn option_quantity(&self, contract: &OptionContract) -> u32 {
+        self.option_positions
+            .iter()
+            .find(|position| position.contract == *contract)
+            .map_or(0, |position| position.quantity)
+    }
+
+    // options trade straight away at the model price, one contract is one share
+    pub fn buy_option(&mut self, contract: &OptionContract, price: f64) -> Result<(), String> {
+        if price > self.cash {
+            return Err("insufficient cash".to_string());
+        }
+        self.post(
+            CashKind::Option,
+            -price,
+            &format!("Buy {0}", contract_note(contract)),
+        );
+        match self
+            .option_positions
+            .iter_mut()
+            .find(|position| position.contract == *contract)
+        {
+            Some(position) => {
+                let cost = position.avg_cost * position.quantity as f64 + price;
+                position.quantity += 1;
+                position.avg_cost = cost / position.quantity as f64;
+            }
+            None => self.option_positions.push(OptionPosition {
+                contract: contract.clone(),
+                quantity: 1,
+                avg_cost: price,
+            }),
+        }
+        Ok(())
+    }
+
+    pub fn sell_option(&mut self, contract: &OptionContract, price: f64) -> Result<(), String> {
+        let Some(position) = self
+            .option_positions
+            .iter_mut()
+            .find(|position| position.contract == *contract)
+        else {
+            return Err("no contracts held".to_string());
+        };
+        position.quantity -= 1;
+        self.option_positions
+            .retain(|position| position.quantity > 0);
+        self.post(
+            CashKind::Option,
+            price,
+            &format!("Sell {0}", contract_note(contract)),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(days: i64) -> DateTime<Utc> {
+        NaiveDate::from_ymd_opt(2026, 1, 5)
+            .unwrap()
+            .and_time(Default::default())
+            .and_utc()
+            + TimeDelta::days(days)
+    }
+
+    fn fill(side: Side, quantity: u32, price: f64, time: DateTime<Utc>) -> Fill {
+        Fill {
+            order_id: 1,
+            ticker: "BRSS".to_string(),
+            side,
+            quantity,
+            price,
+            commission: 0.0,
+            avg_cost_before: 0.0,
+            time,
+            account: 0,
+        }
+    }
+
+    // ten shares at 10, ten more at 20 two hundred days later, then 15 sold at 30
+    fn partly_sold(sold: DateTime<Utc>) -> Portfolio {
+        let mut portfolio = Portfolio::new(1_000.0);
+        portfolio.apply_fill(&fill(Side::Buy, 10, 10.0, day(0)));
+        portfolio.apply_fill(&fill(Side::Buy, 10, 20.0, day(200)));
+        portfolio.apply_fill(&fill(Side::Sell, 15, 30.0, sold));
+        portfolio
+    }
+
+    #[test]
+    fn a_sale_uses_up_the_oldest_lot_first_and_leaves_the_rest_of_the_next() {
+        let portfolio = partly_sold(day(400));
+        let gains = portfolio
+            .gains
+            .iter()
+            .map(|gain| (gain.quantity, gain.bought, gain.cost, gain.proceeds))
+            .collect::<Vec<(u32, DateTime<Utc>, f64, f64)>>();
+        assert_eq!(
+            gains,
+            [(10, day(0), 100.0, 300.0), (5, day(200), 100.0, 150.0)]
+        );
+        let position = portfolio.position("BRSS").unwrap();
+        assert_eq!(position.quantity, 5);
+        let lots = position
+            .lots
+            .iter()
+            .map(|lot| (lot.quantity, lot.price, lot.bought))
+            .collect::<Vec<(u32, f64, DateTime<Utc>)>>();
+        assert_eq!(lots, [(5, 20.0, day(200))]);
+    }
+
+    #[test]
+    fn each_lot_sold_is_long_or_short_term_by_when_it_was_bought() {
+        let long_term = TimeDelta::days(365);
+        let portfolio = partly_sold(day(400));
+        let terms = portfolio
+            .gains
+            .iter()
+            .map(|gain| (gain.is_long_term(long_term), gain.amount()))
+            .collect::<Vec<(bool, f64)>>();
+        assert_eq!(terms, [(true, 200.0), (false, 50.0)]);
+        // a year to the day is long-term already
+        let portfolio = partly_sold(day(365));
+        assert!(portfolio.gains[0].is_long_term(long_term));
+        assert!(!portfolio.gains[1].is_long_term(long_term));
+        let portfolio = partly_sold(day(364));
+        assert!(!portfolio.gains[0].is_long_term(long_term));
+    }
+
+    #[test]
+    fn a_thursday_trade_settles_on_monday() {
+        let thursday = NaiveDate::from_ymd_opt(2026, 1, 8).unwrap();
+        assert_eq!(thursday.weekday(), Weekday::Thu);
+        assert_eq!(
+            settlement_date(thursday, 2),
+            NaiveDate::from_ymd_opt(2026, 1, 12).unwrap()
+        );
+    }
+
+    #[test]
+    fn a_friday_trade_settles_on_tuesday() {
+        let friday = NaiveDate::from_ymd_opt(2026, 1, 9).unwrap();
+        assert_eq!(friday.weekday(), Weekday::Fri);
+        assert_eq!(
+            settlement_date(friday, 2),
+            NaiveDate::from_ymd_opt(2026, 1, 13).unwrap()
+        );
+        // and the same day without a settlement period
+        assert_eq!(settlement_date(friday, 0), friday);
+    }
+}
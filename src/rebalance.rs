@@ -0,0 +1,156 @@
+use crate::app::AppState;
+use crate::market::{Instrument, InstrumentClass};
+use crate::orders::Side;
+use std::collections::HashMap;
+
+// what a target share of the portfolio is for
+#[derive(Clone, PartialEq)]
+pub enum Bucket {
+    Ticker(String),
+    Class(InstrumentClass), // spread evenly over what's held of it without a target of its own
+}
+
+#[derive(Clone)]
+pub struct Target {
+    pub bucket: Bucket,
+    pub percent: f64,
+}
+
+// [trading] targets, keys are tickers or asset classes like "Bonds", sorted so the view
+// doesn't shuffle
+pub fn parse_targets(
+    targets: &HashMap<String, f64>,
+    quotes: &[Instrument],
+) -> Result<Vec<Target>, String> {
+    let mut parsed = targets
+        .iter()
+        .map(|(key, percent)| {
+            let bucket = if quotes.iter().any(|quote| quote.listing.ticker == *key) {
+                Bucket::Ticker(key.clone())
+            } else {
+                let class = InstrumentClass::ALL
+                    .into_iter()
+                    .find(|class| class.label().eq_ignore_ascii_case(key))
+                    .ok_or_else(|| {
+                        format!(
+                            "\"{0}\" in [trading.targets] is neither a ticker nor an asset class",
+                            key
+                        )
+                    })?;
+                Bucket::Class(class)
+            };
+            if !percent.is_finite() || *percent < 0.0 || *percent > 100.0 {
+                return Err(format!(
+                    "target of {0} in [trading.targets] must be between 0 and 100",
+                    key
+                ));
+            }
+            Ok(Target {
+                bucket,
+                percent: *percent,
+            })
+        })
+        .collect::<Result<Vec<Target>, String>>()?;
+    if parsed.iter().map(|target| target.percent).sum::<f64>() > 100.0 + 1e-9 {
+        return Err("the targets in [trading.targets] add up to more than 100%".to_string());
+    }
+    parsed.sort_by_key(|target| match &target.bucket {
+        Bucket::Ticker(ticker) => (0, ticker.clone()),
+        Bucket::Class(class) => (1, class.label().to_string()),
+    });
+    Ok(parsed)
+}
+
+// one instrument of the plan, shares of the portfolio in percent
+#[derive(Debug, Clone)]
+pub struct Leg {
+    pub ticker: String,
+    pub target: f64,
+    pub current: f64,
+    pub side: Side,
+    pub quantity: u32, // 0 when it's as close as whole shares get
+}
+
+pub struct Plan {
+    pub legs: Vec<Leg>, // sells first, so they free up the cash for the buys
+    pub empty: Vec<InstrumentClass>, // class targets with nothing held to spread over
+}
+
+impl Plan {
+    pub fn orders(&self) -> Vec<Leg> {
+        self.legs
+            .iter()
+            .filter(|leg| leg.quantity > 0)
+            .cloned()
+            .collect()
+    }
+}
+
+// the market orders that bring every target to its share of the active account at current
+// prices, whatever has no target is left alone
+pub fn plan(app_state: &AppState, targets: &[Target]) -> Plan {
+    let value = app_state.value_of(app_state.portfolio());
+    let own_target = |ticker: &str| {
+        targets
+            .iter()
+            .any(|target| target.bucket == Bucket::Ticker(ticker.to_string()))
+    };
+    let mut shares = vec![];
+    let mut empty = vec![];
+    for target in targets {
+        match &target.bucket {
+            Bucket::Ticker(ticker) => shares.push((ticker.clone(), target.percent)),
+            Bucket::Class(class) => {
+                let held = app_state
+                    .portfolio()
+                    .positions
+                    .iter()
+                    .filter(|position| !own_target(&position.ticker))
+                    .filter(|position| {
+                        app_state
+                            .quote_index(&position.ticker)
+                            .is_some_and(|idx| app_state.quotes[idx].class() == *class)
+                    })
+                    .map(|position| position.ticker.clone())
+                    .collect::<Vec<String>>();
+                if held.is_empty() {
+                    empty.push(*class);
+                }
+                let percent = target.percent / held.len().max(1) as f64;
+                shares.extend(held.into_iter().map(|ticker| (ticker, percent)));
+            }
+        }
+    }
+    let mut legs = shares
+        .into_iter()
+        .filter_map(|(ticker, target)| {
+            let price = app_state.quotes[app_state.quote_index(&ticker)?]
+                .quote
+                .price;
+            let held = app_state.portfolio().quantity(&ticker);
+            let current = held as f64 * price / value * 100.0;
+            let difference = (target - current) / 100.0 * value / price;
+            let (side, quantity) = if difference < 0.0 {
+                (Side::Sell, (-difference).floor().min(held as f64) as u32)
+            } else {
+                (Side::Buy, difference.floor() as u32)
+            };
+            Some(Leg {
+                ticker,
+                target,
+                current,
+                side,
+                quantity,
+            })
+        })
+        .collect::<Vec<Leg>>();
+    legs.sort_by_key(|leg| leg.side != Side::Sell);
+    Plan { legs, empty }
+}
+
+// the plan is worked out again on every frame until it's staged, then kept as it was for a
+// look before the orders go in
+#[derive(Default)]
+pub struct RebalanceView {
+    pub staged: Option<Vec<Leg>>,
+}
@@ -0,0 +1,106 @@
+use chrono::Utc;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+// an asciinema v2 recording, a header line and then one line per chunk of output with
+// the seconds since the start, see https://docs.asciinema.org/manual/asciicast/v2/
+pub struct Cast {
+    file: BufWriter<File>,
+    started: Instant,
+}
+
+impl Cast {
+    pub fn create(path: &Path, (width, height): (u16, u16)) -> io::Result<Cast> {
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(
+            file,
+            "{{\"version\": 2, \"width\": {0}, \"height\": {1}, \"timestamp\": {2}, \"env\": {{\"TERM\": {3}}}}}",
+            width,
+            height,
+            Utc::now().timestamp(),
+            json_string(&env::var("TERM").unwrap_or_default())
+        )?;
+        file.flush()?;
+        Ok(Cast {
+            file,
+            started: Instant::now(),
+        })
+    }
+
+    fn output(&mut self, data: &str) -> io::Result<()> {
+        writeln!(
+            self.file,
+            "[{0:.6}, \"o\", {1}]",
+            self.started.elapsed().as_secs_f64(),
+            json_string(data)
+        )?;
+        self.file.flush()
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut json = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            c if c.is_control() => json.push_str(&format!("\\u{0:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+// goes under the terminal backend, passing everything through and putting each frame
+// in the recording when the backend flushes it
+pub struct Recorder<W: Write> {
+    inner: W,
+    cast: Option<Cast>,
+    pending: Vec<u8>,
+}
+
+impl<W: Write> Recorder<W> {
+    pub fn new(inner: W, cast: Option<Cast>) -> Recorder<W> {
+        Recorder {
+            inner,
+            cast,
+            pending: vec![],
+        }
+    }
+}
+
+impl<W: Write> Write for Recorder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if self.cast.is_some() {
+            self.pending.extend_from_slice(&buf[..written]);
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+        let Some(cast) = self.cast.as_mut() else {
+            return Ok(());
+        };
+        // a character cut off at the end waits for the rest of it
+        let complete = match std::str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(_) => self.pending.len(),
+        };
+        if complete == 0 {
+            return Ok(());
+        }
+        let rest = self.pending.split_off(complete);
+        cast.output(&String::from_utf8_lossy(&self.pending))?;
+        self.pending = rest;
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// recorded prices played back instead of the random walk, one update per line like
+//
+// tick,ticker,price
+// 1,AETH,2710.5
+// 1,BCI,88.2
+// 2,AETH,2716.0
+//
+// lines with the same tick arrive together, playback starts over after the last one
+#[derive(Debug)]
+pub struct Replay {
+    pub path: PathBuf,
+    steps: Vec<Vec<(String, f64)>>,
+    pub pos: usize, // the next step to play
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Replay, String> {
+        let text =
+            fs::read_to_string(path).map_err(|err| format!("{0}: {1}", path.display(), err))?;
+        let mut steps: Vec<Vec<(String, f64)>> = vec![];
+        let mut last_tick = None;
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("tick") {
+                continue;
+            }
+            let invalid = || {
+                format!(
+                    "{0}:{1}: expected tick,ticker,price",
+                    path.display(),
+                    line_no + 1
+                )
+            };
+            let [tick, ticker, price] = line.split(',').map(str::trim).collect::<Vec<_>>()[..]
+            else {
+                return Err(invalid());
+            };
+            let (Ok(tick), Ok(price)) = (tick.parse::<u64>(), price.parse::<f64>()) else {
+                return Err(invalid());
+            };
+            if last_tick != Some(tick) {
+                steps.push(vec![]);
+                last_tick = Some(tick);
+            }
+            steps.last_mut().unwrap().push((ticker.to_string(), price));
+        }
+        if steps.is_empty() {
+            return Err(format!("{0}: no prices", path.display()));
+        }
+        Ok(Replay {
+            path: path.to_path_buf(),
+            steps,
+            pos: 0,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    // never, a replay without steps doesn't load
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn next_step(&mut self) -> Vec<(String, f64)> {
+        let step = self.steps[self.pos].clone();
+        self.pos = (self.pos + 1) % self.steps.len();
+        step
+    }
+}
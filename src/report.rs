@@ -0,0 +1,481 @@
+use crate::app::AppState;
+use crate::benchmark::{rebased, relative, Relative};
+use crate::config::data_dir;
+use crate::exchanges::Currency;
+use crate::orders::{BlotterEntry, Side};
+use crate::portfolio::CashEntry;
+use crate::timefmt::TimeFormat;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::fs;
+use std::path::PathBuf;
+
+// columns of the text sparkline in the Markdown export
+const SPARKLINE_WIDTH: usize = 60;
+const SPARKLINE_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+// size of the SVG chart in the HTML export
+const SVG_WIDTH: f64 = 600.0;
+const SVG_HEIGHT: f64 = 150.0;
+
+// a curve squeezed into a line of block characters at most this wide, each one averaging its
+// stretch, from the bottom at min to the top at max
+pub fn sparkline(curve: &[f64], width: usize, (min, max): (f64, f64)) -> String {
+    let len = curve.len();
+    let width = len.min(width);
+    (0..width)
+        .map(|col| {
+            let chunk = &curve[col * len / width..(col + 1) * len / width];
+            let value = chunk.iter().sum::<f64>() / chunk.len() as f64;
+            let level = if max > min {
+                ((value - min) / (max - min) * (SPARKLINE_LEVELS.len() - 1) as f64).round() as usize
+            } else {
+                0
+            };
+            SPARKLINE_LEVELS[level.min(SPARKLINE_LEVELS.len() - 1)]
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+// realized this session plus what's unrealized on shares still held
+#[derive(Debug)]
+pub struct PositionResult {
+    pub ticker: String,
+    pub pnl: f64,
+}
+
+// gains realized on a ticker this session, by how long the lots sold were held
+#[derive(Debug)]
+pub struct TickerGains {
+    pub ticker: String,
+    pub short_term: f64,
+    pub long_term: f64,
+}
+
+// how the session went, taken when the report is opened
+#[derive(Debug)]
+pub struct SessionReport {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub ticks: usize,
+    pub start_value: f64,
+    pub end_value: f64,
+    pub buys: usize,
+    pub sells: usize,
+    pub traded_value: f64,
+    pub commissions: f64,
+    pub positions: Vec<PositionResult>, // best first
+    pub gains: Vec<TickerGains>,        // by ticker
+    pub equity_curve: Vec<f64>,
+    pub composite: Vec<f64>, // rebased to the starting value
+    pub relative: Relative,
+    time_format: TimeFormat,
+    currency: Currency,
+}
+
+impl SessionReport {
+    pub fn new(app_state: &AppState) -> SessionReport {
+        let fills = app_state
+            .blotter
+            .iter()
+            .filter_map(|entry| match entry {
+                BlotterEntry::Fill(fill) if fill.time >= app_state.started_at => Some(fill),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let portfolio = app_state.combined();
+        let mut positions = portfolio
+            .realized_pnl
+            .iter()
+            .map(|(ticker, pnl)| PositionResult {
+                ticker: ticker.clone(),
+                pnl: *pnl,
+            })
+            .collect::<Vec<_>>();
+        for position in portfolio.positions.iter() {
+            let Some(idx) = app_state.quote_index(&position.ticker) else {
+                continue;
+            };
+            let unrealized =
+                (app_state.quotes[idx].quote.price - position.avg_cost) * position.quantity as f64;
+            match positions
+                .iter_mut()
+                .find(|result| result.ticker == position.ticker)
+            {
+                Some(result) => result.pnl += unrealized,
+                None => positions.push(PositionResult {
+                    ticker: position.ticker.clone(),
+                    pnl: unrealized,
+                }),
+            }
+        }
+        positions.sort_by(|a, b| b.pnl.total_cmp(&a.pnl));
+        let mut gains: Vec<TickerGains> = vec![];
+        for gain in portfolio
+            .gains
+            .iter()
+            .filter(|gain| gain.sold >= app_state.started_at)
+        {
+            let pos = match gains.iter().position(|gains| gains.ticker == gain.ticker) {
+                Some(pos) => pos,
+                None => {
+                    gains.push(TickerGains {
+                        ticker: gain.ticker.clone(),
+                        short_term: 0.0,
+                        long_term: 0.0,
+                    });
+                    gains.len() - 1
+                }
+            };
+            if gain.is_long_term(app_state.long_term) {
+                gains[pos].long_term += gain.amount();
+            } else {
+                gains[pos].short_term += gain.amount();
+            }
+        }
+        gains.sort_by(|a, b| a.ticker.cmp(&b.ticker));
+        let end_value = app_state.portfolio_value();
+        let start_value = app_state.equity_curve.first().copied().unwrap_or(end_value);
+        SessionReport {
+            started_at: app_state.started_at,
+            ended_at: Utc::now(),
+            ticks: app_state.equity_curve.len().saturating_sub(1),
+            start_value,
+            end_value,
+            buys: fills.iter().filter(|fill| fill.side == Side::Buy).count(),
+            sells: fills.iter().filter(|fill| fill.side == Side::Sell).count(),
+            traded_value: fills
+                .iter()
+                .map(|fill| fill.price * fill.quantity as f64)
+                .sum(),
+            commissions: fills.iter().map(|fill| fill.commission).sum(),
+            positions,
+            gains,
+            equity_curve: app_state.equity_curve.clone(),
+            composite: rebased(&app_state.composite.curve, start_value),
+            relative: relative(&app_state.equity_curve, &app_state.composite.curve),
+            time_format: app_state.time_format,
+            currency: app_state.currency.clone(),
+        }
+    }
+
+    pub fn pnl(&self) -> f64 {
+        self.end_value - self.start_value
+    }
+
+    pub fn pnl_pct(&self) -> f64 {
+        self.pnl() / self.start_value * 100.0
+    }
+
+    pub fn short_term(&self) -> f64 {
+        self.gains.iter().map(|gains| gains.short_term).sum()
+    }
+
+    pub fn long_term(&self) -> f64 {
+        self.gains.iter().map(|gains| gains.long_term).sum()
+    }
+
+    pub fn money(&self, value: f64) -> String {
+        self.currency.format(value, 2)
+    }
+
+    pub fn period(&self) -> String {
+        format!(
+            "{0} {1} – {2}, {3} ticks",
+            self.time_format.short_date(self.started_at),
+            self.time_format.time(self.started_at),
+            self.time_format.time(self.ended_at),
+            self.ticks
+        )
+    }
+
+    // label and value pairs, shared by the screen and both exports
+    pub fn summary(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Starting value", self.money(self.start_value)),
+            ("Final value", self.money(self.end_value)),
+            (
+                "P&L",
+                format!("{0} ({1:+.2}%)", self.money(self.pnl()), self.pnl_pct()),
+            ),
+            (
+                "Trades",
+                format!(
+                    "{0} ({1} buys, {2} sells)",
+                    self.buys + self.sells,
+                    self.buys,
+                    self.sells
+                ),
+            ),
+            ("Traded value", self.money(self.traded_value)),
+            ("Commissions", self.money(self.commissions)),
+            ("Short-term gain", self.money(self.short_term())),
+            ("Long-term gain", self.money(self.long_term())),
+            (
+                "Composite index",
+                format!("{0:+.2}%", self.relative.index_return),
+            ),
+            ("Relative", format!("{0:+.2} pts", self.relative.excess)),
+            (
+                "Beta",
+                self.relative
+                    .beta
+                    .map_or("-".to_string(), |beta| format!("{0:.2}", beta)),
+            ),
+            (
+                "Alpha",
+                self.relative
+                    .alpha
+                    .map_or("-".to_string(), |alpha| format!("{0:+.2}%", alpha)),
+            ),
+        ]
+    }
+
+    // of both curves, so they're drawn to the same scale
+    fn value_range(&self) -> (f64, f64) {
+        let values = self
+            .equity_curve
+            .iter()
+            .chain(self.composite.iter())
+            .copied();
+        let min = values.clone().fold(f64::MAX, f64::min);
+        let max = values.fold(f64::MIN, f64::max);
+        (min, max)
+    }
+
+    // both curves on the same scale
+    fn sparkline(&self, curve: &[f64]) -> String {
+        sparkline(curve, SPARKLINE_WIDTH, self.value_range())
+    }
+
+    fn svg_polyline(&self, curve: &[f64], color: &str) -> String {
+        let (min, max) = self.value_range();
+        let last = curve.len().saturating_sub(1).max(1) as f64;
+        let points = curve
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| {
+                let y = if max > min {
+                    (max - value) / (max - min) * SVG_HEIGHT
+                } else {
+                    SVG_HEIGHT / 2.0
+                };
+                format!("{0:.1},{1:.1}", idx as f64 / last * SVG_WIDTH, y)
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!(
+            "<polyline fill=\"none\" stroke=\"{0}\" stroke-width=\"2\" points=\"{1}\"/>",
+            color, points
+        )
+    }
+
+    // the composite index in gray under the portfolio
+    fn svg_chart(&self) -> String {
+        format!(
+            "<svg width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">{2}{3}</svg>",
+            SVG_WIDTH,
+            SVG_HEIGHT,
+            self.svg_polyline(&self.composite, "gray"),
+            self.svg_polyline(&self.equity_curve, "teal")
+        )
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut text = format!("# Session report\n\n{0}\n\n", self.period());
+        text.push_str("| | |\n|---|---:|\n");
+        for (label, value) in self.summary() {
+            text.push_str(&format!("| {0} | {1} |\n", label, value));
+        }
+        text.push_str(&format!(
+            "\n## Equity curve\n\n`{0}`\n\n`{1}` composite index, rebased\n\nfrom {2} to {3}\n",
+            self.sparkline(&self.equity_curve),
+            self.sparkline(&self.composite),
+            self.money(self.value_range().0),
+            self.money(self.value_range().1)
+        ));
+        if !self.positions.is_empty() {
+            text.push_str("\n## Positions\n\n| Ticker | P&L |\n|---|---:|\n");
+            for result in self.positions.iter() {
+                text.push_str(&format!(
+                    "| {0} | {1} |\n",
+                    result.ticker,
+                    self.money(result.pnl)
+                ));
+            }
+        }
+        if !self.gains.is_empty() {
+            text.push_str(
+                "\n## Capital gains\n\n| Ticker | Short-term | Long-term |\n|---|---:|---:|\n",
+            );
+            for gains in self.gains.iter() {
+                text.push_str(&format!(
+                    "| {0} | {1} | {2} |\n",
+                    gains.ticker,
+                    self.money(gains.short_term),
+                    self.money(gains.long_term)
+                ));
+            }
+        }
+        text
+    }
+
+    pub fn to_html(&self) -> String {
+        let rows = |cells: Vec<(String, String)>| {
+            cells
+                .into_iter()
+                .map(|(label, value)| {
+                    format!(
+                        "<tr><td>{0}</td><td align=\"right\">{1}</td></tr>\n",
+                        escape_html(&label),
+                        escape_html(&value)
+                    )
+                })
+                .collect::<String>()
+        };
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Session report</title></head>\n\
+             <body>\n<h1>Session report</h1>\n<p>{0}</p>\n<table>\n{1}</table>\n\
+             <h2>Equity curve</h2>\n{2}\n",
+            escape_html(&self.period()),
+            rows(
+                self.summary()
+                    .into_iter()
+                    .map(|(label, value)| (label.to_string(), value))
+                    .collect()
+            ),
+            self.svg_chart()
+        );
+        if !self.positions.is_empty() {
+            html.push_str(&format!(
+                "<h2>Positions</h2>\n<table>\n{0}</table>\n",
+                rows(
+                    self.positions
+                        .iter()
+                        .map(|result| (result.ticker.clone(), self.money(result.pnl)))
+                        .collect()
+                )
+            ));
+        }
+        if !self.gains.is_empty() {
+            html.push_str(&format!(
+                "<h2>Capital gains</h2>\n<table>\n\
+                 <tr><th>Ticker</th><th>Short-term</th><th>Long-term</th></tr>\n{0}</table>\n",
+                self.gains
+                    .iter()
+                    .map(|gains| format!(
+                        "<tr><td>{0}</td><td align=\"right\">{1}</td><td align=\"right\">{2}</td></tr>\n",
+                        escape_html(&gains.ticker),
+                        escape_html(&self.money(gains.short_term)),
+                        escape_html(&self.money(gains.long_term))
+                    ))
+                    .collect::<String>()
+            ));
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// reports go next to the saves, named after when the session ended
+pub fn write_report(report: &SessionReport, format: ReportFormat) -> Result<PathBuf, String> {
+    let dir = data_dir()
+        .map(|dir| dir.join("reports"))
+        .ok_or_else(|| "no data directory to write the report to".to_string())?;
+    fs::create_dir_all(&dir).map_err(|err| format!("{0}: {1}", dir.display(), err))?;
+    let path = dir.join(format!(
+        "session-{0}.{1}",
+        report.ended_at.format("%Y%m%d-%H%M%S"),
+        format.extension()
+    ));
+    let text = match format {
+        ReportFormat::Markdown => report.to_markdown(),
+        ReportFormat::Html => report.to_html(),
+    };
+    fs::write(&path, text).map_err(|err| format!("{0}: {1}", path.display(), err))?;
+    Ok(path)
+}
+
+// quoted when it has to be, with quotes doubled
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{0}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+// the ledger entries given, in the reports folder next to the session reports
+pub fn write_ledger(entries: &[&CashEntry]) -> Result<PathBuf, String> {
+    let dir = data_dir()
+        .map(|dir| dir.join("reports"))
+        .ok_or_else(|| "no data directory to write the ledger to".to_string())?;
+    fs::create_dir_all(&dir).map_err(|err| format!("{0}: {1}", dir.display(), err))?;
+    let path = dir.join(format!(
+        "ledger-{0}.csv",
+        Utc::now().format("%Y%m%d-%H%M%S")
+    ));
+    let mut text = "time,kind,amount,balance,note\n".to_string();
+    for entry in entries {
+        text.push_str(&format!(
+            "{0},{1},{2:.2},{3:.2},{4}\n",
+            entry.at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            entry.kind.label(),
+            entry.amount,
+            entry.balance,
+            csv_field(&entry.note)
+        ));
+    }
+    fs::write(&path, text).map_err(|err| format!("{0}: {1}", path.display(), err))?;
+    Ok(path)
+}
+
+// the session's gains by ticker and holding period with a total at the bottom, for the tax
+// return
+pub fn write_gains(report: &SessionReport) -> Result<PathBuf, String> {
+    let dir = data_dir()
+        .map(|dir| dir.join("reports"))
+        .ok_or_else(|| "no data directory to write the capital gains to".to_string())?;
+    fs::create_dir_all(&dir).map_err(|err| format!("{0}: {1}", dir.display(), err))?;
+    let path = dir.join(format!(
+        "gains-{0}.csv",
+        report.ended_at.format("%Y%m%d-%H%M%S")
+    ));
+    let mut text = "ticker,short_term,long_term,total\n".to_string();
+    for gains in report.gains.iter() {
+        text.push_str(&format!(
+            "{0},{1:.2},{2:.2},{3:.2}\n",
+            csv_field(&gains.ticker),
+            gains.short_term,
+            gains.long_term,
+            gains.short_term + gains.long_term
+        ));
+    }
+    text.push_str(&format!(
+        "Total,{0:.2},{1:.2},{2:.2}\n",
+        report.short_term(),
+        report.long_term(),
+        report.short_term() + report.long_term()
+    ));
+    fs::write(&path, text).map_err(|err| format!("{0}: {1}", path.display(), err))?;
+    Ok(path)
+}
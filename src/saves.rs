@@ -0,0 +1,123 @@
+use crate::config::data_dir;
+use crate::difficulty::Difficulty;
+use crate::market::PricingModel;
+use crate::orders::{BlotterEntry, Order};
+use crate::portfolio::{Account, Portfolio};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+// long enough to tell campaigns apart, short enough for the menu column
+const MAX_SLOT_NAME_LEN: usize = 20;
+
+// a campaign as written to a save slot, prices and the trading record but not the news
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub saved_at: DateTime<Utc>,
+    pub portfolio_value: f64, // at the time of saving, for the menu preview
+    pub difficulty: Difficulty,
+    pub ticks: u64,
+    pub next_order_id: u64,
+    pub quotes: Vec<SavedQuote>, // delisted instruments are left out
+    // only in saves from before accounts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub portfolio: Option<Portfolio>,
+    #[serde(default)]
+    pub accounts: Vec<Account>,
+    #[serde(default)]
+    pub active: usize,
+    pub orders: Vec<Order>,
+    pub blotter: Vec<BlotterEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedQuote {
+    pub ticker: String,
+    pub model: PricingModel,
+    pub price: f64,
+    pub price_yesterday: f64,
+    pub sentiment_drift: f64,
+}
+
+// what the load/save menu shows of a slot without keeping the whole game around
+#[derive(Debug)]
+pub struct SaveSlot {
+    pub name: String,
+    pub saved_at: DateTime<Utc>,
+    pub portfolio_value: f64,
+    pub difficulty: Difficulty,
+}
+
+fn saves_dir() -> Result<PathBuf, String> {
+    data_dir()
+        .map(|dir| dir.join("saves"))
+        .ok_or_else(|| "no data directory to keep saves in".to_string())
+}
+
+// slot names become file names
+pub fn validate_slot_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("the save needs a name".to_string());
+    }
+    if name.trim().chars().count() > MAX_SLOT_NAME_LEN {
+        return Err(format!(
+            "save names are at most {0} characters",
+            MAX_SLOT_NAME_LEN
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_')
+    {
+        return Err("only letters, digits, spaces, - and _ in save names".to_string());
+    }
+    Ok(())
+}
+
+fn slot_path(name: &str) -> Result<PathBuf, String> {
+    validate_slot_name(name)?;
+    Ok(saves_dir()?.join(format!("{0}.toml", name.trim())))
+}
+
+// newest first, files that don't parse are left out
+pub fn list_slots() -> Vec<SaveSlot> {
+    let Ok(entries) = saves_dir().and_then(|dir| fs::read_dir(dir).map_err(|err| err.to_string()))
+    else {
+        return vec![];
+    };
+    let mut slots: Vec<SaveSlot> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "toml" {
+                return None;
+            }
+            let name = path.file_stem()?.to_str()?.to_string();
+            let game = toml::from_str::<SavedGame>(&fs::read_to_string(&path).ok()?).ok()?;
+            Some(SaveSlot {
+                name,
+                saved_at: game.saved_at,
+                portfolio_value: game.portfolio_value,
+                difficulty: game.difficulty,
+            })
+        })
+        .collect();
+    slots.sort_by_key(|slot| std::cmp::Reverse(slot.saved_at));
+    slots
+}
+
+// an existing slot with the same name is overwritten
+pub fn write_slot(name: &str, game: &SavedGame) -> Result<(), String> {
+    let path = slot_path(name)?;
+    let text = toml::to_string(game).map_err(|err| err.to_string())?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|err| format!("{0}: {1}", dir.display(), err))?;
+    }
+    fs::write(&path, text).map_err(|err| format!("{0}: {1}", path.display(), err))
+}
+
+pub fn read_slot(name: &str) -> Result<SavedGame, String> {
+    let path = slot_path(name)?;
+    let text = fs::read_to_string(&path).map_err(|err| format!("{0}: {1}", path.display(), err))?;
+    toml::from_str(&text).map_err(|err| format!("{0}: {1}", path.display(), err))
+}
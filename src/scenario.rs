@@ -0,0 +1,96 @@
+use chrono::NaiveTime;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+// a scripted story played on top of the random simulation, loaded from TOML like
+//
+// [[events]]
+// at = "10:30"
+// kind = "move"
+// ticker = "AETH"
+// pct = -15
+#[derive(Debug, Default, Deserialize)]
+pub struct Scenario {
+    #[serde(default)]
+    events: Vec<ScriptedEvent>,
+    // wall clock time of the previous check, events fire as the clock passes them
+    #[serde(skip)]
+    last_checked: Option<NaiveTime>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScriptedEvent {
+    // time of day in the [time] timezone, or a number of ticks since start
+    at: Option<NaiveTime>,
+    after_ticks: Option<u64>,
+    #[serde(flatten)]
+    action: ScriptedAction,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScriptedAction {
+    // jumps the price by pct percent, with a headline explaining it if given
+    Move {
+        ticker: String,
+        pct: f64,
+        headline: Option<String>,
+    },
+    News {
+        title: String,
+        #[serde(default)]
+        subtitle: String,
+        #[serde(default)]
+        tickers: Vec<String>,
+        #[serde(default)]
+        sentiment: f64,
+    },
+    // starts a takeover storyline, a random bidder is picked when none is given
+    MergerRumor {
+        target: String,
+        acquirer: Option<String>,
+    },
+}
+
+impl Scenario {
+    pub fn load(path: &Path) -> Result<Scenario, String> {
+        let text =
+            fs::read_to_string(path).map_err(|err| format!("{0}: {1}", path.display(), err))?;
+        let scenario: Scenario =
+            toml::from_str(&text).map_err(|err| format!("{0}: {1}", path.display(), err))?;
+        if let Some(pos) = scenario
+            .events
+            .iter()
+            .position(|event| event.at.is_some() == event.after_ticks.is_some())
+        {
+            return Err(format!(
+                "{0}: event {1} needs exactly one of at and after_ticks",
+                path.display(),
+                pos + 1
+            ));
+        }
+        Ok(scenario)
+    }
+
+    // takes out the events that are due, in file order
+    pub fn due(&mut self, ticks: u64, now: NaiveTime) -> Vec<ScriptedAction> {
+        let last_checked = self.last_checked.replace(now);
+        let passed = |at: NaiveTime| match last_checked {
+            None => false,
+            Some(last) if last <= now => last < at && at <= now,
+            // the clock went past midnight since the last check
+            Some(last) => last < at || at <= now,
+        };
+        let mut actions = vec![];
+        self.events.retain(|event| {
+            let is_due = event.after_ticks.is_some_and(|after| ticks >= after)
+                || event.at.is_some_and(passed);
+            if is_due {
+                actions.push(event.action.clone());
+            }
+            !is_due
+        });
+        actions
+    }
+}
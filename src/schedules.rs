@@ -0,0 +1,178 @@
+use crate::forms::{Field, Form};
+use crate::orders::Side;
+use chrono::{NaiveDate, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+pub const SIDES: &[&str] = &["buy", "sell"];
+pub const INTERVALS: &[&str] = &["day", "week"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interval {
+    Day,
+    Week,
+}
+
+impl Interval {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Interval::Day => "day",
+            Interval::Week => "week",
+        }
+    }
+
+    fn days(&self) -> i64 {
+        match self {
+            Interval::Day => 1,
+            Interval::Week => 7,
+        }
+    }
+}
+
+// a market order placed again every interval, once the exchange has opened
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schedule {
+    pub ticker: String,
+    pub side: Side,
+    pub quantity: u32,
+    pub every: Interval,
+    #[serde(default)]
+    pub paused: bool,
+    #[serde(default)]
+    pub last_run: Option<NaiveDate>,
+    #[serde(default)]
+    pub account: usize, // the orders go to the account it was set up from
+}
+
+impl Schedule {
+    // days missed while the ledger was closed aren't made up for
+    fn is_due(&self, today: NaiveDate) -> bool {
+        !self.paused
+            && self
+                .last_run
+                .is_none_or(|last_run| today >= last_run + TimeDelta::days(self.every.days()))
+    }
+}
+
+// what gets written to the data directory
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SavedSchedules {
+    #[serde(default)]
+    schedules: Vec<Schedule>,
+}
+
+#[derive(Debug, Default)]
+pub struct Schedules {
+    pub schedules: Vec<Schedule>,
+    path: Option<PathBuf>, // not saved when there's nowhere to save to
+}
+
+impl Schedules {
+    // a missing or unreadable file just means starting from scratch
+    pub fn load(path: Option<PathBuf>) -> Schedules {
+        let saved = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|text| toml::from_str::<SavedSchedules>(&text).ok())
+            .unwrap_or_default();
+        Schedules {
+            schedules: saved.schedules,
+            path,
+        }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let saved = SavedSchedules {
+            schedules: self.schedules.clone(),
+        };
+        let Ok(text) = toml::to_string(&saved) else {
+            return;
+        };
+        if path
+            .parent()
+            .is_some_and(|dir| fs::create_dir_all(dir).is_err())
+        {
+            return;
+        }
+        let _ = fs::write(path, text);
+    }
+
+    pub fn add(&mut self, schedule: Schedule) {
+        self.schedules.push(schedule);
+        self.save();
+    }
+
+    pub fn remove(&mut self, idx: usize) {
+        if idx < self.schedules.len() {
+            self.schedules.remove(idx);
+            self.save();
+        }
+    }
+
+    pub fn toggle_paused(&mut self, idx: usize) {
+        if let Some(schedule) = self.schedules.get_mut(idx) {
+            schedule.paused = !schedule.paused;
+            self.save();
+        }
+    }
+
+    // the orders to place today, for the tickers whose exchange is open, marked as run
+    pub fn due(&mut self, today: NaiveDate, open: impl Fn(&str) -> bool) -> Vec<Schedule> {
+        let mut due = vec![];
+        for schedule in self.schedules.iter_mut() {
+            if schedule.is_due(today) && open(&schedule.ticker) {
+                schedule.last_run = Some(today);
+                due.push(schedule.clone());
+            }
+        }
+        if !due.is_empty() {
+            self.save();
+        }
+        due
+    }
+}
+
+// setting up recurring orders of an instrument
+pub struct ScheduleForm {
+    pub ticker: String,
+    pub account: usize,
+    pub form: Form,
+}
+
+impl ScheduleForm {
+    pub fn new(ticker: &str, account: usize) -> ScheduleForm {
+        ScheduleForm {
+            ticker: ticker.to_string(),
+            account,
+            form: Form::new(vec![
+                Field::select("Side", SIDES, 0),
+                Field::number("Quantity", false).spinner(1.0, 0.0),
+                Field::select("Every", INTERVALS, 0),
+            ]),
+        }
+    }
+
+    pub fn parse(&mut self) -> Result<Schedule, String> {
+        self.form.validate()?;
+        Ok(Schedule {
+            ticker: self.ticker.clone(),
+            side: match self.form.choice(0) {
+                0 => Side::Buy,
+                _ => Side::Sell,
+            },
+            quantity: self.form.number(1).unwrap_or(0.0) as u32,
+            every: match self.form.choice(2) {
+                0 => Interval::Day,
+                _ => Interval::Week,
+            },
+            paused: false,
+            last_run: None,
+            account: self.account,
+        })
+    }
+}
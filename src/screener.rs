@@ -0,0 +1,144 @@
+use crate::market::Instrument;
+
+// a screen is a list of conditions that must all hold, like "short_interest > 20 and price < 1000"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScreenField {
+    Price,
+    Change,
+    ShortInterest,
+}
+
+impl ScreenField {
+    const ALL: [ScreenField; 3] = [
+        ScreenField::Price,
+        ScreenField::Change,
+        ScreenField::ShortInterest,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            ScreenField::Price => "price",
+            ScreenField::Change => "change",
+            ScreenField::ShortInterest => "short_interest",
+        }
+    }
+
+    // None when the instrument has no such value, bonds are never shorted
+    fn value(&self, row: &ScreenRow) -> Option<f64> {
+        match self {
+            ScreenField::Price => Some(row.price),
+            ScreenField::Change => Some(row.change),
+            ScreenField::ShortInterest => row.short_interest,
+        }
+    }
+}
+
+// what a screen looks at of an instrument, copied out so screens can run off the main thread
+#[derive(Debug, Clone, Copy)]
+pub struct ScreenRow {
+    price: f64,
+    change: f64,
+    short_interest: Option<f64>,
+}
+
+impl ScreenRow {
+    pub fn of(quote: &Instrument) -> ScreenRow {
+        ScreenRow {
+            price: quote.quote.price,
+            change: (quote.quote.price - quote.quote.price_yesterday) / quote.quote.price_yesterday
+                * 100.0,
+            short_interest: quote.short_interest_pct(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+}
+
+impl Comparison {
+    // two character operators first so ">=" is not read as ">"
+    const ALL: [(&'static str, Comparison); 5] = [
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+        ("=", Comparison::Equal),
+    ];
+
+    fn holds(&self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Comparison::Less => lhs < rhs,
+            Comparison::LessOrEqual => lhs <= rhs,
+            Comparison::Greater => lhs > rhs,
+            Comparison::GreaterOrEqual => lhs >= rhs,
+            Comparison::Equal => (lhs - rhs).abs() < 0.005,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Condition {
+    field: ScreenField,
+    comparison: Comparison,
+    value: f64,
+}
+
+#[derive(Debug)]
+pub struct Screen {
+    conditions: Vec<Condition>,
+}
+
+impl Screen {
+    pub fn parse(query: &str) -> Result<Screen, String> {
+        let conditions = query
+            .split(" and ")
+            .map(|condition| {
+                let (op, (lhs, rhs), comparison) = Comparison::ALL
+                    .iter()
+                    .find_map(|(op, comparison)| {
+                        condition
+                            .split_once(op)
+                            .map(|parts| (op, parts, *comparison))
+                    })
+                    .ok_or(format!("no comparison in \"{0}\"", condition.trim()))?;
+                let field = ScreenField::ALL
+                    .into_iter()
+                    .find(|field| field.name() == lhs.trim())
+                    .ok_or(format!(
+                        "unknown field \"{0}\", try {1}",
+                        lhs.trim(),
+                        ScreenField::ALL.map(|field| field.name()).join(", ")
+                    ))?;
+                let value = rhs
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| format!("\"{0}\" after {1} is not a number", rhs.trim(), op))?;
+                Ok(Condition {
+                    field,
+                    comparison,
+                    value,
+                })
+            })
+            .collect::<Result<Vec<Condition>, String>>()?;
+        Ok(Screen { conditions })
+    }
+
+    pub fn matches(&self, quote: &Instrument) -> bool {
+        self.matches_row(&ScreenRow::of(quote))
+    }
+
+    pub fn matches_row(&self, row: &ScreenRow) -> bool {
+        self.conditions.iter().all(|condition| {
+            condition
+                .field
+                .value(row)
+                .is_some_and(|value| condition.comparison.holds(value, condition.value))
+        })
+    }
+}
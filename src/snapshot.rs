@@ -0,0 +1,89 @@
+use crate::app::AppState;
+use crate::locale::tr;
+use crate::numbers::large;
+use crate::terminal::{ascii_text, limited, ColorDepth};
+use crate::theme::Theme;
+use crossterm::style::Stylize;
+use std::io::{self, Write};
+use textwrap::core::display_width;
+
+const HEADERS: [&str; 6] = ["Ticker", "Name", "Exchange", "Price", "Change%", "Volume"];
+// the numbers line up on the right
+const RIGHT_ALIGNED: [bool; 6] = [false, false, false, true, true, true];
+
+// the market table for --once, one line per instrument loaded
+pub fn print_quotes(
+    out: &mut impl Write,
+    app_state: &AppState,
+    theme: &Theme,
+    colored: bool, // changes in the theme colors, for a terminal
+    ascii: bool,
+    depth: ColorDepth,
+) -> io::Result<()> {
+    let rows = app_state
+        .quotes
+        .iter()
+        .map(|quote| {
+            let exchange = &app_state.exchanges[quote.exchange];
+            let price = exchange.local_price(quote.quote.price);
+            let price_yesterday = exchange.local_price_yesterday(quote.quote.price_yesterday);
+            let percent_change = (price - price_yesterday) / price_yesterday * 100.0;
+            let cells = [
+                quote.listing.ticker.clone(),
+                quote.listing.name.clone(),
+                exchange.code.clone(),
+                exchange.currency.format(price, quote.price_decimals()),
+                format!(
+                    "{0}{1:.2}%",
+                    theme.change_arrow(percent_change),
+                    percent_change
+                ),
+                large(quote.volume),
+            ]
+            .map(|cell| if ascii { ascii_text(&cell) } else { cell });
+            (cells, percent_change)
+        })
+        .collect::<Vec<([String; 6], f64)>>();
+    let headers = HEADERS.map(|header| tr(header).to_string());
+    let mut widths = headers.clone().map(|header| display_width(&header));
+    for (cells, _) in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(cells) {
+            *width = (*width).max(display_width(cell));
+        }
+    }
+    let pad = |idx: usize, cell: &str| {
+        let fill = " ".repeat(widths[idx] - display_width(cell));
+        if RIGHT_ALIGNED[idx] {
+            format!("{0}{1}", fill, cell)
+        } else {
+            format!("{0}{1}", cell, fill)
+        }
+    };
+
+    let header = (0..headers.len())
+        .map(|idx| pad(idx, &headers[idx]))
+        .collect::<Vec<String>>()
+        .join("  ");
+    if colored {
+        writeln!(out, "{0}", header.trim_end().italic())?;
+    } else {
+        writeln!(out, "{0}", header.trim_end())?;
+    }
+    for (cells, percent_change) in rows.iter() {
+        let line = (0..cells.len())
+            .map(|idx| {
+                let cell = pad(idx, &cells[idx]);
+                // padded first, escape sequences don't take up any room
+                if colored && HEADERS[idx] == "Change%" {
+                    let color = limited(theme.change_color(*percent_change), depth);
+                    cell.with(color.into()).to_string()
+                } else {
+                    cell
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("  ");
+        writeln!(out, "{0}", line.trim_end())?;
+    }
+    out.flush()
+}
@@ -0,0 +1,290 @@
+use crossterm::cursor::MoveTo;
+use crossterm::queue;
+use crossterm::style::{
+    Attribute, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor,
+};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier};
+use std::env;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use textwrap::core::display_width;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorDepth {
+    Sixteen,
+    TwoFiftySix,
+    TrueColor,
+}
+
+impl ColorDepth {
+    pub fn parse(name: &str) -> Option<ColorDepth> {
+        match name {
+            "16" => Some(ColorDepth::Sixteen),
+            "256" => Some(ColorDepth::TwoFiftySix),
+            "truecolor" | "24bit" => Some(ColorDepth::TrueColor),
+            _ => None,
+        }
+    }
+
+    // by what the terminal advertises, 16 colors when it says nothing
+    pub fn detect() -> ColorDepth {
+        let colorterm = env::var("COLORTERM").unwrap_or_default().to_lowercase();
+        let term = env::var("TERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::TwoFiftySix
+        } else {
+            ColorDepth::Sixteen
+        }
+    }
+}
+
+// whether the locale can carry anything but ASCII
+pub fn utf8_locale() -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default()
+        .to_lowercase();
+    locale.contains("utf-8") || locale.contains("utf8")
+}
+
+// the xterm defaults of the 16 named colors
+const NAMED: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn indexed_rgb(idx: u8) -> (u8, u8, u8) {
+    match idx {
+        0..=15 => NAMED[idx as usize].1,
+        16..=231 => {
+            let idx = idx - 16;
+            (
+                CUBE_LEVELS[(idx / 36) as usize],
+                CUBE_LEVELS[(idx / 6 % 6) as usize],
+                CUBE_LEVELS[(idx % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (idx - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    [(r1, r2), (g1, g2), (b1, b2)]
+        .iter()
+        .map(|(a, b)| (*a as i32 - *b as i32).pow(2) as u32)
+        .sum()
+}
+
+fn nearest_named(rgb: (u8, u8, u8)) -> Color {
+    NAMED
+        .iter()
+        .min_by_key(|(_, named)| distance(rgb, *named))
+        .unwrap()
+        .0
+}
+
+// the closest of the 6x6x6 cube and the gray ramp
+fn nearest_indexed(rgb: (u8, u8, u8)) -> Color {
+    (16..=255)
+        .min_by_key(|idx| distance(rgb, indexed_rgb(*idx)))
+        .map(Color::Indexed)
+        .unwrap()
+}
+
+pub fn limited(color: Color, depth: ColorDepth) -> Color {
+    match (color, depth) {
+        (_, ColorDepth::TrueColor) => color,
+        (Color::Rgb(red, green, blue), ColorDepth::TwoFiftySix) => {
+            nearest_indexed((red, green, blue))
+        }
+        (Color::Rgb(red, green, blue), ColorDepth::Sixteen) => nearest_named((red, green, blue)),
+        (Color::Indexed(idx), ColorDepth::Sixteen) => nearest_named(indexed_rgb(idx)),
+        _ => color,
+    }
+}
+
+fn ascii(symbol: char) -> char {
+    match symbol {
+        symbol if symbol.is_ascii() => symbol,
+        '↑' | '▲' | '▴' => '^',
+        '↓' | '▼' | '▾' => 'v',
+        '←' | '◀' => '<',
+        '→' | '▶' => '>',
+        '·' | '•' | '●' | '◐' | '◷' => '*',
+        '○' => 'o',
+        '✔' => '+',
+        '₡' => 'C',
+        '£' => 'L',
+        '§' => 'S',
+        '₳' => 'A',
+        '─' | '━' | '═' | '┄' | '╌' => '-',
+        '│' | '┃' | '║' | '┆' | '╎' => '|',
+        // the rest of the box drawing characters are corners and joints
+        '\u{2500}'..='\u{257f}' => '+',
+        '\u{2580}'..='\u{259f}' | '■' => '#',
+        '\u{2800}' => ' ',
+        '\u{2801}'..='\u{28ff}' => '.',
+        _ => '?',
+    }
+}
+
+pub fn ascii_text(text: &str) -> String {
+    text.chars().map(ascii).collect()
+}
+
+// run over every frame before it goes out, for terminals that can't show what was drawn
+pub fn downgrade(buffer: &mut Buffer, ascii_only: bool, depth: ColorDepth) {
+    for cell in buffer.content.iter_mut() {
+        cell.fg = limited(cell.fg, depth);
+        cell.bg = limited(cell.bg, depth);
+        if ascii_only && !cell.symbol().is_ascii() {
+            // a cell's symbol is one character plus whatever combines with it
+            let symbol = cell.symbol().chars().next().map_or(' ', ascii);
+            cell.set_char(symbol);
+        }
+    }
+}
+
+// a clickable part of the last frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hyperlink {
+    pub area: Rect,
+    pub url: String,
+}
+
+// ratatui counts an escape sequence in a cell as that many columns, so links are written
+// after the frame instead, printing the cells again between the start and the end of an OSC 8
+pub fn write_hyperlinks(buffer: &Buffer, links: &[Hyperlink]) -> io::Result<()> {
+    if links.is_empty() {
+        return Ok(());
+    }
+    let mut stdout = io::stdout();
+    for link in links {
+        let area = link.area.intersection(buffer.area);
+        for row in area.top()..area.bottom() {
+            queue!(
+                stdout,
+                MoveTo(area.x, row),
+                Print(format!("\x1b]8;;{0}\x1b\\", link.url))
+            )?;
+            let mut x = area.left();
+            while x < area.right() {
+                let cell = &buffer[(x, row)];
+                queue!(
+                    stdout,
+                    SetAttribute(Attribute::Reset),
+                    SetForegroundColor(cell.fg.into()),
+                    SetBackgroundColor(cell.bg.into())
+                )?;
+                for (modifier, attribute) in [
+                    (Modifier::BOLD, Attribute::Bold),
+                    (Modifier::DIM, Attribute::Dim),
+                    (Modifier::ITALIC, Attribute::Italic),
+                    (Modifier::UNDERLINED, Attribute::Underlined),
+                    (Modifier::REVERSED, Attribute::Reverse),
+                ] {
+                    if cell.modifier.contains(modifier) {
+                        queue!(stdout, SetAttribute(attribute))?;
+                    }
+                }
+                queue!(stdout, Print(cell.symbol()))?;
+                // the cells under the rest of a wide character are blank
+                x += display_width(cell.symbol()).max(1) as u16;
+            }
+            queue!(stdout, Print("\x1b]8;;\x1b\\"))?;
+        }
+    }
+    queue!(stdout, SetAttribute(Attribute::Reset), ResetColor)?;
+    stdout.flush()
+}
+
+// in the default browser, without waiting for it
+pub fn open_url(url: &str) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/c", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+// keeps whatever the title was on the terminal's title stack, restore_title brings it back
+pub fn save_title() -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(b"\x1b[22;0t")?;
+    stdout.flush()
+}
+
+pub fn restore_title() -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(b"\x1b[23;0t")?;
+    stdout.flush()
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let triple = chunk.iter().enumerate().fold(0u32, |triple, (idx, byte)| {
+            triple | (*byte as u32) << (16 - 8 * idx)
+        });
+        for idx in 0..4 {
+            encoded.push(if idx <= chunk.len() {
+                BASE64[(triple >> (18 - 6 * idx) & 0x3f) as usize] as char
+            } else {
+                '='
+            });
+        }
+    }
+    encoded
+}
+
+// through the terminal with OSC 52, so it works over ssh too, terminals that don't
+// allow it just ignore the sequence
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{0}\x07", base64(text.as_bytes()))?;
+    stdout.flush()
+}
+
+// for a big move when [movers] asks for it, terminals that are set to flash instead do that
+pub fn ring_bell() -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x07")?;
+    stdout.flush()
+}
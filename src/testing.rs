@@ -0,0 +1,39 @@
+// a made-up market for the tests and benches, in memory without saves or config
+use crate::app::{AppState, UIState};
+use crate::config::Config;
+use crate::exchanges::{Currency, Exchange, SymbolPosition};
+use crate::market::{gen_equities, Listing};
+use crate::portfolio::{Account, Portfolio};
+
+// the companies on one exchange and one account with some cash, after the given ticks
+pub fn app_state(companies: &[Listing], ticks: usize) -> AppState<'_> {
+    let mut rng = rand::rng();
+    let quotes = gen_equities(&mut rng, companies);
+    let exchanges = vec![Exchange::new(
+        "CGX",
+        "Cogmark Exchange",
+        "Cogmarks",
+        Currency::new("₡", SymbolPosition::Suffix, true),
+        None,
+    )];
+    let accounts = vec![Account {
+        name: "Main".to_string(),
+        portfolio: Portfolio::new(100_000.0),
+    }];
+    let mut app_state = AppState::new(
+        &mut rng,
+        quotes,
+        exchanges,
+        accounts,
+        &Config::default(),
+        None,
+    );
+    for _ in 0..ticks {
+        app_state.tick(&mut rng);
+    }
+    app_state
+}
+
+pub fn ui_state() -> UIState {
+    UIState::new(&Config::default())
+}
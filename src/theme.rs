@@ -0,0 +1,104 @@
+use crate::config::{Palette, ThemeConfig};
+use crate::forms::{Field, Form};
+use ratatui::style::{Color, Modifier, Style};
+
+// in the order of Palette::ALL
+const PALETTE_NAMES: &[&str] = &["Standard", "High contrast", "Colorblind"];
+
+// how the market table is dressed up and which colors tell gains from losses
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub palette: Palette,
+    pub stripe: Option<Color>,    // background of every other row
+    pub separator: Option<Color>, // of the lines between rows
+    pub cell_padding: u16,        // blank columns between cells
+}
+
+impl Theme {
+    pub fn new(config: &ThemeConfig) -> Theme {
+        Theme {
+            palette: config.palette,
+            stripe: config.zebra_stripes.then_some(Color::Indexed(235)),
+            separator: config.row_separators.then_some(Color::Indexed(238)),
+            cell_padding: config.cell_padding,
+        }
+    }
+
+    // the settings screen, filled in with what's in use now
+    pub fn form(&self) -> Form {
+        let palette = Palette::ALL
+            .iter()
+            .position(|palette| *palette == self.palette)
+            .unwrap();
+        Form::new(vec![
+            Field::select("Palette", PALETTE_NAMES, palette),
+            Field::checkbox("Zebra stripes", self.stripe.is_some()),
+            Field::checkbox("Row separators", self.separator.is_some()),
+            Field::number("Cell padding", false)
+                .within(0.0, 4.0)
+                .spinner(1.0, 1.0)
+                .filled(&self.cell_padding.to_string()),
+        ])
+    }
+
+    // from a settings screen that passed validation
+    pub fn from_form(form: &Form) -> Theme {
+        Theme::new(&ThemeConfig {
+            palette: Palette::ALL[form.choice(0)],
+            zebra_stripes: form.checked(1),
+            row_separators: form.checked(2),
+            cell_padding: form.number(3).unwrap_or(0.0) as u16,
+        })
+    }
+
+    pub fn next_palette(&mut self) {
+        let pos = Palette::ALL
+            .iter()
+            .position(|palette| *palette == self.palette)
+            .unwrap();
+        self.palette = Palette::ALL[(pos + 1) % Palette::ALL.len()];
+    }
+
+    pub fn gain(&self) -> Color {
+        match self.palette {
+            Palette::Standard => Color::Green,
+            Palette::HighContrast => Color::LightGreen,
+            // blue and orange from the Okabe-Ito set, apart for red-green colorblindness
+            Palette::Colorblind => Color::Rgb(86, 180, 233),
+        }
+    }
+
+    pub fn loss(&self) -> Color {
+        match self.palette {
+            Palette::Standard => Color::Red,
+            Palette::HighContrast => Color::LightRed,
+            Palette::Colorblind => Color::Rgb(230, 159, 0),
+        }
+    }
+
+    // for a change of that sign, zero counts as a gain
+    pub fn change_color(&self, change: f64) -> Color {
+        if change >= 0.0 {
+            self.gain()
+        } else {
+            self.loss()
+        }
+    }
+
+    pub fn change_style(&self, change: f64) -> Style {
+        let style = Style::from(self.change_color(change));
+        match self.palette {
+            Palette::HighContrast => style.add_modifier(Modifier::BOLD),
+            _ => style,
+        }
+    }
+
+    // the palettes made to be told apart without color put an arrow in front of changes too
+    pub fn change_arrow(&self, change: f64) -> &'static str {
+        match self.palette {
+            Palette::Standard => "",
+            _ if change >= 0.0 => "▲",
+            _ => "▼",
+        }
+    }
+}
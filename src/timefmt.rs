@@ -0,0 +1,86 @@
+use crate::config::{ClockFormat, TimeConfig};
+use crate::locale::{tr, trf};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+// everything that shows a point in time goes through here so the timezone and
+// clock settings apply everywhere
+#[derive(Debug, Clone, Copy)]
+pub struct TimeFormat {
+    timezone: Option<Tz>, // system local time when not set
+    clock: ClockFormat,
+}
+
+impl TimeFormat {
+    pub fn new(config: &TimeConfig) -> TimeFormat {
+        TimeFormat {
+            timezone: config.timezone,
+            clock: config.clock,
+        }
+    }
+
+    fn format_in_zone(&self, time: DateTime<Utc>, fmt: &str) -> String {
+        match self.timezone {
+            Some(tz) => tz
+                .from_utc_datetime(&time.naive_utc())
+                .format(fmt)
+                .to_string(),
+            None => time.with_timezone(&Local).format(fmt).to_string(),
+        }
+    }
+
+    // "just now", "2m ago", "1h ago", "3d ago"
+    pub fn relative(&self, time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+        let elapsed = now.signed_duration_since(time);
+        if elapsed.num_minutes() < 1 {
+            tr("just now").to_string()
+        } else if elapsed.num_hours() < 1 {
+            trf("{0}m ago", &[&elapsed.num_minutes()])
+        } else if elapsed.num_days() < 1 {
+            trf("{0}h ago", &[&elapsed.num_hours()])
+        } else {
+            trf("{0}d ago", &[&elapsed.num_days()])
+        }
+    }
+
+    // time of day, "14:05" or " 2:05 PM"
+    pub fn time(&self, time: DateTime<Utc>) -> String {
+        match self.clock {
+            ClockFormat::TwentyFourHour => self.format_in_zone(time, "%H:%M"),
+            ClockFormat::TwelveHour => self.format_in_zone(time, "%l:%M %p"),
+        }
+    }
+
+    // with seconds, "14:05:09" or " 2:05:09 PM"
+    pub fn precise_time(&self, time: DateTime<Utc>) -> String {
+        match self.clock {
+            ClockFormat::TwentyFourHour => self.format_in_zone(time, "%H:%M:%S"),
+            ClockFormat::TwelveHour => self.format_in_zone(time, "%l:%M:%S %p"),
+        }
+    }
+
+    pub fn date(&self, time: DateTime<Utc>) -> String {
+        self.format_in_zone(time, "%A, %-d %B %Y")
+    }
+
+    // "14 Oct 2026", for lists with a date on every row
+    pub fn short_date(&self, time: DateTime<Utc>) -> String {
+        self.format_in_zone(time, "%-d %b %Y")
+    }
+
+    // calendar day in the configured timezone, for grouping by date
+    pub fn day(&self, time: DateTime<Utc>) -> NaiveDate {
+        match self.timezone {
+            Some(tz) => tz.from_utc_datetime(&time.naive_utc()).date_naive(),
+            None => time.with_timezone(&Local).date_naive(),
+        }
+    }
+
+    // wall clock time in the configured timezone, for market session hours
+    pub fn time_of_day(&self, time: DateTime<Utc>) -> NaiveTime {
+        match self.timezone {
+            Some(tz) => tz.from_utc_datetime(&time.naive_utc()).time(),
+            None => time.with_timezone(&Local).time(),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::app::{AppState, MarketDataActivePanel, UIState};
+
+// each step is done once the state shows the user did it, whichever keys got them there
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TutorialStep {
+    SwitchPanels,
+    SelectRow,
+    OpenDetail,
+    PlaceOrder,
+    Done,
+}
+
+impl TutorialStep {
+    pub const COUNT: usize = 4; // steps that ask for something
+
+    pub fn number(&self) -> usize {
+        match self {
+            TutorialStep::SwitchPanels => 1,
+            TutorialStep::SelectRow => 2,
+            TutorialStep::OpenDetail => 3,
+            TutorialStep::PlaceOrder => 4,
+            TutorialStep::Done => TutorialStep::COUNT,
+        }
+    }
+
+    pub fn instructions(&self) -> &'static str {
+        match self {
+            TutorialStep::SwitchPanels => {
+                "Panels are focused with ← and →, the focused one has a cyan border. Move the focus to another panel."
+            }
+            TutorialStep::SelectRow => {
+                "Focus the market table again and press ↓. The highlighted top row is the selected instrument."
+            }
+            TutorialStep::OpenDetail => {
+                "Press Enter to open the detail view of the selected instrument."
+            }
+            TutorialStep::PlaceOrder => {
+                "Press b to buy, type a quantity and press Enter to send a market order."
+            }
+            TutorialStep::Done => "That's the basics. Press t to close the tutorial.",
+        }
+    }
+
+    fn next(&self) -> TutorialStep {
+        match self {
+            TutorialStep::SwitchPanels => TutorialStep::SelectRow,
+            TutorialStep::SelectRow => TutorialStep::OpenDetail,
+            TutorialStep::OpenDetail => TutorialStep::PlaceOrder,
+            TutorialStep::PlaceOrder | TutorialStep::Done => TutorialStep::Done,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Tutorial {
+    pub step: TutorialStep,
+    first_order_id: u64, // orders from before the tutorial don't count
+}
+
+impl Tutorial {
+    pub fn new(app_state: &AppState) -> Tutorial {
+        Tutorial {
+            step: TutorialStep::SwitchPanels,
+            first_order_id: app_state.next_order_id,
+        }
+    }
+
+    fn step_done(&self, app_state: &AppState, uistate: &UIState) -> bool {
+        match self.step {
+            TutorialStep::SwitchPanels => {
+                uistate.market_data_active_panel != MarketDataActivePanel::MarketData
+            }
+            TutorialStep::SelectRow => {
+                uistate.market_data_active_panel == MarketDataActivePanel::MarketData
+                    && uistate.market_table.scroll.pos > 0
+            }
+            TutorialStep::OpenDetail => uistate.instrument_detail.is_some(),
+            TutorialStep::PlaceOrder => app_state.next_order_id > self.first_order_id,
+            TutorialStep::Done => false,
+        }
+    }
+
+    // the tutorial after whatever the last key did
+    pub fn advanced(self, app_state: &AppState, uistate: &UIState) -> Tutorial {
+        if self.step_done(app_state, uistate) {
+            Tutorial {
+                step: self.step.next(),
+                ..self
+            }
+        } else {
+            self
+        }
+    }
+}
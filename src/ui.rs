@@ -0,0 +1,4667 @@
+use crate::achievements::Achievement;
+use crate::alerts::{Alert, AlertForm, Mode};
+use crate::analysts::{consensus, current_ratings, Rating};
+use crate::app::{
+    AccountsView, AppState, ChartView, Confirm, DetailView, LedgerView, MarketDataActivePanel,
+    OptionsView, OrderForm, ReportView, RowMenu, SavesMenu, UIState, NEWS_ARCHIVE_PAGE_SIZE,
+};
+use crate::components::{
+    Component, Context, MarketTable, NewsList, OrdersTable, PortfolioPanel, ScrollView, StatusBar,
+};
+use crate::config::{ChartStyle, ColumnAlign, ColumnConfig};
+use crate::connection::{Connection, DataSource, LinkState};
+use crate::exchanges::{Currency, Exchange};
+use crate::formatting::{rule_style, Accent};
+use crate::forms::Form;
+use crate::fx::{FxView, FX_SPREAD_PCT};
+use crate::graphics::ChartImage;
+use crate::history::{Candle, Timeframe, CANDLE_TICKS};
+use crate::keymap::{self, Action, Keymap};
+use crate::locale::{tr, trf};
+use crate::market::{ChangeHorizon, Instrument, InstrumentClass, PricingModel};
+use crate::news::{NewsCategory, NewsItem};
+use crate::numbers::{abbreviated, decimal, large};
+use crate::options::{OptionContract, OptionKind};
+use crate::orders::{BlotterEntry, Fill as OrderFill, OrderStatus, Side};
+use crate::overlay::Overlay;
+use crate::panels::Panel;
+use crate::portfolio::Portfolio;
+use crate::rebalance::{plan, RebalanceView};
+use crate::report::sparkline;
+use crate::schedules::ScheduleForm;
+use crate::screener::Screen;
+use crate::terminal::{utf8_locale, Hyperlink};
+use crate::theme::Theme;
+use crate::timefmt::TimeFormat;
+use crate::tutorial::{Tutorial, TutorialStep};
+use crate::worker::Distribution;
+use chrono::{DateTime, TimeDelta, Utc};
+use ratatui::layout::{Alignment, Constraint};
+use ratatui::layout::{Layout, Margin, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::symbols::Marker;
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{
+    Axis, Bar, BarChart, BarGroup, Block, Borders, Cell, Chart, Clear, Dataset, GraphType,
+    LegendPosition, Paragraph, Row, Table, Wrap,
+};
+use ratatui::Frame;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::env;
+use textwrap::Options;
+
+fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let [_, vertical, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(height),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+    let [_, rect, _] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(width),
+        Constraint::Fill(1),
+    ])
+    .areas(vertical);
+    rect
+}
+
+fn draw_news_filter_menu(frame: &mut Frame, area: Rect, uistate: &UIState, cursor: usize) {
+    let lines = NewsCategory::ALL
+        .iter()
+        .enumerate()
+        .map(|(idx, category)| {
+            let checkbox = if uistate.news_list.hidden_categories.contains(category) {
+                "[ ] "
+            } else {
+                "[x] "
+            };
+            let line = Line::from(vec![
+                checkbox.into(),
+                tr(category.label()).fg(category.color()).bold(),
+            ]);
+            if idx == cursor {
+                line.bg(Color::DarkGray)
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<Line>>();
+
+    let menu_area = centered_rect(area, 24, lines.len() as u16 + 2);
+    frame.render_widget(Clear, menu_area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::bordered()
+                .title(tr("Show categories"))
+                .border_style(Style::default().fg(Color::Cyan)),
+        ),
+        menu_area,
+    );
+}
+
+fn draw_row_menu(frame: &mut Frame, area: Rect, menu: &RowMenu) {
+    let lines = menu
+        .actions
+        .iter()
+        .enumerate()
+        .map(|(idx, action)| {
+            let line = Line::from(tr(action.label()));
+            if idx == menu.cursor {
+                line.bg(Color::DarkGray)
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<Line>>();
+
+    let menu_area = centered_rect(area, 24, lines.len() as u16 + 2);
+    frame.render_widget(Clear, menu_area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::bordered()
+                .title(menu.ticker.as_str())
+                .border_style(Style::default().fg(Color::Cyan)),
+        ),
+        menu_area,
+    );
+}
+
+fn sentiment_marker(sentiment: f64, theme: &Theme) -> Span<'static> {
+    if sentiment == 0.0 {
+        return "● 0.0".fg(Color::Gray);
+    }
+    let text = format!(
+        "{0}{1:+.1}",
+        if sentiment >= 0.0 { "▲" } else { "▼" },
+        sentiment
+    );
+    let text = text.fg(theme.change_color(sentiment));
+    if sentiment.abs() >= 0.5 {
+        text.bold()
+    } else {
+        text
+    }
+}
+
+// sentiment, category, feed and ticker tags shown with every news item
+fn news_tags<'a>(
+    news_item: &'a NewsItem,
+    theme: &Theme,
+    accents: &HashMap<String, Accent>,
+) -> Vec<Span<'a>> {
+    let category = news_item.category;
+    [
+        sentiment_marker(news_item.sentiment, theme),
+        " ".into(),
+        format!(" {0} ", tr(category.label()))
+            .fg(Color::Black)
+            .bg(category.color()),
+        " ".into(),
+    ]
+    .into_iter()
+    .chain(news_item.source.iter().flat_map(|source| {
+        [
+            format!(" {0} ", source)
+                .fg(Color::White)
+                .bg(Color::DarkGray),
+            " ".into(),
+        ]
+    }))
+    // a ticker's accent color is the background of its tag
+    .chain(news_item.tickers.iter().flat_map(|ticker| {
+        let accent = accents.get(ticker).cloned().unwrap_or_default();
+        let label = accent.span(ticker).content;
+        [
+            format!(" {0} ", label)
+                .fg(Color::Black)
+                .bg(accent.color.unwrap_or(Color::Yellow)),
+            " ".into(),
+        ]
+    }))
+    .collect()
+}
+
+fn search_line(news_list: &NewsList) -> Line<'_> {
+    let query = news_list.search.clone().unwrap_or_default();
+    let mut spans = vec!["/".fg(Color::Cyan).bold()];
+    spans.extend(query.spans(Style::default(), news_list.search_editing));
+    Line::from(spans)
+}
+
+// the screen query under the market table, with the reason when it doesn't parse
+fn screen_line(market_table: &MarketTable) -> Line<'_> {
+    let query = market_table.screen.clone().unwrap_or_default();
+    let mut spans = vec!["/".fg(Color::Cyan).bold()];
+    spans.extend(query.spans(Style::default(), market_table.screen_editing));
+    if !query.text.is_empty() {
+        if let Err(err) = Screen::parse(&query.text) {
+            spans.push(format!("  {0}", err).fg(Color::Red));
+        }
+    }
+    // the rows are the last screen's until the worker is done with this one
+    if market_table.is_screening() {
+        spans.push(format!("  {0}", tr("Computing…")).fg(Color::Gray));
+    }
+    Line::from(spans)
+}
+
+fn draw_news_archive(
+    frame: &mut Frame,
+    area: Rect,
+    app_state: &AppState,
+    uistate: &UIState,
+    page: usize,
+) {
+    let indices = uistate.news_list.indices(app_state);
+    let page_count = uistate.news_archive_page_count(app_state);
+    let block = Block::bordered()
+        .title(trf(
+            "News archive · page {0}/{1} · {2} items",
+            &[&(page + 1), &page_count, &indices.len()],
+        ))
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner_area = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let [search_area, list_area] =
+        Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner_area);
+
+    // items come newest first, start a new group whenever the day changes
+    let mut lines = vec![];
+    let mut current_date = None;
+    for idx in indices
+        .into_iter()
+        .skip(page * NEWS_ARCHIVE_PAGE_SIZE)
+        .take(NEWS_ARCHIVE_PAGE_SIZE)
+    {
+        let news_item = &app_state.news[idx];
+        let time_format = &app_state.time_format;
+        let day = time_format.day(news_item.published);
+        if current_date != Some(day) {
+            if current_date.is_some() {
+                lines.push(Line::from(""));
+            }
+            current_date = Some(day);
+            lines.push(Line::styled(
+                time_format.date(news_item.published),
+                Style::default().fg(Color::Gray).italic(),
+            ));
+        }
+        let mut spans = vec![
+            time_format.time(news_item.published).fg(Color::Gray),
+            "  ".into(),
+        ];
+        spans.extend(news_tags(news_item, &uistate.theme, &uistate.accents));
+        spans.push(news_item.title.as_str().fg(Color::White));
+        lines.push(Line::from(spans));
+    }
+    if lines.is_empty() {
+        lines.push(Line::styled(
+            tr("No news matches"),
+            Style::default().fg(Color::Gray),
+        ));
+    }
+
+    if uistate.news_list.search.is_some() || uistate.news_list.search_editing {
+        frame.render_widget(search_line(&uistate.news_list), search_area);
+    }
+    frame.render_widget(Paragraph::new(lines), list_area);
+}
+
+// "Iron Ledger · 104,320 ₡ (+4.3%) · AETH +7.1%", the change is since the session started
+pub fn window_title(app_state: &AppState) -> String {
+    let value = app_state.portfolio_value();
+    let start = app_state.equity_curve.first().copied().unwrap_or(value);
+    let mut title = format!(
+        "Iron Ledger · {0} ({1:+.1}%)",
+        app_state.currency.format(value, 0),
+        (value - start) / start * 100.0
+    );
+    // cross-listings move with their primary listing
+    let top_mover = app_state
+        .quotes
+        .iter()
+        .filter(|quote| quote.primary.is_none())
+        .map(|quote| {
+            let change = (quote.quote.price - quote.quote.price_yesterday)
+                / quote.quote.price_yesterday
+                * 100.0;
+            (quote.listing.ticker.as_str(), change)
+        })
+        .max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()));
+    if let Some((ticker, change)) = top_mover {
+        title.push_str(&format!(" · {0} {1:+.1}%", ticker, change));
+    }
+    title
+}
+
+fn money(value: f64, currency: &Currency) -> String {
+    currency.format(value, 2)
+}
+
+// the linux console and non-UTF-8 locales can't show braille, half blocks work everywhere
+pub fn chart_marker(style: ChartStyle) -> Marker {
+    match style {
+        ChartStyle::Braille => Marker::Braille,
+        ChartStyle::Block => Marker::HalfBlock,
+        ChartStyle::Auto => {
+            let console = env::var("TERM").is_ok_and(|term| term == "linux");
+            if console || !utf8_locale() {
+                Marker::HalfBlock
+            } else {
+                Marker::Braille
+            }
+        }
+    }
+}
+
+pub fn draw_orders(frame: &mut Frame, area: Rect, orders_table: &OrdersTable, ctx: &Context) {
+    use Constraint::{Fill, Length};
+
+    let app_state = ctx.app_state;
+    let open_count = app_state
+        .orders
+        .iter()
+        .filter(|order| order.is_open())
+        .count();
+    let mut title = trf("Orders · {0} working", &[&open_count]);
+    let last_fill = app_state
+        .blotter
+        .iter()
+        .rev()
+        .find_map(|entry| match entry {
+            BlotterEntry::Fill(fill) => Some(fill),
+            _ => None,
+        });
+    if let Some(fill) = last_fill {
+        title.push_str(&trf(
+            " · last fill #{0} {1} {2} {3} @ {4}, {5}",
+            &[
+                &fill.order_id,
+                &tr(fill.side.label()),
+                &fill.quantity,
+                &fill.ticker,
+                &format!(
+                    "{0:.1$}",
+                    fill.price,
+                    app_state.price_decimals(&fill.ticker)
+                ),
+                &app_state.time_format.relative(fill.time, Utc::now()),
+            ],
+        ));
+    }
+    if orders_table.show_blotter {
+        title.push_str(tr(" · history"));
+    }
+    let block = Block::bordered()
+        .title(title)
+        .border_style(focus_style(ctx, MarketDataActivePanel::Orders));
+    let active = ctx.focus == MarketDataActivePanel::Orders;
+
+    if orders_table.show_blotter {
+        draw_blotter(frame, block.inner(area), app_state, orders_table, active);
+        frame.render_widget(block, area);
+        return;
+    }
+
+    let rows = app_state
+        .orders
+        .iter()
+        .enumerate()
+        .skip(orders_table.scroll.pos)
+        .map(|(idx, order)| {
+            let decimals = app_state.price_decimals(&order.ticker);
+            let (mut status, status_color) = match &order.status {
+                OrderStatus::Working if order.stop_price.is_some() && !order.stop_triggered => {
+                    (tr("Stop armed").to_string(), Color::Blue)
+                }
+                OrderStatus::Working if order.filled > 0 => {
+                    (tr("Partial").to_string(), Color::Yellow)
+                }
+                OrderStatus::Working => (tr("Working").to_string(), Color::Cyan),
+                OrderStatus::Filled => (tr("Filled").to_string(), Color::Green),
+                OrderStatus::Cancelled(reason) => (trf("Cancelled: {0}", &[reason]), Color::Gray),
+            };
+            if order.is_open() {
+                if let Some(sibling) = order.oco_with {
+                    status.push_str(&format!(" · OCO #{0}", sibling));
+                } else if order.bracket.is_some() {
+                    status.push_str(tr(" · bracket"));
+                }
+            }
+            Row::new(vec![
+                Cell::from(app_state.time_format.time(order.created)),
+                Cell::from(tr(order.side.label())).style(match order.side {
+                    Side::Buy => Color::Green,
+                    Side::Sell => Color::Red,
+                }),
+                Cell::from(order.ticker.as_str()),
+                Cell::from(format!(
+                    "{0:>11}",
+                    format!("{0}/{1}", order.filled, order.quantity)
+                )),
+                Cell::from(format!("{0:>6}", order.remaining())),
+                Cell::from(match (order.stop_price, order.limit_price) {
+                    (Some(stop), _) => {
+                        format!(
+                            "{0:>12}",
+                            trf("stop {0}", &[&format!("{0:.1$}", stop, decimals)])
+                        )
+                    }
+                    (None, Some(price)) => format!("{0:>12.1$}", price, decimals),
+                    (None, None) => format!("{0:>12}", tr("market")),
+                }),
+                Cell::from(match order.avg_fill_price() {
+                    Some(price) => format!("{0:>9.1$}", price, decimals),
+                    None => format!("{0:>9}", "-"),
+                }),
+                Cell::from(status).style(status_color),
+            ])
+            .style(if active && idx == orders_table.scroll.pos {
+                Style::default().fg(Color::White).bg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::White)
+            })
+        });
+    let table = Table::new(
+        rows,
+        [
+            Length(8),
+            Length(4),
+            Length(6),
+            Length(11),
+            Length(6),
+            Length(12),
+            Length(9),
+            Fill(1),
+        ],
+    )
+    .column_spacing(1)
+    .header(
+        Row::new(vec![
+            tr("Time").to_string(),
+            tr("Side").to_string(),
+            tr("Ticker").to_string(),
+            format!("{0:>11}", tr("Filled/Qty")),
+            format!("{0:>6}", tr("Left")),
+            format!("{0:>12}", tr("Limit/Stop")),
+            format!("{0:>9}", tr("Avg fill")),
+            tr("Status").to_string(),
+        ])
+        .style(Style::new().fg(Color::Gray).italic()),
+    );
+
+    let inner_area = block.inner(area);
+    frame.render_widget(block, area);
+    if app_state.orders.is_empty() {
+        frame.render_widget(
+            Line::styled(
+                tr("No orders yet, press b or s on a market row"),
+                (Color::Gray, Modifier::ITALIC),
+            ),
+            inner_area,
+        );
+    } else {
+        frame.render_widget(table, inner_area);
+    }
+}
+
+fn limit_label(limit_price: Option<f64>, decimals: usize) -> String {
+    limit_price.map_or(tr("market").to_string(), |price| {
+        format!("{0:.1$}", price, decimals)
+    })
+}
+
+// blotter is kept oldest first, show the latest entries on top
+fn draw_blotter(
+    frame: &mut Frame,
+    area: Rect,
+    app_state: &AppState,
+    orders_table: &OrdersTable,
+    active: bool,
+) {
+    let lines = app_state
+        .blotter
+        .iter()
+        .rev()
+        .enumerate()
+        .skip(orders_table.scroll.pos)
+        .map(|(idx, entry)| {
+            let (kind, kind_color, details) = match entry {
+                BlotterEntry::Fill(fill) => (
+                    tr("Fill"),
+                    Color::Green,
+                    if fill.commission > 0.0 {
+                        trf(
+                            "{0} {1} {2} @ {3}, fee {4}",
+                            &[
+                                &tr(fill.side.label()),
+                                &fill.quantity,
+                                &fill.ticker,
+                                &format!(
+                                    "{0:.1$}",
+                                    fill.price,
+                                    app_state.price_decimals(&fill.ticker)
+                                ),
+                                &money(fill.commission, &app_state.currency),
+                            ],
+                        )
+                    } else {
+                        format!(
+                            "{0} {1} {2} @ {3:.4$}",
+                            tr(fill.side.label()),
+                            fill.quantity,
+                            fill.ticker,
+                            fill.price,
+                            app_state.price_decimals(&fill.ticker)
+                        )
+                    },
+                ),
+                BlotterEntry::Amendment(amendment) => {
+                    let decimals = app_state
+                        .orders
+                        .iter()
+                        .find(|order| order.id == amendment.order_id)
+                        .map_or(2, |order| app_state.price_decimals(&order.ticker));
+                    (
+                        tr("Amend"),
+                        Color::Yellow,
+                        trf(
+                            "qty {0} → {1}, limit {2} → {3}",
+                            &[
+                                &amendment.old_quantity,
+                                &amendment.new_quantity,
+                                &limit_label(amendment.old_limit_price, decimals),
+                                &limit_label(amendment.new_limit_price, decimals),
+                            ],
+                        ),
+                    )
+                }
+                BlotterEntry::Cancellation { reason, .. } => {
+                    (tr("Cancel"), Color::Gray, trf("cancelled {0}", &[reason]))
+                }
+            };
+            Line::from(vec![
+                format!("{0:<9}", app_state.time_format.time(entry.time())).fg(Color::Gray),
+                format!("{0:<7}", kind).fg(kind_color),
+                format!("#{0:<5}", entry.order_id()).fg(Color::White),
+                details.fg(Color::White),
+            ])
+            .style(if active && idx == orders_table.scroll.pos {
+                Style::default().bg(Color::DarkGray)
+            } else {
+                Style::default()
+            })
+        })
+        .collect::<Vec<Line>>();
+    if lines.is_empty() {
+        frame.render_widget(
+            Line::styled(
+                tr("Nothing has happened yet"),
+                (Color::Gray, Modifier::ITALIC),
+            ),
+            area,
+        );
+    } else {
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+}
+
+// the title, the question and the hint for y, nothing when what it was about is gone
+fn confirm_text(
+    app_state: &AppState,
+    confirm: &Confirm,
+) -> Option<(&'static str, String, &'static str)> {
+    Some(match confirm {
+        Confirm::CancelOrder(order_id) => {
+            let order = app_state
+                .orders
+                .iter()
+                .find(|order| order.id == *order_id)?;
+            (
+                "Cancel order",
+                trf(
+                    "Cancel #{0} {1} {2} {3} @ {4}?",
+                    &[
+                        &order.id,
+                        &tr(order.side.label()),
+                        &order.remaining(),
+                        &order.ticker,
+                        &limit_label(order.limit_price, app_state.price_decimals(&order.ticker)),
+                    ],
+                ),
+                "y - Cancel Order",
+            )
+        }
+        Confirm::Liquidate(ticker) => (
+            "Sell whole position",
+            trf(
+                "Sell all {0} {1} at market?",
+                &[&app_state.portfolio().quantity(ticker), ticker],
+            ),
+            "y - Sell All",
+        ),
+        Confirm::ClearBlotter => (
+            "Clear history",
+            trf(
+                "Clear all {0} entries of the history? Fills can't be undone after.",
+                &[&app_state.blotter.len()],
+            ),
+            "y - Clear History",
+        ),
+        Confirm::NewGame => (
+            "New game",
+            tr("Start over with fresh cash and the starting prices? Anything not saved is lost.")
+                .to_string(),
+            "y - Start Over",
+        ),
+    })
+}
+
+fn draw_confirm(frame: &mut Frame, area: Rect, app_state: &AppState, confirm: &Confirm) {
+    let Some((title, question, yes)) = confirm_text(app_state, confirm) else {
+        return;
+    };
+    let lines = vec![
+        Line::from(question),
+        Line::from(""),
+        Line::styled(
+            format!("{0}   {1}", tr(yes), tr("n/Esc - Keep")),
+            Style::default().fg(Color::Gray),
+        ),
+    ];
+    let confirm_area = centered_rect(area, 44, 7);
+    frame.render_widget(Clear, confirm_area);
+    frame.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+            Block::bordered()
+                .title(tr(title))
+                .border_style(Style::default().fg(Color::Red)),
+        ),
+        confirm_area,
+    );
+}
+
+// bottom right corner, counting down while the latest fill can still be undone
+fn undo_toast_text(app_state: &AppState) -> Option<String> {
+    let (fill, left) = app_state.undoable_fill()?;
+    Some(trf(
+        "{0} {1} {2} @ {3} · u to undo ({4}s)",
+        &[
+            &tr(match fill.side {
+                Side::Buy => "Bought",
+                Side::Sell => "Sold",
+            }),
+            &fill.quantity,
+            &fill.ticker,
+            &format!(
+                "{0:.1$}",
+                fill.price,
+                app_state.price_decimals(&fill.ticker)
+            ),
+            // round up so the countdown never shows 0s while undo still works
+            &((left.num_milliseconds() + 999) / 1000),
+        ],
+    ))
+}
+
+fn draw_undo_toast(frame: &mut Frame, area: Rect, app_state: &AppState) {
+    let Some(text) = undo_toast_text(app_state) else {
+        return;
+    };
+    let text = format!(" {0} ", text);
+    let width = (text.chars().count() as u16 + 2).min(area.width);
+    let [_, toast_area] = Layout::vertical([Constraint::Fill(1), Constraint::Length(3)])
+        .areas(area.inner(Margin::new(1, 1)));
+    let [_, toast_area] =
+        Layout::horizontal([Constraint::Fill(1), Constraint::Length(width)]).areas(toast_area);
+    frame.render_widget(Clear, toast_area);
+    frame.render_widget(
+        Paragraph::new(text.fg(Color::White))
+            .block(Block::bordered().border_style(Style::default().fg(Color::Yellow))),
+        toast_area,
+    );
+}
+
+// how long an unlocked achievement is announced for
+const ACHIEVEMENT_TOAST_SECS: i64 = 5;
+
+// announces fresh unlocks in the top right corner
+fn achievement_toast_text(app_state: &AppState) -> Option<String> {
+    let unlocked =
+        app_state.achievements.unlocked.last().filter(|unlocked| {
+            Utc::now() - unlocked.at < TimeDelta::seconds(ACHIEVEMENT_TOAST_SECS)
+        })?;
+    Some(format!(
+        "{0}: {1} · {2}",
+        tr("Achievement unlocked"),
+        unlocked.achievement.title(),
+        tr("g to view")
+    ))
+}
+
+fn draw_achievement_toast(frame: &mut Frame, area: Rect, app_state: &AppState) {
+    let Some(text) = achievement_toast_text(app_state) else {
+        return;
+    };
+    let text = format!(" {0} ", text);
+    let width = (text.chars().count() as u16 + 2).min(area.width);
+    let [toast_area, _] = Layout::vertical([Constraint::Length(3), Constraint::Fill(1)])
+        .areas(area.inner(Margin::new(1, 1)));
+    let [_, toast_area] =
+        Layout::horizontal([Constraint::Fill(1), Constraint::Length(width)]).areas(toast_area);
+    frame.render_widget(Clear, toast_area);
+    frame.render_widget(
+        Paragraph::new(text.fg(Color::White).bold())
+            .block(Block::bordered().border_style(Style::default().fg(Color::Green))),
+        toast_area,
+    );
+}
+
+fn draw_saves_menu(frame: &mut Frame, area: Rect, app_state: &AppState, menu: &SavesMenu) {
+    let currency = &app_state.currency;
+    let new_save = match &menu.naming {
+        Some(name) => {
+            let mut spans = vec![tr("Name: ").fg(Color::Gray)];
+            spans.extend(name.spans(Style::default().fg(Color::White).bold(), true));
+            Line::from(spans)
+        }
+        None => Line::from(tr("+ New save").fg(Color::Green).bold()),
+    };
+    let mut lines = vec![new_save];
+    lines.extend(menu.slots.iter().map(|slot| {
+        Line::from(vec![
+            format!("{0:<20} ", slot.name).fg(Color::White).bold(),
+            format!("{0:>14} ", money(slot.portfolio_value, currency)).fg(Color::White),
+            format!("{0:<7} ", tr(slot.difficulty.label())).fg(Color::Gray),
+            format!(
+                "{0} {1}",
+                app_state.time_format.short_date(slot.saved_at),
+                app_state.time_format.time(slot.saved_at)
+            )
+            .fg(Color::Gray),
+        ])
+    }));
+    let mut lines = lines
+        .into_iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            if idx == menu.cursor {
+                line.bg(Color::DarkGray)
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<Line>>();
+    if menu.slots.is_empty() {
+        lines.push(Line::from(tr("No saved games yet").fg(Color::DarkGray)));
+    }
+    if let Some(err) = &menu.error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(err.as_str().fg(Color::Red)));
+    }
+    let width = area.width.saturating_sub(4).min(72);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let menu_area = centered_rect(area, width, height);
+    frame.render_widget(Clear, menu_area);
+    frame.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+            Block::bordered()
+                .title(tr("Saved games"))
+                .border_style(Style::default().fg(Color::Cyan)),
+        ),
+        menu_area,
+    );
+}
+
+// the rows with the best and the worst results, without repeating any
+const REPORT_POSITIONS_EACH_WAY: usize = 3;
+
+fn draw_report(frame: &mut Frame, area: Rect, view: &ReportView, uistate: &UIState) {
+    use Constraint::{Fill, Length};
+
+    let report = &view.report;
+    let block = Block::bordered()
+        .title(trf("Session report · {0}", &[&report.period()]))
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner_area = block.inner(area).inner(Margin::new(1, 0));
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let summary = report.summary();
+    let positions = &report.positions;
+    let shown_positions = if positions.len() > REPORT_POSITIONS_EACH_WAY * 2 {
+        REPORT_POSITIONS_EACH_WAY * 2 + 1
+    } else {
+        positions.len()
+    };
+    let [summary_area, chart_area, positions_area, message_area] = Layout::vertical([
+        Length(summary.len() as u16 + 1),
+        Fill(1),
+        Length(shown_positions as u16 + 2),
+        Length(1),
+    ])
+    .areas(inner_area);
+
+    let theme = &uistate.theme;
+    let pnl_color = theme.change_color(report.pnl());
+    frame.render_widget(
+        Paragraph::new(
+            summary
+                .into_iter()
+                .map(|(label, value)| {
+                    let value = if label == "P&L" {
+                        value.fg(pnl_color).bold()
+                    } else if label == "Relative" {
+                        value.fg(theme.change_color(report.relative.excess)).bold()
+                    } else {
+                        value.fg(Color::White).bold()
+                    };
+                    Line::from(vec![format!("{0:<16}", label).fg(Color::Gray), value])
+                })
+                .collect::<Vec<Line>>(),
+        ),
+        summary_area,
+    );
+
+    let points = |curve: &[f64]| {
+        curve
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| (idx as f64, *value))
+            .collect::<Vec<(f64, f64)>>()
+    };
+    let (equity, composite) = (points(&report.equity_curve), points(&report.composite));
+    let values = || {
+        equity
+            .iter()
+            .chain(composite.iter())
+            .map(|(_, value)| *value)
+    };
+    let min = values().fold(f64::MAX, f64::min);
+    let max = values().fold(f64::MIN, f64::max);
+    // a flat curve still needs some room to be drawn in
+    let pad = ((max - min) * 0.05).max(1.0);
+    frame.render_widget(
+        Chart::new(vec![
+            Dataset::default()
+                .name(tr("Composite index"))
+                .marker(uistate.chart_marker)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::DarkGray))
+                .data(&composite),
+            Dataset::default()
+                .name(tr("Portfolio"))
+                .marker(uistate.chart_marker)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(pnl_color))
+                .data(&equity),
+        ])
+        .block(Block::new().title(tr("Portfolio value").fg(Color::Gray)))
+        .x_axis(Axis::default().bounds([0.0, (equity.len().max(2) - 1) as f64]))
+        .y_axis(
+            Axis::default()
+                .bounds([min - pad, max + pad])
+                .labels([report.money(min), report.money(max)])
+                .style(Style::default().fg(Color::Gray)),
+        ),
+        chart_area,
+    );
+
+    let position_line = |result: &crate::report::PositionResult| {
+        Line::from(vec![
+            format!("{0:<16}", result.ticker).fg(Color::White),
+            report.money(result.pnl).fg(theme.change_color(result.pnl)),
+        ])
+    };
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(tr("Best and worst positions").fg(Color::Gray)),
+    ];
+    if positions.len() > REPORT_POSITIONS_EACH_WAY * 2 {
+        lines.extend(
+            positions[..REPORT_POSITIONS_EACH_WAY]
+                .iter()
+                .map(position_line),
+        );
+        lines.push(Line::from("…".fg(Color::DarkGray)));
+        lines.extend(
+            positions[positions.len() - REPORT_POSITIONS_EACH_WAY..]
+                .iter()
+                .map(position_line),
+        );
+    } else {
+        lines.extend(positions.iter().map(position_line));
+    }
+    if positions.is_empty() {
+        lines[1] = Line::from(tr("No positions this session").fg(Color::DarkGray));
+    }
+    frame.render_widget(Paragraph::new(lines), positions_area);
+
+    match &view.message {
+        Some(Ok(path)) => frame.render_widget(
+            Line::from(trf("Written to {0}", &[path]).fg(Color::Green)),
+            message_area,
+        ),
+        Some(Err(err)) => {
+            frame.render_widget(Line::from(err.as_str().fg(Color::Red)), message_area)
+        }
+        None => {}
+    }
+}
+
+fn draw_achievements(frame: &mut Frame, area: Rect, app_state: &AppState) {
+    let lines = Achievement::ALL
+        .iter()
+        .flat_map(|achievement| {
+            let unlocked = app_state
+                .achievements
+                .unlocked
+                .iter()
+                .find(|unlocked| unlocked.achievement == *achievement);
+            let heading = match unlocked {
+                Some(unlocked) => Line::from(vec![
+                    "✔ ".fg(Color::Green).bold(),
+                    achievement.title().fg(Color::White).bold(),
+                    format!("  {0}", app_state.time_format.date(unlocked.at)).fg(Color::Gray),
+                ]),
+                None => Line::from(vec![
+                    "· ".fg(Color::Gray),
+                    achievement.title().fg(Color::Gray),
+                    format!("  {0}", tr("locked")).fg(Color::DarkGray),
+                ]),
+            };
+            vec![
+                heading,
+                Line::from(format!("  {0}", achievement.description())).fg(Color::Gray),
+                Line::from(""),
+            ]
+        })
+        .collect::<Vec<Line>>();
+    let width = area.width.saturating_sub(4).min(72);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let [_, popup_area, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(height),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+    let [_, popup_area, _] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(width),
+        Constraint::Fill(1),
+    ])
+    .areas(popup_area);
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::bordered()
+                .title(trf(
+                    "Achievements · {0}/{1}",
+                    &[
+                        &app_state.achievements.unlocked.len(),
+                        &Achievement::ALL.len(),
+                    ],
+                ))
+                .border_style(Style::default().fg(Color::Cyan)),
+        ),
+        popup_area,
+    );
+}
+
+// the keymap as it is after the config, scrolled a line at a time
+fn draw_help(frame: &mut Frame, area: Rect, keymap: &Keymap, scroll: &ScrollView) {
+    let lines = help_lines(keymap);
+    let popup_area = centered_rect(
+        area,
+        area.width.saturating_sub(4).min(56),
+        (lines.len() as u16 + 2).min(area.height),
+    );
+    let text_area = popup_area.inner(Margin::new(1, 1));
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Block::bordered()
+            .title(tr("Keys"))
+            .border_style(Style::default().fg(Color::Cyan)),
+        popup_area,
+    );
+    let len = lines.len();
+    frame.render_widget(
+        Paragraph::new(lines.into_iter().skip(scroll.pos).collect::<Vec<Line>>()),
+        text_area,
+    );
+    scroll.render(
+        frame,
+        popup_area,
+        len,
+        text_area.height as usize,
+        Style::default().fg(Color::Cyan),
+    );
+}
+
+// "BCI above 1200.00 ₡", in the currency of the instrument's exchange
+fn alert_text(app_state: &AppState, alert: &Alert, price: f64) -> String {
+    let currency = app_state
+        .quote_index(&alert.ticker)
+        .map_or(&app_state.currency, |idx| {
+            &app_state.exchanges[app_state.quotes[idx].exchange].currency
+        });
+    format!(
+        "{0} {1} {2}",
+        alert.ticker,
+        tr(alert.direction.label()),
+        currency.format(price, app_state.price_decimals(&alert.ticker))
+    )
+}
+
+// the alerts waiting to go off, then every time one did, newest first
+fn draw_alerts(frame: &mut Frame, area: Rect, app_state: &AppState, cursor: usize) {
+    let alerts = &app_state.alerts;
+    let mut lines = vec![Line::from(tr("Set")).fg(Color::Gray).italic()];
+    if alerts.alerts.is_empty() {
+        lines.push(Line::from(tr("No alerts set, m on a market row sets one")).fg(Color::DarkGray));
+    }
+    lines.extend(alerts.alerts.iter().enumerate().map(|(idx, alert)| {
+        let mode = match alert.mode {
+            Mode::Once => String::new(),
+            Mode::Rearm if alert.disarmed => tr("waiting to re-arm").to_string(),
+            Mode::Rearm => trf("re-arms past {0}%", &[&alert.band]),
+            Mode::Snooze => match alert.snoozed_until {
+                Some(until) => trf("snoozed till {0}", &[&app_state.time_format.time(until)]),
+                None => trf("snoozes {0} min", &[&alert.snooze_minutes]),
+            },
+        };
+        let line = Line::from(vec![
+            Span::raw(alert_text(app_state, alert, alert.price)),
+            Span::raw(if mode.is_empty() {
+                mode
+            } else {
+                format!(" · {0}", mode)
+            })
+            .fg(Color::Gray),
+        ]);
+        if idx == cursor {
+            line.bg(Color::DarkGray)
+        } else {
+            line
+        }
+    }));
+    lines.push(Line::from(""));
+    lines.push(Line::from(tr("Went off")).fg(Color::Gray).italic());
+    if alerts.triggers.is_empty() {
+        lines.push(Line::from(tr("Nothing yet")).fg(Color::DarkGray));
+    }
+    let time_format = &app_state.time_format;
+    lines.extend(alerts.triggers.iter().rev().map(|trigger| {
+        let time = format!(
+            "{0} {1}  ",
+            time_format.short_date(trigger.at),
+            time_format.time(trigger.at)
+        );
+        let what = match trigger.moved {
+            Some(moved) => trf(
+                "{0} moved {1}",
+                &[&trigger.alert.ticker, &format!("{0:+.1}%", moved)],
+            ),
+            None => alert_text(app_state, &trigger.alert, trigger.alert.price),
+        };
+        let text = format!(
+            "{0}, {1}",
+            what,
+            trf(
+                "at {0}",
+                &[&format!(
+                    "{0:.1$}",
+                    trigger.price,
+                    app_state.price_decimals(&trigger.alert.ticker)
+                )]
+            )
+        );
+        // the ones not seen yet stand out until they're acknowledged
+        if trigger.acknowledged {
+            Line::from(vec![time.fg(Color::DarkGray), text.fg(Color::Gray)])
+        } else {
+            Line::from(vec![time.fg(Color::Yellow), text.fg(Color::Yellow).bold()])
+        }
+    }));
+    let popup_area = centered_rect(
+        area,
+        area.width.saturating_sub(4).min(72),
+        (lines.len() as u16 + 2).min(area.height),
+    );
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::bordered()
+                .title(trf(
+                    "Alerts · {0} set · {1} new",
+                    &[&alerts.alerts.len(), &alerts.unacknowledged()],
+                ))
+                .border_style(Style::default().fg(Color::Cyan)),
+        ),
+        popup_area,
+    );
+}
+
+// what's moving the most right now, biggest move first
+fn draw_movers(frame: &mut Frame, area: Rect, app_state: &AppState, theme: &Theme, cursor: usize) {
+    let movers = &app_state.movers;
+    let mut lines = vec![];
+    if movers.movers.is_empty() {
+        lines.push(Line::from(tr("Nothing is moving much")).fg(Color::DarkGray));
+    }
+    let time_format = &app_state.time_format;
+    lines.extend(movers.movers.iter().enumerate().map(|(idx, mover)| {
+        let name = app_state
+            .quote_index(&mover.ticker)
+            .map_or("", |idx| app_state.quotes[idx].listing.name.as_str());
+        let line = Line::from(vec![
+            format!("{0:<8}", mover.ticker).fg(Color::White),
+            format!("{0:<24}", shortened(name, 22)).fg(Color::Gray),
+            Span::styled(
+                format!("{0:>7}", format!("{0:+.1}%", mover.change)),
+                theme.change_style(mover.change),
+            ),
+            format!("  {0}", trf("since {0}", &[&time_format.time(mover.since)])).fg(Color::Gray),
+        ]);
+        if idx == cursor {
+            line.bg(Color::DarkGray)
+        } else {
+            line
+        }
+    }));
+    let popup_area = centered_rect(
+        area,
+        area.width.saturating_sub(4).min(64),
+        (lines.len() as u16 + 2).min(area.height),
+    );
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::bordered()
+                .title(trf(
+                    "Movers · {0}% in {1}s",
+                    &[&movers.config.threshold_pct, &movers.config.window_secs],
+                ))
+                .border_style(Style::default().fg(Color::Cyan)),
+        ),
+        popup_area,
+    );
+}
+
+// the targets with how far off they are and the orders to get there, or the orders staged
+// for a last look
+fn draw_rebalance(frame: &mut Frame, area: Rect, app_state: &AppState, view: &RebalanceView) {
+    let currency = &app_state.currency;
+    let mut lines = vec![];
+    let title = match &view.staged {
+        Some(orders) => {
+            for leg in orders {
+                let price = app_state
+                    .quote_index(&leg.ticker)
+                    .map_or(0.0, |idx| app_state.quotes[idx].quote.price);
+                lines.push(Line::from(trf(
+                    "{0} {1} {2} at market, about {3}",
+                    &[
+                        &tr(match leg.side {
+                            Side::Buy => "Buy",
+                            Side::Sell => "Sell",
+                        }),
+                        &leg.quantity,
+                        &leg.ticker,
+                        &money(price * leg.quantity as f64, currency),
+                    ],
+                )));
+            }
+            lines.push(Line::from(""));
+            lines.push(
+                Line::from(trf(
+                    "Enter places all {0} orders, Esc goes back",
+                    &[&orders.len()],
+                ))
+                .fg(Color::Gray),
+            );
+            tr("Rebalance · Review")
+        }
+        None if app_state.targets.is_empty() => {
+            lines.push(
+                Line::from(tr("No targets set, see [trading] targets in the Readme"))
+                    .fg(Color::DarkGray),
+            );
+            tr("Rebalance")
+        }
+        None => {
+            let plan = plan(app_state, &app_state.targets);
+            lines.push(
+                Line::from(format!(
+                    "{0:<8} {1:>7} {2:>7}  {3}",
+                    tr("Ticker"),
+                    tr("Target"),
+                    tr("Now"),
+                    tr("Order")
+                ))
+                .fg(Color::Gray)
+                .italic(),
+            );
+            lines.extend(plan.legs.iter().map(|leg| {
+                let order = match (leg.quantity, leg.side) {
+                    (0, _) => "—".fg(Color::DarkGray),
+                    (quantity, Side::Buy) => {
+                        format!("{0} {1}", tr("Buy"), quantity).fg(Color::Green)
+                    }
+                    (quantity, Side::Sell) => {
+                        format!("{0} {1}", tr("Sell"), quantity).fg(Color::Red)
+                    }
+                };
+                Line::from(vec![
+                    Span::raw(format!(
+                        "{0:<8} {1:>6.1}% {2:>6.1}%  ",
+                        leg.ticker, leg.target, leg.current
+                    )),
+                    order,
+                ])
+            }));
+            lines.extend(plan.empty.iter().map(|class| {
+                Line::from(trf(
+                    "Nothing held in {0} to spread its target over",
+                    &[&tr(class.label())],
+                ))
+                .fg(Color::DarkGray)
+            }));
+            tr("Rebalance")
+        }
+    };
+    let popup_area = centered_rect(
+        area,
+        area.width.saturating_sub(4).min(60),
+        (lines.len() as u16 + 2).min(area.height),
+    );
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::bordered()
+                .title(title)
+                .border_style(Style::default().fg(Color::Cyan)),
+        ),
+        popup_area,
+    );
+}
+
+// recurring orders with when they last went in
+fn draw_schedules(frame: &mut Frame, area: Rect, app_state: &AppState, cursor: usize) {
+    let schedules = &app_state.schedules.schedules;
+    let mut lines = vec![];
+    if schedules.is_empty() {
+        lines.push(
+            Line::from(tr("No schedules, m on a market row sets one up")).fg(Color::DarkGray),
+        );
+    }
+    lines.extend(schedules.iter().enumerate().map(|(idx, schedule)| {
+        let state = match (schedule.paused, schedule.last_run) {
+            (true, _) => tr("paused").to_string(),
+            (false, Some(last_run)) => {
+                trf("last {0}", &[&last_run.format("%-d %b %Y").to_string()])
+            }
+            (false, None) => tr("not run yet").to_string(),
+        };
+        let line = Line::from(vec![
+            Span::raw(trf(
+                "{0} {1} {2} every {3}",
+                &[
+                    &tr(match schedule.side {
+                        Side::Buy => "Buy",
+                        Side::Sell => "Sell",
+                    }),
+                    &schedule.quantity,
+                    &schedule.ticker,
+                    &tr(schedule.every.label()),
+                ],
+            )),
+            format!(" · {0}", state).fg(Color::Gray),
+        ]);
+        let line = if schedule.paused {
+            line.fg(Color::DarkGray)
+        } else {
+            line
+        };
+        if idx == cursor {
+            line.bg(Color::DarkGray)
+        } else {
+            line
+        }
+    }));
+    let popup_area = centered_rect(
+        area,
+        area.width.saturating_sub(4).min(60),
+        (lines.len() as u16 + 2).min(area.height),
+    );
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::bordered()
+                .title(trf("Schedules · {0}", &[&schedules.len()]))
+                .border_style(Style::default().fg(Color::Cyan)),
+        ),
+        popup_area,
+    );
+}
+
+fn draw_schedule_form(frame: &mut Frame, area: Rect, form: &ScheduleForm) {
+    let lines = form.form.lines();
+    let form_area = centered_rect(area, 44, lines.len() as u16 + 2);
+    frame.render_widget(Clear, form_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().title(trf("Schedule orders of {0}", &[&form.ticker]))),
+        form_area,
+    );
+}
+
+// cash and value of every account, the active one marked
+fn draw_accounts(frame: &mut Frame, area: Rect, app_state: &AppState, view: &AccountsView) {
+    let currency = &app_state.currency;
+    let mut lines = app_state
+        .accounts
+        .iter()
+        .enumerate()
+        .map(|(idx, account)| {
+            let line = Line::from(vec![
+                if idx == app_state.active {
+                    "● "
+                } else {
+                    "  "
+                }
+                .fg(Color::Cyan),
+                format!("{0:<16}", account.name).into(),
+                format!("{0:>16}", money(account.portfolio.cash, currency)).fg(Color::Gray),
+                format!(
+                    "{0:>18}",
+                    money(app_state.value_of(&account.portfolio), currency)
+                )
+                .fg(Color::White),
+            ]);
+            if idx == view.cursor {
+                line.bg(Color::DarkGray)
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<Line>>();
+    lines.insert(
+        0,
+        Line::from(format!(
+            "  {0:<16}{1:>16}{2:>18}",
+            tr("Account"),
+            tr("Cash"),
+            tr("Value")
+        ))
+        .fg(Color::Gray)
+        .italic(),
+    );
+    lines.push(Line::from(vec![
+        format!("  {0:<16}", tr("All accounts")).bold(),
+        format!("{0:>16}", money(app_state.combined().cash, currency)).fg(Color::Gray),
+        format!("{0:>18}", money(app_state.portfolio_value(), currency))
+            .fg(Color::White)
+            .bold(),
+    ]));
+    let popup_area = centered_rect(
+        area,
+        area.width.saturating_sub(4).min(56),
+        (lines.len() as u16 + 2).min(area.height),
+    );
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::bordered()
+                .title(trf("Accounts · {0}", &[&app_state.accounts.len()]))
+                .border_style(Style::default().fg(Color::Cyan)),
+        ),
+        popup_area,
+    );
+    if let Some(form) = &view.transfer {
+        let lines = form.lines();
+        let form_area = centered_rect(area, 44, lines.len() as u16 + 2);
+        frame.render_widget(Clear, form_area);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: true })
+                .block(Block::bordered().title(transfer_title(app_state, view))),
+            form_area,
+        );
+    }
+}
+
+// "Move cash from Speculation to Pension"
+fn transfer_title(app_state: &AppState, view: &AccountsView) -> String {
+    trf(
+        "Move cash from {0} to {1}",
+        &[
+            &app_state.accounts[app_state.active].name,
+            &app_state.accounts[view.cursor].name,
+        ],
+    )
+}
+
+// the rates against the home currency with what the active account holds of each, the cross
+// rates between all of them and a chart of the one under the cursor
+fn draw_fx(frame: &mut Frame, area: Rect, app_state: &AppState, uistate: &UIState, view: &FxView) {
+    use Constraint::{Fill, Length};
+
+    let theme = &uistate.theme;
+    let fx = &app_state.fx;
+    let home = &app_state.currency;
+    let portfolio = app_state.portfolio();
+    let popup_area = centered_rect(
+        area,
+        area.width.saturating_sub(4).min(72),
+        area.height.saturating_sub(2).min(28),
+    );
+    let block = Block::bordered()
+        .title(trf("FX · {0}% spread", &[&decimal(FX_SPREAD_PCT, 2)]))
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner_area = block.inner(popup_area).inner(Margin::new(1, 0));
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(block, popup_area);
+    let len = fx.currencies.len() as u16;
+    let [rates_area, _, cross_area, chart_area] =
+        Layout::vertical([Length(len + 1), Length(1), Length(len + 1), Fill(1)]).areas(inner_area);
+
+    let mut lines = vec![Line::from(format!(
+        "  {0:<16}{1:>11}{2:>9}{3:>15}{4:>15}",
+        tr("Currency"),
+        tr("Rate"),
+        tr("Change"),
+        tr("Held"),
+        tr("Value")
+    ))
+    .fg(Color::Gray)
+    .italic()];
+    lines.extend(fx.currencies.iter().enumerate().map(|(idx, currency)| {
+        let (held, value) = if currency.is_home() {
+            (portfolio.cash, portfolio.cash)
+        } else {
+            let held = portfolio.foreign(&currency.code);
+            (held, held * currency.rate)
+        };
+        let change = currency.change_pct();
+        let line = Line::from(vec![
+            format!("{0} ", currency.code).fg(Color::Cyan),
+            format!("{0:<14}", currency.name).into(),
+            format!("{0:>11}", home.format(currency.rate, 4)).fg(Color::White),
+            Span::styled(
+                format!(
+                    "{0:>9}",
+                    format!("{0}{1:.2}%", theme.change_arrow(change), change)
+                ),
+                theme.change_style(change),
+            ),
+            format!("{0:>15}", currency.currency.format(held, 2)).fg(Color::Gray),
+            format!("{0:>15}", money(value, home)).fg(Color::White),
+        ]);
+        if idx == view.cursor {
+            line.bg(Color::DarkGray)
+        } else {
+            line
+        }
+    }));
+    frame.render_widget(Paragraph::new(lines), rates_area);
+
+    // a row's currency in units of every column's
+    let mut header = format!("{0:<8}", tr("Cross"));
+    for currency in fx.currencies.iter() {
+        header.push_str(&format!("{0:>12}", currency.code));
+    }
+    let mut lines = vec![Line::from(header).fg(Color::Gray).italic()];
+    for (base, currency) in fx.currencies.iter().enumerate() {
+        let mut spans = vec![format!("{0:<8}", currency.code).fg(Color::Cyan)];
+        for quote in 0..fx.currencies.len() {
+            let cross = format!("{0:>12}", decimal(fx.cross(base, quote), 4));
+            spans.push(if base == quote {
+                cross.fg(Color::DarkGray)
+            } else {
+                cross.fg(Color::White)
+            });
+        }
+        lines.push(Line::from(spans));
+    }
+    frame.render_widget(Paragraph::new(lines), cross_area);
+
+    let selected = &fx.currencies[view.cursor];
+    let points = selected
+        .history
+        .iter()
+        .enumerate()
+        .map(|(idx, rate)| (idx as f64, *rate))
+        .collect::<Vec<(f64, f64)>>();
+    let min = selected.history.iter().copied().fold(f64::MAX, f64::min);
+    let max = selected.history.iter().copied().fold(f64::MIN, f64::max);
+    // a flat line still needs some room to be drawn in
+    let pad = ((max - min) * 0.05).max(max * 0.0005);
+    frame.render_widget(
+        Chart::new(vec![Dataset::default()
+            .marker(uistate.chart_marker)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(theme.change_color(selected.change_pct())))
+            .data(&points)])
+        .block(
+            Block::new().title(
+                trf(
+                    "{0} in {1}",
+                    &[&selected.name, &app_state.exchanges[0].currency_name_plural],
+                )
+                .fg(Color::Gray),
+            ),
+        )
+        .x_axis(Axis::default().bounds([0.0, (points.len().max(2) - 1) as f64]))
+        .y_axis(
+            Axis::default()
+                .bounds([min - pad, max + pad])
+                .labels([home.format(min, 4), home.format(max, 4)])
+                .style(Style::default().fg(Color::Gray)),
+        ),
+        chart_area,
+    );
+
+    if let Some((_, form)) = &view.form {
+        let lines = form.lines();
+        let form_area = centered_rect(area, 44, lines.len() as u16 + 2);
+        frame.render_widget(Clear, form_area);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: true })
+                .block(Block::bordered().title(fx_form_title(app_state, view))),
+            form_area,
+        );
+    }
+}
+
+// "Buy Sovereigns at 7.5300 ₡", the rate with the spread
+fn fx_form_title(app_state: &AppState, view: &FxView) -> String {
+    let currency = &app_state.fx.currencies[view.cursor];
+    let side = view.form.as_ref().map_or(Side::Buy, |(side, _)| *side);
+    let template = match side {
+        Side::Buy => "Buy {0} at {1}",
+        Side::Sell => "Sell {0} at {1}",
+    };
+    trf(
+        template,
+        &[
+            &currency.name,
+            &app_state.currency.format(currency.price(side), 4),
+        ],
+    )
+}
+
+// every cash movement, newest first with the balance it left
+fn draw_ledger(frame: &mut Frame, area: Rect, app_state: &AppState, view: &LedgerView) {
+    let currency = &app_state.currency;
+    let entries = view.entries(app_state.portfolio());
+    let popup_area = centered_rect(
+        area,
+        area.width.saturating_sub(4).min(100),
+        (entries.len() as u16 + 4).max(6).min(area.height),
+    );
+    let block = Block::bordered()
+        .title(trf(
+            "Cash ledger · {0}% interest · {1}% on a negative balance",
+            &[&app_state.cash_interest_pct, &app_state.borrow_rate_pct],
+        ))
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner_area = block.inner(popup_area);
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(block, popup_area);
+    let [status_area, _, entries_area] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Fill(1),
+    ])
+    .areas(inner_area);
+    let status = match &view.message {
+        Some(Ok(path)) => trf("Written to {0}", &[path]).fg(Color::Green),
+        Some(Err(err)) => err.clone().fg(Color::Red),
+        // the active account's, the name's left out with just the one
+        None => (match app_state.accounts.len() {
+            1 => String::new(),
+            _ => format!("{0} · ", app_state.accounts[app_state.active].name),
+        } + &trf(
+            "{0} · {1} entries",
+            &[
+                &view.filter.map_or(tr("All"), |kind| tr(kind.label())),
+                &entries.len(),
+            ],
+        ))
+            .fg(Color::Gray),
+    };
+    frame.render_widget(Line::from(status), status_area);
+    if entries.is_empty() {
+        frame.render_widget(
+            Line::from(tr("Nothing posted of this kind yet")).fg(Color::DarkGray),
+            entries_area,
+        );
+        return;
+    }
+    let time_format = &app_state.time_format;
+    let lines = entries
+        .iter()
+        .skip(view.scroll.pos)
+        .map(|entry| {
+            Line::from(vec![
+                format!(
+                    "{0} {1}  ",
+                    time_format.short_date(entry.at),
+                    time_format.time(entry.at)
+                )
+                .fg(Color::Gray),
+                format!("{0:<12}", tr(entry.kind.label())).into(),
+                format!("{0:>14}", currency.signed(entry.amount, 2)).fg(if entry.amount < 0.0 {
+                    Color::Red
+                } else {
+                    Color::Green
+                }),
+                format!("  {0:>16}  ", money(entry.balance, currency)).fg(Color::White),
+                entry.note.clone().fg(Color::Gray),
+            ])
+        })
+        .collect::<Vec<Line>>();
+    frame.render_widget(Paragraph::new(lines), entries_area);
+    view.scroll.render(
+        frame,
+        popup_area,
+        entries.len(),
+        entries_area.height as usize,
+        Style::default().fg(Color::Cyan),
+    );
+}
+
+fn draw_alert_form(frame: &mut Frame, area: Rect, form: &AlertForm) {
+    let lines = form.form.lines();
+    let form_area = centered_rect(area, 44, lines.len() as u16 + 2);
+    frame.render_widget(Clear, form_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().title(if form.editing.is_some() {
+                trf("Edit alert on {0}", &[&form.ticker])
+            } else {
+                trf("Alert on {0}", &[&form.ticker])
+            })),
+        form_area,
+    );
+}
+
+// "1h 05m", "4m 12s", "9s"
+fn duration_text(duration: TimeDelta) -> String {
+    let secs = duration.num_seconds().max(0);
+    if secs >= 3600 {
+        format!("{0}h {1:02}m", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{0}m {1:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{0}s", secs)
+    }
+}
+
+fn draw_panel(frame: &mut Frame, area: Rect, app_state: &AppState, panel: &dyn Panel) {
+    let width = area.width.saturating_sub(4).min(72);
+    let height = area.height.saturating_sub(4).min(10);
+    let [_, popup_area, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(height),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+    let [_, popup_area, _] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(width),
+        Constraint::Fill(1),
+    ])
+    .areas(popup_area);
+    let block = Block::bordered()
+        .title(panel.title())
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner_area = block.inner(popup_area);
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(block, popup_area);
+    panel.render(frame, inner_area, app_state);
+}
+
+fn draw_connection_manager(frame: &mut Frame, area: Rect, app_state: &AppState) {
+    let connection = &app_state.connection;
+    let field = |label: &'static str, value: String| {
+        Line::from(vec![
+            format!("{0:<12}", tr(label)).fg(Color::Gray),
+            value.fg(Color::White),
+        ])
+    };
+    let state = match connection.state {
+        LinkState::Connected => tr(connection.state.label()).fg(Color::Green),
+        LinkState::Degraded => tr(connection.state.label()).fg(Color::Yellow),
+        LinkState::Disconnected => tr(connection.state.label()).fg(Color::Red).bold(),
+    };
+    let mut lines = vec![
+        Line::from(vec![format!("{0:<12}", tr("State")).fg(Color::Gray), state]),
+        field(
+            "Uptime",
+            match (connection.uptime(), connection.down_since) {
+                (Some(uptime), _) => duration_text(uptime),
+                (None, Some(since)) => trf("down for {0}", &[&duration_text(Utc::now() - since)]),
+                (None, None) => "-".to_string(),
+            },
+        ),
+        field(
+            "Latency",
+            if connection.flaky {
+                format!("{0} ms", connection.latency_ms)
+            } else {
+                "-".to_string()
+            },
+        ),
+        field("Updates", format!("{0:.1}/s", connection.update_rate())),
+        field(
+            "Received",
+            trf(
+                "{0} · {1} lost",
+                &[
+                    &abbreviated(connection.received),
+                    &abbreviated(connection.lost),
+                ],
+            ),
+        ),
+        field("Reconnects", connection.reconnects.to_string()),
+        Line::from(""),
+        tr("Sources").fg(Color::Cyan).bold().into(),
+    ];
+    for (idx, source) in connection.sources.iter().enumerate() {
+        let progress = match source {
+            DataSource::Replay(replay) => {
+                format!(
+                    "  {0}",
+                    trf("step {0}/{1}", &[&(replay.pos + 1), &replay.len()])
+                )
+            }
+            DataSource::Simulation => String::new(),
+        };
+        lines.push(if idx == connection.active {
+            Line::from(vec![
+                "▶ ".fg(Color::Yellow),
+                source.label().fg(Color::White).bold(),
+                progress.fg(Color::Gray),
+            ])
+        } else {
+            Line::from(format!("  {0}", source.label())).fg(Color::Gray)
+        });
+    }
+    lines.push(Line::from(""));
+    lines.push(tr("Errors").fg(Color::Cyan).bold().into());
+    if connection.errors.is_empty() {
+        lines.push(Line::from(format!("  {0}", tr("none"))).fg(Color::DarkGray));
+    }
+    for error in connection.errors.iter().rev() {
+        lines.push(Line::from(vec![
+            format!("  {0} ", app_state.time_format.time(error.at)).fg(Color::Gray),
+            error.message.clone().fg(Color::Red),
+        ]));
+    }
+    let width = area.width.saturating_sub(4).min(64);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let [_, popup_area, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(height),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+    let [_, popup_area, _] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(width),
+        Constraint::Fill(1),
+    ])
+    .areas(popup_area);
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::bordered()
+                .title(tr("Connection"))
+                .border_style(Style::default().fg(Color::Cyan)),
+        ),
+        popup_area,
+    );
+}
+
+// sits in the bottom left corner, out of the way of the panels it talks about
+fn draw_tutorial(frame: &mut Frame, area: Rect, tutorial: &Tutorial) {
+    let width = area.width.saturating_sub(2).min(52);
+    let [_, tutorial_area] = Layout::vertical([Constraint::Fill(1), Constraint::Length(4)])
+        .areas(area.inner(Margin::new(1, 1)));
+    let [tutorial_area, _] =
+        Layout::horizontal([Constraint::Length(width), Constraint::Fill(1)]).areas(tutorial_area);
+    frame.render_widget(Clear, tutorial_area);
+    frame.render_widget(
+        Paragraph::new(tutorial.step.instructions().fg(Color::White))
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::bordered()
+                    .title(trf(
+                        "Tutorial {0}/{1}",
+                        &[&tutorial.step.number(), &TutorialStep::COUNT],
+                    ))
+                    .border_style(Style::default().fg(Color::Green)),
+            ),
+        tutorial_area,
+    );
+}
+
+fn draw_options_chain(frame: &mut Frame, area: Rect, app_state: &AppState, view: &OptionsView) {
+    use Constraint::Length;
+
+    let Some(chain) = app_state.option_chain(&view.ticker) else {
+        return;
+    };
+    let Some(selected) = view.selected_contract(app_state) else {
+        return;
+    };
+    let spot = app_state
+        .quote_index(&view.ticker)
+        .map_or(0.0, |idx| app_state.quotes[idx].quote.price);
+
+    let mut expiries = vec![tr("Expires in ").fg(Color::Gray)];
+    for expiry_tick in chain.expiries.iter() {
+        let label = format!(
+            " {0} ",
+            trf("{0} ticks", &[&(expiry_tick - app_state.ticks)])
+        );
+        expiries.push(if *expiry_tick == selected.expiry_tick {
+            label.fg(Color::Black).bg(Color::Cyan)
+        } else {
+            label.fg(Color::White)
+        });
+    }
+
+    let cell = |kind: OptionKind, strike: f64| {
+        let contract = OptionContract {
+            ticker: view.ticker.clone(),
+            kind,
+            strike,
+            expiry_tick: selected.expiry_tick,
+        };
+        let price = app_state.option_price(&contract).unwrap_or(0.0);
+        let held = app_state.portfolio().option_quantity(&contract);
+        let text = if held > 0 {
+            format!("{0:>9.2} ×{1:<3}", price, held)
+        } else {
+            format!("{0:>9.2}     ", price)
+        };
+        // in-the-money contracts are brighter
+        let style = if contract == selected {
+            Style::default().fg(Color::White).bg(Color::DarkGray)
+        } else if contract.intrinsic_value(spot) > 0.0 {
+            Style::default().fg(Color::White)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        Cell::from(text).style(style)
+    };
+    let rows = chain.strikes.iter().map(|strike| {
+        Row::new(vec![
+            cell(OptionKind::Call, *strike),
+            Cell::from(format!("{0:^10.0}", strike)).style(Style::default().fg(Color::Yellow)),
+            cell(OptionKind::Put, *strike),
+        ])
+    });
+    let table = Table::new(rows, [Length(14), Length(10), Length(14)])
+        .column_spacing(1)
+        .header(
+            Row::new(vec![
+                format!("{0:>13}", tr("Call")),
+                format!("{0:>8}", tr("Strike")),
+                format!("{0:>13}", tr("Put")),
+            ])
+            .style(Style::new().fg(Color::Gray).italic()),
+        );
+
+    let height = chain.strikes.len() as u16 + 7;
+    let chain_area = centered_rect(area, 46, height);
+    let block = Block::bordered()
+        .title(trf(
+            "Options · {0} · spot {1}",
+            &[&view.ticker, &money(spot, &app_state.currency)],
+        ))
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner_area = block.inner(chain_area);
+    frame.render_widget(Clear, chain_area);
+    frame.render_widget(block, chain_area);
+    let [expiries_area, _, table_area, _, message_area] = Layout::vertical([
+        Length(1),
+        Length(1),
+        Length(chain.strikes.len() as u16 + 1),
+        Length(1),
+        Length(1),
+    ])
+    .areas(inner_area);
+    frame.render_widget(Line::from(expiries), expiries_area);
+    frame.render_widget(table, table_area);
+    frame.render_widget(
+        match &view.error {
+            Some(err) => Line::styled(err.as_str(), Style::default().fg(Color::Red)),
+            None => Line::styled(
+                trf(
+                    "{0} {1} {2}, one contract is one share",
+                    &[
+                        &view.ticker,
+                        &format!("{0:.0}", selected.strike),
+                        &tr(selected.kind.label()),
+                    ],
+                ),
+                Style::default().fg(Color::Gray),
+            ),
+        },
+        message_area,
+    );
+}
+
+// everything known about one instrument, covering the market panel
+// one per slot of UIState::compare_tickers, none of them the red or green of price moves
+const COMPARE_COLORS: [Color; 4] = [Color::Cyan, Color::Yellow, Color::Magenta, Color::LightBlue];
+
+// percent from the start of the window every marked ticker has prices for
+fn draw_comparison(frame: &mut Frame, area: Rect, app_state: &AppState, uistate: &UIState) {
+    let area = if uistate.compare_tickers.len() >= 2 {
+        let [area, correlation_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(uistate.compare_tickers.len() as u16 + 3),
+        ])
+        .areas(area);
+        draw_correlation(frame, correlation_area, uistate);
+        area
+    } else {
+        area
+    };
+    let histories = uistate
+        .compare_tickers
+        .iter()
+        .filter_map(|ticker| {
+            let idx = app_state.quote_index(ticker)?;
+            let candles = app_state.quotes[idx].history.candles();
+            (!candles.is_empty()).then_some((ticker, candles))
+        })
+        .collect::<Vec<_>>();
+    let start = histories
+        .iter()
+        .filter_map(|(_, candles)| candles.first().map(|candle| candle.opened))
+        .max()
+        .unwrap_or_else(Utc::now);
+    let series = histories
+        .iter()
+        .map(|(ticker, candles)| {
+            let candles = candles
+                .iter()
+                .filter(|candle| candle.opened >= start)
+                .collect::<Vec<_>>();
+            let base = candles.first().map_or(1.0, |candle| candle.open);
+            let mut points = vec![(0.0, 0.0)];
+            points.extend(candles.iter().map(|candle| {
+                (
+                    (candle.opened - start).num_milliseconds() as f64 / 1000.0,
+                    (candle.close / base - 1.0) * 100.0,
+                )
+            }));
+            (ticker.as_str(), points)
+        })
+        .collect::<Vec<_>>();
+    let values = || series.iter().flat_map(|(_, points)| points.iter());
+    let min = values().map(|(_, pct)| *pct).fold(0.0, f64::min);
+    let max = values().map(|(_, pct)| *pct).fold(0.0, f64::max);
+    let end = values().map(|(secs, _)| *secs).fold(1.0, f64::max);
+    let pad = ((max - min) * 0.05).max(0.1);
+
+    let datasets = series
+        .iter()
+        .map(|(ticker, points)| {
+            let slot = uistate
+                .compare_tickers
+                .iter()
+                .position(|marked| marked == ticker)
+                .unwrap_or(0);
+            Dataset::default()
+                .name(ticker_span(&uistate.accents, &uistate.compare_tickers, ticker).content)
+                .marker(uistate.chart_marker)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(COMPARE_COLORS[slot]))
+                .data(points)
+        })
+        .collect::<Vec<Dataset>>();
+    let block = Block::bordered()
+        .title(trf(
+            "Comparison · % since {0}",
+            &[&app_state.time_format.time(start)],
+        ))
+        .border_style(Style::default().fg(Color::Cyan));
+    frame.render_widget(Clear, area);
+    if datasets.is_empty() {
+        frame.render_widget(
+            Paragraph::new(tr("Waiting for the first prices").fg(Color::Gray)).block(block),
+            area,
+        );
+        return;
+    }
+    frame.render_widget(
+        Chart::new(datasets)
+            .block(block)
+            .legend_position(Some(LegendPosition::TopRight))
+            .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)))
+            .x_axis(
+                Axis::default()
+                    .bounds([0.0, end])
+                    .labels([
+                        app_state.time_format.time(start),
+                        app_state.time_format.time(Utc::now()),
+                    ])
+                    .style(Style::default().fg(Color::Gray)),
+            )
+            .y_axis(
+                Axis::default()
+                    .bounds([min - pad, max + pad])
+                    .labels([
+                        format!("{0:+.1}%", min),
+                        "0.0%".to_string(),
+                        format!("{0:+.1}%", max),
+                    ])
+                    .style(Style::default().fg(Color::Gray)),
+            ),
+        area,
+    );
+}
+
+// how the candle returns of every marked pair go together, under the comparison chart
+fn draw_correlation(frame: &mut Frame, area: Rect, uistate: &UIState) {
+    use Constraint::Length;
+
+    let block = Block::bordered()
+        .title(tr("Correlation of returns"))
+        .border_style(Style::default().fg(Color::Cyan));
+    frame.render_widget(Clear, area);
+    // worked out by the worker, until then there's only the placeholder
+    let Some((_, matrix)) = uistate
+        .correlation
+        .as_ref()
+        .filter(|(tickers, _)| *tickers == uistate.compare_tickers)
+    else {
+        frame.render_widget(
+            Paragraph::new(tr("Computing…").fg(Color::Gray)).block(block),
+            area,
+        );
+        return;
+    };
+    let ticker = |slot: usize| {
+        Cell::from(uistate.compare_tickers[slot].clone())
+            .style(Style::default().fg(COMPARE_COLORS[slot]))
+    };
+    let rows = matrix.iter().enumerate().map(|(row, values)| {
+        let mut cells = vec![ticker(row)];
+        cells.extend(values.iter().enumerate().map(|(column, value)| {
+            let text = value.map_or("–".to_string(), |value| format!("{0:+.2}", value));
+            let color = if row == column {
+                Color::DarkGray
+            } else {
+                Color::White
+            };
+            Cell::from(format!("{0:>6}", text)).style(Style::default().fg(color))
+        }));
+        Row::new(cells)
+    });
+    let mut header = vec![Cell::from("")];
+    header.extend((0..matrix.len()).map(|slot| {
+        Cell::from(format!("{0:>6}", uistate.compare_tickers[slot]))
+            .style(Style::default().fg(COMPARE_COLORS[slot]))
+    }));
+    let mut widths = vec![Length(8)];
+    widths.extend((0..matrix.len()).map(|_| Length(6)));
+    frame.render_widget(
+        Table::new(rows, widths)
+            .column_spacing(1)
+            .header(Row::new(header))
+            .block(block),
+        area,
+    );
+}
+
+// headlines in the crosshair readout are cut to keep the box clear of the candles
+const ANNOTATION_TITLE_LEN: usize = 32;
+const ANNOTATION_LINES: usize = 4;
+
+// what happened to the instrument while a candle was open
+enum Annotation<'s> {
+    Trade(&'s OrderFill),
+    News(&'s NewsItem),
+}
+
+// one list per candle, events before the oldest candle are left out
+// what a candle of the timeframe covers, the zoom merging several of them
+fn timeframe_label(timeframe: Timeframe, per: usize) -> String {
+    match timeframe {
+        Timeframe::Tick if per == 1 => tr("1 tick per candle").to_string(),
+        Timeframe::Tick => trf("{0} ticks per candle", &[&per]),
+        Timeframe::Minute => trf("{0} min per candle", &[&per]),
+        Timeframe::FiveMinutes => trf("{0} min per candle", &[&(5 * per)]),
+        Timeframe::Day if per == 1 => tr("1 day per candle").to_string(),
+        Timeframe::Day => trf("{0} days per candle", &[&per]),
+    }
+}
+
+// under the chart, seconds only matter between ticks and the time of day doesn't for days,
+// or for the backfilled days before today
+fn candle_time(time_format: &TimeFormat, timeframe: Timeframe, time: DateTime<Utc>) -> String {
+    match timeframe {
+        Timeframe::Day => time_format.short_date(time),
+        _ if time_format.day(time) != time_format.day(Utc::now()) => time_format.short_date(time),
+        Timeframe::Tick => time_format.precise_time(time),
+        Timeframe::Minute | Timeframe::FiveMinutes => time_format.time(time),
+    }
+}
+
+fn candle_readout_time(
+    time_format: &TimeFormat,
+    timeframe: Timeframe,
+    time: DateTime<Utc>,
+) -> String {
+    match timeframe {
+        Timeframe::Day => time_format.date(time),
+        _ => format!(
+            "{0} {1}",
+            time_format.short_date(time),
+            candle_time(time_format, timeframe, time)
+        ),
+    }
+}
+
+fn chart_annotations<'s>(
+    app_state: &'s AppState,
+    ticker: &str,
+    candles: &[Candle],
+) -> Vec<Vec<Annotation<'s>>> {
+    let mut annotations = candles.iter().map(|_| vec![]).collect::<Vec<Vec<_>>>();
+    let candle_at = |time| {
+        candles
+            .partition_point(|candle| candle.opened <= time)
+            .checked_sub(1)
+    };
+    for entry in app_state.blotter.iter() {
+        if let BlotterEntry::Fill(fill) = entry {
+            if let Some(idx) = candle_at(fill.time).filter(|_| fill.ticker == ticker) {
+                annotations[idx].push(Annotation::Trade(fill));
+            }
+        }
+    }
+    // the news is newest first, oldest first matches the blotter
+    for item in app_state.news.iter().rev() {
+        if let Some(idx) =
+            candle_at(item.published).filter(|_| item.tickers.iter().any(|t| t == ticker))
+        {
+            annotations[idx].push(Annotation::News(item));
+        }
+    }
+    annotations
+}
+
+fn trade_marker(side: Side) -> Span<'static> {
+    match side {
+        Side::Buy => "▲".fg(Color::Green),
+        Side::Sell => "▼".fg(Color::Red),
+    }
+}
+
+fn news_marker(category: NewsCategory) -> Span<'static> {
+    category.label()[..1].fg(category.color())
+}
+
+fn shortened(text: &str, len: usize) -> String {
+    if text.chars().count() <= len {
+        return text.to_string();
+    }
+    format!("{0}…", text.chars().take(len - 1).collect::<String>())
+}
+
+// trades win over news, several kinds of trades or news in one candle get a dot
+fn annotation_marker(events: &[Annotation]) -> Option<(&'static str, Color)> {
+    let trades = events
+        .iter()
+        .filter_map(|event| match event {
+            Annotation::Trade(fill) => Some(fill.side),
+            _ => None,
+        })
+        .collect::<Vec<Side>>();
+    if let Some(side) = trades.first() {
+        return Some(if trades.iter().all(|other| other == side) {
+            match side {
+                Side::Buy => ("▲", Color::Green),
+                Side::Sell => ("▼", Color::Red),
+            }
+        } else {
+            ("◆", Color::Yellow)
+        });
+    }
+    let categories = events
+        .iter()
+        .filter_map(|event| match event {
+            Annotation::News(item) => Some(item.category),
+            _ => None,
+        })
+        .collect::<Vec<NewsCategory>>();
+    let category = categories.first()?;
+    Some(if categories.iter().all(|other| other == category) {
+        (&category.label()[..1], category.color())
+    } else {
+        ("•", Color::White)
+    })
+}
+
+// one candle every few columns, wider when zoomed in, the volume bars below line up with them
+fn draw_price_chart(
+    frame: &mut Frame,
+    area: Rect,
+    app_state: &AppState,
+    quote: &Instrument,
+    chart: &ChartView,
+    theme: &Theme,
+    image: Option<&RefCell<Option<ChartImage>>>,
+) {
+    use Constraint::{Fill, Length};
+
+    let currency = &app_state.currency;
+    let decimals = quote.price_decimals();
+    let label_width = 12;
+    let [plot_area, label_area] = Layout::horizontal([Fill(1), Length(label_width)]).areas(area);
+    let [candle_area, marker_area, volume_area, axis_area, footer_area] =
+        Layout::vertical([Fill(1), Length(1), Length(4), Length(1), Length(1)]).areas(plot_area);
+    let [price_label_area, _, volume_label_area, _, _] =
+        Layout::vertical([Fill(1), Length(1), Length(4), Length(1), Length(1)]).areas(label_area);
+
+    let candles = chart.candles(&quote.history);
+    if candles.is_empty() {
+        frame.render_widget(
+            Paragraph::new(tr("Waiting for the first prices").fg(Color::Gray)),
+            area,
+        );
+        return;
+    }
+    let candle_width = chart.candle_width();
+    // too small for a single candle, the cells would land outside the panel
+    if candle_area.height == 0 || candle_area.width < candle_width {
+        return;
+    }
+    let visible = ((candle_area.width + 1) / (candle_width + 1)).max(1) as usize;
+    let (pan, cursor) = chart.window(candles.len(), visible);
+    let end = candles.len() - pan;
+    let annotations = chart_annotations(app_state, &quote.listing.ticker, &candles);
+    let annotations = &annotations[end.saturating_sub(visible)..end];
+    let candles = &candles[end.saturating_sub(visible)..end];
+    let low = candles
+        .iter()
+        .map(|candle| candle.low)
+        .fold(f64::MAX, f64::min);
+    let high = candles
+        .iter()
+        .map(|candle| candle.high)
+        .fold(f64::MIN, f64::max);
+    let rows = candle_area.height.saturating_sub(1) as f64;
+    let row_of = |price: f64| {
+        if high > low {
+            ((high - price) / (high - low) * rows).round() as u16
+        } else {
+            (rows / 2.0) as u16
+        }
+    };
+    let column_of = |idx: usize| candle_area.x + idx as u16 * (candle_width + 1);
+    // the crosshair is counted back from the latest candle like the pan
+    let selected = cursor.map(|cursor| candles.len() - 1 - (cursor - pan));
+
+    // with terminal graphics main draws the candles as an image over the blank area
+    if let Some(image) = image {
+        *image.borrow_mut() = Some(ChartImage {
+            area: candle_area,
+            candles: candles.to_vec(),
+            candle_width,
+            low,
+            high,
+            selected,
+            up_color: theme.gain(),
+            down_color: theme.loss(),
+        });
+    } else {
+        let buffer = frame.buffer_mut();
+        if let Some(idx) = selected {
+            let x = column_of(idx) + candle_width / 2;
+            let y = candle_area.y + row_of(candles[idx].close);
+            for row in candle_area.top()..candle_area.bottom() {
+                buffer[(x, row)].set_symbol("┊").set_fg(Color::DarkGray);
+            }
+            for col in candle_area.left()..candle_area.right() {
+                buffer[(col, y)].set_symbol("┈").set_fg(Color::DarkGray);
+            }
+        }
+        for (idx, candle) in candles.iter().enumerate() {
+            let x = column_of(idx);
+            let color = if candle.is_up() {
+                theme.gain()
+            } else {
+                theme.loss()
+            };
+            let body =
+                row_of(candle.open.max(candle.close))..=row_of(candle.open.min(candle.close));
+            for row in row_of(candle.high)..=row_of(candle.low) {
+                for col in x..x + candle_width {
+                    let symbol = if body.contains(&row) {
+                        "█"
+                    } else if col == x + candle_width / 2 {
+                        "│"
+                    } else {
+                        continue;
+                    };
+                    buffer[(col, candle_area.y + row)]
+                        .set_symbol(symbol)
+                        .set_fg(color);
+                }
+            }
+        }
+    }
+
+    let buffer = frame.buffer_mut();
+    for (idx, events) in annotations.iter().enumerate() {
+        if let Some((marker, color)) = annotation_marker(events) {
+            buffer[(column_of(idx) + candle_width / 2, marker_area.y)]
+                .set_symbol(marker)
+                .set_fg(color);
+        }
+    }
+
+    let bars = candles
+        .iter()
+        .map(|candle| {
+            Bar::default()
+                .value(candle.volume)
+                .text_value(String::new())
+                .style(Style::default().fg(if candle.is_up() {
+                    theme.gain()
+                } else {
+                    theme.loss()
+                }))
+        })
+        .collect::<Vec<Bar>>();
+    frame.render_widget(
+        BarChart::default()
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(candle_width)
+            .bar_gap(1),
+        volume_area,
+    );
+
+    let price_label = |price: f64| format!(" {0:.1$}", price, decimals).fg(Color::Gray);
+    frame.render_widget(Line::from(price_label(high)), price_label_area);
+    frame.render_widget(
+        Line::from(price_label(low)),
+        Rect {
+            y: price_label_area.bottom().saturating_sub(1),
+            height: 1,
+            ..price_label_area
+        },
+    );
+    let max_volume = candles
+        .iter()
+        .map(|candle| candle.volume)
+        .max()
+        .unwrap_or(0);
+    frame.render_widget(
+        Line::from(format!(" {0} {1}", abbreviated(max_volume), tr("vol")).fg(Color::Gray)),
+        volume_label_area,
+    );
+    // the oldest and latest candle in the window, the middle one too when there's room
+    let time_label = |time| candle_time(&app_state.time_format, chart.timeframe, time);
+    let mut axis = vec![
+        (0, time_label(candles[0].opened)),
+        (
+            candles.len() - 1,
+            time_label(candles[candles.len() - 1].opened),
+        ),
+    ];
+    let widest = axis
+        .iter()
+        .map(|(_, label)| label.chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+    if candles.len() > 2 && candle_area.width > 3 * (widest + 2) {
+        let middle = candles.len() / 2;
+        axis.insert(1, (middle, time_label(candles[middle].opened)));
+    }
+    let mut axis_line = String::new();
+    for (idx, label) in axis {
+        let len = label.chars().count();
+        let center = (column_of(idx) + candle_width / 2 - axis_area.x) as usize;
+        let x = center
+            .saturating_sub(len / 2)
+            .min((axis_area.width as usize).saturating_sub(len));
+        // labels never run into each other
+        let used = axis_line.chars().count();
+        let x = if used == 0 { x } else { x.max(used + 1) };
+        axis_line.push_str(&" ".repeat(x - used));
+        axis_line.push_str(&label);
+    }
+    frame.render_widget(Line::from(axis_line.fg(Color::Gray)), axis_area);
+    let mut footer = trf(
+        "{0} · range {1} – {2}",
+        &[
+            &timeframe_label(chart.timeframe, chart.candles_per_bar()),
+            &currency.format(low, decimals),
+            &currency.format(high, decimals),
+        ],
+    );
+    if pan > 0 {
+        footer.push_str(&trf(" · {0} candles back", &[&pan]));
+    }
+    frame.render_widget(Line::from(footer.fg(Color::Gray)), footer_area);
+
+    if let Some(idx) = selected {
+        let candle = &candles[idx];
+        let price = |price: f64| format!("{0:.1$}", price, decimals);
+        let mut lines = vec![
+            Line::from(
+                candle_readout_time(&app_state.time_format, chart.timeframe, candle.opened)
+                    .fg(Color::White)
+                    .bold(),
+            ),
+            Line::from(format!(
+                "O {0}  H {1}",
+                price(candle.open),
+                price(candle.high)
+            )),
+            Line::from(format!(
+                "L {0}  C {1}",
+                price(candle.low),
+                price(candle.close)
+            )),
+            Line::from(format!("{0} {1}", tr("Vol"), abbreviated(candle.volume)).fg(Color::Gray)),
+        ];
+        let events = &annotations[idx];
+        lines.extend(
+            events
+                .iter()
+                .take(ANNOTATION_LINES)
+                .map(|event| match event {
+                    Annotation::Trade(fill) => Line::from(vec![
+                        trade_marker(fill.side),
+                        format!(
+                            " {0} {1} @ {2}",
+                            tr(if fill.side == Side::Buy {
+                                "Bought"
+                            } else {
+                                "Sold"
+                            }),
+                            fill.quantity,
+                            price(fill.price)
+                        )
+                        .into(),
+                    ]),
+                    Annotation::News(item) => Line::from(vec![
+                        news_marker(item.category),
+                        format!(" {0}", shortened(&item.title, ANNOTATION_TITLE_LEN)).into(),
+                    ]),
+                }),
+        );
+        if events.len() > ANNOTATION_LINES {
+            lines.push(Line::from(
+                format!("+{0} more", events.len() - ANNOTATION_LINES).fg(Color::Gray),
+            ));
+        }
+        // an image would cover the box, the readout takes the footer instead
+        if image.is_some() {
+            let spans = lines
+                .into_iter()
+                .flat_map(|line| line.spans.into_iter().chain(["  ".into()]))
+                .collect::<Vec<Span>>();
+            frame.render_widget(Clear, footer_area);
+            frame.render_widget(Line::from(spans), footer_area);
+            return;
+        }
+        let width = lines.iter().map(|line| line.width()).max().unwrap_or(0) as u16 + 2;
+        // the readout sits in the corner away from the cursor
+        let readout_x = if column_of(idx) < candle_area.x + candle_area.width / 2 {
+            candle_area.right().saturating_sub(width)
+        } else {
+            candle_area.x
+        };
+        let readout_area = Rect {
+            x: readout_x,
+            y: candle_area.y,
+            width: width.min(candle_area.width),
+            height: (lines.len() as u16 + 2).min(candle_area.height),
+        };
+        frame.render_widget(Clear, readout_area);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(Block::bordered().border_style(Style::default().fg(Color::DarkGray))),
+            readout_area,
+        );
+    }
+}
+
+// cumulative shares bid and offered, bids fill in from the left and asks from the right
+// with the spread as the gap between them
+fn draw_depth_chart(frame: &mut Frame, area: Rect, quote: &Instrument, currency: &Currency) {
+    use Constraint::{Fill, Length};
+
+    let book = &quote.book;
+    let decimals = quote.price_decimals();
+    let (Some((low, high)), Some(mid), Some(spread)) =
+        (book.price_range(), book.mid(), book.spread())
+    else {
+        frame.render_widget(
+            Paragraph::new(tr("No orders in the book").fg(Color::Gray)),
+            area,
+        );
+        return;
+    };
+    let [summary_area, chart_area, axis_area] =
+        Layout::vertical([Length(2), Fill(1), Length(1)]).areas(area);
+    frame.render_widget(
+        Line::from(vec![
+            format!("{0} ", tr("Bid depth")).fg(Color::Gray),
+            book.bid_depth(low).to_string().fg(Color::Green).bold(),
+            format!("  {0} ", tr("Ask depth")).fg(Color::Gray),
+            book.ask_depth(high).to_string().fg(Color::Red).bold(),
+            format!("  {0} ", tr("Spread")).fg(Color::Gray),
+            currency.format(spread, decimals).fg(Color::White),
+        ]),
+        summary_area,
+    );
+
+    let columns = chart_area.width.max(1) as usize;
+    let bars = (0..columns)
+        .map(|col| {
+            let price = low + (col as f64 + 0.5) / columns as f64 * (high - low);
+            let (depth, color) = if price < mid {
+                (book.bid_depth(price), Color::Green)
+            } else {
+                (book.ask_depth(price), Color::Red)
+            };
+            Bar::default()
+                .value(depth as u64)
+                .text_value(String::new())
+                .style(Style::default().fg(color))
+        })
+        .collect::<Vec<Bar>>();
+    frame.render_widget(
+        BarChart::default()
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(1)
+            .bar_gap(0),
+        chart_area,
+    );
+
+    let [low_area, mid_area, high_area] =
+        Layout::horizontal([Fill(1), Fill(1), Fill(1)]).areas(axis_area);
+    let axis_label = |price: f64| format!("{0:.1$}", price, decimals).fg(Color::Gray);
+    frame.render_widget(Line::from(axis_label(low)), low_area);
+    frame.render_widget(
+        Line::from(axis_label(mid)).alignment(Alignment::Center),
+        mid_area,
+    );
+    frame.render_widget(
+        Line::from(axis_label(high)).alignment(Alignment::Right),
+        high_area,
+    );
+}
+
+// how candle-to-candle returns are spread, with the mean and one standard deviation either side
+fn draw_returns_histogram(
+    frame: &mut Frame,
+    area: Rect,
+    quote: &Instrument,
+    uistate: &UIState,
+    theme: &Theme,
+) {
+    use Constraint::{Fill, Length};
+
+    // worked out by the worker, until then there's only the placeholder
+    let Some((_, distribution)) = uistate
+        .distribution
+        .as_ref()
+        .filter(|(ticker, _)| *ticker == quote.listing.ticker)
+    else {
+        frame.render_widget(Paragraph::new(tr("Computing…").fg(Color::Gray)), area);
+        return;
+    };
+    let Distribution {
+        returns,
+        mean,
+        std_dev,
+        low,
+        high,
+    } = distribution;
+    let (mean, std_dev, low, high) = (*mean, *std_dev, *low, *high);
+    if returns.len() < 2 || high <= low {
+        frame.render_widget(
+            Paragraph::new(tr("Not enough prices for a distribution yet").fg(Color::Gray)),
+            area,
+        );
+        return;
+    }
+    let [summary_area, chart_area, marker_area, axis_area] =
+        Layout::vertical([Length(2), Fill(1), Length(1), Length(1)]).areas(area);
+    frame.render_widget(
+        Line::from(vec![
+            format!("{0} returns per {1} ticks", returns.len(), CANDLE_TICKS).fg(Color::Gray),
+            "  Mean ".fg(Color::Gray),
+            format!("{0:+.3}%", mean).fg(Color::White).bold(),
+            "  Std dev ".fg(Color::Gray),
+            format!("{0:.3}%", std_dev).fg(Color::White).bold(),
+        ]),
+        summary_area,
+    );
+
+    // bars a column apart, the extremes land in the outermost bins
+    let bins = chart_area.width.div_ceil(2).max(1) as usize;
+    let bin_of = |value: f64| (((value - low) / (high - low) * bins as f64) as usize).min(bins - 1);
+    let mut counts = vec![0u64; bins];
+    for value in returns.iter() {
+        counts[bin_of(*value)] += 1;
+    }
+    let bars = counts
+        .iter()
+        .enumerate()
+        .map(|(bin, count)| {
+            let middle = low + (bin as f64 + 0.5) / bins as f64 * (high - low);
+            Bar::default()
+                .value(*count)
+                .text_value(String::new())
+                .style(Style::default().fg(theme.change_color(middle)))
+        })
+        .collect::<Vec<Bar>>();
+    frame.render_widget(
+        BarChart::default()
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(1)
+            .bar_gap(1),
+        chart_area,
+    );
+    let buffer = frame.buffer_mut();
+    for (value, marker, color) in [
+        (mean - std_dev, "σ", Color::Gray),
+        (mean + std_dev, "σ", Color::Gray),
+        (mean, "μ", Color::White),
+    ] {
+        // squeezed out of a short panel
+        if (low..=high).contains(&value) && !marker_area.is_empty() {
+            buffer[(marker_area.x + bin_of(value) as u16 * 2, marker_area.y)]
+                .set_symbol(marker)
+                .set_fg(color);
+        }
+    }
+
+    let [low_area, high_area] = Layout::horizontal([Fill(1), Fill(1)]).areas(axis_area);
+    frame.render_widget(
+        Line::from(format!("{0:+.2}%", low).fg(Color::Gray)),
+        low_area,
+    );
+    frame.render_widget(
+        Line::from(format!("{0:+.2}%", high).fg(Color::Gray)).alignment(Alignment::Right),
+        high_area,
+    );
+}
+
+fn draw_instrument_detail(
+    frame: &mut Frame,
+    area: Rect,
+    app_state: &AppState,
+    uistate: &UIState,
+    ticker: &str,
+    image: Option<&RefCell<Option<ChartImage>>>,
+) {
+    let Some(idx) = app_state.quote_index(ticker) else {
+        return;
+    };
+    let view = uistate.instrument_detail_view;
+    let theme = &uistate.theme;
+    let quote = &app_state.quotes[idx];
+    let currency = &app_state.currency;
+    let decimals = quote.price_decimals();
+    let exchange = &app_state.exchanges[quote.exchange];
+    let local_price = exchange.local_price(quote.quote.price);
+    let local_price_yesterday = exchange.local_price_yesterday(quote.quote.price_yesterday);
+    let percent_change = (local_price - local_price_yesterday) / local_price_yesterday * 100.0;
+    let label = |text: &'static str| format!("{0:<10}", tr(text)).fg(Color::Gray);
+
+    let mut price_line = vec![
+        label("Price"),
+        exchange
+            .currency
+            .format(local_price, decimals)
+            .fg(Color::White)
+            .bold(),
+        "  ".into(),
+        Span::styled(
+            format!(
+                "{0}{1:.2}%",
+                theme.change_arrow(percent_change),
+                percent_change
+            ),
+            theme.change_style(percent_change),
+        ),
+    ];
+    if let Some(secs) = app_state.stale_secs(quote) {
+        price_line
+            .push(format!("  {0}", trf("stale, updated {0}s ago", &[&secs])).fg(Color::DarkGray));
+    }
+    // orders and the portfolio are settled at home
+    if !exchange.is_home() {
+        price_line.push(
+            format!(
+                "  {0} · {1}",
+                currency.format(quote.quote.price, decimals),
+                exchange.name
+            )
+            .fg(Color::Gray),
+        );
+    }
+    let mut lines = vec![
+        Line::from(price_line),
+        Line::from(match app_state.portfolio().position(ticker) {
+            Some(position) => vec![
+                label("Holding"),
+                format!(
+                    "{0} @ {1}",
+                    position.quantity,
+                    currency.format(position.avg_cost, decimals)
+                )
+                .fg(Color::White),
+            ],
+            None => vec![label("Holding"), tr("none").fg(Color::Gray)],
+        }),
+    ];
+    if let Some(level) = app_state.distress_level(ticker) {
+        lines.push(Line::from(vec![
+            label("Status"),
+            trf("distressed, {0}", &[&tr(level.label())])
+                .fg(Color::Red)
+                .bold(),
+        ]));
+    }
+    if let Some(short_interest_pct) = quote.short_interest_pct() {
+        lines.push(Line::from(vec![
+            label("Short int."),
+            trf("{0}% of float", &[&format!("{0:.1}", short_interest_pct)]).fg(Color::White),
+        ]));
+    }
+    lines.extend([
+        Line::from(""),
+        Line::from(quote.listing.description.as_str()),
+    ]);
+
+    if quote.class() == InstrumentClass::Equity {
+        let ratings = current_ratings(&app_state.analyst_notes, ticker);
+        lines.push(Line::from(""));
+        let mut summary = vec![tr("Analysts").bold()];
+        if let Some(rating) = consensus(&app_state.analyst_notes, ticker) {
+            let count =
+                |rating: Rating| ratings.iter().filter(|note| note.rating == rating).count();
+            let avg_target =
+                ratings.iter().map(|note| note.price_target).sum::<f64>() / ratings.len() as f64;
+            summary.extend([
+                format!(" · {0} ", tr("consensus")).fg(Color::Gray),
+                tr(rating.label()).fg(rating.color()).bold(),
+                trf(
+                    " ({0} Buy, {1} Hold, {2} Sell) · avg target {3}",
+                    &[
+                        &count(Rating::Buy),
+                        &count(Rating::Hold),
+                        &count(Rating::Sell),
+                        &currency.format(avg_target, decimals),
+                    ],
+                )
+                .fg(Color::Gray),
+            ]);
+        } else {
+            summary.push(tr(" · no coverage yet").fg(Color::Gray));
+        }
+        lines.push(Line::from(summary));
+        for note in app_state
+            .analyst_notes
+            .iter()
+            .filter(|note| note.ticker == ticker)
+        {
+            lines.push(Line::from(vec![
+                format!("  {0:<24}", note.firm).fg(Color::White),
+                format!("{0:<12}", tr(note.action().label())).fg(Color::Gray),
+                format!("{0:<6}", tr(note.rating.label())).fg(note.rating.color()),
+                format!("{0} {1:>9.2$}  ", tr("target"), note.price_target, decimals)
+                    .fg(Color::White),
+                app_state
+                    .time_format
+                    .relative(note.published, Utc::now())
+                    .fg(Color::Gray),
+            ]));
+        }
+
+        let filings = app_state
+            .insider_filings
+            .iter()
+            .filter(|filing| filing.ticker == ticker)
+            .collect::<Vec<_>>();
+        lines.push(Line::from(""));
+        lines.push(Line::from(if filings.is_empty() {
+            vec![
+                tr("Insider filings").bold(),
+                tr(" · none yet").fg(Color::Gray),
+            ]
+        } else {
+            vec![tr("Insider filings").bold()]
+        }));
+        for filing in filings {
+            lines.push(Line::from(vec![
+                format!("  {0:<24}", filing.insider).fg(Color::White),
+                format!("{0:<6}", tr(filing.side.label())).fg(match filing.side {
+                    Side::Buy => Color::Green,
+                    Side::Sell => Color::Red,
+                }),
+                format!(
+                    "{0:>6} @ {1}  ",
+                    filing.quantity,
+                    currency.format(filing.price, decimals)
+                )
+                .fg(Color::White),
+                app_state
+                    .time_format
+                    .relative(filing.filed, Utc::now())
+                    .fg(Color::Gray),
+            ]));
+        }
+    }
+
+    let block = Block::bordered()
+        .title(Line::from(vec![
+            format!("{0} (", quote.listing.name).into(),
+            ticker_span(
+                &uistate.accents,
+                &uistate.compare_tickers,
+                &quote.listing.ticker,
+            ),
+            format!(") · {0}", tr(quote.class().label())).into(),
+        ]))
+        .border_style(Style::default().fg(Color::Cyan));
+    frame.render_widget(Clear, area);
+    if view != DetailView::Details {
+        // the price line stays on top of the charts
+        let inner_area = block.inner(area);
+        frame.render_widget(block, area);
+        let [price_area, chart_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner_area);
+        frame.render_widget(lines.swap_remove(0), price_area);
+        match view {
+            DetailView::Chart => draw_price_chart(
+                frame,
+                chart_area,
+                app_state,
+                quote,
+                &uistate.chart,
+                theme,
+                image,
+            ),
+            DetailView::Depth => draw_depth_chart(frame, chart_area, quote, currency),
+            _ => draw_returns_histogram(frame, chart_area, quote, uistate, theme),
+        }
+        return;
+    }
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(block),
+        area,
+    );
+}
+
+// the active account, or all of them added up
+fn shown_portfolio<'a>(app_state: &'a AppState, all_accounts: bool) -> Cow<'a, Portfolio> {
+    if all_accounts {
+        Cow::Owned(app_state.combined())
+    } else {
+        Cow::Borrowed(app_state.portfolio())
+    }
+}
+
+// like "Pension" or "All accounts", nothing with just the one account
+fn shown_account<'a>(app_state: &'a AppState, all_accounts: bool) -> Option<&'a str> {
+    if app_state.accounts.len() < 2 {
+        None
+    } else if all_accounts {
+        Some(tr("All accounts"))
+    } else {
+        Some(app_state.accounts[app_state.active].name.as_str())
+    }
+}
+
+pub fn draw_portfolio(frame: &mut Frame, area: Rect, panel: &PortfolioPanel, ctx: &Context) {
+    use Constraint::{Fill, Length};
+
+    let app_state = ctx.app_state;
+    let theme = ctx.theme;
+    let portfolio = shown_portfolio(app_state, panel.all_accounts);
+    let currency = &app_state.currency;
+    let value = money(app_state.value_of(&portfolio), currency);
+    let difficulty = tr(app_state.difficulty.label());
+    let block = Block::bordered().title(match shown_account(app_state, panel.all_accounts) {
+        Some(account) => trf(
+            "Portfolio · {0} · {1} · {2}",
+            &[&account, &value, &difficulty],
+        ),
+        None => trf("Portfolio · {0} · {1}", &[&value, &difficulty]),
+    });
+    let inner_area = block.inner(area);
+    frame.render_widget(block, area);
+    // the settled and unsettled balances under the cash while there's anything to settle
+    let next_settlement = portfolio
+        .unsettled
+        .iter()
+        .map(|unsettled| unsettled.settles)
+        .min();
+    // and foreign cash on a line of its own
+    let foreign = portfolio
+        .foreign_cash
+        .iter()
+        .filter_map(|held| {
+            let currency = app_state.fx.find(&held.code)?;
+            Some(currency.currency.format(held.amount, 2))
+        })
+        .collect::<Vec<String>>();
+    let [cash_area, positions_area] = Layout::vertical([
+        Length(1 + next_settlement.is_some() as u16 + !foreign.is_empty() as u16),
+        Fill(1),
+    ])
+    .areas(inner_area);
+    let mut cash_lines = vec![Line::from(vec![
+        format!("{0} ", tr("Cash")).fg(Color::Gray),
+        money(portfolio.cash, currency).fg(Color::White).bold(),
+        format!("  {0}", tr("$ ledger  w accounts")).fg(Color::DarkGray),
+    ])];
+    if let Some(settles) = next_settlement {
+        cash_lines.push(Line::from(
+            trf(
+                "Settled {0}, {1} on {2}",
+                &[
+                    &money(portfolio.settled_cash(), currency),
+                    &money(portfolio.unsettled_cash(), currency),
+                    &settles.format("%-d %b"),
+                ],
+            )
+            .fg(Color::Gray),
+        ));
+    }
+    if !foreign.is_empty() {
+        cash_lines.push(Line::from(
+            trf("Foreign {0}", &[&foreign.join(" · ")]).fg(Color::Gray),
+        ));
+    }
+    frame.render_widget(Paragraph::new(cash_lines), cash_area);
+
+    let position_row = |label: String, quantity: u32, avg_cost: f64, price: f64, decimals| {
+        let pnl_pct = (price - avg_cost) / avg_cost * 100.0;
+        Row::new(vec![
+            Cell::from(label),
+            Cell::from(format!("{0:>6}", quantity)),
+            Cell::from(format!("{0:>9.1$}", avg_cost, decimals)),
+            Cell::from(format!(
+                "{0:>8}",
+                format!("{0}{1:.2}%", theme.change_arrow(pnl_pct), pnl_pct)
+            ))
+            .style(theme.change_style(pnl_pct)),
+        ])
+    };
+    let rows = portfolio
+        .positions
+        .iter()
+        .map(|position| {
+            let price = app_state
+                .quote_index(&position.ticker)
+                .map_or(position.avg_cost, |idx| app_state.quotes[idx].quote.price);
+            position_row(
+                position.ticker.clone(),
+                position.quantity,
+                position.avg_cost,
+                price,
+                app_state.price_decimals(&position.ticker),
+            )
+        })
+        .chain(portfolio.option_positions.iter().map(|position| {
+            let price = app_state
+                .option_price(&position.contract)
+                .unwrap_or(position.avg_cost);
+            position_row(
+                position.contract.short_label(app_state.ticks),
+                position.quantity,
+                position.avg_cost,
+                price,
+                2,
+            )
+        }))
+        .collect::<Vec<Row>>();
+    frame.render_widget(
+        Table::new(rows, [Length(16), Length(6), Length(9), Length(8)])
+            .column_spacing(1)
+            .header(
+                Row::new(vec![
+                    tr("Ticker").to_string(),
+                    format!("{0:>6}", tr("Qty")),
+                    format!("{0:>9}", tr("Avg cost")),
+                    format!("{0:>8}", tr("P&L")),
+                ])
+                .style(Style::new().fg(Color::Gray).italic()),
+            ),
+        positions_area,
+    );
+}
+
+fn draw_order_form(frame: &mut Frame, area: Rect, app_state: &AppState, form: &OrderForm) {
+    let price = app_state
+        .quote_index(&form.ticker)
+        .map(|idx| app_state.quotes[idx].quote.price);
+    let mut lines = vec![
+        Line::from(vec![
+            format!("{0:<12}", tr("Price")).fg(Color::Gray),
+            price
+                .map_or("-".to_string(), |price| {
+                    app_state
+                        .currency
+                        .format(price, app_state.price_decimals(&form.ticker))
+                })
+                .fg(Color::White),
+        ]),
+        Line::from(vec![
+            format!("{0:<12}", tr("Holding")).fg(Color::Gray),
+            format!("{0}", app_state.portfolio().quantity(&form.ticker)).fg(Color::White),
+        ]),
+        Line::from(""),
+    ];
+    lines.extend(form.form.lines());
+
+    let form_area = centered_rect(area, 44, lines.len() as u16 + 2);
+    frame.render_widget(Clear, form_area);
+    frame.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+            Block::bordered()
+                .title(match form.amending {
+                    Some(order_id) => trf(
+                        "Modify #{0} {1} {2}",
+                        &[&order_id, &tr(form.side.label()), &form.ticker],
+                    ),
+                    None => format!("{0} {1}", tr(form.side.label()), form.ticker),
+                })
+                .border_style(Style::default().fg(match form.side {
+                    Side::Buy => Color::Green,
+                    Side::Sell => Color::Red,
+                })),
+        ),
+        form_area,
+    );
+}
+
+fn draw_settings(frame: &mut Frame, area: Rect, form: &Form) {
+    let lines = form.lines();
+    let settings_area = centered_rect(area, 44, lines.len() as u16 + 2);
+    frame.render_widget(Clear, settings_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().title(tr("Settings"))),
+        settings_area,
+    );
+}
+
+// what the rows shown add up to, so a screen shows how its matches did together
+fn totals_line(app_state: &AppState, market_table: &MarketTable, theme: &Theme) -> Line<'static> {
+    let indices = market_table.indices(app_state);
+    let changes = indices
+        .iter()
+        .map(|idx| {
+            app_state
+                .change(&app_state.quotes[*idx], market_table.horizon)
+                .1
+        })
+        .collect::<Vec<f64>>();
+    let advancing = changes.iter().filter(|change| **change > 0.0).count();
+    let declining = changes.iter().filter(|change| **change < 0.0).count();
+    let average = changes.iter().sum::<f64>() / changes.len().max(1) as f64;
+    let mut spans = vec![
+        format!("{0} · ", trf("{0} shown", &[&indices.len()])).fg(Color::Gray),
+        Span::styled(format!("▲{0}", advancing), theme.change_style(1.0)),
+        " ".into(),
+        Span::styled(format!("▼{0}", declining), theme.change_style(-1.0)),
+        format!(" · {0} ", tr("avg")).fg(Color::Gray),
+        Span::styled(format!("{0:+.2}%", average), theme.change_style(average)),
+    ];
+    // in the currency of the tab's exchange, watching mixes exchanges so it's the home one
+    let caps = indices
+        .iter()
+        .filter_map(|idx| app_state.quotes[*idx].market_cap())
+        .collect::<Vec<f64>>();
+    if !caps.is_empty() {
+        let (cap, currency) = if market_table.watch {
+            (caps.iter().sum::<f64>(), &app_state.currency)
+        } else {
+            let exchange = &app_state.exchanges[market_table.exchange];
+            (
+                exchange.local_price(caps.iter().sum::<f64>()),
+                &exchange.currency,
+            )
+        };
+        spans.push(
+            format!(" · {0} {1}", tr("cap"), currency.around(&large(cap as u64))).fg(Color::Gray),
+        );
+    }
+    Line::from(spans)
+}
+
+// what the market table's prices are in, with the rate for foreign currencies
+fn exchange_line(app_state: &AppState, exchange: &Exchange) -> String {
+    if exchange.is_home() {
+        return trf(
+            "{0} · Prices in {1}",
+            &[&exchange.name, &exchange.currency_name_plural],
+        );
+    }
+    format!(
+        "{0} · {1} = {2}",
+        trf(
+            "{0} · Prices in {1}",
+            &[&exchange.name, &exchange.currency_name_plural],
+        ),
+        exchange.currency.around("1"),
+        app_state.currency.format(exchange.fx_rate, 3)
+    )
+}
+
+// the market table columns by their [display.columns] name, with the natural width,
+// None for the one filling the rest, and alignment
+pub const MARKET_COLUMNS: [(&str, Option<u16>, Alignment); 9] = [
+    ("ticker", Some(8), Alignment::Left),
+    ("name", Some(30), Alignment::Left),
+    ("price", Some(12), Alignment::Right),
+    ("change", Some(8), Alignment::Right),
+    ("net_change", Some(9), Alignment::Right),
+    ("volume", Some(6), Alignment::Right),
+    ("market_cap", Some(8), Alignment::Right),
+    ("rating", Some(6), Alignment::Left),
+    ("description", None, Alignment::Left),
+];
+const DESCRIPTION_COLUMN: usize = 8;
+// the composite index in the status bar, a character for every few ticks
+const INDEX_SPARKLINE_TICKS: usize = 60;
+const INDEX_SPARKLINE_WIDTH: usize = 20;
+// behind the rows of instruments flagged for a big move
+const MOVER_BACKGROUND: Color = Color::Indexed(58);
+// the high and low in a row's details are over this many candles
+const RANGE_CANDLES: usize = 52;
+// narrower than this and descriptions wrap into a column of single words
+const DESCRIPTION_MIN_WIDTH: u16 = 19;
+
+struct MarketColumns {
+    visible: Vec<usize>, // indices into MARKET_COLUMNS, the frozen ones and what's not scrolled off
+    widths: Vec<Constraint>,
+    alignments: Vec<Alignment>,
+    description_width: Option<u16>,
+    separator: Option<Color>, // a line under every row in this color
+    horizon: ChangeHorizon,   // what the change is since
+}
+
+// the columns laid out in the table area, with the config overrides applied
+fn market_columns(
+    columns: &HashMap<String, ColumnConfig>,
+    visible: Vec<usize>,
+    horizon: ChangeHorizon,
+    area: Rect,
+    theme: &Theme,
+) -> MarketColumns {
+    let column = |name: &str| columns.get(name).copied().unwrap_or_default();
+    let clamped = |width: u16, config: ColumnConfig| {
+        width
+            .max(config.min_width.unwrap_or(0))
+            .min(config.max_width.unwrap_or(u16::MAX))
+    };
+    let mut widths = visible
+        .iter()
+        .map(|idx| match MARKET_COLUMNS[*idx] {
+            (name, Some(width), _) => Constraint::Length(clamped(width, column(name))),
+            (_, None, _) => Constraint::Fill(1),
+        })
+        .collect::<Vec<Constraint>>();
+    let laid_out = Layout::horizontal(widths.clone())
+        .spacing(theme.cell_padding)
+        .split(area);
+    // the description gets what is left, and descriptions are wrapped to that
+    let description = column("description");
+    let description_pos = visible.iter().position(|idx| *idx == DESCRIPTION_COLUMN);
+    let description_width = description_pos.map(|pos| {
+        clamped(
+            laid_out[pos].width,
+            ColumnConfig {
+                min_width: description.min_width.or(Some(DESCRIPTION_MIN_WIDTH)),
+                ..description
+            },
+        )
+    });
+    if let (Some(pos), Some(width)) = (description_pos, description_width) {
+        widths[pos] = Constraint::Length(width);
+    }
+    MarketColumns {
+        alignments: visible
+            .iter()
+            .map(|idx| {
+                let (name, _, alignment) = MARKET_COLUMNS[*idx];
+                match column(name).align {
+                    Some(ColumnAlign::Left) => Alignment::Left,
+                    Some(ColumnAlign::Center) => Alignment::Center,
+                    Some(ColumnAlign::Right) => Alignment::Right,
+                    None => alignment,
+                }
+            })
+            .collect(),
+        visible,
+        widths,
+        description_width,
+        separator: theme.separator,
+        horizon,
+    }
+}
+
+// the market table's row of every instrument at the area's width, for the benches to time
+pub fn market_rows<'a>(app_state: &'a AppState, uistate: &UIState, area: Rect) -> Vec<Row<'a>> {
+    let market_table = &uistate.market_table;
+    let columns = market_columns(
+        &market_table.columns,
+        market_table.visible_columns(),
+        market_table.horizon,
+        area,
+        &uistate.theme,
+    );
+    app_state
+        .quotes
+        .iter()
+        .map(|quote| {
+            build_market_data_row(
+                quote,
+                app_state,
+                ticker_span(
+                    &uistate.accents,
+                    &uistate.compare_tickers,
+                    &quote.listing.ticker,
+                ),
+                &columns,
+                false,
+                &uistate.theme,
+                None,
+            )
+            .0
+        })
+        .collect()
+}
+
+// a ticker with its glyph and color, tickers marked for comparison wear their chart color instead
+fn ticker_span<'a>(
+    accents: &HashMap<String, Accent>,
+    compare_tickers: &[String],
+    ticker: &'a str,
+) -> Span<'a> {
+    let accent = accents.get(ticker).cloned().unwrap_or_default();
+    match compare_tickers.iter().position(|marked| marked == ticker) {
+        Some(slot) => accent.span(ticker).fg(COMPARE_COLORS[slot]).bold(),
+        None => accent.span(ticker),
+    }
+}
+
+fn build_market_data_row<'a>(
+    quote: &'a Instrument<'a>,
+    app_state: &AppState,
+    ticker: Span<'a>,
+    columns: &MarketColumns,
+    selected: bool,
+    theme: &Theme,
+    highlight: Option<Style>, // from the stripes, a big move and the format rules the quote matches
+) -> (Row<'a>, u16) {
+    let highlight = highlight.unwrap_or_default();
+    let rating = consensus(&app_state.analyst_notes, &quote.listing.ticker);
+    let distress = app_state.distress_level(&quote.listing.ticker);
+    let stale_secs = app_state.stale_secs(quote);
+    let exchange = &app_state.exchanges[quote.exchange];
+    // in the local currency, so abroad the exchange rate moves it too
+    let price = exchange.local_price(quote.quote.price);
+    let (change, percent_change) = app_state.change(quote, columns.horizon);
+
+    let mut description_lines = vec![];
+    if let Some(secs) = stale_secs {
+        description_lines.push(Line::styled(
+            trf("Stale · {0}s", &[&secs]),
+            Style::default().fg(Color::DarkGray).italic(),
+        ));
+    }
+    if let Some(level) = distress {
+        description_lines.push(Line::styled(
+            trf("Distressed · {0}", &[&tr(level.label())]),
+            Style::default().fg(Color::Red).bold(),
+        ));
+    }
+    if let PricingModel::Consol { yield_pct, .. } = quote.model {
+        description_lines.push(Line::styled(
+            trf("Yield {0}%", &[&format!("{0:.2}", yield_pct)]),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    description_lines.extend(
+        textwrap::wrap(
+            quote.listing.description.as_str(),
+            Options::new(columns.description_width.unwrap_or(1) as usize),
+        )
+        .iter()
+        .map(|s| Line::from(s.clone())),
+    );
+    let description_text = Text::from(description_lines);
+    // scrolled off, it doesn't make the rows any taller
+    let description_height = match columns.description_width {
+        Some(_) => description_text.lines.len() as u16,
+        None => 1,
+    };
+
+    let mut cells: [Text; 9] = [
+        // a clock marks an old quote
+        Line::from(vec![
+            ticker,
+            if app_state.quote_age(quote).is_some() {
+                " ◷".fg(Color::DarkGray)
+            } else {
+                "".into()
+            },
+        ])
+        .into(),
+        quote.listing.name.as_str().into(),
+        exchange
+            .currency
+            .format(price, quote.price_decimals())
+            .into(),
+        // the rule colors win over the usual ones
+        Text::styled(
+            format!(
+                "{0}{1:.2}%",
+                theme.change_arrow(percent_change),
+                percent_change
+            ),
+            theme.change_style(percent_change).patch(highlight),
+        ),
+        Text::styled(
+            format!("{0:+.1$}", change, quote.price_decimals()),
+            theme.change_style(percent_change).patch(highlight),
+        ),
+        large(quote.volume).into(),
+        quote
+            .market_cap()
+            .map_or(String::new(), |cap| large(exchange.local_price(cap) as u64))
+            .into(),
+        match rating {
+            Some(rating) => Text::styled(
+                tr(rating.label()),
+                Style::from(rating.color()).patch(highlight),
+            ),
+            None => "".into(),
+        },
+        description_text,
+    ];
+    let height = description_height + columns.separator.is_some() as u16;
+    let row = Row::new(
+        columns
+            .visible
+            .iter()
+            .map(|idx| std::mem::take(&mut cells[*idx]))
+            .zip(columns.alignments.iter().copied())
+            .zip(columns.widths.iter().copied())
+            .map(|((mut text, alignment), width)| {
+                // padded out to the next cell so the line only breaks at the spacing
+                if let (Some(color), Constraint::Length(width)) = (columns.separator, width) {
+                    text.lines
+                        .resize(description_height as usize, Line::default());
+                    text.lines.push(
+                        Line::from("─".repeat(width as usize))
+                            .fg(color)
+                            .alignment(Alignment::Left),
+                    );
+                }
+                Cell::from(text.alignment(alignment))
+            }),
+    )
+    .style(match (selected, stale_secs) {
+        (true, _) => Style::default().fg(Color::White).bg(Color::DarkGray),
+        // quotes that stopped coming in fade out
+        (false, Some(_)) => Style::default()
+            .fg(Color::Gray)
+            .add_modifier(Modifier::DIM)
+            .patch(highlight),
+        (false, None) => Style::default().fg(Color::White).patch(highlight),
+    })
+    .height(height);
+    (row, height)
+}
+
+// opened up under a row with Space, drawn over the table across its whole width
+fn expanded_lines(quote: &Instrument, app_state: &AppState) -> Vec<Line<'static>> {
+    let exchange = &app_state.exchanges[quote.exchange];
+    let decimals = quote.price_decimals();
+    let price = |price: f64| {
+        exchange
+            .currency
+            .format(exchange.local_price(price), decimals)
+    };
+    let quotes = match (quote.book.bids.first(), quote.book.asks.first()) {
+        (Some(bid), Some(ask)) => format!("{0} / {1}", price(bid.price), price(ask.price)),
+        _ => "-".to_string(),
+    };
+    let candles = quote.history.candles();
+    let candles = &candles[candles.len().saturating_sub(RANGE_CANDLES)..];
+    let range = match candles.is_empty() {
+        true => "-".to_string(),
+        false => format!(
+            "{0} – {1}",
+            price(
+                candles
+                    .iter()
+                    .map(|candle| candle.low)
+                    .fold(f64::MAX, f64::min)
+            ),
+            price(
+                candles
+                    .iter()
+                    .map(|candle| candle.high)
+                    .fold(f64::MIN, f64::max)
+            ),
+        ),
+    };
+    // there's no calendar of reports, so the last one is what there is to go by
+    let earnings = match app_state.news.iter().find(|news_item| {
+        news_item.category == NewsCategory::Earnings && news_item.mentions(&quote.listing.ticker)
+    }) {
+        Some(news_item) => trf(
+            "not scheduled, last {0}",
+            &[&app_state
+                .time_format
+                .relative(news_item.published, Utc::now())],
+        ),
+        None => tr("not scheduled").to_string(),
+    };
+    [
+        (tr("Bid/Ask").to_string(), quotes),
+        (tr("Volume").to_string(), large(quote.volume)),
+        (trf("{0} range", &[&RANGE_CANDLES]), range),
+        (tr("Earnings").to_string(), earnings),
+    ]
+    .into_iter()
+    .map(|(label, value)| {
+        Line::from(vec![
+            format!("  {0:<12}", label).fg(Color::Gray),
+            value.fg(Color::White),
+        ])
+    })
+    .collect()
+}
+
+// link health in the status bar, only shown when the connection is simulated as flaky
+fn connection_line(connection: &Connection) -> Line<'static> {
+    if !connection.flaky {
+        return Line::default();
+    }
+    Line::from(match connection.state {
+        LinkState::Connected => format!("● {0} ms", connection.latency_ms).fg(Color::Green),
+        LinkState::Degraded => format!(
+            "◐ {0} {1} ms",
+            tr(connection.state.label()),
+            connection.latency_ms
+        )
+        .fg(Color::Yellow),
+        LinkState::Disconnected => format!(
+            "○ {0} {1}s",
+            tr(connection.state.label()),
+            connection
+                .down_since
+                .map_or(0, |since| (Utc::now() - since).num_seconds())
+        )
+        .fg(Color::Red)
+        .bold(),
+    })
+}
+
+// "up 2.30 percent", spelled out for screen readers
+fn change_words(percent: f64) -> String {
+    if percent.abs() < 0.005 {
+        tr("unchanged").to_string()
+    } else if percent > 0.0 {
+        trf("up {0} percent", &[&format!("{0:.2}", percent)])
+    } else {
+        trf("down {0} percent", &[&format!("{0:.2}", -percent)])
+    }
+}
+
+// one line per item, the selected one marked with ">" when its section has focus
+fn linear_section<'a>(
+    heading: String,
+    items: Vec<String>,
+    scroll_pos: usize,
+    focused: bool,
+    rows: u16,
+) -> Vec<Line<'a>> {
+    let heading = if focused {
+        trf("{0} (focused)", &[&heading])
+    } else {
+        heading
+    };
+    let mut lines = vec![Line::from(heading).bold()];
+    lines.extend(
+        items
+            .into_iter()
+            .enumerate()
+            .skip(scroll_pos)
+            .take(rows.saturating_sub(1) as usize)
+            .map(|(pos, item)| {
+                if focused && pos == scroll_pos {
+                    Line::from(format!("> {0}", item)).bold()
+                } else {
+                    Line::from(format!("  {0}", item))
+                }
+            }),
+    );
+    lines
+}
+
+// for screen readers, plain labelled lines in sections that stay where they are
+fn draw_linear(frame: &mut Frame, app_state: &AppState, uistate: &UIState) {
+    use Constraint::{Fill, Length};
+
+    let home = &app_state.exchanges[0];
+    let exchange = &app_state.exchanges[uistate.market_table.exchange];
+    let panel = uistate.market_data_active_panel;
+    let all_accounts = uistate.portfolio_panel.all_accounts;
+    let portfolio = shown_portfolio(app_state, all_accounts);
+    let [status_area, market_area, orders_area, portfolio_area, news_area, focus_area, keys_area] =
+        Layout::vertical([
+            Length(3),
+            Fill(1),
+            Length(5),
+            Length(5),
+            Length(6),
+            Length(3),
+            Length(2),
+        ])
+        .areas(frame.area());
+
+    let mut value_text = trf(
+        "{0}Portfolio value {1} {3}, cash {2} {3}.",
+        &[
+            &shown_account(app_state, all_accounts)
+                .map_or(String::new(), |account| format!("{0}: ", account)),
+            &format!("{0:.2}", app_state.value_of(&portfolio)),
+            &format!("{0:.2}", portfolio.cash),
+            &home.currency_name_plural,
+        ],
+    );
+    if !portfolio.unsettled.is_empty() {
+        value_text.push_str(&trf(
+            " {0} {1} of it unsettled.",
+            &[
+                &format!("{0:.2}", portfolio.unsettled_cash()),
+                &home.currency_name_plural,
+            ],
+        ));
+    }
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(trf(
+                "Iron Ledger. {0} {1}. Connection {2}. {3} unread news.",
+                &[
+                    &exchange.name,
+                    &tr(if app_state.exchange_open(uistate.market_table.exchange) {
+                        "open"
+                    } else {
+                        "closed"
+                    }),
+                    &tr(app_state.connection.state.label()),
+                    &app_state.unread_news_count(),
+                ],
+            )),
+            Line::from(value_text),
+            // the toasts of the other layout
+            Line::from(
+                [
+                    achievement_toast_text(app_state),
+                    undo_toast_text(app_state),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<String>>()
+                .join(". "),
+            ),
+        ]),
+        status_area,
+    );
+
+    let market_tab_indices = uistate.market_table.indices(app_state);
+    let quotes = market_tab_indices
+        .iter()
+        .map(|idx| {
+            let quote = &app_state.quotes[*idx];
+            let price = exchange.local_price(quote.quote.price);
+            let price_yesterday = exchange.local_price_yesterday(quote.quote.price_yesterday);
+            let mut item = trf(
+                "{0} price {1} {2}, {3}, volume {4}",
+                &[
+                    &quote.listing.ticker,
+                    &format!("{0:.1$}", price, quote.price_decimals()),
+                    &exchange.currency_name_plural,
+                    &change_words((price - price_yesterday) / price_yesterday * 100.0),
+                    &large(quote.volume),
+                ],
+            );
+            if let Some(rating) = consensus(&app_state.analyst_notes, &quote.listing.ticker) {
+                item.push_str(&trf(", rated {0}", &[&tr(rating.label())]));
+            }
+            if let Some(secs) = app_state.stale_secs(quote) {
+                item.push_str(&trf(", stale for {0} seconds", &[&secs]));
+            }
+            item
+        })
+        .collect::<Vec<String>>();
+    frame.render_widget(
+        Paragraph::new(linear_section(
+            trf(
+                "Market, {0} on {1}, {2} instruments",
+                &[
+                    &tr(uistate.market_table.tab.label()),
+                    &exchange.code,
+                    &quotes.len(),
+                ],
+            ),
+            quotes,
+            uistate.market_table.scroll.pos,
+            panel == MarketDataActivePanel::MarketData,
+            market_area.height,
+        )),
+        market_area,
+    );
+
+    let orders = app_state
+        .orders
+        .iter()
+        .map(|order| {
+            let decimals = app_state.price_decimals(&order.ticker);
+            let status = match &order.status {
+                OrderStatus::Working if order.stop_price.is_some() && !order.stop_triggered => {
+                    tr("stop armed").to_string()
+                }
+                OrderStatus::Working => tr("working").to_string(),
+                OrderStatus::Filled => tr("filled").to_string(),
+                OrderStatus::Cancelled(reason) => trf("cancelled, {0}", &[reason]),
+            };
+            trf(
+                "Order {0}, {1} {2} {3}, {4}, {5} of {2} filled, {6}",
+                &[
+                    &order.id,
+                    &tr(order.side.label()).to_lowercase(),
+                    &order.quantity,
+                    &order.ticker,
+                    &match (order.stop_price, order.limit_price) {
+                        (Some(stop), _) => trf("stop {0}", &[&format!("{0:.1$}", stop, decimals)]),
+                        (None, Some(price)) => {
+                            trf("limit {0}", &[&format!("{0:.1$}", price, decimals)])
+                        }
+                        (None, None) => tr("market").to_string(),
+                    },
+                    &order.filled,
+                    &status,
+                ],
+            )
+        })
+        .collect::<Vec<String>>();
+    frame.render_widget(
+        Paragraph::new(linear_section(
+            trf("Orders, {0}", &[&orders.len()]),
+            orders,
+            uistate.orders_table.scroll.pos,
+            panel == MarketDataActivePanel::Orders && !uistate.orders_table.show_blotter,
+            orders_area.height,
+        )),
+        orders_area,
+    );
+
+    let positions = portfolio
+        .positions
+        .iter()
+        .map(|position| {
+            let price = app_state
+                .quote_index(&position.ticker)
+                .map_or(position.avg_cost, |idx| app_state.quotes[idx].quote.price);
+            trf(
+                "{0}, {1} held, average cost {2}, {3}",
+                &[
+                    &position.ticker,
+                    &position.quantity,
+                    &format!(
+                        "{0:.1$}",
+                        position.avg_cost,
+                        app_state.price_decimals(&position.ticker)
+                    ),
+                    &change_words((price - position.avg_cost) / position.avg_cost * 100.0),
+                ],
+            )
+        })
+        .collect::<Vec<String>>();
+    frame.render_widget(
+        Paragraph::new(linear_section(
+            trf("Positions, {0}", &[&positions.len()]),
+            positions,
+            0,
+            false,
+            portfolio_area.height,
+        )),
+        portfolio_area,
+    );
+
+    let now = Utc::now();
+    let visible_news = uistate.news_list.visible(app_state);
+    let news = visible_news
+        .iter()
+        .map(|news_item| {
+            format!(
+                "{0}{1}. {2}, {3}, {4}",
+                if news_item.seen { "" } else { tr("Unread. ") },
+                news_item.title,
+                tr(news_item.category.label()),
+                tr(if news_item.sentiment > 0.0 {
+                    "positive"
+                } else if news_item.sentiment < 0.0 {
+                    "negative"
+                } else {
+                    "neutral"
+                }),
+                app_state.time_format.relative(news_item.published, now)
+            )
+        })
+        .collect::<Vec<String>>();
+    frame.render_widget(
+        Paragraph::new(linear_section(
+            trf("Latest news, {0}", &[&news.len()]),
+            news,
+            uistate.news_list.scroll.pos,
+            panel == MarketDataActivePanel::LatestNews,
+            news_area.height,
+        )),
+        news_area,
+    );
+
+    // what an open form or detail view would show, other screens are drawn as usual on top
+    let focus = if let Some(form) = &uistate.order_form {
+        vec![
+            trf(
+                "{0} order for {1}.",
+                &[&tr(form.side.label()), &form.ticker],
+            ),
+            form.form.summary(),
+            form.form.error.clone().unwrap_or_default(),
+        ]
+    } else if let Some(form) = &uistate.settings {
+        vec![
+            tr("Settings").to_string(),
+            form.summary(),
+            form.error.clone().unwrap_or_default(),
+        ]
+    } else if let Some(form) = &uistate.schedule_form {
+        vec![
+            trf("Schedule orders of {0}", &[&form.ticker]),
+            form.form.summary(),
+            form.form.error.clone().unwrap_or_default(),
+        ]
+    } else if let Some(form) = &uistate.alert_form {
+        vec![
+            trf("Alert on {0}", &[&form.ticker]),
+            form.form.summary(),
+            form.form.error.clone().unwrap_or_default(),
+        ]
+    } else if let Some((view, form)) = uistate
+        .accounts
+        .as_ref()
+        .and_then(|view| Some((view, view.transfer.as_ref()?)))
+    {
+        vec![
+            transfer_title(app_state, view),
+            form.summary(),
+            form.error.clone().unwrap_or_default(),
+        ]
+    } else if let Some((view, (_, form))) = uistate
+        .fx
+        .as_ref()
+        .and_then(|view| Some((view, view.form.as_ref()?)))
+    {
+        vec![
+            fx_form_title(app_state, view),
+            form.summary(),
+            form.error.clone().unwrap_or_default(),
+        ]
+    } else if let Some((_, question, _)) = uistate
+        .confirm
+        .as_ref()
+        .and_then(|confirm| confirm_text(app_state, confirm))
+    {
+        vec![trf("{0} y or n.", &[&question])]
+    } else if let Some(quote) = uistate
+        .instrument_detail
+        .as_deref()
+        .and_then(|ticker| app_state.quote_index(ticker))
+        .map(|idx| &app_state.quotes[idx])
+    {
+        vec![
+            trf(
+                "Details of {0}, {1}, {2}.",
+                &[
+                    &quote.listing.name,
+                    &quote.listing.ticker,
+                    &tr(quote.class().label()),
+                ],
+            ),
+            quote.listing.description.clone(),
+        ]
+    } else {
+        vec![]
+    };
+    frame.render_widget(
+        Paragraph::new(focus.into_iter().map(Line::from).collect::<Vec<Line>>())
+            .wrap(Wrap { trim: true }),
+        focus_area,
+    );
+    frame.render_widget(
+        Paragraph::new(format!(
+            "{0}: {1}.",
+            tr("Keys"),
+            key_hints(app_state, uistate).join(", ")
+        ))
+        .wrap(Wrap { trim: true }),
+        keys_area,
+    );
+    let areas = OverlayAreas {
+        main: frame.area(),
+        market: frame.area(),
+        orders: frame.area(),
+        news: news_area,
+    };
+    for overlay in Overlay::open(uistate) {
+        match overlay {
+            // read out in the focus line instead
+            Overlay::InstrumentDetail(_) | Overlay::OrderForm(_) | Overlay::Confirm(_) => {}
+            _ => draw_overlay(frame, &areas, app_state, uistate, &overlay, false),
+        }
+    }
+}
+
+// the keys that do something right now, for the status bar
+fn key_hints(app_state: &AppState, uistate: &UIState) -> Vec<String> {
+    let key_hints = if let Some((_, _, yes)) = uistate
+        .confirm
+        .as_ref()
+        .and_then(|confirm| confirm_text(app_state, confirm))
+    {
+        vec![yes, "n/Esc - Keep"]
+    } else if let Some(view) = &uistate.report_view {
+        if view.quitting {
+            vec![
+                "m/h - Save Markdown/HTML",
+                "c - Capital Gains CSV",
+                "q/Enter - Quit",
+                "Esc - Back",
+            ]
+        } else {
+            vec![
+                "m/h - Save Markdown/HTML",
+                "c - Capital Gains CSV",
+                "Esc/R - Close",
+            ]
+        }
+    } else if uistate.row_menu.is_some() {
+        vec!["↑↓ - Move", "Enter - Choose", "Esc/M - Close"]
+    } else if uistate.options_view.is_some() {
+        vec![
+            "↑↓ - Strike",
+            "←→ - Expiry",
+            "Tab - Call/Put",
+            "b/s - Buy/Sell 1",
+            "Esc/O - Close",
+        ]
+    } else if uistate.order_form.is_none() && uistate.instrument_detail.is_some() {
+        let mut key_hints = vec![if uistate.instrument_detail_view == DetailView::Chart {
+            "c - Details"
+        } else {
+            "c - Chart"
+        }];
+        if uistate.instrument_detail_view == DetailView::Chart {
+            key_hints.extend([
+                "+/- - Zoom",
+                if uistate.chart.cursor.is_some() {
+                    "←→ - Move Cursor"
+                } else {
+                    "←→ - Pan"
+                },
+                "x - Crosshair",
+                "t - Timeframe",
+            ]);
+        }
+        key_hints.extend([
+            if uistate.instrument_detail_view == DetailView::Depth {
+                "d - Details"
+            } else {
+                "d - Depth Chart"
+            },
+            if uistate.instrument_detail_view == DetailView::Returns {
+                "r - Details"
+            } else {
+                "r - Returns"
+            },
+            "b/s - Buy/Sell",
+            "Enter/m - Actions",
+            "Esc - Close",
+        ]);
+        key_hints
+    } else if let Some(form) = &uistate.order_form {
+        let mut key_hints = vec!["Tab - Next Field", "↑↓ - Step", "Enter - Submit"];
+        if form.amending.is_none() {
+            key_hints.push(if uistate.enhanced_keys {
+                "Ctrl-Enter - Submit, Keep Open"
+            } else {
+                "Alt-Enter - Submit, Keep Open"
+            });
+        }
+        key_hints.push("Esc - Cancel");
+        key_hints
+    } else if uistate.settings.is_some() {
+        vec![
+            "Tab - Next Field",
+            "←→ - Change",
+            "Space - Toggle",
+            "Enter - Apply",
+            "Esc - Cancel",
+        ]
+    } else if uistate.schedule_form.is_some() || uistate.alert_form.is_some() {
+        vec![
+            "Tab - Next Field",
+            "←→ - Change",
+            "↑↓ - Step",
+            "Enter - Set",
+            "Esc - Cancel",
+        ]
+    } else if uistate.schedules_open.is_some() {
+        vec![
+            "↑↓ - Move",
+            "p - Pause/Resume",
+            "d - Delete",
+            "Esc/W - Close",
+        ]
+    } else if let Some(view) = &uistate.rebalance {
+        if view.staged.is_some() {
+            vec!["Enter - Place All", "Esc - Back"]
+        } else {
+            vec!["s - Stage Orders", "Esc/B - Close"]
+        }
+    } else if uistate.movers_open.is_some() {
+        vec!["↑↓ - Move", "Enter - Details", "Esc - Close"]
+    } else if uistate.alerts_open.is_some() {
+        vec![
+            "↑↓ - Move",
+            "e - Edit",
+            "d - Delete",
+            "a - Acknowledge",
+            "Esc/! - Close",
+        ]
+    } else if uistate.news_list.search_editing {
+        vec!["Enter - Done", "Esc - Clear Search"]
+    } else if uistate.market_table.screen_editing {
+        vec!["Enter - Done", "Esc - Clear Screen"]
+    } else if uistate.news_filter_menu.is_some() {
+        vec!["↑↓ - Move", "Space - Toggle", "Esc/F - Close"]
+    } else if uistate
+        .saves_menu
+        .as_ref()
+        .is_some_and(|menu| menu.naming.is_some())
+    {
+        vec!["Enter - Save", "Esc - Cancel"]
+    } else if uistate.saves_menu.is_some() {
+        vec![
+            "↑↓ - Move",
+            "Enter - Load/New",
+            "s - Overwrite",
+            "n - New Game",
+            "Esc/S - Close",
+        ]
+    } else if uistate.compare_open {
+        vec!["Esc/C - Close"]
+    } else if uistate.achievements_open {
+        vec!["Esc/G - Close"]
+    } else if uistate
+        .accounts
+        .as_ref()
+        .is_some_and(|view| view.transfer.is_some())
+    {
+        vec!["Enter - Move", "Esc - Cancel"]
+    } else if let Some(view) = &uistate.accounts {
+        let mut key_hints = vec!["↑↓ - Move", "Enter - Trade Here"];
+        if view.cursor != app_state.active {
+            key_hints.push("t - Move Cash Here");
+        }
+        key_hints.extend([
+            if uistate.portfolio_panel.all_accounts {
+                "a - Active Account"
+            } else {
+                "a - All Accounts"
+            },
+            "Esc/w - Close",
+        ]);
+        key_hints
+    } else if uistate.fx.as_ref().is_some_and(|view| view.form.is_some()) {
+        vec!["Enter - Change", "Esc - Cancel"]
+    } else if uistate.fx.is_some() {
+        vec!["↑↓ - Move", "b - Buy", "s - Sell", "Esc/K - Close"]
+    } else if uistate.ledger.is_some() {
+        vec![
+            "↑↓ - Scroll",
+            "f - Filter",
+            "x - Export CSV",
+            "Esc/$ - Close",
+        ]
+    } else if uistate.connection_open {
+        let mut key_hints = vec!["r - Reconnect"];
+        if app_state.connection.sources.len() > 1 {
+            key_hints.push("Tab - Switch Source");
+        }
+        key_hints.push("Esc/L - Close");
+        key_hints
+    } else if let Some(idx) = uistate.panel_open {
+        let mut key_hints = uistate.panels[idx].key_hints();
+        if uistate.panels.len() > 1 {
+            key_hints.push("Tab - Next Panel");
+        }
+        key_hints.push("Esc/V - Close");
+        key_hints
+    } else if uistate.news_archive_page.is_some() {
+        vec![
+            "←→ - Page",
+            "Home/End - First/Last",
+            "/ - Search",
+            "F - Categories",
+            "Esc/A - Close",
+        ]
+    } else if uistate.help.is_some() {
+        vec!["↑↓ - Scroll", "Esc - Close"]
+    } else {
+        return normal_key_hints(app_state, uistate);
+    };
+    key_hints
+        .into_iter()
+        .map(|hint| tr(hint).to_string())
+        .collect()
+}
+
+// what the keymap binds for the focused panel and anywhere, as far as it does something now
+fn normal_key_hints(app_state: &AppState, uistate: &UIState) -> Vec<String> {
+    let panel = uistate.market_data_active_panel;
+    let watch = uistate.market_table.watch;
+    let mut key_hints = vec!["↑↓ - Scroll Up/Down"];
+    // the panel's own keys, nothing can be bound over them
+    match panel {
+        MarketDataActivePanel::MarketData if !watch => key_hints.extend([
+            "Tab - Asset Class",
+            "e - Exchange",
+            "Shift-←→ - Columns",
+            "Space - Expand",
+            "% - Change Since",
+        ]),
+        MarketDataActivePanel::Orders => key_hints.push("h - History"),
+        _ => {}
+    }
+    let mut key_hints = key_hints
+        .into_iter()
+        .map(|hint| tr(hint).to_string())
+        .collect::<Vec<String>>();
+    let link = uistate
+        .news_list
+        .visible(app_state)
+        .get(uistate.news_list.scroll.pos)
+        .is_some_and(|news_item| news_item.link.is_some());
+    for action in uistate.keymap.footer(keymap::Context::of(panel)) {
+        let shown = match action {
+            // the watch layout has the market panel only and none of the windows
+            _ if watch => matches!(
+                action,
+                Action::Details
+                    | Action::Buy
+                    | Action::Sell
+                    | Action::RowMenu
+                    | Action::Screen
+                    | Action::Report
+                    | Action::Quit
+            ),
+            Action::Compare => !uistate.compare_tickers.is_empty(),
+            Action::Options | Action::ByCompany => {
+                uistate.market_table.tab == InstrumentClass::Equity
+            }
+            Action::ClearHistory => {
+                uistate.orders_table.show_blotter && !app_state.blotter.is_empty()
+            }
+            Action::OpenLink => link,
+            Action::Panels => !uistate.panels.is_empty(),
+            Action::Play => !uistate.macros.is_empty(),
+            Action::Redo => uistate.history.can_redo(),
+            _ => true,
+        };
+        let label = match action {
+            Action::Record if uistate.macros.recording.is_some() => "Stop Recording",
+            _ => action.label(),
+        };
+        if let Some(hint) = uistate.keymap.hint(action, label).filter(|_| shown) {
+            key_hints.push(hint);
+        }
+    }
+    key_hints
+}
+
+// every binding by where it works, for the help
+pub fn help_lines(keymap: &Keymap) -> Vec<Line<'static>> {
+    keymap::Context::ALL
+        .iter()
+        .flat_map(|context| {
+            let mut lines = vec![Line::from(tr(context.label()).fg(Color::White).bold())];
+            lines.extend(keymap.all(*context).into_iter().map(|action| {
+                let keys = keymap
+                    .keys(action)
+                    .iter()
+                    .map(|key| key.label())
+                    .collect::<Vec<String>>();
+                Line::from(vec![
+                    format!("  {0:<16}", keys.join(" ")).fg(Color::Cyan),
+                    tr(action.label()).fg(Color::Gray),
+                ])
+            }));
+            lines.push(Line::from(""));
+            lines
+        })
+        .collect()
+}
+
+// focused panels get a cyan border and scrollbar
+fn focus_style(ctx: &Context, panel: MarketDataActivePanel) -> Style {
+    if ctx.focus == panel {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default()
+    }
+}
+
+pub fn draw_market_table(frame: &mut Frame, area: Rect, market_table: &MarketTable, ctx: &Context) {
+    use Constraint::{Fill, Length};
+
+    let app_state = ctx.app_state;
+    let border_style = focus_style(ctx, MarketDataActivePanel::MarketData);
+    let mut market_data_title = vec!["The Iron Ledger ".into()];
+    // watching, there's just the one tab
+    if market_table.watch {
+        market_data_title.push(format!(" {0} ", tr("watching")).fg(Color::Gray));
+    } else {
+        for (idx, exchange) in app_state.exchanges.iter().enumerate() {
+            market_data_title.push(if idx == market_table.exchange {
+                format!(" {0} ", exchange.code)
+                    .bg(Color::Yellow)
+                    .fg(Color::Black)
+                    .bold()
+            } else {
+                format!(" {0} ", exchange.code).fg(Color::Gray)
+            });
+        }
+        market_data_title.push(" ".into());
+        for class in InstrumentClass::ALL {
+            market_data_title.push(if class == market_table.tab {
+                format!(" {0} ", tr(class.label()))
+                    .bg(Color::Cyan)
+                    .fg(Color::Black)
+                    .bold()
+            } else {
+                format!(" {0} ", tr(class.label())).fg(Color::Gray)
+            });
+        }
+    }
+    if !app_state.exchange_open(market_table.exchange) {
+        market_data_title.push(" ".into());
+        market_data_title.push(
+            format!(" {0} ", tr("market closed"))
+                .bg(Color::Red)
+                .fg(Color::White)
+                .bold(),
+        );
+    }
+    let market_data_block = Block::bordered()
+        .title(Line::from(market_data_title))
+        .border_style(border_style);
+    let [market_data_table_area, market_data_totals_area, market_data_status_area] =
+        Layout::vertical([Fill(1), Length(market_table.totals as u16), Length(1)])
+            .areas(market_data_block.inner(area));
+
+    let theme = ctx.theme;
+    let market_columns = market_columns(
+        &market_table.columns,
+        market_table.visible_columns(),
+        market_table.horizon,
+        market_data_table_area,
+        theme,
+    );
+
+    let market_data_active = ctx.focus == MarketDataActivePanel::MarketData;
+    let market_tab_indices = market_table.indices(app_state);
+    // how far down the table the open row's details go, under the header and its margin
+    let mut top: u16 = 2;
+    let mut expanded = None;
+    let rows = market_tab_indices
+        .iter()
+        .enumerate()
+        .skip(market_table.scroll.pos)
+        .flat_map(|(pos, idx)| {
+            let quote = &app_state.quotes[*idx];
+            let (row, height) = build_market_data_row(
+                quote,
+                app_state,
+                ticker_span(ctx.accents, ctx.compare_tickers, &quote.listing.ticker),
+                &market_columns,
+                market_data_active && pos == market_table.scroll.pos,
+                theme,
+                // stripes go under the big movers and those under whatever the rules say
+                theme
+                    .stripe
+                    .filter(|_| pos % 2 == 1)
+                    .map(|stripe| Style::default().bg(stripe))
+                    .into_iter()
+                    .chain(
+                        app_state
+                            .movers
+                            .get(&quote.listing.ticker)
+                            .map(|_| Style::default().bg(MOVER_BACKGROUND).bold()),
+                    )
+                    .chain(rule_style(&market_table.format_rules, quote))
+                    .reduce(|style, next| style.patch(next)),
+            );
+            top = top.saturating_add(height);
+            // a blank row for the details pushes the rows under it down
+            let details =
+                (market_table.expanded.as_ref() == Some(&quote.listing.ticker)).then(|| {
+                    let lines = expanded_lines(quote, app_state);
+                    let height = lines.len() as u16;
+                    expanded = Some((top, lines));
+                    top = top.saturating_add(height);
+                    Row::default().height(height)
+                });
+            std::iter::once(row).chain(details)
+        });
+
+    let titles = [
+        "Ticker",
+        "Name",
+        "Price",
+        market_table.horizon.label(),
+        "Change",
+        "Volume",
+        "Mkt Cap",
+        "Rating",
+        "Description",
+    ];
+    // the header is the table's own, so it stays put whatever the rows do
+    let table = Table::new(rows, market_columns.widths.clone())
+        .column_spacing(theme.cell_padding)
+        .header(
+            Row::new(
+                market_columns
+                    .visible
+                    .iter()
+                    .zip(market_columns.alignments.iter())
+                    .map(|(idx, alignment)| {
+                        Cell::from(Line::from(tr(titles[*idx])).alignment(*alignment))
+                    }),
+            )
+            .style(Style::new().fg(Color::Gray).italic())
+            .bottom_margin(1),
+        );
+
+    frame.render_widget(market_data_block, area);
+    frame.render_widget(table, market_data_table_area);
+    if let Some((top, lines)) = expanded {
+        let details_area = Rect {
+            y: market_data_table_area.y.saturating_add(top),
+            height: lines.len() as u16,
+            ..market_data_table_area
+        }
+        .intersection(market_data_table_area);
+        frame.render_widget(Paragraph::new(lines), details_area);
+    }
+    // under the header and its margin
+    let viewport = market_data_table_area.height.saturating_sub(2) as usize;
+    market_table.scroll.render(
+        frame,
+        area,
+        market_tab_indices.len(),
+        viewport,
+        border_style,
+    );
+    if market_table.totals {
+        frame.render_widget(
+            totals_line(app_state, market_table, theme),
+            market_data_totals_area,
+        );
+    }
+    frame.render_widget(
+        if market_table.screen.is_some() || market_table.screen_editing {
+            screen_line(market_table)
+        } else {
+            Line::styled(
+                exchange_line(app_state, &app_state.exchanges[market_table.exchange]),
+                (Color::Gray, Modifier::ITALIC),
+            )
+        }
+        .alignment(Alignment::Left),
+        market_data_status_area,
+    );
+}
+
+// headlines with a link are left in ctx.hyperlinks
+pub fn draw_news_list(frame: &mut Frame, area: Rect, news_list: &NewsList, ctx: &Context) {
+    use Constraint::{Fill, Length};
+
+    let app_state = ctx.app_state;
+    let border_style = focus_style(ctx, MarketDataActivePanel::LatestNews);
+    let unread_news_count = app_state.unread_news_count();
+    let mut latest_news_title = vec![match &news_list.ticker_filter {
+        Some(ticker) => format!("{0} · {1}", tr("Latest news"), ticker).into(),
+        None => tr("Latest news").into(),
+    }];
+    if let Some(query) = &news_list.search {
+        latest_news_title.push(format!(" · /{0}", query.text).into());
+    }
+    if !app_state.offline_feeds.is_empty() {
+        latest_news_title.push(" ".into());
+        latest_news_title.push(
+            format!(
+                " {0} ",
+                trf("{0} offline", &[&app_state.offline_feeds.join(", ")])
+            )
+            .bg(Color::Red)
+            .fg(Color::White)
+            .bold(),
+        );
+    }
+    if unread_news_count > 0 {
+        latest_news_title.push(" ".into());
+        latest_news_title.push(
+            format!(" {0} ", trf("{0} unread", &[&unread_news_count]))
+                .bg(Color::Yellow)
+                .fg(Color::Black)
+                .bold(),
+        );
+    }
+    let latest_news_block = Block::bordered()
+        .title(Line::from(latest_news_title))
+        .border_style(border_style);
+    let latest_news_inner_area = latest_news_block.inner(area);
+    let searching = news_list.search_editing && !ctx.news_archive;
+    let [latest_news_search_area, latest_news_list_area] = if searching {
+        Layout::vertical([Length(2), Fill(1)]).areas(latest_news_inner_area)
+    } else {
+        [Rect::default(), latest_news_inner_area]
+    };
+
+    let latest_news_active = ctx.focus == MarketDataActivePanel::LatestNews;
+    let visible_news = news_list.visible(app_state);
+    let now = Utc::now();
+    let mut news_links = ctx.hyperlinks.borrow_mut();
+    let mut news_rows = 0;
+    let news = Paragraph::new(
+        visible_news
+            .iter()
+            .enumerate()
+            .skip(news_list.scroll.pos)
+            .flat_map(|(idx, news_item)| {
+                let title = Line::from(news_item.title.as_str()).style(
+                    if latest_news_active && idx == news_list.scroll.pos {
+                        Style::default().fg(Color::Cyan).bold()
+                    } else if news_item.seen {
+                        Style::default().fg(Color::White)
+                    } else {
+                        Style::default().fg(Color::White).bold()
+                    },
+                );
+                let mut tags = news_tags(news_item, ctx.theme, ctx.accents);
+                tags.push(
+                    app_state
+                        .time_format
+                        .relative(news_item.published, now)
+                        .fg(Color::Gray),
+                );
+                let tags = Line::from(tags);
+                let subtitle = Line::from(news_item.subtitle.as_str());
+                let lines = vec![title, tags, subtitle, Line::from("")];
+                // lines wrap on their own, so the headline's rows can be counted up front
+                let rows = |line: &Line| {
+                    Paragraph::new(line.clone())
+                        .wrap(Wrap { trim: true })
+                        .line_count(latest_news_list_area.width) as u16
+                };
+                if news_rows < latest_news_list_area.height {
+                    if let Some(link) = &news_item.link {
+                        news_links.push(Hyperlink {
+                            area: Rect {
+                                y: latest_news_list_area.y + news_rows,
+                                height: rows(&lines[0])
+                                    .min(latest_news_list_area.height - news_rows),
+                                ..latest_news_list_area
+                            },
+                            url: link.clone(),
+                        });
+                    }
+                    news_rows += lines.iter().map(rows).sum::<u16>();
+                }
+                lines
+            })
+            .collect::<Vec<Line>>(),
+    )
+    .wrap(Wrap { trim: true });
+
+    frame.render_widget(latest_news_block, area);
+    frame.render_widget(news, latest_news_list_area);
+    if searching {
+        frame.render_widget(search_line(news_list), latest_news_search_area);
+    }
+    // headlines take four rows at least, so this many of them fit at most
+    let viewport = (latest_news_list_area.height / 4) as usize;
+    news_list
+        .scroll
+        .render(frame, area, visible_news.len(), viewport, border_style);
+}
+
+// the composite index over the last ticks, whatever is open, in the color of where it went
+fn index_line<'a>(app_state: &AppState, theme: &Theme) -> Line<'a> {
+    let curve = &app_state.composite.curve;
+    let recent = &curve[curve.len().saturating_sub(INDEX_SPARKLINE_TICKS)..];
+    let (Some(first), Some(last)) = (recent.first(), recent.last()) else {
+        return Line::default();
+    };
+    let min = recent.iter().copied().fold(f64::MAX, f64::min);
+    let max = recent.iter().copied().fold(f64::MIN, f64::max);
+    let change = (last / first - 1.0) * 100.0;
+    let style = theme.change_style(change);
+    Line::from(vec![
+        "─".fg(Color::Cyan),
+        format!("{0} {1:.1} ", tr("Index"), last).fg(Color::Gray),
+        Span::styled(sparkline(recent, INDEX_SPARKLINE_WIDTH, (min, max)), style),
+        Span::styled(format!(" {0:+.1}%", change), style),
+    ])
+}
+
+pub fn draw_status_bar(frame: &mut Frame, area: Rect, status_bar: &StatusBar, ctx: &Context) {
+    let app_state = ctx.app_state;
+    // on its own at the right end, so however many hints there are they can't cover it
+    let index = index_line(app_state, ctx.theme);
+    let [area, index_area] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(index.width() as u16),
+    ])
+    .areas(area);
+    frame.render_widget(index, index_area);
+    let unread_news_count = app_state.unread_news_count();
+    let mut block = status_bar
+        .hints
+        .iter()
+        .fold(Block::new().borders(Borders::TOP), |block, hint| {
+            block.title(hint.as_str().bg(Color::Cyan).fg(Color::Black).bold())
+        });
+    if let Some(register) = status_bar.recording {
+        block = block.title(
+            Line::from(
+                trf("recording @{0}", &[&register])
+                    .bg(Color::Red)
+                    .fg(Color::White)
+                    .bold(),
+            )
+            .right_aligned(),
+        );
+    }
+    // stays until acknowledged in the alerts view
+    let unacknowledged = app_state.alerts.unacknowledged();
+    if unacknowledged > 0 {
+        block = block.title(
+            Line::from(
+                trf("{0} alerts, ! to see", &[&unacknowledged])
+                    .bg(Color::Red)
+                    .fg(Color::White)
+                    .bold(),
+            )
+            .right_aligned(),
+        );
+    }
+    frame.render_widget(
+        block
+            .title(connection_line(&app_state.connection).right_aligned())
+            .title(
+                Line::from(if unread_news_count > 0 {
+                    trf("{0} unread news", &[&unread_news_count])
+                        .bg(Color::Yellow)
+                        .fg(Color::Black)
+                        .bold()
+                } else {
+                    tr("no unread news").fg(Color::Gray)
+                })
+                .right_aligned(),
+            )
+            .border_style(Style::default().fg(Color::Cyan)),
+        area,
+    );
+}
+
+pub fn draw(frame: &mut Frame, app_state: &AppState, uistate: &UIState) {
+    use Constraint::{Fill, Length, Min};
+
+    uistate.hyperlinks.borrow_mut().clear();
+    if uistate.accessible {
+        return draw_linear(frame, app_state, uistate);
+    }
+
+    let main_vertical_layout = Layout::vertical([Min(0), Length(1)]);
+    let [main_area, status_area] = main_vertical_layout.areas(frame.area());
+    let middle_horizontal_layout = Layout::horizontal([Fill(3), Fill(2)]);
+    let [trading_area, side_area] = middle_horizontal_layout.areas(main_area);
+    let [market_data_area, orders_area] =
+        Layout::vertical([Fill(1), Length(9)]).areas(trading_area);
+    let [portfolio_area, latest_news_area] =
+        Layout::vertical([Length(9), Fill(1)]).areas(side_area);
+    // the other panels get no room at all when watching
+    let [market_data_area, orders_area, portfolio_area, latest_news_area] =
+        if uistate.market_table.watch {
+            [main_area, Rect::default(), Rect::default(), Rect::default()]
+        } else {
+            [
+                market_data_area,
+                orders_area,
+                portfolio_area,
+                latest_news_area,
+            ]
+        };
+
+    let ctx = Context::new(app_state, uistate);
+    uistate.market_table.render(frame, market_data_area, &ctx);
+    uistate.news_list.render(frame, latest_news_area, &ctx);
+    uistate.orders_table.render(frame, orders_area, &ctx);
+    uistate.portfolio_panel.render(frame, portfolio_area, &ctx);
+    let status_bar = StatusBar {
+        hints: key_hints(app_state, uistate),
+        recording: uistate.macros.recording,
+    };
+    status_bar.render(frame, status_area, &ctx);
+
+    let areas = OverlayAreas {
+        main: main_area,
+        market: market_data_area,
+        orders: orders_area,
+        news: latest_news_area,
+    };
+    let overlays = Overlay::open(uistate);
+    // links would work through anything drawn over the headlines
+    if overlays.iter().any(Overlay::covers_news) {
+        uistate.hyperlinks.borrow_mut().clear();
+    }
+    // the toasts go over everything but the report
+    let report = overlays
+        .iter()
+        .position(|overlay| matches!(overlay, Overlay::Report(_)))
+        .unwrap_or(overlays.len());
+    for (pos, overlay) in overlays[..report].iter().enumerate() {
+        draw_overlay(
+            frame,
+            &areas,
+            app_state,
+            uistate,
+            overlay,
+            pos + 1 == overlays.len(),
+        );
+    }
+    draw_achievement_toast(frame, main_area, app_state);
+    draw_undo_toast(frame, main_area, app_state);
+    for overlay in &overlays[report..] {
+        draw_overlay(frame, &areas, app_state, uistate, overlay, true);
+    }
+}
+
+// where the overlays go, the linear layout only has somewhere for the news filter
+struct OverlayAreas {
+    main: Rect,
+    market: Rect,
+    orders: Rect,
+    news: Rect,
+}
+
+// one of the screens over the panels, an image of the chart only when nothing is over it
+fn draw_overlay(
+    frame: &mut Frame,
+    areas: &OverlayAreas,
+    app_state: &AppState,
+    uistate: &UIState,
+    overlay: &Overlay,
+    topmost: bool,
+) {
+    let main_area = areas.main;
+    match overlay {
+        Overlay::InstrumentDetail(ticker) => draw_instrument_detail(
+            frame,
+            areas.market,
+            app_state,
+            uistate,
+            ticker,
+            (uistate.graphics && topmost).then_some(&uistate.chart_image),
+        ),
+        Overlay::OrderForm(form) => draw_order_form(frame, areas.market, app_state, form),
+        Overlay::OptionsChain(view) => draw_options_chain(frame, main_area, app_state, view),
+        Overlay::RowMenu(menu) => draw_row_menu(frame, main_area, menu),
+        Overlay::NewsArchive(page) => {
+            draw_news_archive(frame, main_area, app_state, uistate, *page)
+        }
+        Overlay::NewsFilter(cursor) => draw_news_filter_menu(frame, areas.news, uistate, *cursor),
+        Overlay::Comparison => draw_comparison(frame, main_area, app_state, uistate),
+        Overlay::Achievements => draw_achievements(frame, main_area, app_state),
+        Overlay::Ledger(view) => draw_ledger(frame, main_area, app_state, view),
+        Overlay::Accounts(view) => draw_accounts(frame, main_area, app_state, view),
+        Overlay::Fx(view) => draw_fx(frame, main_area, app_state, uistate, view),
+        Overlay::Connection => draw_connection_manager(frame, main_area, app_state),
+        Overlay::Panel(panel) => draw_panel(frame, main_area, app_state, *panel),
+        Overlay::Saves(menu) => draw_saves_menu(frame, main_area, app_state, menu),
+        Overlay::Settings(form) => draw_settings(frame, main_area, form),
+        Overlay::Alerts(cursor) => draw_alerts(frame, main_area, app_state, *cursor),
+        Overlay::Movers(cursor) => {
+            draw_movers(frame, main_area, app_state, &uistate.theme, *cursor)
+        }
+        Overlay::Rebalance(view) => draw_rebalance(frame, main_area, app_state, view),
+        Overlay::Schedules(cursor) => draw_schedules(frame, main_area, app_state, *cursor),
+        Overlay::ScheduleForm(form) => draw_schedule_form(frame, main_area, form),
+        Overlay::AlertForm(form) => draw_alert_form(frame, main_area, form),
+        Overlay::Help(scroll) => draw_help(frame, main_area, &uistate.keymap, scroll),
+        Overlay::Tutorial(tutorial) => draw_tutorial(frame, main_area, tutorial),
+        // on top of whatever it was opened from
+        Overlay::Confirm(confirm) => {
+            let area = match confirm {
+                Confirm::CancelOrder(_) | Confirm::ClearBlotter => areas.orders,
+                Confirm::Liquidate(_) | Confirm::NewGame => main_area,
+            };
+            draw_confirm(frame, area, app_state, confirm);
+        }
+        Overlay::Report(view) => draw_report(frame, main_area, view, uistate),
+    }
+}
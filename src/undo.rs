@@ -0,0 +1,115 @@
+use crate::app::UIState;
+use crate::forms::TextInput;
+use crate::news::NewsCategory;
+use crate::theme::Theme;
+use chrono::{DateTime, Utc};
+
+// older changes fall off the bottom
+const MAX_CHANGES: usize = 100;
+
+// what a change replaced, undoing it puts that back and keeps what was there for redo
+pub enum Change {
+    Compare(Vec<String>),
+    NewsFilter {
+        ticker_filter: Option<String>,
+        hidden_categories: Vec<NewsCategory>,
+        search: Option<TextInput>,
+    },
+    Screen(Option<TextInput>),
+    Theme(Theme),
+}
+
+impl Change {
+    pub fn compare(uistate: &UIState) -> Change {
+        Change::Compare(uistate.compare_tickers.clone())
+    }
+
+    pub fn news_filter(uistate: &UIState) -> Change {
+        Change::NewsFilter {
+            ticker_filter: uistate.news_list.ticker_filter.clone(),
+            hidden_categories: uistate.news_list.hidden_categories.clone(),
+            search: uistate.news_list.search.clone(),
+        }
+    }
+
+    pub fn screen(uistate: &UIState) -> Change {
+        Change::Screen(uistate.market_table.screen.clone())
+    }
+
+    pub fn theme(uistate: &UIState) -> Change {
+        Change::Theme(uistate.theme.clone())
+    }
+
+    // the same kind of change, holding what's there now
+    fn current(&self, uistate: &UIState) -> Change {
+        match self {
+            Change::Compare(_) => Change::compare(uistate),
+            Change::NewsFilter { .. } => Change::news_filter(uistate),
+            Change::Screen(_) => Change::screen(uistate),
+            Change::Theme(_) => Change::theme(uistate),
+        }
+    }
+
+    fn restore(self, uistate: &mut UIState) {
+        match self {
+            Change::Compare(tickers) => uistate.compare_tickers = tickers,
+            Change::NewsFilter {
+                ticker_filter,
+                hidden_categories,
+                search,
+            } => {
+                uistate.news_list.ticker_filter = ticker_filter;
+                uistate.news_list.hidden_categories = hidden_categories;
+                uistate.news_list.search = search;
+                uistate.reset_news_positions();
+            }
+            Change::Screen(screen) => {
+                uistate.market_table.screen = screen;
+                uistate.market_table.scroll.pos = 0;
+            }
+            Change::Theme(theme) => uistate.theme = theme,
+        }
+    }
+}
+
+// the changes made to the view, newest last, trades are undone through the blotter
+#[derive(Default)]
+pub struct History {
+    undo: Vec<(DateTime<Utc>, Change)>,
+    redo: Vec<Change>,
+}
+
+impl History {
+    // before making the change
+    pub fn record(&mut self, change: Change) {
+        if self.undo.len() == MAX_CHANGES {
+            self.undo.remove(0);
+        }
+        self.undo.push((Utc::now(), change));
+        self.redo.clear();
+    }
+
+    pub fn last_change(&self) -> Option<DateTime<Utc>> {
+        self.undo.last().map(|(time, _)| *time)
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+pub fn undo(uistate: &mut UIState) {
+    if let Some((_, change)) = uistate.history.undo.pop() {
+        let current = change.current(uistate);
+        change.restore(uistate);
+        uistate.history.redo.push(current);
+    }
+}
+
+pub fn redo(uistate: &mut UIState) {
+    if let Some(change) = uistate.history.redo.pop() {
+        let current = change.current(uistate);
+        change.restore(uistate);
+        uistate.history.undo.push((Utc::now(), current));
+    }
+}
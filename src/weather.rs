@@ -0,0 +1,82 @@
+use crate::app::AppState;
+use crate::panels::Panel;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+
+// the weather turns every this many ticks
+const SPELL_TICKS: u64 = 120;
+const CONDITIONS: [(&str, &str); 6] = [
+    (
+        "Pea-soup fog",
+        "Lamplighters out at noon, river traffic at a crawl",
+    ),
+    ("Drizzle", "Umbrellas up along the Strand"),
+    (
+        "Smog",
+        "A chance of soot, foundries asked to bank their fires",
+    ),
+    ("Steady rain", "Thames running high under the bridges"),
+    (
+        "Clear and crisp",
+        "Airships leaving on time from the Victoria mast",
+    ),
+    ("Gusty", "Dirigibles grounded, kites aloft over the Heath"),
+];
+
+// the weather in Londinium, made up from the tick count so every spell lasts a while
+#[derive(Default)]
+pub struct WeatherPanel {
+    fahrenheit: bool,
+}
+
+impl WeatherPanel {
+    fn spell(&self, app_state: &AppState) -> u64 {
+        // a cheap scramble so spells don't come round in order
+        (app_state.ticks / SPELL_TICKS).wrapping_mul(2_654_435_761) >> 7
+    }
+
+    fn temperature(&self, celsius: f64) -> String {
+        if self.fahrenheit {
+            format!("{0:.0}°F", celsius * 9.0 / 5.0 + 32.0)
+        } else {
+            format!("{0:.0}°C", celsius)
+        }
+    }
+}
+
+impl Panel for WeatherPanel {
+    fn title(&self) -> String {
+        "Weather in Londinium".to_string()
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, app_state: &AppState) {
+        let spell = self.spell(app_state);
+        let (condition, remark) = CONDITIONS[(spell % CONDITIONS.len() as u64) as usize];
+        let celsius = 4.0 + (spell % 15) as f64;
+        let ticks_left = SPELL_TICKS - app_state.ticks % SPELL_TICKS;
+        let lines = vec![
+            Line::from(vec![
+                condition.fg(Color::White).bold(),
+                format!("  {0}", self.temperature(celsius)).fg(Color::Yellow),
+            ]),
+            Line::from(remark).fg(Color::Gray),
+            Line::from(""),
+            Line::from(format!("Turning in {0} ticks", ticks_left)).fg(Color::DarkGray),
+        ];
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+
+    fn handle_action(&mut self, key: KeyEvent, _app_state: &AppState) {
+        if let KeyCode::Char('u') | KeyCode::Char('U') = key.code {
+            self.fahrenheit = !self.fahrenheit;
+        }
+    }
+
+    fn key_hints(&self) -> Vec<&'static str> {
+        vec!["u - °C/°F"]
+    }
+}
@@ -0,0 +1,224 @@
+use crate::app::AppState;
+use crate::history::PriceHistory;
+use crate::screener::{Screen, ScreenRow};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+// the simulation keeps the other cores, these are for the views
+const WORKER_THREADS: usize = 2;
+
+// a number a view needs that can take a while with many instruments or a long history, worked
+// out on the worker's threads so a frame never waits for it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Job {
+    Screen(String),           // the query, over every instrument
+    Returns(String),          // the distribution of the ticker's candle returns
+    Correlation(Vec<String>), // of the tickers' returns, every pair
+}
+
+// correlations of every pair of tickers, none for a pair without movement to go by
+pub type Matrix = Vec<Vec<Option<f64>>>;
+
+// how candle-to-candle returns are spread, in percent
+#[derive(Debug, Clone)]
+pub struct Distribution {
+    pub returns: Vec<f64>,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Distribution {
+    fn of(returns: Vec<f64>) -> Distribution {
+        let count = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / count;
+        let std_dev = (returns
+            .iter()
+            .map(|value| (value - mean).powi(2))
+            .sum::<f64>()
+            / count)
+            .sqrt();
+        Distribution {
+            low: returns.iter().copied().fold(f64::MAX, f64::min),
+            high: returns.iter().copied().fold(f64::MIN, f64::max),
+            returns,
+            mean,
+            std_dev,
+        }
+    }
+}
+
+// a finished job with what it was for, so the view can tell whether it's still the one it wants
+pub enum Outcome {
+    Screen {
+        query: String,
+        matched: HashSet<String>, // tickers
+    },
+    Returns {
+        ticker: String,
+        distribution: Distribution,
+    },
+    Correlation {
+        tickers: Vec<String>,
+        matrix: Matrix,
+    },
+}
+
+impl Outcome {
+    fn job(&self) -> Job {
+        match self {
+            Outcome::Screen { query, .. } => Job::Screen(query.clone()),
+            Outcome::Returns { ticker, .. } => Job::Returns(ticker.clone()),
+            Outcome::Correlation { tickers, .. } => Job::Correlation(tickers.clone()),
+        }
+    }
+}
+
+// jobs go to a pool of their own with copies of what they need and come back through a
+// channel, each is done again once a tick has changed the prices it was worked out from
+pub struct Worker {
+    pool: ThreadPool,
+    sender: Sender<(u64, Outcome)>,
+    receiver: Receiver<(u64, Outcome)>,
+    pending: HashSet<Job>,
+    done: HashMap<Job, u64>, // the tick each was last worked out on
+}
+
+impl Default for Worker {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Worker {
+            pool: ThreadPoolBuilder::new()
+                .num_threads(WORKER_THREADS)
+                .thread_name(|idx| format!("worker-{0}", idx))
+                .build()
+                .expect("failed to start the worker threads"),
+            sender,
+            receiver,
+            pending: HashSet::new(),
+            done: HashMap::new(),
+        }
+    }
+}
+
+impl Worker {
+    // the jobs the open views need, the ones not running and not done this tick are started
+    pub fn request(&mut self, app_state: &AppState, jobs: Vec<Job>) {
+        self.done.retain(|job, _| jobs.contains(job));
+        for job in jobs {
+            if self.pending.contains(&job) || self.done.get(&job) == Some(&app_state.ticks) {
+                continue;
+            }
+            let work = work(&job, app_state);
+            let (sender, tick) = (self.sender.clone(), app_state.ticks);
+            self.pool.spawn(move || {
+                let _ = sender.send((tick, work()));
+            });
+            self.pending.insert(job);
+        }
+    }
+
+    // whatever finished since the last call
+    pub fn collect(&mut self) -> Vec<Outcome> {
+        self.receiver
+            .try_iter()
+            .map(|(tick, outcome)| {
+                let job = outcome.job();
+                self.pending.remove(&job);
+                self.done.insert(job, tick);
+                outcome
+            })
+            .collect()
+    }
+
+    pub fn is_busy(&self) -> bool {
+        !self.pending.is_empty()
+    }
+}
+
+// the inputs are copied here, on the main thread, the work is left to the closure
+fn work(job: &Job, app_state: &AppState) -> Box<dyn FnOnce() -> Outcome + Send> {
+    let history = |ticker: &String| {
+        app_state
+            .quote_index(ticker)
+            .map(|idx| app_state.quotes[idx].history.clone())
+            .unwrap_or_default()
+    };
+    match job.clone() {
+        Job::Screen(query) => {
+            let rows = app_state
+                .quotes
+                .iter()
+                .map(|quote| (quote.listing.ticker.clone(), ScreenRow::of(quote)))
+                .collect::<Vec<(String, ScreenRow)>>();
+            Box::new(move || {
+                // a screen that doesn't parse is never asked for
+                let screen = Screen::parse(&query).ok();
+                let matched = rows
+                    .into_iter()
+                    .filter(|(_, row)| {
+                        screen
+                            .as_ref()
+                            .is_some_and(|screen| screen.matches_row(row))
+                    })
+                    .map(|(ticker, _)| ticker)
+                    .collect();
+                Outcome::Screen { query, matched }
+            })
+        }
+        Job::Returns(ticker) => {
+            let history = history(&ticker);
+            Box::new(move || Outcome::Returns {
+                ticker,
+                distribution: Distribution::of(history.returns()),
+            })
+        }
+        Job::Correlation(tickers) => {
+            let histories = tickers.iter().map(history).collect::<Vec<PriceHistory>>();
+            Box::new(move || Outcome::Correlation {
+                tickers,
+                matrix: correlations(&histories),
+            })
+        }
+    }
+}
+
+// Pearson's of every pair over the latest returns both have, the candles end on the same tick
+fn correlations(histories: &[PriceHistory]) -> Matrix {
+    let returns = histories
+        .iter()
+        .map(PriceHistory::returns)
+        .collect::<Vec<Vec<f64>>>();
+    returns
+        .iter()
+        .map(|a| {
+            returns
+                .iter()
+                .map(|b| {
+                    let len = a.len().min(b.len());
+                    correlation(&a[a.len() - len..], &b[b.len() - len..])
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn correlation(a: &[f64], b: &[f64]) -> Option<f64> {
+    let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len().max(1) as f64;
+    let (mean_a, mean_b) = (mean(a), mean(b));
+    let covariance = a
+        .iter()
+        .zip(b)
+        .map(|(a, b)| (a - mean_a) * (b - mean_b))
+        .sum::<f64>();
+    let variance = |values: &[f64], mean: f64| {
+        values
+            .iter()
+            .map(|value| (value - mean).powi(2))
+            .sum::<f64>()
+    };
+    let spread = (variance(a, mean_a) * variance(b, mean_b)).sqrt();
+    (a.len() >= 2 && spread > 0.0).then(|| covariance / spread)
+}
@@ -0,0 +1,154 @@
+// what has to hold for the lists whatever keys come and however the content and the terminal
+// change under them, the ground the variable-height rows will be built on
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use proptest::prelude::*;
+use ratatui::backend::TestBackend;
+use ratatui::style::Style;
+use ratatui::Terminal;
+use rust_tui_test::components::{Component, ScrollView};
+use rust_tui_test::market::{gen_universe, InstrumentClass};
+use rust_tui_test::testing::{app_state, ui_state};
+use rust_tui_test::ui::draw;
+
+#[derive(Debug, Clone)]
+enum Action {
+    Key(KeyCode),
+    Content(usize), // the list grows or shrinks to this many rows
+    Resize(u16, u16),
+}
+
+fn key() -> impl Strategy<Value = KeyCode> {
+    prop_oneof![
+        4 => Just(KeyCode::Down),
+        4 => Just(KeyCode::Up),
+        1 => Just(KeyCode::PageDown),
+        1 => Just(KeyCode::Home),
+        1 => Just(KeyCode::Char('x')),
+    ]
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        8 => key().prop_map(Action::Key),
+        1 => (0usize..60).prop_map(Action::Content),
+        1 => (1u16..200, 1u16..80).prop_map(|(width, height)| Action::Resize(width, height)),
+    ]
+}
+
+// the selection is a row of the content, or the top of an empty list
+fn in_bounds(scroll: &ScrollView, len: usize) -> bool {
+    scroll.pos < len || scroll.pos == 0
+}
+
+proptest! {
+    #[test]
+    fn scroll_stays_within_the_content(
+        start in 0usize..60,
+        actions in prop::collection::vec(action(), 0..200),
+    ) {
+        let mut scroll = ScrollView::default();
+        let mut len = start;
+        let mut terminal = Terminal::new(TestBackend::new(40, 20)).unwrap();
+        for action in actions {
+            match action {
+                Action::Key(code) => {
+                    let before = scroll.pos;
+                    let handled =
+                        scroll.on_event(KeyEvent::new(code, KeyModifiers::NONE), len);
+                    // only Up and Down move it, a row at a time
+                    prop_assert_eq!(handled, matches!(code, KeyCode::Up | KeyCode::Down));
+                    prop_assert!(scroll.pos.abs_diff(before.min(len.saturating_sub(1))) <= 1);
+                }
+                // the main loop clamps after every tick that can change the lists
+                Action::Content(rows) => {
+                    len = rows;
+                    scroll.clamp(len);
+                }
+                // drawn again with as many rows showing as the terminal has now
+                Action::Resize(width, height) => {
+                    terminal.backend_mut().resize(width, height);
+                    terminal.autoresize().unwrap();
+                    terminal
+                        .draw(|frame| {
+                            let area = frame.area();
+                            scroll.render(frame, area, len, area.height as usize, Style::default())
+                        })
+                        .unwrap();
+                }
+            }
+            prop_assert!(in_bounds(&scroll, len));
+        }
+    }
+
+    #[test]
+    fn clamp_keeps_what_still_fits(pos in 0usize..100, len in 0usize..100) {
+        let mut scroll = ScrollView { pos };
+        scroll.clamp(len);
+        prop_assert!(in_bounds(&scroll, len));
+        if pos < len {
+            prop_assert_eq!(scroll.pos, pos);
+        }
+    }
+
+    #[test]
+    fn scrollbar_draws_at_any_size(
+        width in 1u16..120,
+        height in 1u16..60,
+        len in 0usize..500,
+        viewport in 0usize..100,
+        pos in 0usize..500,
+    ) {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        let scroll = ScrollView { pos: pos.min(len.saturating_sub(1)) };
+        terminal
+            .draw(|frame| scroll.render(frame, frame.area(), len, viewport, Style::default()))
+            .unwrap();
+    }
+}
+
+proptest! {
+    // a market per case is slow to make, these are fewer
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn market_selection_stays_on_a_row(
+        actions in prop::collection::vec(
+            prop_oneof![
+                8 => key().prop_map(Action::Key),
+                1 => Just(Action::Key(KeyCode::Tab)),
+                // a row opened up is taller than the others
+                1 => Just(Action::Key(KeyCode::Char(' '))),
+                1 => (1u16..200, 1u16..80).prop_map(|(width, height)| Action::Resize(width, height)),
+            ],
+            0..100,
+        ),
+    ) {
+        let companies = gen_universe(40);
+        let app_state = app_state(&companies, 10);
+        let mut ui_state = ui_state();
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        for action in actions {
+            match action {
+                Action::Key(code) => {
+                    ui_state
+                        .market_table
+                        .on_event(KeyEvent::new(code, KeyModifiers::NONE), &app_state);
+                }
+                Action::Resize(width, height) => {
+                    terminal.backend_mut().resize(width, height);
+                    terminal.autoresize().unwrap();
+                }
+                Action::Content(_) => {}
+            }
+            let len = ui_state.market_table.indices(&app_state).len();
+            prop_assert!(in_bounds(&ui_state.market_table.scroll, len));
+            // only equities are made up, the other tabs are empty
+            let selected = ui_state.market_table.selected(&app_state);
+            prop_assert_eq!(
+                selected.is_some(),
+                ui_state.market_table.tab == InstrumentClass::Equity
+            );
+            terminal.draw(|frame| draw(frame, &app_state, &ui_state)).unwrap();
+        }
+    }
+}